@@ -0,0 +1,69 @@
+//! Compares the generic XML -> JSON pipeline with the direct XML -> struct parsers.
+//!
+//! Run with `cargo bench --bench xml_parsing`.
+
+use std::hint::black_box;
+use std::time::{ Duration, Instant };
+
+use namecheap::response::typed_xml::{ parse_domains_xml, parse_hosts_xml };
+use namecheap::utils::xml_parser::parse_xml_to_json;
+
+const ITERATIONS: u32 = 200;
+
+fn hosts_xml(count: usize) -> String {
+    let hosts: String = (0..count)
+        .map(|i| {
+            format!(
+                r#"<host HostId="{i}" Name="host{i}" Type="A" Address="203.0.113.{}" MXPref="10" TTL="1800" IsActive="true" IsDDNSEnabled="false" />"#,
+                i % 255
+            )
+        })
+        .collect();
+    format!(
+        r#"<ApiResponse Status="OK"><Errors /><CommandResponse><DomainDNSGetHostsResult Domain="example.com">{hosts}</DomainDNSGetHostsResult></CommandResponse><GMTTimeDifference>--5:00</GMTTimeDifference></ApiResponse>"#
+    )
+}
+
+fn domains_xml(count: usize) -> String {
+    let domains: String = (0..count)
+        .map(|i| {
+            format!(
+                r#"<Domain ID="{i}" Name="example{i}.com" User="owner" Created="07/14/2024" Expires="07/14/2025" IsExpired="false" IsLocked="false" AutoRenew="true" WhoisGuard="ENABLED" IsPremium="false" IsOurDNS="true" />"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<ApiResponse Status="OK"><Errors /><CommandResponse><DomainGetListResult>{domains}</DomainGetListResult><Paging><TotalItems>{count}</TotalItems><CurrentPage>1</CurrentPage><PageSize>100</PageSize></Paging></CommandResponse><GMTTimeDifference>--5:00</GMTTimeDifference></ApiResponse>"#
+    )
+}
+
+fn measure(name: &str, operation: impl Fn()) -> Duration {
+    let start: Instant = Instant::now();
+    for _ in 0..ITERATIONS {
+        operation();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{:<32} {:>10.1?} per iteration", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let hosts: String = hosts_xml(150);
+    let domains: String = domains_xml(100);
+
+    let json: Duration = measure("getHosts xml -> json", || {
+        black_box(parse_xml_to_json(black_box(&hosts)).unwrap());
+    });
+    let direct: Duration = measure("getHosts xml -> Vec<Host>", || {
+        black_box(parse_hosts_xml(black_box(&hosts)).unwrap());
+    });
+    println!("getHosts speedup: {:.2}x", json.as_secs_f64() / direct.as_secs_f64());
+
+    let json: Duration = measure("getList xml -> json", || {
+        black_box(parse_xml_to_json(black_box(&domains)).unwrap());
+    });
+    let direct: Duration = measure("getList xml -> Vec<Domain>", || {
+        black_box(parse_domains_xml(black_box(&domains)).unwrap());
+    });
+    println!("getList speedup: {:.2}x", json.as_secs_f64() / direct.as_secs_f64());
+}
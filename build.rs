@@ -0,0 +1,11 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Compiles the gRPC service definition, protox avoids requiring a system protoc
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/namecheap.proto");
+        let descriptors = protox::compile(["proto/namecheap.proto"], ["proto"]).expect("Invalid proto/namecheap.proto");
+        tonic_build::configure().compile_fds(descriptors).expect("Failed to generate the gRPC service");
+    }
+}
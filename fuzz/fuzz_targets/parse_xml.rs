@@ -0,0 +1,16 @@
+//! Fuzzes the response parsers with arbitrary bytes.
+//!
+//! Run with `cargo +nightly fuzz run parse_xml` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use namecheap::response::typed_xml::{ parse_domains_xml, parse_hosts_xml };
+use namecheap::utils::xml_parser::parse_xml_bytes;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_xml_bytes(data);
+    if let Ok(xml) = std::str::from_utf8(data) {
+        let _ = parse_hosts_xml(xml);
+        let _ = parse_domains_xml(xml);
+    }
+});
@@ -0,0 +1,388 @@
+//! ## Accounts
+//! Agencies run several NameCheap accounts from one process. NameCheap throttles each account
+//! separately, so an `AccountManager` gives every account its own `RateLimiter` (its own quota
+//! of 20 calls per minute) while the process-wide capacity, `max_in_flight` requests at once,
+//! is shared fairly between them:
+//! - Every request of a managed client takes one slot for the time it is in flight.
+//! - Free slots go to the waiting accounts in turn, round-robin, whatever the number of
+//!   requests each one queued: an account fanning out over 5000 domains gets one slot per
+//!   turn, like an account sending a single call.
+//! - Requests and wait and busy times are tracked per account, see [`AccountManager::metrics`].
+//!
+//! # Example
+//!
+//! ```rust
+//! let accounts = AccountManager::new(4)
+//!     .add("clientA", NameCheapClient::new_from_env()?)
+//!     .add("clientB", client_b);
+//! let domains = accounts.client("clientA").unwrap().domains_get_list_all().await?;
+//! println!("{:#?}", accounts.metrics());
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use std::collections::{ BTreeMap, BTreeSet, VecDeque };
+use std::fmt;
+use std::sync::{ Arc, Mutex, MutexGuard };
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::cooldown::Cooldown;
+use crate::utils::rate_limit::RateLimiter;
+
+/// The default number of requests in flight at once, across all accounts.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+
+/// ### Account Stats
+///
+/// #### Fields
+/// - `requests`: The number of requests sent.
+/// - `waiting`: The number of requests waiting for a slot.
+/// - `in_flight`: The number of requests in flight.
+/// - `waited`: The total time requests waited for a slot.
+/// - `busy`: The total time requests held a slot.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct AccountStats {
+    pub requests: u64,
+    pub waiting: usize,
+    pub in_flight: usize,
+    pub waited: Duration,
+    pub busy: Duration,
+}
+
+impl AccountStats {
+    /// Adds the counters of `other`.
+    fn add(&mut self, other: &AccountStats) {
+        self.requests += other.requests;
+        self.waiting += other.waiting;
+        self.in_flight += other.in_flight;
+        self.waited += other.waited;
+        self.busy += other.busy;
+    }
+}
+
+/// ### Account Metrics
+///
+/// #### Fields
+/// - `accounts`: The stats of each account, by name.
+/// - `total`: The stats of all accounts added up.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct AccountMetrics {
+    pub accounts: BTreeMap<String, AccountStats>,
+    pub total: AccountStats,
+}
+
+/// Hands out `max_in_flight` slots to the waiting accounts, round-robin.
+#[derive(Debug)]
+pub struct FairScheduler {
+    max_in_flight: usize,
+    state: Mutex<SchedulerState>,
+    changed: Notify,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    in_flight: usize,
+    next_ticket: u64,
+    /// The waiting tickets of each account, oldest first.
+    waiting: BTreeMap<String, VecDeque<u64>>,
+    /// The accounts with waiting tickets, the next one to serve first.
+    turn: VecDeque<String>,
+    /// Tickets given a slot, not picked up by their caller yet.
+    granted: BTreeSet<u64>,
+    stats: BTreeMap<String, AccountStats>,
+}
+
+impl SchedulerState {
+    /// Gives the free slots to the accounts in turn.
+    fn dispatch(&mut self, max_in_flight: usize) -> bool {
+        let mut granted: bool = false;
+        while self.in_flight < max_in_flight && let Some(account) = self.turn.pop_front() {
+            let Some(queue) = self.waiting.get_mut(&account) else { continue };
+            let Some(ticket) = queue.pop_front() else { continue };
+            if !queue.is_empty() {
+                // Back of the line, every other waiting account goes first
+                self.turn.push_back(account.clone());
+            }
+
+            let stats: &mut AccountStats = self.stats.entry(account).or_default();
+            stats.waiting -= 1;
+            stats.in_flight += 1;
+            self.in_flight += 1;
+            self.granted.insert(ticket);
+            granted = true;
+        }
+        granted
+    }
+
+    /// Removes a ticket that gave up waiting.
+    fn withdraw(&mut self, account: &str, ticket: u64) {
+        if let Some(queue) = self.waiting.get_mut(account) {
+            queue.retain(|waiting| *waiting != ticket);
+            if queue.is_empty() {
+                self.turn.retain(|name| name != account);
+            }
+        }
+        if let Some(stats) = self.stats.get_mut(account) {
+            stats.waiting -= 1;
+        }
+    }
+}
+
+/// A slot held while a request is in flight, freed on drop.
+pub struct SlotGuard {
+    scheduler: Arc<FairScheduler>,
+    account: String,
+    since: Instant,
+}
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        let mut state = self.scheduler.lock();
+        state.in_flight -= 1;
+        if let Some(stats) = state.stats.get_mut(&self.account) {
+            stats.in_flight -= 1;
+            stats.busy += self.since.elapsed();
+        }
+        let max_in_flight: usize = self.scheduler.max_in_flight;
+        if state.dispatch(max_in_flight) {
+            drop(state);
+            self.scheduler.changed.notify_waiters();
+        }
+    }
+}
+
+/// A waiting request, withdrawn if dropped before it gets its slot.
+struct Waiting<'a> {
+    scheduler: &'a Arc<FairScheduler>,
+    account: &'a str,
+    ticket: u64,
+    done: bool,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut state = self.scheduler.lock();
+        if state.granted.remove(&self.ticket) {
+            // Granted in the meantime: hand the slot on
+            state.in_flight -= 1;
+            if let Some(stats) = state.stats.get_mut(self.account) {
+                stats.in_flight -= 1;
+            }
+            let max_in_flight: usize = self.scheduler.max_in_flight;
+            state.dispatch(max_in_flight);
+        } else {
+            state.withdraw(self.account, self.ticket);
+        }
+        drop(state);
+        self.scheduler.changed.notify_waiters();
+    }
+}
+
+impl FairScheduler {
+    /// Creates a scheduler allowing `max_in_flight` requests at once, at least one.
+    pub fn new(max_in_flight: usize) -> Self {
+        FairScheduler {
+            max_in_flight: max_in_flight.max(1),
+            state: Mutex::new(SchedulerState::default()),
+            changed: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Waits for a slot for `account`, held until the guard is dropped.
+    pub async fn acquire(self: &Arc<Self>, account: &str) -> SlotGuard {
+        let start: Instant = Instant::now();
+        let mut waiting: Waiting<'_> = {
+            let mut state = self.lock();
+            let ticket: u64 = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiting.entry(account.to_string()).or_default().push_back(ticket);
+            if !state.turn.iter().any(|name| name == account) {
+                state.turn.push_back(account.to_string());
+            }
+            state.stats.entry(account.to_string()).or_default().waiting += 1;
+            state.dispatch(self.max_in_flight);
+            Waiting { scheduler: self, account, ticket, done: false }
+        };
+
+        loop {
+            // Registered before the state is read, so no grant is missed
+            let changed = self.changed.notified();
+            {
+                let mut state = self.lock();
+                if state.granted.remove(&waiting.ticket) {
+                    let stats: &mut AccountStats = state.stats.entry(account.to_string()).or_default();
+                    stats.requests += 1;
+                    stats.waited += start.elapsed();
+                    waiting.done = true;
+                    return SlotGuard { scheduler: self.clone(), account: account.to_string(), since: Instant::now() };
+                }
+            }
+            changed.await;
+        }
+    }
+
+    /// Returns the stats of every account that sent or queued a request.
+    pub fn metrics(&self) -> AccountMetrics {
+        let accounts: BTreeMap<String, AccountStats> = self.lock().stats.clone();
+        let mut total: AccountStats = AccountStats::default();
+        accounts.values().for_each(|stats| total.add(stats));
+        AccountMetrics { accounts, total }
+    }
+}
+
+/// The account a client's requests are scheduled under.
+#[derive(Clone)]
+pub struct AccountSlot {
+    scheduler: Arc<FairScheduler>,
+    account: String,
+}
+
+impl AccountSlot {
+    /// Waits for a slot of the account.
+    pub async fn acquire(&self) -> SlotGuard {
+        self.scheduler.acquire(&self.account).await
+    }
+}
+
+impl fmt::Debug for AccountSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AccountSlot").field(&self.account).finish()
+    }
+}
+
+/// The clients of several accounts, sharing the process capacity fairly.
+#[derive(Debug)]
+pub struct AccountManager {
+    scheduler: Arc<FairScheduler>,
+    clients: BTreeMap<String, NameCheapClient>,
+}
+
+impl Default for AccountManager {
+    fn default() -> Self {
+        AccountManager::new(DEFAULT_MAX_IN_FLIGHT)
+    }
+}
+
+impl AccountManager {
+    /// Creates a manager allowing `max_in_flight` requests at once across all accounts.
+    pub fn new(max_in_flight: usize) -> Self {
+        AccountManager {
+            scheduler: Arc::new(FairScheduler::new(max_in_flight)),
+            clients: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the client of account `name`, paced by a default `RateLimiter` of its own.
+    pub fn add(self, name: &str, client: NameCheapClient) -> Self {
+        self.add_with_limiter(name, client, RateLimiter::default())
+    }
+
+    /// Adds the client of account `name`, paced by `limiter`.
+    ///
+    /// The client is rebuilt with its own limiter and slot, replacing any limiter it had: like
+    /// `with_rate_limiter`, add it before cloning. An account added twice is replaced.
+    pub fn add_with_limiter(mut self, name: &str, client: NameCheapClient, limiter: RateLimiter) -> Self {
+        let slot: AccountSlot = AccountSlot { scheduler: self.scheduler.clone(), account: name.to_string() };
+        let client: NameCheapClient = client.with_rate_limiter(limiter).with_account_slot(slot);
+        self.clients.insert(name.to_string(), client);
+        self
+    }
+
+    /// Returns the client of account `name`.
+    pub fn client(&self, name: &str) -> Option<&NameCheapClient> {
+        self.clients.get(name)
+    }
+
+    /// Returns the account names, sorted.
+    pub fn accounts(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    /// Returns the request stats of each account and their total.
+    pub fn metrics(&self) -> AccountMetrics {
+        self.scheduler.metrics()
+    }
+}
+
+impl NameCheapClient {
+    /// Schedules every request of this client and its clones under `slot`.
+    pub(crate) fn with_account_slot(mut self, slot: AccountSlot) -> Self {
+        // A throttled account pauses alone, whatever client it was cloned from
+        self.shared = self.shared.with_account_slot(slot).with_cooldown(Arc::new(Cooldown::new()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slots_rotate_between_accounts() {
+        let scheduler: Arc<FairScheduler> = Arc::new(FairScheduler::new(1));
+        let order: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        // Holds the only slot while both accounts queue up
+        let first: SlotGuard = scheduler.acquire("busy").await;
+
+        let mut handles = Vec::new();
+        for account in ["busy", "busy", "busy", "quiet"] {
+            let (scheduler, order) = (scheduler.clone(), order.clone());
+            handles.push(tokio::spawn(async move {
+                let _slot: SlotGuard = scheduler.acquire(account).await;
+                order.lock().unwrap().push(account.to_string());
+            }));
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        assert_eq!(scheduler.metrics().accounts["busy"].waiting, 3);
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        // The quiet account does not wait behind the three queued requests of the busy one
+        assert_eq!(*order.lock().unwrap(), ["busy", "quiet", "busy", "busy"]);
+        let metrics: AccountMetrics = scheduler.metrics();
+        assert_eq!((metrics.accounts["busy"].requests, metrics.accounts["quiet"].requests), (4, 1));
+        assert_eq!((metrics.total.requests, metrics.total.waiting, metrics.total.in_flight), (5, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_request_frees_its_turn() {
+        let scheduler: Arc<FairScheduler> = Arc::new(FairScheduler::new(1));
+        let first: SlotGuard = scheduler.acquire("a").await;
+
+        let gave_up = tokio::time::timeout(Duration::from_millis(5), scheduler.acquire("b"));
+        assert!(gave_up.await.is_err());
+        assert_eq!(scheduler.metrics().accounts["b"].waiting, 0);
+
+        drop(first);
+        let _second: SlotGuard = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire("a")).await.unwrap();
+        assert_eq!(scheduler.metrics().total.in_flight, 1);
+    }
+
+    #[test]
+    fn test_accounts_get_their_own_limiter() {
+        let client = |user: &str| NameCheapClient::new(user.to_string(), "key".to_string(), "127.0.0.1".to_string(), user.to_string(), false);
+        let accounts: AccountManager = AccountManager::default().add("a", client("a")).add("b", client("b"));
+
+        let (a, b) = (accounts.client("a").unwrap(), accounts.client("b").unwrap());
+        assert!(a.shared.limiter().is_some() && a.shared.account_slot().is_some());
+        assert!(!std::ptr::eq(a.shared.limiter().unwrap(), b.shared.limiter().unwrap()));
+        assert!(!std::ptr::eq(a.shared.cooldown(), b.shared.cooldown()));
+        assert_eq!(accounts.accounts().collect::<Vec<&str>>(), ["a", "b"]);
+    }
+}
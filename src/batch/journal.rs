@@ -0,0 +1,121 @@
+//! ### Batch Journal
+//!
+//! A journal records the outcome of every batch item as soon as it completes, so a long
+//! batch (renewing 500 domains) that dies midway can be resumed with [`Batch::resume`]
+//! without repeating the items that already succeeded.
+//!
+//! The journal is an append-only JSON lines file, one line per completed item. A crash can
+//! at worst lose the line of the item in flight. When an item appears more than once the
+//! last line wins, so a failed item that succeeds on resume is recorded as a success.
+
+use chrono::{ DateTime, Utc };
+use serde::{ Serialize, Deserialize };
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{ File, OpenOptions };
+use std::io::{ BufRead, BufReader, Write };
+use std::path::{ Path, PathBuf };
+use tracing::warn;
+
+// crate imports
+use crate::batch::BatchOutcome;
+use crate::utils::correlation::current_correlation_id;
+
+/// A recorded item outcome.
+///
+/// #### Fields
+/// - `key`: The batch item, usually a domain name.
+/// - `recorded_at`: When the item completed.
+/// - `outcome`: The outcome, with the value serialized as JSON.
+/// - `correlation_id`: The batch run that recorded the outcome (optional).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub key: String,
+    pub recorded_at: DateTime<Utc>,
+    pub outcome: BatchOutcome<Value>,
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+}
+
+/// An append-only record of batch item outcomes.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    entries: BTreeMap<String, JournalEntry>,
+}
+
+impl Journal {
+    /// Opens the journal at `path`, a missing file yields an empty journal.
+    ///
+    /// Unreadable lines (e.g. a line cut short by a crash) are skipped with a warning.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let mut entries: BTreeMap<String, JournalEntry> = BTreeMap::new();
+
+        if path.exists() {
+            for (number, line) in BufReader::new(File::open(&path)?).lines().enumerate() {
+                let line: String = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<JournalEntry>(&line) {
+                    Ok(entry) => {
+                        entries.insert(entry.key.clone(), entry);
+                    }
+                    Err(error) => warn!("Skipping journal line {}: {}", number + 1, error),
+                }
+            }
+        }
+
+        Ok(Journal { path, entries })
+    }
+
+    /// Appends the outcome of an item to the journal file.
+    pub fn record<T: Serialize>(&mut self, key: &str, outcome: &BatchOutcome<T>) -> Result<(), Box<dyn Error>> {
+        let outcome: BatchOutcome<Value> = match outcome {
+            BatchOutcome::Success(value) => BatchOutcome::Success(serde_json::to_value(value)?),
+            BatchOutcome::Failed(message) => BatchOutcome::Failed(message.clone()),
+        };
+        let entry: JournalEntry = JournalEntry {
+            key: key.to_string(),
+            recorded_at: Utc::now(),
+            outcome,
+            correlation_id: current_correlation_id(),
+        };
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file: File = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.flush()?;
+
+        self.entries.insert(entry.key.clone(), entry);
+        Ok(())
+    }
+
+    /// Returns the recorded value of an item that succeeded, if any.
+    pub fn succeeded<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        match &self.entries.get(key)?.outcome {
+            BatchOutcome::Success(value) => serde_json::from_value(value.clone()).ok(),
+            BatchOutcome::Failed(_) => None,
+        }
+    }
+
+    /// Returns every recorded entry, keyed by item.
+    pub fn entries(&self) -> &BTreeMap<String, JournalEntry> {
+        &self.entries
+    }
+
+    /// Deletes the journal file and forgets every entry, e.g. once a batch fully succeeded.
+    pub fn clear(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        self.entries.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+//! ## Batch Executor
+//! The batch executor runs the same operation over many items (usually domain names)
+//! and collects a per-item outcome instead of aborting on the first failure.
+//!
+//! Items are processed in input order with a bounded number of operations in flight,
+//! which defaults to one to stay well within the NameCheap API rate limits.
+//!
+//! With a [`journal::Journal`], outcomes are persisted as they complete and an interrupted
+//! batch can be resumed with [`Batch::resume`].
+//!
+//! With [`Batch::with_cancellation`], no item is started once the token is cancelled: the report
+//! holds the items that finished and a [`Cancelled`] with the progress made.
+//!
+//! A batch run is one operation: all its items share a correlation ID, see
+//! [`correlation`](crate::utils::correlation).
+
+use serde::{ Serialize, Deserialize };
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::future::{ self, Future };
+use std::sync::Mutex;
+use futures_util::stream::{ self, StreamExt };
+use tokio_util::sync::CancellationToken;
+use tracing::{ info, info_span, warn, Instrument };
+
+// crate imports
+use crate::batch::journal::Journal;
+use crate::utils::cancellation::{ CancelHandle, Cancelled };
+use crate::utils::correlation::in_operation;
+use crate::utils::progress::{ ProgressHandle, ProgressSink, ProgressTracker };
+
+/// - **batch journal**
+pub mod journal;
+
+/// The outcome of a single batch item.
+///
+/// #### Variants
+/// - `Success`: The operation completed and returned a value.
+/// - `Failed`: The operation returned an error, the message is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status", content = "value")]
+pub enum BatchOutcome<T> {
+    Success(T),
+    Failed(String),
+}
+
+/// The result of a single batch item.
+///
+/// #### Fields
+/// - `key`: The item the operation ran for, e.g. the domain name.
+/// - `outcome`: The outcome of the operation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemResult<T> {
+    pub key: String,
+    pub outcome: BatchOutcome<T>,
+}
+
+/// The per-item results of a batch run, in input order.
+///
+/// #### Fields
+/// - `results`: The result of every finished item.
+/// - `cancelled`: Set when the batch was cancelled before every item was run (optional).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport<T> {
+    pub results: Vec<BatchItemResult<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled: Option<Cancelled>,
+}
+
+impl<T> BatchReport<T> {
+    /// Returns the items that completed successfully.
+    pub fn succeeded(&self) -> impl Iterator<Item = &BatchItemResult<T>> {
+        self.results.iter().filter(|r| matches!(r.outcome, BatchOutcome::Success(_)))
+    }
+
+    /// Returns the items that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &BatchItemResult<T>> {
+        self.results.iter().filter(|r| matches!(r.outcome, BatchOutcome::Failed(_)))
+    }
+
+    /// Returns `true` if every item ran and completed successfully.
+    pub fn is_success(&self) -> bool {
+        self.cancelled.is_none() && self.failed().next().is_none()
+    }
+}
+
+/// A builder for running an operation over many items.
+///
+/// #### Example
+/// ```rust
+/// let report = Batch::new()
+///     .with_concurrency(2)
+///     .run(domains, |domain| async move { client.domains_get_contacts(&domain).await })
+///     .await;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Batch {
+    /// The maximum number of operations in flight at once.
+    concurrency: usize,
+    /// Where progress is reported, after every finished item.
+    #[serde(skip)]
+    progress: ProgressHandle,
+    /// Stops starting items once cancelled.
+    #[serde(skip)]
+    cancellation: CancelHandle,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batch {
+    /// Creates a new `Batch` processing one item at a time.
+    pub fn new() -> Self {
+        Batch { concurrency: 1, progress: ProgressHandle::default(), cancellation: CancelHandle::default() }
+    }
+
+    /// Sets the maximum number of operations in flight at once (at least one).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Reports progress to `sink` after every finished item.
+    pub fn with_progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.progress = ProgressHandle::new(sink);
+        self
+    }
+
+    /// Reports progress to a shared handle after every finished item.
+    pub fn with_progress_handle(mut self, progress: ProgressHandle) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Stops starting items once `token` is cancelled.
+    ///
+    /// Items in flight run to completion, unless their operation watches the token too, e.g.
+    /// through a client set up with `with_cancellation`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = CancelHandle::new(token);
+        self
+    }
+
+    /// Runs `operation` for every item and collects the outcomes in input order.
+    ///
+    /// # Parameters
+    ///
+    /// - `items`: The items to process, usually domain names.
+    /// - `operation`: The async operation to run for each item.
+    ///
+    /// # Returns
+    ///
+    /// A `BatchReport` with one result per item, or per item started before a cancellation.
+    pub async fn run<T, F, Fut>(&self, items: Vec<String>, operation: F) -> BatchReport<T>
+        where F: Fn(String) -> Fut, Fut: Future<Output = Result<T, Box<dyn Error>>>
+    {
+        in_operation("batch.run", async {
+            let total: usize = items.len();
+            info!("Running batch over {} items", total);
+            let tracker: &ProgressTracker = &self.progress.tracker("batch.run", Some(total));
+
+            let cancellation: &CancelHandle = &self.cancellation;
+            let results: Vec<BatchItemResult<T>> = stream
+                ::iter(items)
+                .take_while(|_| future::ready(!cancellation.is_cancelled()))
+                .map(|key| {
+                    let future = operation(key.clone());
+                    let span = info_span!("batch_item", key = %key);
+                    async move {
+                        let outcome: BatchOutcome<T> = match future.await {
+                            Ok(value) => BatchOutcome::Success(value),
+                            Err(error) => {
+                                warn!("Batch item {} failed: {}", key, error);
+                                BatchOutcome::Failed(error.to_string())
+                            }
+                        };
+                        tracker.advance(&key);
+                        BatchItemResult { key, outcome }
+                    }.instrument(span)
+                })
+                .buffered(self.concurrency)
+                .collect().await;
+
+            let cancelled: Option<Cancelled> = (results.len() < total).then(|| {
+                warn!("Batch cancelled after {} of {} items", results.len(), total);
+                Cancelled::new("batch.run", results.len(), Some(total))
+            });
+            BatchReport { results, cancelled }
+        }).await
+    }
+
+    /// Runs `operation` for every item not yet successful in `journal`, recording outcomes.
+    ///
+    /// Items that already succeeded are not run again, their recorded value is returned.
+    /// Failed and unseen items are run and each outcome is appended to the journal as soon
+    /// as it completes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut journal = Journal::open("renewals.jsonl")?;
+    /// let report = Batch::new()
+    ///     .resume(&mut journal, domains, |domain| async move { client.domains_renew(&domain, 1).await })
+    ///     .await;
+    /// ```
+    pub async fn resume<T, F, Fut>(&self, journal: &mut Journal, items: Vec<String>, operation: F) -> BatchReport<T>
+        where
+            T: Serialize + DeserializeOwned,
+            F: Fn(String) -> Fut,
+            Fut: Future<Output = Result<T, Box<dyn Error>>>
+    {
+        let mut recorded: Vec<Option<T>> = items
+            .iter()
+            .map(|key| journal.succeeded::<T>(key))
+            .collect();
+        let pending: Vec<String> = items
+            .iter()
+            .zip(&recorded)
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        let items_total: usize = items.len();
+        info!("Resuming batch: {} of {} items already done", items_total - pending.len(), items_total);
+
+        let journal: Mutex<&mut Journal> = Mutex::new(journal);
+        let report: BatchReport<T> = self.run(pending, |key| {
+            let future = operation(key.clone());
+            let journal: &Mutex<&mut Journal> = &journal;
+            async move {
+                let result: Result<T, Box<dyn Error>> = future.await;
+                let outcome: BatchOutcome<&T> = match &result {
+                    Ok(value) => BatchOutcome::Success(value),
+                    Err(error) => BatchOutcome::Failed(error.to_string()),
+                };
+                if let Err(error) = journal.lock().map_err(|e| e.to_string())?.record(&key, &outcome) {
+                    warn!("Failed to journal batch item {}: {}", key, error);
+                }
+                result
+            }
+        }).await;
+
+        // Merge the new outcomes back in input order
+        let mut fresh = report.results.into_iter();
+        let results: Vec<BatchItemResult<T>> = items
+            .into_iter()
+            .zip(recorded.iter_mut())
+            .filter_map(|(key, value)| {
+                match value.take() {
+                    Some(value) => Some(BatchItemResult { key, outcome: BatchOutcome::Success(value) }),
+                    None => fresh.next(),
+                }
+            })
+            .collect();
+
+        // The progress covers the items done by earlier runs as well
+        let cancelled: Option<Cancelled> = report.cancelled.map(|_| Cancelled::new("batch.resume", results.len(), Some(items_total)));
+        BatchReport { results, cancelled }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_batch_collects_outcomes_in_order() {
+        let items: Vec<String> = vec!["a.com".to_string(), "bad.com".to_string(), "c.com".to_string()];
+
+        let report: BatchReport<usize> = Batch::new()
+            .with_concurrency(2)
+            .run(items, |key| async move {
+                if key.starts_with("bad") {
+                    Err("rejected".into())
+                } else {
+                    Ok(key.len())
+                }
+            }).await;
+
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.results[0].outcome, BatchOutcome::Success(5));
+        assert_eq!(report.results[1].outcome, BatchOutcome::Failed("rejected".to_string()));
+        assert_eq!(report.succeeded().count(), 2);
+        assert!(!report.is_success());
+    }
+
+    #[tokio::test]
+    async fn test_batch_reports_progress() {
+        use crate::utils::progress::Progress;
+
+        let updates: Arc<Mutex<Vec<Progress>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink: Arc<Mutex<Vec<Progress>>> = updates.clone();
+        let items: Vec<String> = vec!["a.com".to_string(), "b.com".to_string()];
+
+        Batch::new()
+            .with_progress(move |progress: &Progress| sink.lock().unwrap().push(progress.clone()))
+            .run(items, |key| async move { Ok::<usize, Box<dyn Error>>(key.len()) }).await;
+
+        let updates: Vec<Progress> = updates.lock().unwrap().clone();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[1].processed, 2);
+        assert_eq!(updates[1].total, Some(2));
+        assert_eq!(updates[1].current.as_deref(), Some("b.com"));
+        assert!(updates[1].is_done());
+    }
+
+    #[tokio::test]
+    async fn test_batch_resume_skips_succeeded_items() {
+        let path = std::env::temp_dir().join(format!("namecheap-journal-{}.jsonl", std::process::id()));
+        let items: Vec<String> = vec!["a.com".to_string(), "bad.com".to_string(), "c.com".to_string()];
+
+        let mut journal: Journal = Journal::open(&path).unwrap();
+        let first: BatchReport<usize> = Batch::new().resume(&mut journal, items.clone(), |key| async move {
+            if key.starts_with("bad") { Err("rejected".into()) } else { Ok(key.len()) }
+        }).await;
+        assert_eq!(first.failed().count(), 1);
+
+        // Reopen, as a restarted process would
+        let mut journal: Journal = Journal::open(&path).unwrap();
+        let calls: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        let second: BatchReport<usize> = Batch::new().resume(&mut journal, items, |key| {
+            calls.lock().unwrap().push(key.clone());
+            async move { Ok(key.len() * 10) }
+        }).await;
+
+        assert_eq!(calls.into_inner().unwrap(), vec!["bad.com".to_string()]);
+        assert!(second.is_success());
+        assert_eq!(second.results[0].outcome, BatchOutcome::Success(5));
+        assert_eq!(second.results[1].key, "bad.com");
+        assert_eq!(second.results[1].outcome, BatchOutcome::Success(70));
+        assert_eq!(journal.succeeded::<usize>("bad.com"), Some(70));
+
+        journal.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_batch_cancellation() {
+        let token: CancellationToken = CancellationToken::new();
+        let items: Vec<String> = ["a.com", "b.com", "c.com", "d.com"].iter().map(|item| item.to_string()).collect();
+
+        let report: BatchReport<usize> = Batch::new()
+            .with_cancellation(token.clone())
+            .run(items, |key| {
+                let token: CancellationToken = token.clone();
+                async move {
+                    if key == "b.com" {
+                        token.cancel();
+                    }
+                    Ok(key.len())
+                }
+            }).await;
+
+        // The item cancelling the batch finishes, no later one is started
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.cancelled, Some(Cancelled::new("batch.run", 2, Some(4))));
+        assert!(!report.is_success());
+        assert!(serde_json::to_string(&report).unwrap().contains("\"cancelled\":{\"operation\":\"batch.run\""));
+    }
+}
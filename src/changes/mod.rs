@@ -0,0 +1,268 @@
+//! ## Change Summaries
+//! What a mutation changed, computed from the state before and after it rather than from the
+//! `IsSuccess` flag of the response, so callers can log and display meaningful descriptions.
+//!
+//! A [`ChangeSummary`] is returned by:
+//! - `domains_dns_replace_hosts_with_summary` and `domains_dns_set_hosts_with_summary`: the
+//!   records added, updated and removed;
+//! - `domains_set_contacts_with_summary`: the fields changed on each contact.
+//!
+//! With [`PrivacyMode::Minimized`](crate::utils::privacy::PrivacyMode) the contact field values
+//! of a summary are masked, see [`ChangeSummary::minimized`].
+//!
+//! ```rust
+//! let summary = client.domains_dns_replace_hosts_with_summary("example", "com", &hosts).await?;
+//! println!("{}", summary);
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value };
+use std::fmt;
+
+// crate imports
+use crate::{ Contact, ContactSet };
+use crate::utils::privacy::{ is_pii_param, mask_email, mask_value };
+use crate::zone::RecordKey;
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+
+/// The contact fields, in the order of [`Contact::to_params`].
+const CONTACT_FIELDS: [&str; 15] = [
+    "FirstName",
+    "LastName",
+    "Address1",
+    "Address2",
+    "City",
+    "StateProvince",
+    "StateProvinceChoice",
+    "PostalCode",
+    "Country",
+    "Phone",
+    "PhoneExt",
+    "Fax",
+    "EmailAddress",
+    "OrganizationName",
+    "JobTitle",
+];
+
+/// A contact field whose value changed.
+///
+/// #### Fields
+/// - `field`: The field name as sent to the API, e.g. `EmailAddress`.
+/// - `before`: The previous value, empty if it was not set.
+/// - `after`: The new value, empty if it is cleared.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// The changed fields of one contact.
+///
+/// #### Fields
+/// - `role`: The contact role, e.g. `Admin`.
+/// - `fields`: The changed fields, in API parameter order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ContactChange {
+    pub role: String,
+    pub fields: Vec<FieldChange>,
+}
+
+/// ### Change Summary
+///
+/// #### Fields
+/// - `domain`: The domain the mutation applies to.
+/// - `applied`: Whether the change was sent and accepted, `false` on dry runs and when there
+///   was nothing to change.
+/// - `records_added`: The host records created.
+/// - `records_updated`: The host records whose TTL or MX preference changed.
+/// - `records_removed`: The host records deleted.
+/// - `contacts`: The contacts with at least one changed field.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ChangeSummary {
+    pub domain: String,
+    pub applied: bool,
+    pub records_added: Vec<RecordKey>,
+    pub records_updated: Vec<RecordKey>,
+    pub records_removed: Vec<RecordKey>,
+    pub contacts: Vec<ContactChange>,
+}
+
+impl ChangeSummary {
+    /// Summarizes the record changes of a zone plan, not applied yet.
+    pub fn from_plan(plan: &ZonePlan) -> Self {
+        let keys = |kind: ChangeKind| -> Vec<RecordKey> {
+            plan.changes_of(kind).map(|change| change.key.clone()).collect()
+        };
+
+        ChangeSummary {
+            domain: plan.domain.clone(),
+            records_added: keys(ChangeKind::Create),
+            records_updated: keys(ChangeKind::Update),
+            records_removed: keys(ChangeKind::Delete),
+            ..ChangeSummary::default()
+        }
+    }
+
+    /// Summarizes the field changes from the `before` contacts of a domain to `after`, not
+    /// applied yet.
+    pub fn from_contacts(domain: &str, before: &ContactSet, after: &ContactSet) -> Self {
+        let roles: [(&str, &Contact, &Contact); 4] = [
+            ("Registrant", &before.registrant, &after.registrant),
+            ("Tech", &before.tech, &after.tech),
+            ("Admin", &before.admin, &after.admin),
+            ("AuxBilling", &before.aux_billing, &after.aux_billing),
+        ];
+
+        let contacts: Vec<ContactChange> = roles
+            .into_iter()
+            .map(|(role, before, after)| ContactChange { role: role.to_string(), fields: contact_changes(before, after) })
+            .filter(|change| !change.fields.is_empty())
+            .collect();
+
+        ChangeSummary { domain: domain.to_string(), contacts, ..ChangeSummary::default() }
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.records_added.is_empty() &&
+            self.records_updated.is_empty() &&
+            self.records_removed.is_empty() &&
+            self.contacts.is_empty()
+    }
+
+    /// Returns a copy with contact personal data masked, see [`privacy`](crate::utils::privacy).
+    pub fn minimized(&self) -> ChangeSummary {
+        let mask = |field: &str, value: &str| -> String {
+            match field {
+                "EmailAddress" => mask_email(value),
+                field if is_pii_param(field) => mask_value(value),
+                _ => value.to_string(),
+            }
+        };
+
+        let contacts: Vec<ContactChange> = self.contacts
+            .iter()
+            .map(|contact| ContactChange {
+                role: contact.role.clone(),
+                fields: contact.fields
+                    .iter()
+                    .map(|change| FieldChange {
+                        field: change.field.clone(),
+                        before: mask(&change.field, &change.before),
+                        after: mask(&change.field, &change.after),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        ChangeSummary { contacts, ..self.clone() }
+    }
+}
+
+/// Returns the fields that differ between two contacts, in API parameter order.
+fn contact_changes(before: &Contact, after: &Contact) -> Vec<FieldChange> {
+    let before: Map<String, Value> = before.to_params("");
+    let after: Map<String, Value> = after.to_params("");
+    let value = |params: &Map<String, Value>, field: &str| -> String {
+        params.get(field).and_then(Value::as_str).unwrap_or("").to_string()
+    };
+
+    // Empty fields are left out of the parameters, they compare as empty strings
+    CONTACT_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let (before, after) = (value(&before, field), value(&after, field));
+            (before != after).then(|| FieldChange { field: field.to_string(), before, after })
+        })
+        .collect()
+}
+
+impl fmt::Display for ChangeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status: &str = if self.applied { "changed" } else { "not applied" };
+        writeln!(f, "{} ({}):", self.domain, status)?;
+        for (symbol, keys) in [("+", &self.records_added), ("~", &self.records_updated), ("-", &self.records_removed)] {
+            for key in keys {
+                writeln!(f, "  {} {}", symbol, key)?;
+            }
+        }
+        for contact in &self.contacts {
+            for change in &contact.fields {
+                writeln!(f, "  ~ {} {}: {:?} -> {:?}", contact.role, change.field, change.before, change.after)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Host;
+    use crate::domains_dns::set_hosts::HostRequest;
+
+    fn contact(email: &str, city: &str) -> Contact {
+        Contact {
+            type_: String::new(),
+            address_1: "8939 S. Cross Blvd".to_string(),
+            address_2: String::new(),
+            city: city.to_string(),
+            country: "US".to_string(),
+            email_address: email.to_string(),
+            fax: String::new(),
+            first_name: "John".to_string(),
+            job_title: String::new(),
+            last_name: "Smith".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.6613102107".to_string(),
+            phone_ext: String::new(),
+            postal_code: "85044".to_string(),
+            state_province: "AZ".to_string(),
+            state_province_choice: String::new(),
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_from_plan() {
+        let current: Vec<Host> = vec![
+            Host { name: "@".to_string(), type_: "A".to_string(), address: "203.0.113.10".to_string(), ..Host::new() },
+            Host { name: "old".to_string(), type_: "A".to_string(), address: "203.0.113.10".to_string(), ..Host::new() }
+        ];
+        let desired: Vec<HostRequest> = vec![
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, Some("300".to_string()), None, None),
+            HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None)
+        ];
+
+        let summary: ChangeSummary = ChangeSummary::from_plan(&ZonePlan::new("example.com", &current, &desired));
+        assert_eq!(summary.records_added, [RecordKey::new("www", "CNAME", "example.com")]);
+        assert_eq!(summary.records_updated, [RecordKey::new("@", "A", "203.0.113.10")]);
+        assert_eq!(summary.records_removed, [RecordKey::new("old", "A", "203.0.113.10")]);
+        assert!(!summary.applied && !summary.is_empty());
+    }
+
+    #[test]
+    fn test_from_contacts() {
+        let before: ContactSet = ContactSet::uniform(contact("admin@example.com", "Phoenix"));
+        let mut after: ContactSet = before.clone();
+        after.admin = contact("ops@example.com", "Tempe");
+
+        let summary: ChangeSummary = ChangeSummary::from_contacts("example.com", &before, &after);
+        assert_eq!(summary.contacts.len(), 1);
+        assert_eq!(summary.contacts[0].role, "Admin");
+        let fields: Vec<&str> = summary.contacts[0].fields.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, ["City", "EmailAddress"]);
+        assert!(ChangeSummary::from_contacts("example.com", &before, &before).is_empty());
+
+        let minimized: ChangeSummary = summary.minimized();
+        assert!(minimized.contacts[0].fields[1].after.starts_with("o***@example.com#"));
+        assert!(!minimized.to_string().contains("Tempe"));
+    }
+}
@@ -0,0 +1,96 @@
+//! ### `namecheap auth`
+//!
+//! `auth login` asks for the credentials, checks them against the API and stores them in the
+//! encrypted credential cache; later commands unlock it with the passphrase (asked for on the
+//! terminal, or read from `NAMECHEAP_CACHE_PASSPHRASE` in unattended runs). `auth logout`
+//! deletes the cache.
+
+use clap::Subcommand;
+use std::env;
+use std::error::Error;
+
+// crate imports
+use crate::cli::config::CliConfig;
+use crate::cli::confirm::{ prompt, prompt_secret };
+use crate::cli::exit::CliError;
+use crate::utils::credential_cache::{ CredentialCache, StoredCredentials };
+
+/// The `auth` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum AuthCommand {
+    /// Stores the credentials in the encrypted credential cache.
+    Login {
+        /// The account user name, asked for when not set in the environment or config.
+        #[arg(long)]
+        user_name: Option<String>,
+        /// The whitelisted client IP, asked for when not set in the environment or config.
+        #[arg(long)]
+        client_ip: Option<String>,
+        /// Use the production API instead of the sandbox.
+        #[arg(long)]
+        production: bool,
+        /// Store the credentials without checking them against the API.
+        #[arg(long)]
+        no_verify: bool,
+    },
+    /// Deletes the encrypted credential cache.
+    Logout,
+}
+
+/// Runs an `auth` subcommand.
+pub async fn run(config: &CliConfig, command: AuthCommand) -> Result<(), Box<dyn Error>> {
+    let cache: CredentialCache = config.credential_cache()?;
+    match command {
+        AuthCommand::Login { user_name, client_ip, production, no_verify } => {
+            let value = |arg: Option<String>, name: &str, configured: &Option<String>, question: &str| {
+                match arg.or_else(|| env::var(name).ok()).or_else(|| configured.clone()) {
+                    Some(value) => Ok(value),
+                    None => prompt(question),
+                }
+            };
+            let credentials: StoredCredentials = StoredCredentials {
+                user_name: value(user_name, "NAMECHEAP_USER_NAME", &config.user_name, "User name: ")?,
+                client_ip: value(client_ip, "NAMECHEAP_CLIENT_IP", &config.client_ip, "Client IP: ")?,
+                api_key: prompt_secret("API key: ")?,
+                production: production || config.production.unwrap_or(false),
+            };
+            if credentials.user_name.is_empty() || credentials.client_ip.is_empty() || credentials.api_key.is_empty() {
+                return Err(CliError::validation("The user name, client IP and API key are required").into());
+            }
+
+            if !no_verify {
+                let report = credentials.client().validate().await.into_result()?;
+                eprintln!("Credentials accepted by {}", report.endpoint);
+            }
+            login(&cache, &credentials)
+        }
+        AuthCommand::Logout => {
+            if cache.remove()? {
+                println!("Removed {}", cache.path.display());
+            } else {
+                println!("No cached credentials at {}", cache.path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn login(cache: &CredentialCache, credentials: &StoredCredentials) -> Result<(), Box<dyn Error>> {
+    let passphrase: String = match env::var("NAMECHEAP_CACHE_PASSPHRASE") {
+        Ok(passphrase) => passphrase,
+        Err(_) => {
+            let passphrase: String = prompt_secret("New passphrase: ")?;
+            if prompt_secret("Repeat passphrase: ")? != passphrase {
+                return Err(CliError::validation("The passphrases do not match").into());
+            }
+            passphrase
+        }
+    };
+    if passphrase.is_empty() {
+        return Err(CliError::validation("The passphrase must not be empty").into());
+    }
+
+    cache.save(credentials, &passphrase)?;
+    println!("Stored the credentials of {} in {}", credentials.user_name, cache.path.display());
+    Ok(())
+}
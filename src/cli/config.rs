@@ -0,0 +1,241 @@
+//! ### CLI Configuration
+//!
+//! The CLI reads an optional TOML file, `--config <file>`, `$NAMECHEAP_CONFIG` or
+//! `~/.config/namecheap/config.toml`:
+//!
+//! ```toml
+//! user_name = "example"
+//! client_ip = "203.0.113.10"
+//! production = true
+//! credential_helper = "op read op://vault/namecheap/key"
+//! ```
+//!
+//! The API key itself is not accepted in the file, it comes from `NAMECHEAP_API_KEY`, from
+//! the credential helper, which only runs when a command needs the client, or from the
+//! encrypted credential cache written by `namecheap auth login` (`credential_cache`, by default
+//! `~/.config/namecheap/credentials.enc`). The `NAMECHEAP_*` environment variables take
+//! precedence over the file, the file over the cache.
+
+use dotenv::dotenv;
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+// crate imports
+use crate::NameCheapClient;
+use crate::cli::confirm::prompt_secret;
+use crate::cli::exit::{ CliError, ExitClass };
+use crate::utils::credential_cache::{ CredentialCache, StoredCredentials };
+use crate::utils::credential_helper::CredentialHelper;
+
+/// ### CLI Config
+///
+/// #### Fields
+/// - `user_name`: The account user name (optional).
+/// - `client_ip`: The whitelisted client IP (optional).
+/// - `production`: Whether to use the production API (optional).
+/// - `credential_helper`: A command printing the API key (optional).
+/// - `credential_cache`: The encrypted credential cache file (optional).
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct CliConfig {
+    pub user_name: Option<String>,
+    pub client_ip: Option<String>,
+    pub production: Option<bool>,
+    pub credential_helper: Option<String>,
+    pub credential_cache: Option<PathBuf>,
+}
+
+/// Returns the `namecheap` directory in the user config directory.
+fn config_dir() -> Option<PathBuf> {
+    let config_home: PathBuf = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("namecheap"))
+}
+
+/// Returns the default config file, `$NAMECHEAP_CONFIG` or `namecheap/config.toml` in the
+/// user config directory.
+pub fn default_config_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var("NAMECHEAP_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    Some(config_dir()?.join("config.toml"))
+}
+
+impl CliConfig {
+    /// Parses a config file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|err| err.to_string())
+    }
+
+    /// Loads the config from `path`, or from the default path if it exists.
+    ///
+    /// An explicit path that cannot be read is an error, a missing default file is not.
+    pub fn load(path: Option<&Path>) -> Result<Self, CliError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => return Ok(CliConfig::default()),
+            },
+        };
+        let text: String = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) if !required && !path.exists() => return Ok(CliConfig::default()),
+            Err(err) => return Err(CliError::validation(format!("Cannot read {}: {}", path.display(), err))),
+        };
+        CliConfig::parse(&text).map_err(|err| CliError::validation(format!("Invalid config {}: {}", path.display(), err)))
+    }
+
+    /// Returns the encrypted credential cache.
+    pub fn credential_cache(&self) -> Result<CredentialCache, CliError> {
+        let path: PathBuf = self.credential_cache
+            .clone()
+            .or_else(|| Some(config_dir()?.join("credentials.enc")))
+            .ok_or_else(|| CliError::validation("No config directory, set credential_cache in the config"))?;
+        Ok(CredentialCache::new(path))
+    }
+
+    /// Decrypts the credential cache, if there is one.
+    ///
+    /// The passphrase is read from `NAMECHEAP_CACHE_PASSPHRASE` or asked for on the terminal.
+    fn cached_credentials(&self, var: &impl Fn(&str) -> Option<String>) -> Result<Option<StoredCredentials>, Box<dyn Error>> {
+        let Ok(cache) = self.credential_cache() else { return Ok(None) };
+        if !cache.exists() {
+            return Ok(None);
+        }
+        let passphrase: String = match var("NAMECHEAP_CACHE_PASSPHRASE") {
+            Some(passphrase) => passphrase,
+            None => prompt_secret("Passphrase for the credential cache: ")
+                .map_err(|_| CliError::new(ExitClass::Auth, "Set NAMECHEAP_CACHE_PASSPHRASE to unlock the credential cache"))?,
+        };
+        let credentials: StoredCredentials = cache
+            .load(&passphrase)
+            .map_err(|err| CliError::new(ExitClass::Auth, err.to_string()))?;
+        Ok(Some(credentials))
+    }
+
+    /// Creates the client, reading overrides from the environment.
+    pub fn client(&self) -> Result<NameCheapClient, Box<dyn Error>> {
+        dotenv().ok();
+        self.client_with(|name| env::var(name).ok())
+    }
+
+    /// Creates the client, reading overrides with `var`.
+    ///
+    /// Missing credentials, failing credential helpers and a wrong cache passphrase are `auth`
+    /// failures.
+    pub fn client_with(&self, var: impl Fn(&str) -> Option<String>) -> Result<NameCheapClient, Box<dyn Error>> {
+        let missing = |name: &str| CliError::new(ExitClass::Auth, format!("Missing credentials: set {} or run `namecheap auth login`", name));
+
+        let env_key: Option<String> = var("NAMECHEAP_API_KEY");
+        let helper: Option<String> = var("NAMECHEAP_CREDENTIAL_HELPER").or_else(|| self.credential_helper.clone());
+        let cached: Option<StoredCredentials> = if env_key.is_none() && helper.is_none() {
+            self.cached_credentials(&var)?
+        } else {
+            None
+        };
+
+        let user_name: String = var("NAMECHEAP_USER_NAME")
+            .or_else(|| self.user_name.clone())
+            .or_else(|| cached.as_ref().map(|cached| cached.user_name.clone()))
+            .ok_or_else(|| missing("NAMECHEAP_USER_NAME"))?;
+        let client_ip: String = var("NAMECHEAP_CLIENT_IP")
+            .or_else(|| self.client_ip.clone())
+            .or_else(|| cached.as_ref().map(|cached| cached.client_ip.clone()))
+            .ok_or_else(|| missing("NAMECHEAP_CLIENT_IP"))?;
+        let production: bool = var("NAMECHEAP_PRODUCTION")
+            .and_then(|production| production.parse().ok())
+            .or(self.production)
+            .or(cached.as_ref().map(|cached| cached.production))
+            .unwrap_or(false);
+
+        let api_key: String = match (env_key, helper, cached) {
+            (Some(api_key), _, _) => api_key,
+            (None, Some(command), _) => CredentialHelper::new(&command)
+                .fetch()
+                .map_err(|err| CliError::new(ExitClass::Auth, err.to_string()))?,
+            (None, None, Some(cached)) => cached.api_key,
+            (None, None, None) => return Err(missing("NAMECHEAP_API_KEY or a credential_helper").into()),
+        };
+
+        Ok(NameCheapClient::new(user_name.clone(), api_key, client_ip, user_name, production))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: CliConfig = CliConfig::parse(
+            "user_name = \"example\"\nclient_ip = \"203.0.113.10\"\ncredential_helper = \"op read op://vault/namecheap/key\"\n"
+        ).unwrap();
+        assert_eq!(config.user_name.as_deref(), Some("example"));
+        assert_eq!(config.credential_helper.as_deref(), Some("op read op://vault/namecheap/key"));
+
+        // The key itself is never read from the file
+        assert!(CliConfig::parse("api_key = \"plaintext\"\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_client_with() {
+        let config: CliConfig = CliConfig {
+            user_name: Some("example".to_string()),
+            client_ip: Some("203.0.113.10".to_string()),
+            production: None,
+            credential_helper: Some("echo from-helper".to_string()),
+            credential_cache: Some(std::env::temp_dir().join("namecheap-no-such-cache.enc")),
+        };
+
+        let client: NameCheapClient = config.client_with(|_| None).unwrap();
+        assert_eq!(client.api_key, "from-helper");
+        assert!(!client.production);
+
+        let client: NameCheapClient = config.client_with(|name| match name {
+            "NAMECHEAP_API_KEY" => Some("from-env".to_string()),
+            "NAMECHEAP_PRODUCTION" => Some("true".to_string()),
+            _ => None,
+        }).unwrap();
+        assert_eq!(client.api_key, "from-env");
+        assert!(client.production);
+
+        let failing: CliConfig = CliConfig { credential_helper: Some("false".to_string()), ..config.clone() };
+        let error: Box<dyn Error> = failing.client_with(|_| None).unwrap_err();
+        assert_eq!(ExitClass::of(error.as_ref()), ExitClass::Auth);
+
+        let empty: CliConfig = CliConfig { credential_cache: config.credential_cache.clone(), ..Default::default() };
+        let error: Box<dyn Error> = empty.client_with(|_| None).unwrap_err();
+        assert_eq!(ExitClass::of(error.as_ref()), ExitClass::Auth);
+    }
+
+    #[test]
+    fn test_client_from_credential_cache() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-cli-cache-{}.enc", std::process::id()));
+        CredentialCache::new(&path).with_iterations(1_000).save(&StoredCredentials {
+            user_name: "cached".to_string(),
+            api_key: "from-cache".to_string(),
+            client_ip: "203.0.113.20".to_string(),
+            production: true,
+        }, "passphrase").unwrap();
+        let config: CliConfig = CliConfig { credential_cache: Some(path.clone()), ..Default::default() };
+
+        let client: NameCheapClient = config.client_with(|name| {
+            (name == "NAMECHEAP_CACHE_PASSPHRASE").then(|| "passphrase".to_string())
+        }).unwrap();
+        assert_eq!((client.user_name.as_str(), client.api_key.as_str()), ("cached", "from-cache"));
+        assert!(client.production);
+
+        let error: Box<dyn Error> = config.client_with(|name| {
+            (name == "NAMECHEAP_CACHE_PASSPHRASE").then(|| "wrong".to_string())
+        }).unwrap_err();
+        assert_eq!(ExitClass::of(error.as_ref()), ExitClass::Auth);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+//! ### Confirmation Prompts
+//!
+//! Destructive commands (replacing a zone, disabling WhoisGuard, unlocking a domain) print
+//! their `Impact` and ask for confirmation on the terminal before applying anything. The
+//! global `--yes` flag skips the prompt; without a terminal and without `--yes` the command
+//! fails instead of waiting for input that never comes.
+//!
+//! [`prompt`] and [`prompt_secret`] read other answers, e.g. for `auth login`.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{ self, BufRead, IsTerminal, Write };
+
+// crate imports
+use crate::cli::exit::{ CliError, ExitClass };
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+
+/// ### Impact
+///
+/// What a destructive command is about to do.
+///
+/// #### Fields
+/// - `action`: The operation, e.g. `Replace the zone of example.com`.
+/// - `deleted_records`: The number of records that will be deleted, for zone operations.
+/// - `details`: Further lines shown before the prompt.
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Impact {
+    pub action: String,
+    pub deleted_records: Option<usize>,
+    pub details: Vec<String>,
+}
+
+impl Impact {
+    /// Creates an impact for an action.
+    pub fn new(action: &str) -> Self {
+        Impact { action: action.to_string(), ..Default::default() }
+    }
+
+    /// Creates the impact of applying a zone plan.
+    pub fn from_plan(action: &str, plan: &ZonePlan) -> Self {
+        let count = |kind: ChangeKind| plan.changes_of(kind).count();
+        Impact {
+            action: action.to_string(),
+            deleted_records: Some(count(ChangeKind::Delete)),
+            details: vec![format!(
+                "{} created, {} updated, {} kept",
+                count(ChangeKind::Create),
+                count(ChangeKind::Update),
+                count(ChangeKind::Keep)
+            )],
+        }
+    }
+
+    /// Adds a line to show before the prompt.
+    pub fn detail(mut self, detail: &str) -> Self {
+        self.details.push(detail.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.action)?;
+        if let Some(deleted) = self.deleted_records {
+            writeln!(f, "  {} records will be deleted", deleted)?;
+        }
+        for detail in &self.details {
+            writeln!(f, "  {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Asks for confirmation of `impact` on the terminal, unless `yes` is set.
+///
+/// Declining, or running without a terminal and without `--yes`, is an error.
+pub fn confirm(impact: &Impact, yes: bool) -> Result<(), Box<dyn Error>> {
+    let interactive: bool = io::stdin().is_terminal();
+    confirm_with(impact, yes, interactive, &mut io::stdin().lock(), &mut io::stderr())?;
+    Ok(())
+}
+
+/// Asks for confirmation of `impact`, reading the answer from `input`.
+pub fn confirm_with(
+    impact: &Impact,
+    yes: bool,
+    interactive: bool,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write
+) -> Result<(), CliError> {
+    if yes {
+        return Ok(());
+    }
+    if !interactive {
+        return Err(CliError::validation(format!(
+            "{} needs confirmation, pass --yes to apply without a prompt",
+            impact.action
+        )));
+    }
+
+    let _ = write!(output, "{}Continue? [y/N] ", impact);
+    let _ = output.flush();
+    let mut answer: String = String::new();
+    input.read_line(&mut answer).map_err(|err| CliError::new(ExitClass::Failure, err.to_string()))?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(CliError::new(ExitClass::Failure, "Aborted")),
+    }
+}
+
+/// Asks for a line of input on the terminal, returns it trimmed.
+pub fn prompt(question: &str) -> Result<String, Box<dyn Error>> {
+    if !io::stdin().is_terminal() {
+        return Err(CliError::validation(format!("{} needs a terminal", question.trim_end_matches([':', ' ']))).into());
+    }
+    eprint!("{}", question);
+    io::stderr().flush()?;
+    let mut answer: String = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Asks for a secret on the terminal without echoing it.
+pub fn prompt_secret(question: &str) -> Result<String, Box<dyn Error>> {
+    let echo_off: bool = io::stdin().is_terminal() && set_echo(false);
+    let answer = prompt(question);
+    if echo_off {
+        set_echo(true);
+        eprintln!();
+    }
+    answer
+}
+
+/// Turns terminal echo on or off, returns `false` if that is not supported.
+#[cfg(unix)]
+fn set_echo(on: bool) -> bool {
+    std::process::Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn set_echo(_on: bool) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(impact: &Impact, yes: bool, interactive: bool, input: &str) -> (Result<(), CliError>, String) {
+        let mut output: Vec<u8> = Vec::new();
+        let result = confirm_with(impact, yes, interactive, &mut input.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_confirm_with() {
+        let impact: Impact = Impact {
+            action: "Replace the zone of example.com".to_string(),
+            deleted_records: Some(3),
+            details: vec![],
+        };
+
+        let (result, prompt) = answer(&impact, false, true, "y\n");
+        assert!(result.is_ok());
+        assert!(prompt.contains("3 records will be deleted"));
+        assert!(prompt.ends_with("Continue? [y/N] "));
+
+        assert_eq!(answer(&impact, false, true, "\n").0.unwrap_err().class, ExitClass::Failure);
+        assert_eq!(answer(&impact, false, false, "").0.unwrap_err().class, ExitClass::Validation);
+
+        let (result, prompt) = answer(&impact, true, false, "");
+        assert!(result.is_ok());
+        assert!(prompt.is_empty());
+    }
+}
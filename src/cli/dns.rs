@@ -0,0 +1,264 @@
+//! ### `namecheap dns`
+//!
+//! Zone commands of the CLI.
+//!
+//! `dns backup` writes each zone as a `ZoneSnapshot` JSON file, `dns restore` shows the plan to
+//! go back to a snapshot and only applies it once confirmed (or with `--yes`). The apply is a
+//! compare-and-set against the previewed zone, a zone changed in between fails with a
+//! `ZoneConflict`.
+//!
+//! `dns adopt` writes the live zone as a canonical desired-state TOML file (see
+//! [`zone::sync`](crate::zone::sync)), which `dns diff` accepts as well as JSON records.
+
+use clap::Subcommand;
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+// crate imports
+use crate::{ NameCheapClient, Domain, Host };
+use crate::cli::confirm::{ confirm, Impact };
+use crate::cli::exit::CliError;
+use crate::domains_dns::set_hosts::{ host_requests_from_value, HostRequest };
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+use crate::zone::split_domain;
+use crate::utils::progress::{ Progress, ProgressHandle };
+use crate::zone::snapshot::ZoneSnapshot;
+use crate::zone::sync::{ adopt_all, AdoptOutcome, DesiredState, Fragments };
+use crate::zone::template::validate_record;
+
+/// The `dns` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum DnsCommand {
+    /// Compares the live records of a domain with desired records, exits with 5 on drift.
+    Diff {
+        /// The domain, e.g. `example.com`.
+        domain: String,
+        /// A desired-state TOML file written by `dns adopt`, or a JSON file with the desired
+        /// records in any format accepted by `setHosts`.
+        #[arg(long, short)]
+        file: PathBuf,
+    },
+    /// Writes the records of domains to one JSON snapshot file per domain.
+    Backup {
+        /// The domains to back up, e.g. `example.com`.
+        domains: Vec<String>,
+        /// Back up every domain of the account.
+        #[arg(long, conflicts_with = "domains")]
+        all: bool,
+        /// The directory the snapshots are written to.
+        #[arg(long, short, default_value = "backups")]
+        output: PathBuf,
+    },
+    /// Writes the live records of domains to one desired-state TOML file per domain.
+    Adopt {
+        /// The domains to adopt, e.g. `example.com`.
+        domains: Vec<String>,
+        /// Adopt every domain of the account.
+        #[arg(long, conflicts_with = "domains")]
+        all: bool,
+        /// The directory the files are written to.
+        #[arg(long, short, default_value = "zones")]
+        output: PathBuf,
+    },
+    /// Restores a snapshot written by `dns backup`, after a preview and confirmation.
+    Restore {
+        /// The snapshot file, e.g. `backups/example.com.json`.
+        file: PathBuf,
+        /// Only show the preview.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Runs a `dns` subcommand.
+///
+/// `yes` skips the confirmation of destructive commands.
+pub async fn run(client: &NameCheapClient, command: DnsCommand, yes: bool) -> Result<(), Box<dyn Error>> {
+    match command {
+        DnsCommand::Diff { domain, file } => diff(client, &domain, &file).await,
+        DnsCommand::Backup { domains, all, output } => backup(client, domains, all, &output).await,
+        DnsCommand::Adopt { domains, all, output } => adopt(client, domains, all, &output).await,
+        DnsCommand::Restore { file, dry_run } => restore(client, &file, dry_run, yes).await,
+    }
+}
+
+/// Reads and validates desired records from a desired-state TOML file or a JSON file.
+///
+/// TOML files are resolved against the `fragments` directory next to them.
+pub fn read_records(file: &Path) -> Result<Vec<HostRequest>, Box<dyn Error>> {
+    let text: String = fs::read_to_string(file)
+        .map_err(|err| CliError::validation(format!("Cannot read {}: {}", file.display(), err)))?;
+    if file.extension().is_some_and(|extension| extension == "toml") {
+        let state: DesiredState = DesiredState::parse(&text)
+            .map_err(|err| CliError::validation(format!("Invalid desired state {}: {}", file.display(), err)))?;
+        let directory: PathBuf = file.parent().unwrap_or(Path::new(".")).join("fragments");
+        let fragments: Fragments = if state.include.is_empty() { Fragments::new() } else { Fragments::load_dir(&directory)? };
+        return validated(state.resolve(&fragments)?.to_host_requests());
+    }
+    let value: Value = serde_json::from_str(&text)
+        .map_err(|err| CliError::validation(format!("Invalid JSON in {}: {}", file.display(), err)))?;
+    validated(host_requests_from_value(&value)?)
+}
+
+fn validated(records: Vec<HostRequest>) -> Result<Vec<HostRequest>, Box<dyn Error>> {
+    for (index, record) in records.iter().enumerate() {
+        validate_record(record).map_err(|reason| CliError::validation(format!("Host record {}: {}", index, reason)))?;
+    }
+    Ok(records)
+}
+
+/// Splits a domain argument into its SLD and TLD.
+pub fn domain_parts(domain: &str) -> Result<(&str, &str), CliError> {
+    split_domain(domain).ok_or_else(|| CliError::validation(format!("Invalid domain: {}", domain)))
+}
+
+async fn diff(client: &NameCheapClient, domain: &str, file: &Path) -> Result<(), Box<dyn Error>> {
+    let desired: Vec<HostRequest> = read_records(file)?;
+    let (sld, tld) = domain_parts(domain)?;
+    let current: Vec<Host> = client.domains_dns_get_host_records(sld, tld).await?;
+
+    let plan: ZonePlan = ZonePlan::new(domain, &current, &desired);
+    print!("{}", plan);
+    if plan.has_changes() {
+        let changes: usize = plan.changes.iter().filter(|change| change.kind != ChangeKind::Keep).count();
+        return Err(CliError::drift(format!("{} differs from {} in {} records", domain, file.display(), changes)).into());
+    }
+    Ok(())
+}
+
+/// Returns the file a domain's snapshot is written to.
+pub fn backup_path(directory: &Path, domain: &str) -> PathBuf {
+    directory.join(format!("{}.json", domain.to_lowercase()))
+}
+
+async fn backup(client: &NameCheapClient, domains: Vec<String>, all: bool, output: &Path) -> Result<(), Box<dyn Error>> {
+    let domains: Vec<String> = selected_domains(client, domains, all).await?;
+    fs::create_dir_all(output)?;
+
+    let mut failed: Vec<String> = Vec::new();
+    for (index, domain) in domains.iter().enumerate() {
+        let written: Result<PathBuf, Box<dyn Error>> = async {
+            let snapshot: ZoneSnapshot = client.zone_snapshot(domain).await?;
+            let path: PathBuf = backup_path(output, domain);
+            fs::write(&path, serde_json::to_string_pretty(&snapshot)?)?;
+            Ok(path)
+        }.await;
+
+        match written {
+            Ok(path) => eprintln!("[{}/{}] {} -> {}", index + 1, domains.len(), domain, path.display()),
+            Err(err) => {
+                eprintln!("[{}/{}] {} failed: {}", index + 1, domains.len(), domain, err);
+                failed.push(domain.clone());
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(format!("Failed to back up {} of {} domains: {}", failed.len(), domains.len(), failed.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Returns the domains named on the command line, or every domain of the account with `all`.
+async fn selected_domains(client: &NameCheapClient, domains: Vec<String>, all: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    let domains: Vec<String> = if all {
+        client.domains_get_list_all().await?.into_iter().map(|domain: Domain| domain.name).collect()
+    } else {
+        domains
+    };
+    if domains.is_empty() {
+        return Err(CliError::validation("No domains given, pass domain names or --all").into());
+    }
+    for domain in &domains {
+        domain_parts(domain)?;
+    }
+    Ok(domains)
+}
+
+async fn adopt(client: &NameCheapClient, domains: Vec<String>, all: bool, output: &Path) -> Result<(), Box<dyn Error>> {
+    let domains: Vec<String> = selected_domains(client, domains, all).await?;
+    let progress: ProgressHandle = ProgressHandle::new(|progress: &Progress| eprintln!("[{}]", progress));
+    let outcomes: Vec<AdoptOutcome> = adopt_all(client, &domains, output, &progress).await;
+
+    let mut failed: Vec<String> = Vec::new();
+    for (domain, outcome) in outcomes {
+        match outcome {
+            Ok(path) => println!("{} -> {}", domain, path.display()),
+            Err(err) => {
+                eprintln!("{} failed: {}", domain, err);
+                failed.push(domain);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        return Err(format!("Failed to adopt {} of {} domains: {}", failed.len(), domains.len(), failed.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Reads a snapshot written by `dns backup`.
+pub fn read_snapshot(file: &Path) -> Result<ZoneSnapshot, Box<dyn Error>> {
+    let text: String = fs::read_to_string(file)
+        .map_err(|err| CliError::validation(format!("Cannot read {}: {}", file.display(), err)))?;
+    let snapshot: ZoneSnapshot = serde_json::from_str(&text)
+        .map_err(|err| CliError::validation(format!("Invalid snapshot {}: {}", file.display(), err)))?;
+    domain_parts(&snapshot.domain)?;
+    Ok(snapshot)
+}
+
+async fn restore(client: &NameCheapClient, file: &Path, dry_run: bool, yes: bool) -> Result<(), Box<dyn Error>> {
+    let snapshot: ZoneSnapshot = read_snapshot(file)?;
+    let desired: Vec<HostRequest> = snapshot.hosts.iter().map(|host| host.to_host_request()).collect();
+
+    let live: ZoneSnapshot = client.zone_snapshot(&snapshot.domain).await?;
+    let plan: ZonePlan = ZonePlan::new(&snapshot.domain, &live.hosts, &desired);
+    print!("{}", plan);
+
+    if !plan.has_changes() {
+        println!("{} already matches {}", snapshot.domain, file.display());
+        return Ok(());
+    }
+    if dry_run {
+        println!("Preview only, run again without --dry-run to apply");
+        return Ok(());
+    }
+    let action: String = format!("Replace the zone of {} with {}", snapshot.domain, file.display());
+    confirm(&Impact::from_plan(&action, &plan), yes)?;
+
+    // Applied only if the zone did not change since the preview
+    client.set_hosts_if_unchanged(&snapshot.domain, &live.hash, &desired).await?;
+    println!("Restored {} from {}", snapshot.domain, file.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_file_round_trip() {
+        let directory: PathBuf = std::env::temp_dir().join(format!("namecheap-backup-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let mut host: Host = Host::new();
+        host.name = "@".to_string();
+        host.type_ = "A".to_string();
+        host.address = "203.0.113.10".to_string();
+        host.ttl = 1800;
+        let snapshot: ZoneSnapshot = ZoneSnapshot::new("example.com", vec![host]);
+
+        let path: PathBuf = backup_path(&directory, "Example.com");
+        assert!(path.ends_with("example.com.json"));
+        fs::write(&path, serde_json::to_string_pretty(&snapshot).unwrap()).unwrap();
+        assert_eq!(read_snapshot(&path).unwrap(), snapshot);
+
+        fs::write(&path, r#"{"domain": "nodot", "hosts": [], "hash": ""}"#).unwrap();
+        assert!(read_snapshot(&path).is_err());
+
+        let adopted: PathBuf = directory.join("example.com.toml");
+        fs::write(&adopted, DesiredState::from_hosts("example.com", &snapshot.hosts).to_toml()).unwrap();
+        assert_eq!(read_records(&adopted).unwrap()[0].address, "203.0.113.10");
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! ### `namecheap domains`
+//!
+//! Domain commands of the CLI.
+
+use clap::{ Subcommand, ValueEnum };
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::io::{ self, Read };
+use std::path::PathBuf;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::cli::exit::CliError;
+use crate::domains::check::DomainCheckResult;
+use crate::limits::MAX_CHECK_DOMAINS;
+use crate::response::money::Money;
+
+/// The `domains` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum DomainsCommand {
+    /// Checks the availability of domains read from a file or stdin, one per line.
+    Check {
+        /// The file with the domain names, stdin when omitted or `-`.
+        #[arg(long, short)]
+        file: Option<PathBuf>,
+        /// The output format.
+        #[arg(long, value_enum, default_value_t = CheckFormat::Csv)]
+        format: CheckFormat,
+    },
+}
+
+/// The output formats of `domains check`.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum CheckFormat {
+    /// One CSV row per domain, with a header.
+    #[default]
+    Csv,
+    /// A JSON array of `DomainCheckResult`.
+    Json,
+}
+
+/// Runs a `domains` subcommand.
+pub async fn run(client: &NameCheapClient, command: DomainsCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        DomainsCommand::Check { file, format } => check(client, file, format).await,
+    }
+}
+
+/// Reads domain names, one per line.
+///
+/// Blank lines and `#` comments are skipped, names are lowercased and deduplicated in order.
+/// Lines that are not a domain name are rejected with their line numbers.
+pub fn parse_domain_names(input: &str) -> Result<Vec<String>, CliError> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut invalid: Vec<String> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let name: String = line.split('#').next().unwrap_or("").trim().trim_end_matches('.').to_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        let is_domain: bool = name.contains('.') &&
+            !name.starts_with('.') &&
+            name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '.');
+        if !is_domain {
+            invalid.push(format!("line {}: {:?}", index + 1, line.trim()));
+        } else if seen.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(CliError::validation(format!("Invalid domain names: {}", invalid.join(", "))));
+    }
+    if names.is_empty() {
+        return Err(CliError::validation("No domain names to check"));
+    }
+    Ok(names)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats check results as CSV, with a header row.
+pub fn to_csv(results: &[DomainCheckResult]) -> String {
+    let mut csv: String = "domain,available,premium,premium_registration_price,premium_renewal_price,currency,error\n".to_string();
+    for result in results {
+        let price = |price: &Option<Money>| {
+            price.as_ref().map(|price| price.amount.to_string()).unwrap_or_default()
+        };
+        let currency: String = result.premium_registration_price
+            .as_ref()
+            .or(result.premium_renewal_price.as_ref())
+            .map(|price| price.currency.to_string())
+            .unwrap_or_default();
+        let row: Vec<String> = vec![
+            csv_field(&result.domain),
+            result.available.to_string(),
+            result.is_premium_name.to_string(),
+            price(&result.premium_registration_price),
+            price(&result.premium_renewal_price),
+            currency,
+            csv_field(result.description.as_deref().unwrap_or("")),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+async fn check(client: &NameCheapClient, file: Option<PathBuf>, format: CheckFormat) -> Result<(), Box<dyn Error>> {
+    let input: String = match file.filter(|file| file.as_os_str() != "-") {
+        Some(file) => fs::read_to_string(&file)
+            .map_err(|err| CliError::validation(format!("Cannot read {}: {}", file.display(), err)))?,
+        None => {
+            let mut input: String = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let names: Vec<String> = parse_domain_names(&input)?;
+
+    let mut results: Vec<DomainCheckResult> = Vec::with_capacity(names.len());
+    for batch in names.chunks(MAX_CHECK_DOMAINS) {
+        results.extend(client.domains_check(batch).await?);
+        // Progress goes to stderr, stdout only carries the results
+        eprintln!("Checked {}/{} domains", results.len(), names.len());
+    }
+
+    match format {
+        CheckFormat::Csv => print!("{}", to_csv(&results)),
+        CheckFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::money::Currency;
+    use crate::response::parse_value::parse_decimal_str;
+
+    #[test]
+    fn test_parse_domain_names() {
+        let names: Vec<String> = parse_domain_names("Example.com\n\n# wishlist\nexample.net # maybe\nexample.com\n").unwrap();
+        assert_eq!(names, vec!["example.com", "example.net"]);
+
+        let error: CliError = parse_domain_names("example.com\nnot a domain\n").unwrap_err();
+        assert!(error.message.contains("line 2"));
+        assert!(parse_domain_names("# nothing\n").is_err());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let premium: Money = Money::new(parse_decimal_str("13000.00").unwrap(), Currency::Usd);
+        let results: Vec<DomainCheckResult> = vec![DomainCheckResult {
+            domain: "us.xyz".to_string(),
+            available: true,
+            error_no: "0".to_string(),
+            description: Some("Premium, \"hot\"".to_string()),
+            is_premium_name: true,
+            premium_registration_price: Some(premium.clone()),
+            premium_renewal_price: Some(premium),
+            premium_transfer_price: None,
+            premium_restore_price: None,
+            eap_fee: None,
+            icann_fee: None,
+            extra: Default::default(),
+        }];
+
+        let csv: String = to_csv(&results);
+        assert_eq!(csv.lines().nth(1), Some("us.xyz,true,true,13000.00,13000.00,USD,\"Premium, \"\"hot\"\"\""));
+    }
+}
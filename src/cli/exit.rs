@@ -0,0 +1,222 @@
+//! ### Exit Codes and Error Output
+//!
+//! Failures are mapped to an `ExitClass` with a distinct exit code, so pipelines can branch on
+//! the failure type:
+//!
+//! | Class          | Code | Raised for                                               |
+//! |----------------|------|----------------------------------------------------------|
+//! | `failure`      | 1    | Any other error (network, API, I/O)                      |
+//! | `auth`         | 2    | Rejected credentials or a client IP not on the whitelist |
+//! | `rate_limit`   | 3    | The account is throttled by the API                      |
+//! | `validation`   | 4    | Invalid input: arguments, records, templates             |
+//! | `drift_found`  | 5    | A zone differs from its desired records                  |
+//!
+//! With `--error-format json` the error is written to stderr as a single JSON line,
+//! `{"error": {"class": "auth", "code": 2, "message": "...", "correlationId": "..."}}`. The
+//! correlation ID identifies the command run in the logs, see
+//! [`correlation`](crate::utils::correlation).
+
+use clap::ValueEnum;
+use serde::{ Serialize, Deserialize };
+use serde_json::json;
+use std::error::Error;
+use std::fmt;
+use std::process::ExitCode;
+
+// crate imports
+use crate::response::errors::ApiError;
+use crate::utils::correlation::CorrelationId;
+use crate::utils::validate::ValidationFailure;
+use crate::domains_dns::set_hosts::HostRecordError;
+use crate::zone::template::TemplateError;
+use crate::zone::sync::FragmentError;
+use crate::policy::PolicyViolations;
+
+/// The class of a CLI failure, each with its own exit code.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ExitClass {
+    Failure,
+    Auth,
+    RateLimit,
+    Validation,
+    DriftFound,
+}
+
+impl ExitClass {
+    /// Returns the process exit code of the class.
+    pub fn code(&self) -> u8 {
+        match self {
+            ExitClass::Failure => 1,
+            ExitClass::Auth => 2,
+            ExitClass::RateLimit => 3,
+            ExitClass::Validation => 4,
+            ExitClass::DriftFound => 5,
+        }
+    }
+
+    /// Returns the name of the class, as used in JSON error output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitClass::Failure => "failure",
+            ExitClass::Auth => "auth",
+            ExitClass::RateLimit => "rate_limit",
+            ExitClass::Validation => "validation",
+            ExitClass::DriftFound => "drift_found",
+        }
+    }
+
+    /// Classifies an error by its type.
+    pub fn of(error: &(dyn Error + 'static)) -> Self {
+        if let Some(error) = error.downcast_ref::<CliError>() {
+            return error.class;
+        }
+        if let Some(failure) = error.downcast_ref::<ValidationFailure>() {
+            return ExitClass::of_failure(failure);
+        }
+        if let Some(error) = error.downcast_ref::<ApiError>() {
+            return ExitClass::of_failure(&ValidationFailure::from_api_error(error.clone(), ""));
+        }
+        if error.is::<HostRecordError>() || error.is::<TemplateError>() || error.is::<FragmentError>() || error.is::<PolicyViolations>() {
+            return ExitClass::Validation;
+        }
+        ExitClass::Failure
+    }
+
+    fn of_failure(failure: &ValidationFailure) -> Self {
+        match failure {
+            ValidationFailure::InvalidCredentials { .. } | ValidationFailure::IpNotWhitelisted { .. } => ExitClass::Auth,
+            ValidationFailure::RateLimited { .. } => ExitClass::RateLimit,
+            ValidationFailure::Api { .. } | ValidationFailure::Unreachable { .. } => ExitClass::Failure,
+        }
+    }
+}
+
+/// An error raised by the CLI itself, with an explicit class.
+///
+/// #### Fields
+/// - `class`: The class of the failure.
+/// - `message`: The human readable message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CliError {
+    pub class: ExitClass,
+    pub message: String,
+}
+
+impl CliError {
+    /// Creates an error of the given class.
+    pub fn new(class: ExitClass, message: impl Into<String>) -> Self {
+        CliError { class, message: message.into() }
+    }
+
+    /// Creates a `validation` error, for invalid input.
+    pub fn validation(message: impl Into<String>) -> Self {
+        CliError::new(ExitClass::Validation, message)
+    }
+
+    /// Creates a `drift_found` error.
+    pub fn drift(message: impl Into<String>) -> Self {
+        CliError::new(ExitClass::DriftFound, message)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CliError {}
+
+/// How errors are written to stderr.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ErrorFormat {
+    /// `error: <message>`
+    #[default]
+    Text,
+    /// A single JSON line with the class, exit code and message.
+    Json,
+}
+
+/// Formats an error for stderr, with the correlation ID of the failed command if any.
+pub fn format_error(error: &(dyn Error + 'static), format: ErrorFormat, correlation_id: Option<&CorrelationId>) -> String {
+    let class: ExitClass = ExitClass::of(error);
+    let message: String = error.to_string();
+    match format {
+        ErrorFormat::Text => match correlation_id {
+            // Typed API errors already name the ID in their message
+            Some(id) if !message.contains(id.as_str()) => format!("error: {} (correlation id {})", message, id),
+            _ => format!("error: {}", message),
+        },
+        ErrorFormat::Json => {
+            let mut output = json!({ "error": { "class": class.as_str(), "code": class.code(), "message": message } });
+            if let Some(id) = correlation_id {
+                output["error"]["correlationId"] = json!(id);
+            }
+            output.to_string()
+        }
+    }
+}
+
+/// Writes an error to stderr and returns the exit code of its class.
+pub fn report_error(error: &(dyn Error + 'static), format: ErrorFormat, correlation_id: Option<&CorrelationId>) -> ExitCode {
+    eprintln!("{}", format_error(error, format, correlation_id));
+    ExitCode::from(ExitClass::of(error).code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn api_error(number: &str, message: &str) -> ApiError {
+        ApiError::new(number, message)
+    }
+
+    #[test]
+    fn test_classes() {
+        let credentials: Box<dyn Error> = Box::new(api_error("1011102", "Parameter APIKey is invalid"));
+        let throttled: Box<dyn Error> = Box::new(api_error("500000", "Too many requests"));
+        let not_found: Box<dyn Error> = Box::new(api_error("2019166", "Domain not found"));
+        let drift: Box<dyn Error> = Box::new(CliError::drift("example.com has 2 changes"));
+
+        assert_eq!(ExitClass::of(credentials.as_ref()), ExitClass::Auth);
+        assert_eq!(ExitClass::of(throttled.as_ref()), ExitClass::RateLimit);
+        assert_eq!(ExitClass::of(not_found.as_ref()), ExitClass::Failure);
+        assert_eq!(ExitClass::of(drift.as_ref()).code(), 5);
+        let plain: Box<dyn Error> = "plain".into();
+        assert_eq!(ExitClass::of(plain.as_ref()).code(), 1);
+    }
+
+    #[test]
+    fn test_json_format() {
+        let error: Box<dyn Error> = Box::new(CliError::validation("Invalid TTL"));
+        let output: Value = serde_json::from_str(&format_error(error.as_ref(), ErrorFormat::Json, None)).unwrap();
+
+        assert_eq!(output["error"]["class"], "validation");
+        assert_eq!(output["error"]["code"], 4);
+        assert_eq!(format_error(error.as_ref(), ErrorFormat::Text, None), "error: Invalid TTL");
+    }
+
+    #[test]
+    fn test_correlation_id_in_output() {
+        let id: CorrelationId = CorrelationId::from_string("0123456789abcdef");
+        let error: Box<dyn Error> = Box::new(CliError::validation("Invalid TTL"));
+        let output: Value = serde_json::from_str(&format_error(error.as_ref(), ErrorFormat::Json, Some(&id))).unwrap();
+        assert_eq!(output["error"]["correlationId"], "0123456789abcdef");
+        assert_eq!(
+            format_error(error.as_ref(), ErrorFormat::Text, Some(&id)),
+            "error: Invalid TTL (correlation id 0123456789abcdef)"
+        );
+
+        let mut api: ApiError = api_error("2019166", "Domain not found");
+        api.correlation_id = Some(id.to_string());
+        let error: Box<dyn Error> = Box::new(api);
+        assert_eq!(
+            format_error(error.as_ref(), ErrorFormat::Text, Some(&id)),
+            "error: Namecheap API error 2019166: Domain not found (correlation id 0123456789abcdef)"
+        );
+    }
+}
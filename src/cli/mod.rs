@@ -0,0 +1,219 @@
+//! ## Command Line Interface
+//! The `namecheap` binary. Credentials are read from the `NAMECHEAP_*` environment variables
+//! (or a `.env` file) and an optional config file, the API key can come from a credential
+//! helper command, see [`config`].
+//!
+//! Every command exits with a code describing the failure class and supports
+//! `--error-format json` for machine-readable errors, see [`exit`]. Destructive commands show
+//! their impact and ask for confirmation unless `--yes` is passed, see [`confirm`].
+//!
+//! ### Commands
+//! - `namecheap auth login` / `namecheap auth logout`: Stores or deletes the encrypted credential cache.
+//! - `namecheap validate`: Checks the credentials and the IP whitelist.
+//! - `namecheap domains check --file <names.txt>`: Checks availability in bulk, names from a file or stdin.
+//! - `namecheap dns diff <domain> --file <records.json>`: Shows the drift of a zone, exits with 5 on drift.
+//! - `namecheap dns backup --all -o backups/`: Writes one snapshot file per domain.
+//! - `namecheap dns restore backups/example.com.json --yes`: Restores a snapshot after a diff preview.
+//! - `namecheap ssl list --type active`: Lists the certificates of the account.
+//! - `namecheap ssl activate <id> --csr <file.csr> --admin-email <email> --dns-validation`: Activates a certificate, creating the DCV records.
+//! - `namecheap ssl download <id> -o certs/`: Writes an issued certificate and its CA bundle.
+//! - `namecheap watch --expiring 30 --drift zones/ --interval 15m`: Monitors the account in the foreground.
+//!
+
+/// - **exit codes and error output**
+pub mod exit;
+/// - **confirmation prompts**
+pub mod confirm;
+/// - **config file and credential helpers**
+pub mod config;
+/// - **auth commands**
+pub mod auth;
+/// - **domains commands**
+pub mod domains;
+/// - **dns commands**
+pub mod dns;
+/// - **ssl commands**
+pub mod ssl;
+/// - **watch command**
+pub mod watch;
+
+use clap::{ Parser, Subcommand, ValueEnum };
+use clap::error::ErrorKind;
+use std::error::Error;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tracing_subscriber::EnvFilter;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::cli::dns::DnsCommand;
+use crate::cli::domains::DomainsCommand;
+use crate::cli::auth::AuthCommand;
+use crate::cli::config::CliConfig;
+use crate::cli::exit::{ report_error, CliError, ErrorFormat, ExitClass };
+use crate::cli::ssl::SslCommand;
+use crate::cli::watch::WatchArgs;
+use crate::utils::correlation::{ with_correlation_id, CorrelationId };
+use crate::utils::rate_limit::Priority;
+
+/// The command line arguments.
+#[derive(Debug, Parser)]
+#[command(name = "namecheap", version, about = "Manage Namecheap domains and DNS from the command line")]
+pub struct Cli {
+    /// How errors are written to stderr.
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Text, global = true)]
+    pub error_format: ErrorFormat,
+
+    /// The config file, `~/.config/namecheap/config.toml` by default.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Apply destructive changes without asking for confirmation.
+    #[arg(long, short, global = true, alias = "confirm")]
+    pub yes: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// The top level commands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Credential cache commands.
+    #[command(subcommand)]
+    Auth(AuthCommand),
+    /// Checks the API credentials and the client IP whitelist.
+    Validate,
+    /// Domain commands.
+    #[command(subcommand)]
+    Domains(DomainsCommand),
+    /// Zone commands.
+    #[command(subcommand)]
+    Dns(DnsCommand),
+    /// Certificate commands.
+    #[command(subcommand)]
+    Ssl(SslCommand),
+    /// Monitors expiring domains, zone changes and drift until Ctrl-C.
+    Watch(WatchArgs),
+}
+
+/// Runs a parsed command.
+pub async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let config: CliConfig = CliConfig::load(cli.config.as_deref())?;
+    if let Command::Auth(command) = cli.command {
+        return auth::run(&config, command).await;
+    }
+    // Commands are interactive, they go ahead of any background traffic sharing the limiter
+    let client: NameCheapClient = config.client()?.with_priority(Priority::High);
+
+    match cli.command {
+        Command::Auth(_) => unreachable!("auth commands run without a client"),
+        Command::Validate => {
+            let report = client.validate().await.into_result()?;
+            println!(
+                "OK: {} ({}) accepted client IP {} in {} ms",
+                report.endpoint,
+                if report.production { "production" } else { "sandbox" },
+                report.client_ip,
+                report.elapsed.as_millis()
+            );
+            Ok(())
+        }
+        Command::Domains(command) => domains::run(&client, command).await,
+        Command::Dns(command) => dns::run(&client, command, cli.yes).await,
+        Command::Ssl(command) => ssl::run(&client, command).await,
+        Command::Watch(args) => watch::run(&client, args).await,
+    }
+}
+
+/// Returns the error format asked for in `args`, for errors raised before they are parsed.
+fn requested_error_format(args: &[OsString]) -> ErrorFormat {
+    let mut format: ErrorFormat = ErrorFormat::Text;
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy().into_owned());
+    while let Some(arg) = args.next() {
+        let value: Option<String> = match arg.as_str() {
+            "--" => break,
+            "--error-format" => args.next(),
+            arg => arg.strip_prefix("--error-format=").map(str::to_string),
+        };
+        if let Some(value) = value {
+            format = ErrorFormat::from_str(&value, true).unwrap_or(format);
+        }
+    }
+    format
+}
+
+/// Converts a clap usage error to a `validation` error.
+fn usage_error(err: &clap::Error) -> CliError {
+    let message: String = err.render().to_string();
+    CliError::validation(message.trim().trim_start_matches("error: "))
+}
+
+/// Parses the process arguments, runs the command and returns the exit code.
+///
+/// Usage errors exit with the `validation` code rather than clap's default of 2, which is
+/// reserved for authentication failures, and follow `--error-format` like any other error.
+pub async fn main() -> ExitCode {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let cli: Cli = match Cli::try_parse_from(&args) {
+        Ok(cli) => cli,
+        Err(err) if matches!(err.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => {
+            let _ = err.print();
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => match requested_error_format(&args) {
+            ErrorFormat::Json => return report_error(&usage_error(&err), ErrorFormat::Json, None),
+            ErrorFormat::Text => {
+                let _ = err.print();
+                return ExitCode::from(ExitClass::Validation.code());
+            }
+        },
+    };
+
+    // Logs go to stderr, stdout is reserved for command output
+    let filter: EnvFilter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+    tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr).init();
+
+    // One correlation ID per command run, in every log line and in the error output
+    let format: ErrorFormat = cli.error_format;
+    let id: CorrelationId = CorrelationId::new();
+    match with_correlation_id(id.clone(), "cli", run(cli)).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => report_error(err.as_ref(), format, Some(&id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::exit::format_error;
+
+    #[test]
+    fn test_parse_arguments() {
+        let cli: Cli = Cli::try_parse_from(["namecheap", "dns", "diff", "example.com", "-f", "zone.json", "--error-format", "json"]).unwrap();
+        assert_eq!(cli.error_format, ErrorFormat::Json);
+        assert!(matches!(cli.command, Command::Dns(DnsCommand::Diff { ref domain, .. }) if domain == "example.com"));
+        assert!(Cli::try_parse_from(["namecheap", "dns", "diff"]).is_err());
+
+        let cli: Cli = Cli::try_parse_from(["namecheap", "dns", "restore", "backups/example.com.json", "--confirm"]).unwrap();
+        assert!(cli.yes);
+    }
+
+    #[test]
+    fn test_usage_errors_follow_error_format() {
+        let args = |args: &[&str]| -> Vec<OsString> { args.iter().map(OsString::from).collect() };
+        let json: Vec<OsString> = args(&["namecheap", "--error-format", "json", "dns", "diff"]);
+        assert_eq!(requested_error_format(&json), ErrorFormat::Json);
+        assert_eq!(requested_error_format(&args(&["namecheap", "dns", "--error-format=json"])), ErrorFormat::Json);
+        assert_eq!(requested_error_format(&args(&["namecheap", "dns", "diff"])), ErrorFormat::Text);
+        assert_eq!(requested_error_format(&args(&["namecheap", "--", "--error-format", "json"])), ErrorFormat::Text);
+
+        let err: clap::Error = Cli::try_parse_from(&json).unwrap_err();
+        let error: Box<dyn Error> = Box::new(usage_error(&err));
+        let output: serde_json::Value = serde_json::from_str(&format_error(error.as_ref(), ErrorFormat::Json, None)).unwrap();
+        assert_eq!(output["error"]["class"], "validation");
+        assert_eq!(output["error"]["code"], 4);
+        assert!(output["error"]["message"].as_str().unwrap().contains("required"));
+    }
+}
@@ -0,0 +1,255 @@
+//! ### `namecheap ssl`
+//!
+//! Certificate commands of the CLI, covering the issuance workflow end to end:
+//!
+//! ```sh
+//! namecheap ssl list --type new-purchase
+//! namecheap ssl activate 52556 --csr example.com.csr --admin-email admin@example.com --dns-validation
+//! namecheap ssl download 52556 -o certs/
+//! ```
+//!
+//! With `--dns-validation` the DCV CNAME records returned by `ssl.activate` are created in the
+//! zones of the account, so issuance needs no manual DNS step. Records of domains that are not
+//! in the account are printed instead.
+
+use clap::Subcommand;
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+// crate imports
+use crate::{ NameCheapClient, Domain };
+use crate::cli::exit::CliError;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::ssl::activate::{ DcvMethod, DcvRecord, SslActivateRequest, SslActivateResult };
+use crate::ssl::get_info::SslInfo;
+use crate::ssl::get_list::{ SslCertificate, SslListQuery, SslListType };
+use crate::zone::diff::ZonePlan;
+use crate::zone::relative_name;
+
+/// The `ssl` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum SslCommand {
+    /// Lists the certificates of the account.
+    List {
+        /// The statuses to list, e.g. `active` or `new-purchase`.
+        #[arg(long = "type", default_value = "all", value_parser = parse_list_type)]
+        list_type: SslListType,
+    },
+    /// Activates a purchased certificate with a CSR.
+    Activate {
+        /// The certificate ID, see `ssl list`.
+        certificate_id: i64,
+        /// The PEM encoded CSR file.
+        #[arg(long)]
+        csr: PathBuf,
+        /// The email address the issued certificate is sent to.
+        #[arg(long)]
+        admin_email: String,
+        /// The server type the certificate is issued for.
+        #[arg(long)]
+        web_server_type: Option<String>,
+        /// Validate domain control with CNAME records, created in the zones of the account.
+        #[arg(long, conflicts_with = "approver_email")]
+        dns_validation: bool,
+        /// Validate domain control by email to this approver address instead.
+        #[arg(long)]
+        approver_email: Option<String>,
+    },
+    /// Writes an issued certificate and its CA bundle to files.
+    Download {
+        /// The certificate ID, see `ssl list`.
+        certificate_id: i64,
+        /// The directory the files are written to.
+        #[arg(long, short, default_value = ".")]
+        output: PathBuf,
+    },
+}
+
+/// Parses a list type, case and separator insensitive, e.g. `new-purchase` or `NewPurchase`.
+pub fn parse_list_type(value: &str) -> Result<SslListType, String> {
+    let normalized: String = value.replace(['-', '_'], "").to_lowercase();
+    SslListType::ALL
+        .into_iter()
+        .find(|list_type| list_type.as_str().to_lowercase() == normalized)
+        .ok_or_else(|| format!("unknown certificate list type: {}", value))
+}
+
+/// Runs an `ssl` subcommand.
+pub async fn run(client: &NameCheapClient, command: SslCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        SslCommand::List { list_type } => list(client, list_type).await,
+        SslCommand::Activate { certificate_id, csr, admin_email, web_server_type, dns_validation, approver_email } => {
+            let dcv_method: DcvMethod = match (dns_validation, approver_email) {
+                (true, _) => DcvMethod::Dns,
+                (false, Some(approver)) => DcvMethod::Email(approver),
+                (false, None) => {
+                    return Err(CliError::validation("Pass --dns-validation or --approver-email").into());
+                }
+            };
+            let csr: String = fs::read_to_string(&csr)
+                .map_err(|err| CliError::validation(format!("Cannot read {}: {}", csr.display(), err)))?;
+            if !csr.contains("BEGIN CERTIFICATE REQUEST") {
+                return Err(CliError::validation("The CSR file is not a PEM encoded certificate request").into());
+            }
+            let request: SslActivateRequest = SslActivateRequest { certificate_id, csr, admin_email, web_server_type, dcv_method };
+            activate(client, &request).await
+        }
+        SslCommand::Download { certificate_id, output } => download(client, certificate_id, &output).await,
+    }
+}
+
+async fn list(client: &NameCheapClient, list_type: SslListType) -> Result<(), Box<dyn Error>> {
+    let mut query: SslListQuery = SslListQuery::new().list_type(list_type);
+    loop {
+        let (certificates, pagination) = client.ssl_get_list(&query).await?;
+        for certificate in &certificates {
+            println!("{}", format_certificate(certificate));
+        }
+        if pagination.current_page >= pagination.total_pages {
+            break;
+        }
+        query = query.page(pagination.current_page + 1);
+    }
+    Ok(())
+}
+
+/// Formats a certificate as a line of `ssl list`.
+pub fn format_certificate(certificate: &SslCertificate) -> String {
+    let expires: String = certificate.expires_at
+        .map(|expires_at| expires_at.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let host_name: &str = if certificate.host_name.is_empty() { "-" } else { &certificate.host_name };
+    format!("{}\t{}\t{}\t{}\t{}", certificate.certificate_id, certificate.ssl_type, certificate.status, host_name, expires)
+}
+
+/// Returns the account domain whose zone holds `host_name`, the most specific one if several do.
+pub fn zone_of<'a>(domains: &'a [String], host_name: &str) -> Option<&'a String> {
+    domains
+        .iter()
+        .filter(|domain| relative_name(domain, host_name).is_some())
+        .max_by_key(|domain| domain.len())
+}
+
+/// Groups DCV records by the account zone they are created in.
+///
+/// Records outside of every zone are returned separately.
+pub fn plan_dcv_records(domains: &[String], records: &[DcvRecord]) -> (Vec<(String, Vec<HostRequest>)>, Vec<DcvRecord>) {
+    let mut zones: Vec<(String, Vec<HostRequest>)> = Vec::new();
+    let mut outside: Vec<DcvRecord> = Vec::new();
+
+    for record in records {
+        let Some(domain) = zone_of(domains, &record.host_name) else {
+            outside.push(record.clone());
+            continue;
+        };
+        let name: String = relative_name(domain, &record.host_name).unwrap_or_default();
+        let request: HostRequest = HostRequest::new(
+            name,
+            "CNAME".to_string(),
+            format!("{}.", record.target.trim_end_matches('.')),
+            None,
+            None,
+            None,
+            None,
+            None
+        );
+        match zones.iter_mut().find(|(zone, _)| zone == domain) {
+            Some((_, requests)) => requests.push(request),
+            None => zones.push((domain.clone(), vec![request])),
+        }
+    }
+    (zones, outside)
+}
+
+async fn activate(client: &NameCheapClient, request: &SslActivateRequest) -> Result<(), Box<dyn Error>> {
+    let result: SslActivateResult = client.ssl_activate(request).await?;
+    if !result.is_success {
+        return Err(format!("Activation of certificate {} was not accepted", request.certificate_id).into());
+    }
+    println!("Activated certificate {}", request.certificate_id);
+    if result.dcv_records.is_empty() {
+        return Ok(());
+    }
+
+    let domains: Vec<String> = client.domains_get_list_all().await?
+        .into_iter()
+        .map(|domain: Domain| domain.name.to_lowercase())
+        .collect();
+    let (zones, outside) = plan_dcv_records(&domains, &result.dcv_records);
+
+    for (domain, records) in &zones {
+        let plan: ZonePlan = client.ensure_records(domain, records).await?;
+        print!("{}", plan);
+        eprintln!("Created {} DCV records in {}", records.len(), domain);
+    }
+    for record in &outside {
+        println!("Create manually: {} CNAME {}", record.host_name, record.target);
+    }
+    Ok(())
+}
+
+async fn download(client: &NameCheapClient, certificate_id: i64, output: &Path) -> Result<(), Box<dyn Error>> {
+    let info: SslInfo = client.ssl_get_info(certificate_id, true).await?;
+    let Some(certificate) = &info.certificate else {
+        return Err(CliError::validation(format!(
+            "Certificate {} is not issued yet, its status is {}",
+            certificate_id,
+            info.status
+        )).into());
+    };
+
+    let name: String = if info.common_name.is_empty() {
+        certificate_id.to_string()
+    } else {
+        info.common_name.replace('*', "_wildcard")
+    };
+    fs::create_dir_all(output)?;
+
+    let certificate_path: PathBuf = output.join(format!("{}.crt", name));
+    fs::write(&certificate_path, format!("{}\n", certificate.trim()))?;
+    println!("{}", certificate_path.display());
+
+    if !info.ca_certificates.is_empty() {
+        let bundle_path: PathBuf = output.join(format!("{}.ca-bundle", name));
+        fs::write(&bundle_path, info.ca_certificates.join("\n") + "\n")?;
+        println!("{}", bundle_path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_type() {
+        assert_eq!(parse_list_type("new-purchase"), Ok(SslListType::NewPurchase));
+        assert_eq!(parse_list_type("ALL"), Ok(SslListType::All));
+        assert!(parse_list_type("issued").is_err());
+    }
+
+    #[test]
+    fn test_plan_dcv_records() {
+        let domains: Vec<String> = vec!["example.com".to_string(), "shop.example.com".to_string()];
+        let record = |host_name: &str| DcvRecord {
+            domain: host_name.trim_start_matches("_abc.").to_string(),
+            host_name: host_name.to_string(),
+            target: "def.ghi.comodoca.com".to_string(),
+        };
+        let records: Vec<DcvRecord> = vec![
+            record("_abc.example.com"),
+            record("_abc.www.shop.example.com"),
+            record("_abc.example.org"),
+        ];
+
+        let (zones, outside) = plan_dcv_records(&domains, &records);
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].0, "example.com");
+        assert_eq!(zones[0].1[0].host_name, "_abc");
+        assert_eq!(zones[0].1[0].address, "def.ghi.comodoca.com.");
+        assert_eq!(zones[1].0, "shop.example.com");
+        assert_eq!(zones[1].1[0].host_name, "_abc.www");
+        assert_eq!(outside, vec![record("_abc.example.org")]);
+    }
+}
@@ -0,0 +1,197 @@
+//! ### `namecheap watch`
+//!
+//! Runs the `Watcher` in the foreground until Ctrl-C, printing each event and optionally
+//! POSTing it as JSON to a webhook, e.g. as a monitoring sidecar:
+//!
+//! ```sh
+//! namecheap watch --expiring 30 --drift zones/ --interval 15m --webhook https://hooks.example.com/dns
+//! ```
+//!
+//! `--drift` reads a directory of desired zones, one `<domain>.json` or `<domain>.toml` file
+//! per domain, either host records as accepted by `dns diff`, desired states written by
+//! `dns adopt` (with their fragments) or snapshots written by `dns backup`. With `--state`
+//! the watch state is kept in a JSON file, so a restart does not report everything again.
+
+use chrono::Utc;
+use clap::Args;
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::cli::dns::{ domain_parts, read_records, read_snapshot };
+use crate::cli::exit::CliError;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::scheduler::parse_interval;
+use crate::utils::shutdown::Shutdown;
+use crate::utils::state_store::JsonFileStore;
+use crate::watcher::{ WatchEvent, Watcher };
+use crate::watcher::stream::{ EventReceiver, Overflow, StreamConfig };
+
+/// The arguments of `watch`.
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Report domains expiring within this many days.
+    #[arg(long)]
+    pub expiring: Option<i64>,
+    /// A directory of desired zones, one `<domain>.json` or `.toml` per domain, to report drift against.
+    #[arg(long)]
+    pub drift: Option<PathBuf>,
+    /// A domain whose record changes are reported, repeatable.
+    #[arg(long = "zone")]
+    pub zones: Vec<String>,
+    /// The polling interval, e.g. `30s`, `15m`, `6h`.
+    #[arg(long, default_value = "15m", value_parser = parse_interval)]
+    pub interval: Duration,
+    /// A URL each event is POSTed to as JSON.
+    #[arg(long)]
+    pub webhook: Option<String>,
+    /// A JSON file keeping the watch state across restarts.
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+    /// Print events as JSON lines instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// A domain and its desired records.
+pub type DesiredZone = (String, Vec<HostRequest>);
+
+/// Reads the desired zones of a drift directory.
+pub fn read_drift_dir(directory: &Path) -> Result<Vec<DesiredZone>, Box<dyn Error>> {
+    let entries = fs::read_dir(directory)
+        .map_err(|err| CliError::validation(format!("Cannot read {}: {}", directory.display(), err)))?;
+
+    let mut zones: Vec<DesiredZone> = Vec::new();
+    for entry in entries {
+        let path: PathBuf = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "json" && extension != "toml") {
+            continue;
+        }
+        let is_snapshot: bool = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .is_some_and(|value| value.get("hosts").is_some() && value.get("domain").is_some());
+
+        if is_snapshot {
+            let snapshot = read_snapshot(&path)?;
+            zones.push((snapshot.domain, snapshot.hosts.iter().map(|host| host.to_host_request()).collect()));
+        } else {
+            let domain: String = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+            domain_parts(&domain)?;
+            zones.push((domain, read_records(&path)?));
+        }
+    }
+    zones.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(zones)
+}
+
+/// Formats an event as a line of text.
+pub fn format_event(event: &WatchEvent) -> String {
+    let message: String = match event {
+        WatchEvent::Expiring { domain, expires_at, days_left } => match expires_at {
+            Some(expires_at) => format!("{} expires in {} days ({})", domain, days_left, expires_at.format("%Y-%m-%d")),
+            None => format!("{} is expiring", domain),
+        },
+        WatchEvent::ZoneChanged { domain, previous_hash, hash } =>
+            format!("{} records changed ({} -> {})", domain, previous_hash, hash),
+        WatchEvent::Drift { domain, changes } => format!("{} drifted from its desired records in {} records", domain, changes),
+        WatchEvent::DriftResolved { domain } => format!("{} matches its desired records again", domain),
+        WatchEvent::Error { domain: Some(domain), message } => format!("error for {}: {}", domain, message),
+        WatchEvent::Error { domain: None, message } => format!("error: {}", message),
+    };
+    format!("{} {}", Utc::now().to_rfc3339(), message)
+}
+
+/// Runs the watcher until Ctrl-C.
+pub async fn run(client: &NameCheapClient, args: WatchArgs) -> Result<(), Box<dyn Error>> {
+    if args.expiring.is_none() && args.drift.is_none() && args.zones.is_empty() {
+        return Err(CliError::validation("Nothing to watch, pass --expiring, --drift or --zone").into());
+    }
+    for zone in &args.zones {
+        domain_parts(zone)?;
+    }
+
+    let shutdown: Shutdown = Shutdown::new();
+    let mut watcher: Watcher = Watcher::new(client.clone())
+        .interval(args.interval)
+        .with_shutdown(shutdown.clone());
+    if let Some(days) = args.expiring {
+        watcher = watcher.expiring_within(days);
+    }
+    for zone in &args.zones {
+        watcher = watcher.watch_zone(zone);
+    }
+    if let Some(directory) = &args.drift {
+        for (domain, desired) in read_drift_dir(directory)? {
+            watcher = watcher.watch_drift(&domain, desired);
+        }
+    }
+    if let Some(state) = &args.state {
+        watcher = watcher.with_state_store(Arc::new(JsonFileStore::new(state)))?;
+    }
+
+    // A slow webhook slows polling down instead of queueing events without bound
+    let mut events: EventReceiver = watcher.stream(StreamConfig::new(256, Overflow::Wait));
+    let handle = watcher.start();
+    let http: reqwest::Client = reqwest::Client::new();
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else { break };
+                if args.json {
+                    println!("{}", serde_json::to_string(&event)?);
+                } else {
+                    println!("{}", format_event(&event));
+                }
+                if let Some(webhook) = &args.webhook {
+                    let sent = http.post(webhook).json(&event).send().await.and_then(|response| response.error_for_status());
+                    if let Err(err) = sent {
+                        warn!("Failed to POST event to {}: {}", webhook, err);
+                    }
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("Stopping");
+                shutdown.shutdown(Duration::from_secs(10)).await;
+                break;
+            }
+        }
+    }
+    let _ = handle.await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_drift_dir() {
+        let directory: PathBuf = std::env::temp_dir().join(format!("namecheap-drift-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("example.com.json"),
+            r#"[{"HostName": "@", "RecordType": "A", "Address": "203.0.113.10", "TTL": 1800}]"#
+        ).unwrap();
+        fs::write(directory.join("notes.txt"), "ignored").unwrap();
+
+        let zones: Vec<DesiredZone> = read_drift_dir(&directory).unwrap();
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].0, "example.com");
+        assert_eq!(zones[0].1[0].address, "203.0.113.10");
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_format_event() {
+        let event: WatchEvent = WatchEvent::Drift { domain: "example.com".to_string(), changes: 2 };
+        assert!(format_event(&event).ends_with("example.com drifted from its desired records in 2 records"));
+    }
+}
@@ -0,0 +1,336 @@
+//! ### Contract Tests
+//!
+//! The sample responses of the NameCheap API documentation, one per implemented command,
+//! under `src/contracts/fixtures`. Each sample is run through the typed parser of its command
+//! in both shapes of the JSON tree (see [`ParseOptions`]), so a parser regression fails
+//! `cargo test` without network access or credentials.
+//!
+//! Samples follow the documentation verbatim, including its quirks (e.g. the misspelled
+//! `YourAdditonalCost` of `users.getPricing`); only the account specific values are replaced.
+//! A new command gets its sample here alongside its implementation.
+
+use serde_json::Value;
+
+// crate imports
+use crate::{ Contact, Domain, Host, WhoisGuardStatus };
+use crate::domains::check::{ parse_check_results, DomainCheckResult };
+use crate::domains::create::{ parse_create_result, DomainCreateResult };
+use crate::domains::get_contacts::parse_contacts;
+use crate::domains::get_info::{ parse_domain_info, DomainInfo };
+use crate::domains::get_list::parse_domain;
+use crate::domains::get_registrar_lock::{ parse_registrar_lock, DomainRegistrarLock, RegistrarLockStatus };
+use crate::domains::reactivate::{ parse_reactivate_result, DomainReactivateResult };
+use crate::domains::renew::{ parse_renew_result, DomainRenewResult };
+use crate::domains::set_registrar_lock::{ parse_set_registrar_lock_result, DomainSetRegistrarLockResult, LockAction };
+use crate::domains::suggest::registerable_tlds;
+use crate::domains_dns::get_list::parse_nameservers;
+use crate::domains_ns::create::{ parse_ns_create_result, DomainNsCreateResult };
+use crate::domains_ns::delete::{ parse_ns_delete_result, DomainNsDeleteResult };
+use crate::domains_ns::get_info::{ parse_ns_info, DomainNsInfo };
+use crate::domains_ns::update::{ parse_ns_update_result, DomainNsUpdateResult };
+use crate::response::errors::check_api_errors;
+use crate::response::envelope::ResponseEnvelope;
+use crate::response::parse_value::{ get_node_at, get_nodes_at, text_of };
+use crate::response::timestamp::server_offset;
+use crate::response::typed_xml::{ parse_domains_xml, parse_hosts_xml };
+use crate::response::views::host_views;
+use crate::ssl::activate::{ parse_activate_result, SslActivateResult };
+use crate::ssl::get_info::{ parse_ssl_info, SslInfo };
+use crate::ssl::get_list::parse_ssl_list;
+use crate::users::get_balances::{ parse_balances, AccountBalances };
+use crate::users::get_pricing::{ parse_pricing, Action, ProductType };
+use crate::users_address::create::parse_address_id;
+use crate::users_address::get_info::parse_address_info;
+use crate::users_address::get_list::parse_address_list;
+use crate::utils::xml_parser::{ parse_xml_to_json, parse_xml_to_json_with, ParseOptions };
+
+/// The documented sample response of every implemented command.
+const SAMPLES: &[(&str, &str)] = &[
+    ("domains.check", include_str!("fixtures/domains.check.xml")),
+    ("domains.create", include_str!("fixtures/domains.create.xml")),
+    ("domains.dns.getHosts", include_str!("fixtures/domains.dns.getHosts.xml")),
+    ("domains.dns.getList", include_str!("fixtures/domains.dns.getList.xml")),
+    ("domains.dns.setCustom", include_str!("fixtures/domains.dns.setCustom.xml")),
+    ("domains.getContacts", include_str!("fixtures/domains.getContacts.xml")),
+    ("domains.getInfo", include_str!("fixtures/domains.getInfo.xml")),
+    ("domains.getList", include_str!("fixtures/domains.getList.xml")),
+    ("domains.getRegistrarLock", include_str!("fixtures/domains.getRegistrarLock.xml")),
+    ("domains.getTldList", include_str!("fixtures/domains.getTldList.xml")),
+    ("domains.ns.create", include_str!("fixtures/domains.ns.create.xml")),
+    ("domains.ns.delete", include_str!("fixtures/domains.ns.delete.xml")),
+    ("domains.ns.getInfo", include_str!("fixtures/domains.ns.getInfo.xml")),
+    ("domains.ns.update", include_str!("fixtures/domains.ns.update.xml")),
+    ("domains.reactivate", include_str!("fixtures/domains.reactivate.xml")),
+    ("domains.renew", include_str!("fixtures/domains.renew.xml")),
+    ("domains.setRegistrarLock", include_str!("fixtures/domains.setRegistrarLock.xml")),
+    ("ssl.activate", include_str!("fixtures/ssl.activate.xml")),
+    ("ssl.getInfo", include_str!("fixtures/ssl.getInfo.xml")),
+    ("ssl.getList", include_str!("fixtures/ssl.getList.xml")),
+    ("users.address.create", include_str!("fixtures/users.address.create.xml")),
+    ("users.address.getInfo", include_str!("fixtures/users.address.getInfo.xml")),
+    ("users.address.getList", include_str!("fixtures/users.address.getList.xml")),
+    ("users.getBalances", include_str!("fixtures/users.getBalances.xml")),
+    ("users.getPricing", include_str!("fixtures/users.getPricing.xml")),
+];
+
+/// Returns the raw sample of `command`.
+fn xml(command: &str) -> &'static str {
+    SAMPLES
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, xml)| *xml)
+        .unwrap_or_else(|| panic!("No sample for {}", command))
+}
+
+/// Parses the sample of `command` in both shapes: `$text` wrapped, then flattened.
+fn sample(command: &str) -> [Value; 2] {
+    [
+        parse_xml_to_json(xml(command)).unwrap(),
+        parse_xml_to_json_with(xml(command), ParseOptions::flat()).unwrap(),
+    ]
+}
+
+/// Parses both shapes of the sample of `command`, asserting they give the same result.
+fn parsed<T: PartialEq + std::fmt::Debug>(command: &str, parse: impl Fn(&Value) -> T) -> T {
+    let [wrapped, flat] = sample(command);
+    let result: T = parse(&wrapped);
+    assert_eq!(result, parse(&flat), "{} parses differently once flattened", command);
+    result
+}
+
+#[test]
+fn test_envelopes() {
+    for (command, _) in SAMPLES {
+        for response in sample(command) {
+            assert!(check_api_errors(&response).is_ok(), "{}", command);
+            let envelope: ResponseEnvelope = ResponseEnvelope::from_response(&response, false);
+            assert_eq!(envelope.status, "OK");
+            assert_eq!(envelope.command.as_deref(), Some(format!("namecheap.{}", command).as_str()));
+            assert_eq!(server_offset(&response).local_minus_utc(), -5 * 3600);
+        }
+    }
+}
+
+#[test]
+fn test_domains_check() {
+    let results: Vec<DomainCheckResult> = parsed("domains.check", |response| parse_check_results(response).unwrap());
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].available && results[0].is_premium_name);
+    assert_eq!(results[0].premium_restore_price.as_ref().map(|price| price.to_string()).as_deref(), Some("65.0000 USD"));
+    assert_eq!((results[0].eap_fee.as_ref(), results[0].icann_fee.as_ref()), (None, None));
+    assert!(!results[1].available);
+    assert_eq!(results[1].premium_registration_price, None);
+}
+
+#[test]
+fn test_domains_create() {
+    let result: DomainCreateResult = parsed("domains.create", |response| parse_create_result(response).unwrap());
+
+    assert!(result.registered);
+    assert_eq!((result.domain_id, result.order_id, result.transaction_id), (9007, 196074, 380716));
+    assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("20.3600 USD"));
+}
+
+#[test]
+fn test_domains_dns_get_hosts() {
+    let hosts: Vec<Host> = parse_hosts_xml(xml("domains.dns.getHosts")).unwrap();
+    assert_eq!(hosts.len(), 3);
+    assert_eq!((hosts[2].type_.as_str(), hosts[2].address.as_str(), hosts[2].ttl), ("MX", "mail.domain.com.", 1800));
+
+    let views: Vec<Host> = parsed("domains.dns.getHosts", |response| host_views(response).iter().map(|view| view.to_host()).collect());
+    assert_eq!(views, hosts);
+}
+
+#[test]
+fn test_domains_dns_get_list() {
+    let nameservers: Vec<String> = parsed("domains.dns.getList", |response| {
+        let nameservers: Value = parse_nameservers(response).unwrap();
+        get_nodes_at(&nameservers, "").into_iter().filter_map(text_of).map(str::to_string).collect()
+    });
+    assert_eq!(nameservers, ["dns1.name-servers.com", "dns2.name-servers.com"]);
+}
+
+#[test]
+fn test_domains_dns_set_custom() {
+    let updated = parsed("domains.dns.setCustom", |response| {
+        get_node_at(response, "/ApiResponse/CommandResponse/DomainDNSSetCustomResult").map(|result| result["updated"].clone())
+    });
+    assert_eq!(updated, Some(Value::String("true".to_string())));
+}
+
+#[test]
+fn test_domains_get_contacts() {
+    let contacts: Vec<Contact> = parsed("domains.getContacts", |response| {
+        let contacts: Value = parse_contacts(response).unwrap();
+        let role = |set: &str, role: &str| -> Contact { serde_json::from_value(contacts[set][role].clone()).unwrap() };
+        vec![role("contacts", "registrant"), role("contacts", "auxbilling"), role("whois_guard_contacts", "registrant")]
+    });
+
+    assert_eq!(contacts[0].first_name, "John");
+    assert!(!contacts[0].read_only);
+    assert_eq!(contacts[1].type_, "AuxBilling");
+    assert_eq!((contacts[1].postal_code.as_str(), contacts[1].phone_ext.as_str()), ("85284", ""));
+    assert_eq!(contacts[2].first_name, "WhoisGuard");
+    assert!(contacts[2].read_only);
+}
+
+#[test]
+fn test_domains_get_info() {
+    let info: DomainInfo = parsed("domains.getInfo", |response| parse_domain_info(response).unwrap());
+
+    assert_eq!((info.id, info.domain_name.as_str()), (1234, "domain1.com"));
+    assert_eq!(info.whois_guard, WhoisGuardStatus::Enabled);
+    assert_eq!(info.dns_details.as_ref().map(|dns| dns.nameservers.len()), Some(2));
+    assert_eq!(info.premium_dns.as_ref().map(|premium| premium.subscription_id), Some(-1));
+    assert!(!info.is_premium_dns());
+}
+
+#[test]
+fn test_domains_get_list() {
+    let domains: Vec<Domain> = parsed("domains.getList", |response| {
+        get_nodes_at(response, "/ApiResponse/CommandResponse/DomainGetListResult/Domain")
+            .into_iter()
+            .map(|domain| parse_domain(domain, server_offset(response)).unwrap())
+            .collect()
+    });
+    assert_eq!(domains.len(), 3);
+    assert_eq!((domains[0].id, domains[0].name.as_str()), (127, "domain1.com"));
+    assert!(domains[0].is_premium && !domains[0].auto_renew);
+    assert_eq!(domains[1].whois_guard, WhoisGuardStatus::NotPresent);
+
+    let (page, total_pages) = parse_domains_xml(xml("domains.getList")).unwrap();
+    assert_eq!(page.items, domains);
+    assert_eq!(total_pages, 1);
+}
+
+#[test]
+fn test_domains_get_tld_list() {
+    let tlds: Vec<String> = parsed("domains.getTldList", |response| {
+        registerable_tlds(get_node_at(response, "/ApiResponse/CommandResponse/Tlds/Tld").unwrap())
+    });
+    assert_eq!(tlds, ["biz"]);
+}
+
+#[test]
+fn test_domains_get_registrar_lock() {
+    let lock: DomainRegistrarLock = parsed("domains.getRegistrarLock", |response| parse_registrar_lock(response).unwrap());
+
+    assert_eq!(lock.domain, "domain1.com");
+    assert_eq!(lock.status, RegistrarLockStatus::Unlocked);
+}
+
+#[test]
+fn test_domains_ns() {
+    let created: DomainNsCreateResult = parsed("domains.ns.create", |response| parse_ns_create_result(response).unwrap());
+    assert_eq!((created.nameserver.as_str(), created.ip.as_str()), ("ns1.domain.com", "192.168.0.1"));
+    assert!(created.created);
+
+    let info: DomainNsInfo = parsed("domains.ns.getInfo", |response| parse_ns_info(response).unwrap());
+    assert_eq!((info.domain.as_str(), info.ip.as_str()), ("domain.com", "12.23.23.23"));
+    assert_eq!(info.statuses, ["OK", "Linked"]);
+    assert!(info.is_linked());
+
+    let updated: DomainNsUpdateResult = parsed("domains.ns.update", |response| parse_ns_update_result(response, "192.168.0.2").unwrap());
+    assert!(updated.updated);
+    assert_eq!(updated.ip, "192.168.0.2");
+
+    let deleted: DomainNsDeleteResult = parsed("domains.ns.delete", |response| parse_ns_delete_result(response).unwrap());
+    assert!(deleted.deleted);
+}
+
+#[test]
+fn test_domains_reactivate() {
+    let result: DomainReactivateResult = parsed("domains.reactivate", |response| parse_reactivate_result(response).unwrap());
+
+    assert!(result.reactivated);
+    assert_eq!((result.domain.as_str(), result.order_id, result.transaction_id), ("domain1.com", 23569, 25080));
+    assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("650.0000 USD"));
+}
+
+#[test]
+fn test_domains_renew() {
+    let result: DomainRenewResult = parsed("domains.renew", |response| parse_renew_result(response).unwrap());
+
+    assert!(result.renewed);
+    assert_eq!((result.domain.as_str(), result.order_id, result.transaction_id), ("domain1.com", 82511, 150826));
+    assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("650.0000 USD"));
+    assert_eq!(result.expires_at.map(|expires_at| expires_at.to_rfc3339()).as_deref(), Some("2013-10-21T13:41:21+00:00"));
+}
+
+#[test]
+fn test_domains_set_registrar_lock() {
+    let result: DomainSetRegistrarLockResult = parsed("domains.setRegistrarLock", |response| {
+        parse_set_registrar_lock_result(response, LockAction::Lock).unwrap()
+    });
+
+    assert!(result.updated);
+    assert_eq!(result.domain, "domain1.com");
+    assert_eq!(result.status, RegistrarLockStatus::Locked);
+}
+
+#[test]
+fn test_ssl_activate() {
+    let result: SslActivateResult = parsed("ssl.activate", |response| parse_activate_result(response).unwrap());
+
+    assert_eq!(result.certificate_id, 52556);
+    assert!(result.is_success);
+    assert_eq!(result.dcv_records[0].host_name, "_0A1B2C3D4E5F.domain1.com");
+}
+
+#[test]
+fn test_ssl_get_info() {
+    let info: SslInfo = parsed("ssl.getInfo", |response| parse_ssl_info(response, 52556).unwrap());
+
+    assert_eq!((info.common_name.as_str(), info.ssl_type.as_str()), ("domain1.com", "PositiveSSL"));
+    assert!(info.issued_at.is_some() && info.expires_at.is_some());
+    assert_eq!(info.ca_certificates.len(), 1);
+    assert!(info.full_chain().unwrap().contains("INTERMEDIATE"));
+}
+
+#[test]
+fn test_ssl_get_list() {
+    let (certificates, pagination) = parsed("ssl.getList", parse_ssl_list);
+
+    assert_eq!(certificates.len(), 2);
+    assert_eq!(certificates[0].host_name, "domain1.com");
+    assert!(certificates[1].expires_at.is_none());
+    assert_eq!((pagination.current_page, pagination.total_pages), (1, 1));
+}
+
+#[test]
+fn test_users_address() {
+    assert_eq!(parsed("users.address.create", |response| parse_address_id(response, "AddressCreateResult")), Some(1041));
+
+    let profile = parsed("users.address.getInfo", |response| parse_address_info(response).unwrap());
+    assert_eq!(profile.address_name, "billing");
+    assert!(profile.is_default);
+    assert_eq!((profile.contact.organization_name.as_str(), profile.contact.postal_code.as_str()), ("NameCheap.com", "85284"));
+
+    let addresses = parsed("users.address.getList", parse_address_list);
+    assert_eq!(addresses.len(), 2);
+    assert!(addresses[1].is_default);
+}
+
+#[test]
+fn test_users_get_balances() {
+    let balances: AccountBalances = parsed("users.getBalances", |response| parse_balances(response).unwrap());
+
+    assert_eq!(balances.available_balance.to_string(), "4932.96 USD");
+    assert_eq!(balances.earned_amount.map(|amount| amount.to_string()).as_deref(), Some("381.70 USD"));
+}
+
+#[test]
+fn test_users_get_pricing() {
+    let prices = parsed("users.getPricing", |response| parse_pricing(response, ProductType::Domain).unwrap().prices);
+    assert_eq!(prices.len(), 3);
+
+    let [wrapped, _] = sample("users.getPricing");
+    let matrix = parse_pricing(&wrapped, ProductType::Domain).unwrap();
+    let renewal = matrix.price_for("com", Action::Renew, 1).unwrap();
+    assert_eq!(renewal.effective_price().to_string(), "13.98 USD");
+    // The documented `YourAdditonalCost` is misspelled, `AdditionalCost` is read instead
+    assert_eq!(renewal.additional_cost.as_ref().map(|cost| cost.to_string()).as_deref(), Some("0.18 USD"));
+    assert_eq!(matrix.categories(), ["register", "renew"]);
+}
+
@@ -0,0 +1,283 @@
+//! ### Contact Consistency Audit
+//!
+//! This module compares the contacts of every domain in the account against a reference
+//! [`ContactSet`] and reports inconsistencies, e.g. differing admin emails, stale addresses
+//! or a missing organization. It is meant to prepare ICANN compliance checks.
+//!
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, Contact, ContactSet, Domain };
+use crate::batch::{ Batch, BatchOutcome, BatchReport };
+use crate::utils::privacy::{ mask_email, mask_value };
+
+/// The kind of inconsistency found on a contact.
+///
+/// #### Variants
+/// - `EmailMismatch`: The email address differs from the reference.
+/// - `StaleAddress`: One or more postal address fields differ from the reference.
+/// - `NameMismatch`: The first or last name differs from the reference.
+/// - `MissingOrganization`: The reference has an organization but the contact has none.
+/// - `PhoneMismatch`: The phone number differs from the reference.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ContactIssueKind {
+    EmailMismatch,
+    StaleAddress,
+    NameMismatch,
+    MissingOrganization,
+    PhoneMismatch,
+}
+
+/// A single inconsistency found during the audit.
+///
+/// #### Fields
+/// - `domain`: The audited domain.
+/// - `role`: The contact role, e.g. `Admin`.
+/// - `kind`: The kind of inconsistency.
+/// - `expected`: The reference value.
+/// - `actual`: The value found on the domain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ContactFinding {
+    pub domain: String,
+    pub role: String,
+    pub kind: ContactIssueKind,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl ContactFinding {
+    /// Returns a copy with the compared values masked, see [`privacy`](crate::utils::privacy).
+    ///
+    /// Organization names are kept, they do not identify a person.
+    pub fn minimized(&self) -> ContactFinding {
+        let mask = |value: &str| match self.kind {
+            ContactIssueKind::EmailMismatch => mask_email(value),
+            ContactIssueKind::MissingOrganization => value.to_string(),
+            _ => mask_value(value),
+        };
+        ContactFinding {
+            expected: mask(&self.expected),
+            actual: mask(&self.actual),
+            ..self.clone()
+        }
+    }
+}
+
+/// The result of a contact audit.
+///
+/// #### Fields
+/// - `audited`: The number of domains whose contacts were fetched.
+/// - `findings`: The inconsistencies found.
+/// - `errors`: Domains whose contacts could not be fetched, with the error message.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactAuditReport {
+    pub audited: usize,
+    pub findings: Vec<ContactFinding>,
+    pub errors: Vec<(String, String)>,
+}
+
+impl ContactAuditReport {
+    /// Returns `true` if every domain was audited and no inconsistency was found.
+    pub fn is_consistent(&self) -> bool {
+        self.findings.is_empty() && self.errors.is_empty()
+    }
+}
+
+impl NameCheapClient {
+    /// Audits the contacts of every domain in the account against a reference.
+    ///
+    /// With [`PrivacyMode::Minimized`](crate::utils::privacy::PrivacyMode) the findings hold
+    /// masked values, see [`ContactFinding::minimized`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let report = client.audit_contacts(&reference).await.unwrap();
+    /// for finding in &report.findings {
+    ///     println!("{} {}: {:?}", finding.domain, finding.role, finding.kind);
+    /// }
+    /// ```
+    pub async fn audit_contacts(
+        &self,
+        reference: &ContactSet
+    ) -> Result<ContactAuditReport, Box<dyn Error>> {
+        let domains: Vec<String> = self
+            .domains_get_list_all().await?
+            .into_iter()
+            .map(|domain: Domain| domain.name)
+            .collect();
+        info!("Auditing contacts of {} domains", domains.len());
+
+        let fetched: BatchReport<ContactSet> = Batch::new().run(domains, |domain| async move {
+            self.domains_get_contact_set(&domain).await
+        }).await;
+
+        let mut report: ContactAuditReport = ContactAuditReport::default();
+        for item in fetched.results {
+            match item.outcome {
+                BatchOutcome::Success(actual) => {
+                    report.audited += 1;
+                    let findings: Vec<ContactFinding> = audit_contact_set(&item.key, &actual, reference);
+                    if self.privacy.is_minimized() {
+                        report.findings.extend(findings.iter().map(ContactFinding::minimized));
+                    } else {
+                        report.findings.extend(findings);
+                    }
+                }
+                BatchOutcome::Failed(error) => report.errors.push((item.key, error)),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Compares the contacts of one domain against a reference.
+///
+/// # Parameters
+///
+/// - `domain`: The domain the contacts belong to
+/// - `actual`: The contacts currently set on the domain
+/// - `reference`: The expected contacts
+///
+/// # Returns
+///
+/// The inconsistencies found, empty if the contacts match
+pub fn audit_contact_set(
+    domain: &str,
+    actual: &ContactSet,
+    reference: &ContactSet
+) -> Vec<ContactFinding> {
+    let roles: [(&str, &Contact, &Contact); 4] = [
+        ("Registrant", &actual.registrant, &reference.registrant),
+        ("Tech", &actual.tech, &reference.tech),
+        ("Admin", &actual.admin, &reference.admin),
+        ("AuxBilling", &actual.aux_billing, &reference.aux_billing),
+    ];
+
+    let mut findings: Vec<ContactFinding> = Vec::new();
+    for (role, actual, reference) in roles {
+        let mut finding = |kind: ContactIssueKind, expected: String, actual: String| {
+            findings.push(ContactFinding {
+                domain: domain.to_string(),
+                role: role.to_string(),
+                kind,
+                expected,
+                actual,
+            });
+        };
+
+        if !actual.email_address.eq_ignore_ascii_case(&reference.email_address) {
+            finding(
+                ContactIssueKind::EmailMismatch,
+                reference.email_address.clone(),
+                actual.email_address.clone()
+            );
+        }
+
+        if postal_address(actual) != postal_address(reference) {
+            finding(ContactIssueKind::StaleAddress, postal_address(reference), postal_address(actual));
+        }
+
+        if actual.first_name != reference.first_name || actual.last_name != reference.last_name {
+            finding(
+                ContactIssueKind::NameMismatch,
+                format!("{} {}", reference.first_name, reference.last_name),
+                format!("{} {}", actual.first_name, actual.last_name)
+            );
+        }
+
+        if !reference.organization_name.is_empty() && actual.organization_name.trim().is_empty() {
+            finding(
+                ContactIssueKind::MissingOrganization,
+                reference.organization_name.clone(),
+                actual.organization_name.clone()
+            );
+        }
+
+        if actual.phone != reference.phone {
+            finding(ContactIssueKind::PhoneMismatch, reference.phone.clone(), actual.phone.clone());
+        }
+    }
+
+    findings
+}
+
+/// Normalizes the postal address fields of a contact into a single comparable line.
+fn postal_address(contact: &Contact) -> String {
+    [
+        &contact.address_1,
+        &contact.address_2,
+        &contact.city,
+        &contact.state_province,
+        &contact.postal_code,
+        &contact.country,
+    ]
+        .iter()
+        .map(|part| part.trim().to_lowercase())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_contact() -> Contact {
+        Contact {
+            type_: String::new(),
+            address_1: "8939 S. Cross Blvd".to_string(),
+            address_2: String::new(),
+            city: "Phoenix".to_string(),
+            country: "US".to_string(),
+            email_address: "admin@example.com".to_string(),
+            fax: String::new(),
+            first_name: "John".to_string(),
+            job_title: String::new(),
+            last_name: "Smith".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.6613102107".to_string(),
+            phone_ext: String::new(),
+            postal_code: "85044".to_string(),
+            state_province: "AZ".to_string(),
+            state_province_choice: String::new(),
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_audit_contact_set() {
+        let reference: ContactSet = ContactSet::uniform(sample_contact());
+        let mut actual: ContactSet = reference.clone();
+        actual.admin.email_address = "old-admin@example.com".to_string();
+        actual.registrant.city = "Tempe".to_string();
+        actual.tech.organization_name = String::new();
+
+        let findings: Vec<ContactFinding> = audit_contact_set("example.com", &actual, &reference);
+        let kinds: Vec<(&str, ContactIssueKind)> = findings
+            .iter()
+            .map(|f| (f.role.as_str(), f.kind))
+            .collect();
+
+        assert_eq!(findings.len(), 3);
+        assert!(kinds.contains(&("Admin", ContactIssueKind::EmailMismatch)));
+        assert!(kinds.contains(&("Registrant", ContactIssueKind::StaleAddress)));
+        assert!(kinds.contains(&("Tech", ContactIssueKind::MissingOrganization)));
+        assert!(audit_contact_set("example.com", &reference, &reference).is_empty());
+
+        let email: ContactFinding = findings.iter().find(|f| f.kind == ContactIssueKind::EmailMismatch).unwrap().minimized();
+        assert!(email.expected.starts_with("a***@example.com#"));
+        assert_ne!(email.expected, email.actual);
+        let organization: ContactFinding = findings.iter().find(|f| f.kind == ContactIssueKind::MissingOrganization).unwrap().minimized();
+        assert_eq!(organization.expected, "Example Inc");
+    }
+}
@@ -0,0 +1,103 @@
+//! ### Auto-Renew Helpers
+//!
+//! This module provides helpers to read the auto-renew status of domains.
+//!
+//! The NameCheap API reports `AutoRenew` on every `domains.getList` entry but exposes no
+//! command to change it, so these helpers are read-only: they locate domains whose
+//! auto-renew is disabled so it can be switched on from the dashboard, or so the domains
+//! can be renewed explicitly with `domains.renew`.
+//!
+
+use serde_json::{ Value, json };
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Domain };
+use crate::utils::request_builder::Request;
+use crate::domains::get_list::parse_domain;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::get_nodes_at;
+use crate::response::timestamp::server_offset;
+
+impl NameCheapClient {
+    /// Looks up a single domain of the account by name.
+    ///
+    /// Uses the `SearchTerm` parameter of `domains.getList` and returns the exact match, if any.
+    pub async fn domains_find(&self, domain_name: &str) -> Result<Option<Domain>, Box<dyn Error>> {
+        let command: String = "namecheap.domains.getList".to_string();
+        let params: Value = json!({ "SearchTerm": domain_name });
+
+        let response: Value = Request::new(
+            self,
+            command,
+            Some(1),
+            None,
+            Some(params)
+        ).send().await?;
+        check_api_errors(&response)?;
+
+        let offset = server_offset(&response);
+        let domain: Option<Domain> = get_nodes_at(
+            &response,
+            "/ApiResponse/CommandResponse/DomainGetListResult/Domain"
+        )
+            .into_iter()
+            .filter_map(|domain| parse_domain(domain, offset).ok())
+            .find(|domain| domain.name.eq_ignore_ascii_case(domain_name));
+
+        Ok(domain)
+    }
+
+    /// Gets the auto-renew status of a domain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let auto_renew: bool = client.domains_get_auto_renew("example.com").await.unwrap();
+    /// ```
+    pub async fn domains_get_auto_renew(&self, domain_name: &str) -> Result<bool, Box<dyn Error>> {
+        self.domains_find(domain_name).await?
+            .map(|domain| domain.auto_renew)
+            .ok_or_else(|| format!("Domain not found in account: {}", domain_name).into())
+    }
+
+    /// Lists the domains of the account that do not renew automatically.
+    ///
+    /// Expired domains are left out, they need `domains.reactivate` rather than auto-renew.
+    pub async fn domains_without_auto_renew(&self) -> Result<Vec<Domain>, Box<dyn Error>> {
+        Ok(without_auto_renew(self.domains_get_list_all().await?))
+    }
+}
+
+/// Filters the domains that are active but have auto-renew disabled.
+pub fn without_auto_renew(domains: Vec<Domain>) -> Vec<Domain> {
+    domains
+        .into_iter()
+        .filter(|domain| !domain.auto_renew && !domain.is_expired)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_without_auto_renew() {
+        let utc: FixedOffset = FixedOffset::east_opt(0).unwrap();
+        let domains: Vec<Domain> = [
+            json!({ "id": "1", "name": "renews.com", "auto_renew": "true" }),
+            json!({ "id": "2", "name": "manual.com", "auto_renew": "false" }),
+            json!({ "id": "3", "name": "expired.com", "auto_renew": "false", "is_expired": "true" }),
+        ]
+            .iter()
+            .map(|domain| parse_domain(domain, utc).unwrap())
+            .collect();
+
+        let names: Vec<String> = without_auto_renew(domains)
+            .into_iter()
+            .map(|domain| domain.name)
+            .collect();
+        assert_eq!(names, vec!["manual.com".to_string()]);
+    }
+}
@@ -0,0 +1,192 @@
+//! ### `domains.check` Implementation
+//!
+//! This module provides the implementation for the `domains.check` method of the NameCheap API.
+//!
+//! It checks the availability of domains, along with their premium pricing. Lists longer than
+//! `MAX_CHECK_DOMAINS` are checked in several calls.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::{ check_api_errors, UnknownFields };
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::{ extra_fields, get_nodes_at, parse_opt_string, parse_string, Extra };
+use crate::limits::MAX_CHECK_DOMAINS;
+
+/// ### Domain Check Result
+///
+/// #### Fields
+/// - `domain`: The checked domain name.
+/// - `available`: Whether the domain can be registered.
+/// - `error_no`: The error number reported for this domain, `0` if none.
+/// - `description`: The error description reported for this domain (optional).
+/// - `is_premium_name`: Whether the domain is a premium name.
+/// - `premium_registration_price`: The premium registration price (optional).
+/// - `premium_renewal_price`: The premium renewal price (optional).
+/// - `premium_transfer_price`: The premium transfer price (optional).
+/// - `premium_restore_price`: The premium restore price (optional).
+/// - `eap_fee`: The Early Access Program fee charged on top of the registration price (optional).
+/// - `icann_fee`: The ICANN fee charged on top of the registration price (optional).
+/// - `extra`: The attributes the API returned that this struct does not model yet.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainCheckResult {
+    pub domain: String,
+    pub available: bool,
+    pub error_no: String,
+    pub description: Option<String>,
+    pub is_premium_name: bool,
+    pub premium_registration_price: Option<Money>,
+    pub premium_renewal_price: Option<Money>,
+    pub premium_transfer_price: Option<Money>,
+    pub premium_restore_price: Option<Money>,
+    pub eap_fee: Option<Money>,
+    pub icann_fee: Option<Money>,
+    #[serde(default, skip_serializing_if = "Extra::is_empty")]
+    pub extra: Extra,
+}
+
+impl NameCheapClient {
+    /// - `domains.check`: Checks the availability of domains
+    ///
+    /// Results are returned in the order of the API responses, one per checked domain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let results = client.domains_check(&["example.com", "example.net"]).await.unwrap();
+    /// for result in &results {
+    ///     println!("{}: {}", result.domain, result.available);
+    /// }
+    /// ```
+    pub async fn domains_check<S: AsRef<str>>(&self, domain_names: &[S]) -> Result<Vec<DomainCheckResult>, Box<dyn Error>> {
+        let mut results: Vec<DomainCheckResult> = Vec::with_capacity(domain_names.len());
+
+        for chunk in domain_names.chunks(MAX_CHECK_DOMAINS) {
+            let domain_list: String = chunk
+                .iter()
+                .map(|name| name.as_ref().trim())
+                .collect::<Vec<&str>>()
+                .join(",");
+            let params: Value = json!({ "DomainList": domain_list });
+
+            let response: Value = Request::new(
+                self,
+                "namecheap.domains.check".to_string(),
+                None,
+                None,
+                Some(params)
+            ).flatten_text().send().await?;
+            check_api_errors(&response)?;
+            info!("Response: {:#?}", response);
+
+            results.extend(parse_check_results(&response)?);
+        }
+        Ok(results)
+    }
+
+    /// Checks the availability of a single domain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_check_one("example.com").await?;
+    /// if result.available {
+    ///     println!("{} is available, EAP fee: {:?}", result.domain, result.eap_fee);
+    /// }
+    /// ```
+    pub async fn domains_check_one(&self, domain_name: &str) -> Result<DomainCheckResult, Box<dyn Error>> {
+        self.domains_check(&[domain_name])
+            .await?
+            .into_iter()
+            .find(|result| result.domain.eq_ignore_ascii_case(domain_name.trim()))
+            .ok_or_else(|| format!("No check result returned for domain: {}", domain_name).into())
+    }
+}
+
+/// The `DomainCheckResult` attributes mapped by [`parse_check_results`].
+const CHECK_FIELDS: &[&str] = &[
+    "domain",
+    "available",
+    "error_no",
+    "description",
+    "is_premium_name",
+    "premium_registration_price",
+    "premium_renewal_price",
+    "premium_transfer_price",
+    "premium_restore_price",
+    "eap_fee",
+    "icann_fee",
+];
+
+/// Parses the `DomainCheckResult` elements of a `domains.check` response.
+///
+/// Attributes missing from `CHECK_FIELDS` are kept in `extra`, see [`extra_fields`].
+pub(crate) fn parse_check_results(response: &Value) -> Result<Vec<DomainCheckResult>, UnknownFields> {
+    let currency: Currency = detect_currency(response).unwrap_or_default();
+
+    get_nodes_at(response, "/ApiResponse/CommandResponse/DomainCheckResult")
+        .into_iter()
+        .map(|result| {
+            let is_premium_name: bool = parse_string(result, "is_premium_name", "false") == "true";
+            // Non premium names report zero premium prices
+            let premium_price = |key: &str| parse_money(result, key, &currency).filter(|_| is_premium_name);
+            // Fees are reported as zero when none applies
+            let fee = |key: &str| parse_money(result, key, &currency).filter(Money::is_positive);
+
+            Ok(DomainCheckResult {
+                domain: parse_string(result, "domain", ""),
+                available: parse_string(result, "available", "false") == "true",
+                error_no: parse_string(result, "error_no", "0"),
+                description: parse_opt_string(result, "description").filter(|description| !description.is_empty()),
+                is_premium_name,
+                premium_registration_price: premium_price("premium_registration_price"),
+                premium_renewal_price: premium_price("premium_renewal_price"),
+                premium_transfer_price: premium_price("premium_transfer_price"),
+                premium_restore_price: premium_price("premium_restore_price"),
+                eap_fee: fee("eap_fee"),
+                icann_fee: fee("icann_fee"),
+                extra: extra_fields(result, "DomainCheckResult", CHECK_FIELDS)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_check_results() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.check">
+            <DomainCheckResult Domain="example.com" Available="false" ErrorNo="0" Description="" IsPremiumName="false" PremiumRegistrationPrice="0" PremiumRenewalPrice="0" PremiumRestorePrice="0" PremiumTransferPrice="0" IcannFee="0" EapFee="0" />
+            <DomainCheckResult Domain="us.xyz" Available="true" ErrorNo="0" Description="" IsPremiumName="true" PremiumRegistrationPrice="13000.0000" PremiumRenewalPrice="13000.0000" PremiumRestorePrice="65.0000" PremiumTransferPrice="13000.0000" IcannFee="0.1800" EapFee="150.0000" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let results: Vec<DomainCheckResult> = parse_check_results(&response).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].available);
+        assert_eq!(results[0].premium_registration_price, None);
+        assert!(results[1].is_premium_name);
+        assert_eq!(results[1].premium_renewal_price.as_ref().map(|price| price.to_string()).as_deref(), Some("13000.0000 USD"));
+
+        assert_eq!((results[0].eap_fee.as_ref(), results[0].icann_fee.as_ref()), (None, None));
+        assert_eq!(results[0].premium_transfer_price, None);
+        let price = |money: &Option<Money>| money.as_ref().map(Money::to_string);
+        assert_eq!(price(&results[1].premium_transfer_price).as_deref(), Some("13000.0000 USD"));
+        assert_eq!(price(&results[1].premium_restore_price).as_deref(), Some("65.0000 USD"));
+        assert_eq!(price(&results[1].eap_fee).as_deref(), Some("150.0000 USD"));
+        assert_eq!(price(&results[1].icann_fee).as_deref(), Some("0.1800 USD"));
+    }
+}
@@ -0,0 +1,125 @@
+//! ### `domains.create` Implementation
+//!
+//! This module provides the implementation for the `domains.create` method of the NameCheap API.
+//!
+//! It registers a new domain with the four contacts of a [`ContactSet`].
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, ContactSet };
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::{ get_attr, get_node_at, parse_string };
+
+/// ### Domain Create Result
+///
+/// #### Fields
+/// - `domain`: The registered domain name.
+/// - `registered`: Whether the registration succeeded.
+/// - `charged_amount`: The amount charged to the account, in the account currency (optional).
+/// - `domain_id`: The ID of the new domain.
+/// - `order_id`: The ID of the order.
+/// - `transaction_id`: The ID of the transaction.
+/// - `whois_guard_enabled`: Whether WHOIS guard was enabled on the domain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainCreateResult {
+    pub domain: String,
+    pub registered: bool,
+    pub charged_amount: Option<Money>,
+    pub domain_id: i64,
+    pub order_id: i64,
+    pub transaction_id: i64,
+    pub whois_guard_enabled: bool,
+}
+
+impl NameCheapClient {
+    /// - `domains.create`: Registers a new domain
+    ///
+    /// Registers `domain_name` for `years` years with the given contacts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let contacts: ContactSet = ContactSet::uniform(contact);
+    /// let result = client.domains_create("example.com", 1, &contacts).await.unwrap();
+    /// println!("Charged {:?}", result.charged_amount);
+    /// ```
+    pub async fn domains_create(
+        &self,
+        domain_name: &str,
+        years: u8,
+        contacts: &ContactSet
+    ) -> Result<DomainCreateResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.create".to_string();
+        let mut params: Map<String, Value> = contacts.to_params();
+        params.insert("Years".to_string(), json!(years.max(1)));
+
+        let request: Request = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            Some(Value::Object(params))
+        );
+        self.check_policies(&Mutation::CreateDomain { domain: domain_name.to_string(), years: years.max(1) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_create_result(&response).ok_or_else(|| {
+            format!("Failed to extract the registration of domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Parses a full `domains.create` response.
+pub(crate) fn parse_create_result(response: &Value) -> Option<DomainCreateResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainCreateResult")?;
+    // Falls back to USD, the default account currency, when the response carries none
+    let currency: Currency = detect_currency(response).unwrap_or_default();
+
+    Some(DomainCreateResult {
+        domain: parse_string(result, "domain", ""),
+        registered: parse_string(result, "registered", "false") == "true",
+        charged_amount: parse_money(result, "charged_amount", &currency),
+        domain_id: get_attr(result, "domain_id").unwrap_or(0),
+        order_id: get_attr(result, "order_id").unwrap_or(0),
+        transaction_id: get_attr(result, "transaction_id").unwrap_or(0),
+        whois_guard_enabled: parse_string(result, "whoisguard_enable", "false") == "true",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_create_result() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.create">
+            <DomainCreateResult Domain="example.com" Registered="true" ChargedAmount="20.3600" DomainID="9007" OrderID="196074" TransactionID="380716" WhoisguardEnable="false" NonRealTimeDomain="false" />
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let result: DomainCreateResult = parse_create_result(&response).unwrap();
+
+        assert_eq!(result.domain, "example.com");
+        assert!(result.registered);
+        assert_eq!(result.domain_id, 9007);
+        assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("20.3600 USD"));
+    }
+}
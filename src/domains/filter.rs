@@ -0,0 +1,257 @@
+//! ### Domain Filters
+//!
+//! A small typed expression language selecting domains for bulk operations:
+//!
+//! ```rust
+//! use namecheap::domains::filter::{ auto_renew, expiring_within, tld };
+//!
+//! let filter = expiring_within(30) & !auto_renew() & tld("com");
+//! let report = client.domains_set_contacts_bulk(Selector::Filter(filter), &contacts, true).await?;
+//! ```
+//!
+//! Most predicates read the `Domain` returned by `domains.getList`. A few (`premium_dns`,
+//! `status`) need the `DomainInfo` of `domains.getInfo`, which is only fetched for the domains
+//! the listing alone cannot decide, e.g. not for expiring domains when nothing expires.
+
+use serde::{ Serialize, Deserialize };
+use std::fmt;
+use std::ops::{ BitAnd, BitOr, Not };
+
+// crate imports
+use crate::Domain;
+use crate::domains::get_info::DomainInfo;
+use crate::domains::labels::Labels;
+
+/// A predicate over domains, combined with `&`, `|` and `!`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum DomainFilter {
+    /// The domain expires within the number of days.
+    ExpiringWithin(i64),
+    /// Auto-renew is enabled.
+    AutoRenew,
+    /// The domain is registrar locked.
+    Locked,
+    /// The domain is expired.
+    Expired,
+    /// The domain is a premium domain.
+    Premium,
+    /// The domain uses the Namecheap name servers.
+    OurDns,
+    /// WHOIS guard is enabled.
+    WhoisGuard,
+    /// The domain is under the TLD, e.g. `com` or `co.uk`.
+    Tld(String),
+    /// The domain carries the local label, see [`Labels`].
+    Label(String),
+    /// The domain is served by PremiumDNS (needs `DomainInfo`).
+    PremiumDns,
+    /// The `domains.getInfo` status matches, e.g. `Ok` (needs `DomainInfo`).
+    Status(String),
+    And(Box<DomainFilter>, Box<DomainFilter>),
+    Or(Box<DomainFilter>, Box<DomainFilter>),
+    Not(Box<DomainFilter>),
+}
+
+/// Matches domains expiring within `days` days.
+pub fn expiring_within(days: i64) -> DomainFilter {
+    DomainFilter::ExpiringWithin(days)
+}
+
+/// Matches domains with auto-renew enabled.
+pub fn auto_renew() -> DomainFilter {
+    DomainFilter::AutoRenew
+}
+
+/// Matches registrar locked domains.
+pub fn locked() -> DomainFilter {
+    DomainFilter::Locked
+}
+
+/// Matches expired domains.
+pub fn expired() -> DomainFilter {
+    DomainFilter::Expired
+}
+
+/// Matches premium domains.
+pub fn premium() -> DomainFilter {
+    DomainFilter::Premium
+}
+
+/// Matches domains using the Namecheap name servers.
+pub fn our_dns() -> DomainFilter {
+    DomainFilter::OurDns
+}
+
+/// Matches domains with WHOIS guard enabled.
+pub fn whois_guard() -> DomainFilter {
+    DomainFilter::WhoisGuard
+}
+
+/// Matches domains under `tld`, with or without the leading dot.
+pub fn tld(tld: &str) -> DomainFilter {
+    DomainFilter::Tld(tld.trim_start_matches('.').to_ascii_lowercase())
+}
+
+/// Matches domains carrying the local `label`.
+pub fn label(label: &str) -> DomainFilter {
+    DomainFilter::Label(label.to_string())
+}
+
+/// Matches domains served by PremiumDNS.
+pub fn premium_dns() -> DomainFilter {
+    DomainFilter::PremiumDns
+}
+
+/// Matches domains whose `domains.getInfo` status is `status`, case-insensitively.
+pub fn status(status: &str) -> DomainFilter {
+    DomainFilter::Status(status.to_string())
+}
+
+impl DomainFilter {
+    /// Evaluates the filter, `None` when it depends on a `DomainInfo` that is not given.
+    pub fn evaluate(&self, domain: &Domain, info: Option<&DomainInfo>, labels: &Labels) -> Option<bool> {
+        match self {
+            DomainFilter::ExpiringWithin(days) => Some(domain.expires_within(*days)),
+            DomainFilter::AutoRenew => Some(domain.auto_renew),
+            DomainFilter::Locked => Some(domain.is_locked),
+            DomainFilter::Expired => Some(domain.is_expired),
+            DomainFilter::Premium => Some(domain.is_premium),
+            DomainFilter::OurDns => Some(domain.is_our_dns),
+            DomainFilter::WhoisGuard => Some(domain.whois_guard.is_enabled()),
+            DomainFilter::Tld(tld) => {
+                let name: String = domain.name.to_ascii_lowercase();
+                Some(name.strip_suffix(tld.as_str()).is_some_and(|rest| rest.ends_with('.')))
+            }
+            DomainFilter::Label(label) => Some(labels.labels_of(&domain.name).contains(label)),
+            DomainFilter::PremiumDns => info.map(DomainInfo::is_premium_dns),
+            DomainFilter::Status(status) => info.map(|info| info.status.eq_ignore_ascii_case(status)),
+            DomainFilter::And(left, right) => {
+                match (left.evaluate(domain, info, labels), right.evaluate(domain, info, labels)) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }
+            }
+            DomainFilter::Or(left, right) => {
+                match (left.evaluate(domain, info, labels), right.evaluate(domain, info, labels)) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }
+            }
+            DomainFilter::Not(inner) => inner.evaluate(domain, info, labels).map(|matched| !matched),
+        }
+    }
+
+    /// Returns `true` if the domain matches; predicates needing a missing `DomainInfo` fail.
+    pub fn matches(&self, domain: &Domain, info: Option<&DomainInfo>, labels: &Labels) -> bool {
+        self.evaluate(domain, info, labels) == Some(true)
+    }
+
+    /// Returns `true` if the filter reads local labels.
+    pub fn uses_labels(&self) -> bool {
+        match self {
+            DomainFilter::Label(_) => true,
+            DomainFilter::And(left, right) | DomainFilter::Or(left, right) => left.uses_labels() || right.uses_labels(),
+            DomainFilter::Not(inner) => inner.uses_labels(),
+            _ => false,
+        }
+    }
+}
+
+impl BitAnd for DomainFilter {
+    type Output = DomainFilter;
+
+    fn bitand(self, other: DomainFilter) -> DomainFilter {
+        DomainFilter::And(Box::new(self), Box::new(other))
+    }
+}
+
+impl BitOr for DomainFilter {
+    type Output = DomainFilter;
+
+    fn bitor(self, other: DomainFilter) -> DomainFilter {
+        DomainFilter::Or(Box::new(self), Box::new(other))
+    }
+}
+
+impl Not for DomainFilter {
+    type Output = DomainFilter;
+
+    fn not(self) -> DomainFilter {
+        DomainFilter::Not(Box::new(self))
+    }
+}
+
+impl fmt::Display for DomainFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainFilter::ExpiringWithin(days) => write!(f, "expiring_within({})", days),
+            DomainFilter::AutoRenew => write!(f, "auto_renew"),
+            DomainFilter::Locked => write!(f, "locked"),
+            DomainFilter::Expired => write!(f, "expired"),
+            DomainFilter::Premium => write!(f, "premium"),
+            DomainFilter::OurDns => write!(f, "our_dns"),
+            DomainFilter::WhoisGuard => write!(f, "whois_guard"),
+            DomainFilter::Tld(tld) => write!(f, "tld({:?})", tld),
+            DomainFilter::Label(label) => write!(f, "label({:?})", label),
+            DomainFilter::PremiumDns => write!(f, "premium_dns"),
+            DomainFilter::Status(status) => write!(f, "status({:?})", status),
+            DomainFilter::And(left, right) => write!(f, "({} & {})", left, right),
+            DomainFilter::Or(left, right) => write!(f, "({} | {})", left, right),
+            DomainFilter::Not(inner) => write!(f, "!{}", inner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{ Duration, Utc };
+
+    fn domain(name: &str, days_left: i64, auto_renew: bool) -> Domain {
+        let mut domain: Domain = serde_json::from_value(serde_json::json!({ "id": 1, "name": name })).unwrap();
+        domain.expires_at = Some(Utc::now() + Duration::days(days_left));
+        domain.auto_renew = auto_renew;
+        domain
+    }
+
+    #[test]
+    fn test_expression() {
+        let filter: DomainFilter = expiring_within(30) & !auto_renew() & tld("com");
+        let labels: Labels = Labels::new();
+
+        assert!(filter.matches(&domain("example.com", 10, false), None, &labels));
+        assert!(!filter.matches(&domain("example.com", 10, true), None, &labels));
+        assert!(!filter.matches(&domain("example.com", 90, false), None, &labels));
+        assert!(!filter.matches(&domain("example.co.uk", 10, false), None, &labels));
+        assert!(!filter.matches(&domain("examplecom.net", 10, false), None, &labels));
+        assert!(tld(".co.uk").matches(&domain("Example.CO.UK", 10, false), None, &labels));
+        assert_eq!(filter.to_string(), "((expiring_within(30) & !auto_renew) & tld(\"com\"))");
+    }
+
+    #[test]
+    fn test_info_predicates_are_undecided_without_info() {
+        let labels: Labels = Labels::new();
+        let expiring: Domain = domain("example.com", 10, false);
+
+        assert_eq!(premium_dns().evaluate(&expiring, None, &labels), None);
+        // The listing alone decides these
+        assert_eq!((expiring_within(5) & premium_dns()).evaluate(&expiring, None, &labels), Some(false));
+        assert_eq!((expiring_within(30) | premium_dns()).evaluate(&expiring, None, &labels), Some(true));
+        assert_eq!((expiring_within(30) & !status("Locked")).evaluate(&expiring, None, &labels), None);
+    }
+
+    #[test]
+    fn test_labels_in_expression() {
+        let mut labels: Labels = Labels::new();
+        labels.add("example.com", "clientA");
+        let filter: DomainFilter = label("clientA") | label("clientB");
+
+        assert!(filter.uses_labels());
+        assert!(filter.matches(&domain("example.com", 100, true), None, &labels));
+        assert!(!filter.matches(&domain("example.net", 100, true), None, &labels));
+    }
+}
@@ -7,12 +7,12 @@
 
 use serde_json::{ Value, json };
 use std::error::Error;
-use tracing::{ info, error };
+use tracing::error;
 
 // crate imports
-use crate::{ NameCheapClient, Contact };
+use crate::{ NameCheapClient, Contact, ContactSet };
 use crate::utils::request_builder::Request;
-use crate::response::parse_value::parse_string;
+use crate::response::parse_value::{ get_node_at, parse_string };
 
 impl NameCheapClient {
     /// - `domains.getContacts`: Gets contact information for the specified domain
@@ -41,7 +41,7 @@ impl NameCheapClient {
         let command: String = "namecheap.domains.getContacts".to_string();
 
         let response: Value = Request::new(
-            self.clone(),
+            self,
             command,
             None,
             Some(domain_name.to_string()),
@@ -49,125 +49,10 @@ impl NameCheapClient {
   
         ).send().await?;
 
-        info!("Response: {:#?}", response);
+        self.log_contact_response(&response);
         // Extract contacts from the response
-        if let Some(api_response) = response.get("ApiResponse") {
-            if let Some(command_response) = api_response.get("CommandResponse") {
-                if let Some(result) = command_response.get("DomainContactsResult") {
-                    let mut contacts: Value = json!({});
-                    let mut whois_guard_contacts: Value = json!({});
-                    let has_whois_guard: bool = result.get("WhoisGuardContact").is_some();
-
-                    // Process each contact type
-                    for contact_type in &["Registrant", "Tech", "Admin", "AuxBilling"] {
-                        if let Some(contact_info) = result.get(contact_type) {
-                            // Create a Contact struct for each contact type
-                            let contact: Contact = Contact {
-                                type_: contact_type.to_string(),
-                                first_name: parse_string(contact_info, "FirstName", ""),
-                                last_name: parse_string(contact_info, "LastName", ""),
-                                address_1: parse_string(contact_info, "Address1", ""),
-                                address_2: parse_string(contact_info, "Address2", ""),
-                                city: parse_string(contact_info, "City", ""),
-                                state_province: parse_string(contact_info, "StateProvince", ""),
-                                state_province_choice: parse_string(
-                                    contact_info,
-                                    "StateProvinceChoice",
-                                    ""
-                                ),
-                                postal_code: parse_string(contact_info, "PostalCode", ""),
-                                country: parse_string(contact_info, "Country", ""),
-                                phone: parse_string(contact_info, "Phone", ""),
-                                phone_ext: parse_string(contact_info, "PhoneExt", ""),
-                                fax: parse_string(contact_info, "Fax", ""),
-                                email_address: parse_string(contact_info, "EmailAddress", ""),
-                                organization_name: parse_string(
-                                    contact_info,
-                                    "OrganizationName",
-                                    ""
-                                ),
-                                job_title: parse_string(contact_info, "JobTitle", ""),
-                                read_only: parse_string(contact_info, "read_only", "false") ==
-                                "true",
-                            };
-
-                            contacts[contact_type.to_lowercase()] = json!(contact);
-                        }
-
-                        // Process WhoisGuard contacts if available
-                        if has_whois_guard {
-                            if let Some(whois_guard) = result.get("WhoisGuardContact") {
-                                if let Some(whois_contact_info) = whois_guard.get(contact_type) {
-                                    let whois_contact: Contact = Contact {
-                                        type_: contact_type.to_string(),
-                                        first_name: parse_string(
-                                            whois_contact_info,
-                                            "FirstName",
-                                            ""
-                                        ),
-                                        last_name: parse_string(whois_contact_info, "LastName", ""),
-                                        address_1: parse_string(whois_contact_info, "Address1", ""),
-                                        address_2: parse_string(whois_contact_info, "Address2", ""),
-                                        city: parse_string(whois_contact_info, "City", ""),
-                                        state_province: parse_string(
-                                            whois_contact_info,
-                                            "StateProvince",
-                                            ""
-                                        ),
-                                        state_province_choice: parse_string(
-                                            whois_contact_info,
-                                            "StateProvinceChoice",
-                                            ""
-                                        ),
-                                        postal_code: parse_string(
-                                            whois_contact_info,
-                                            "PostalCode",
-                                            ""
-                                        ),
-                                        country: parse_string(whois_contact_info, "Country", ""),
-                                        phone: parse_string(whois_contact_info, "Phone", ""),
-                                        phone_ext: parse_string(whois_contact_info, "PhoneExt", ""),
-                                        fax: parse_string(whois_contact_info, "Fax", ""),
-                                        email_address: parse_string(
-                                            whois_contact_info,
-                                            "EmailAddress",
-                                            ""
-                                        ),
-                                        organization_name: parse_string(
-                                            whois_contact_info,
-                                            "OrganizationName",
-                                            ""
-                                        ),
-                                        job_title: parse_string(whois_contact_info, "JobTitle", ""),
-                                        read_only: parse_string(
-                                            whois_contact_info,
-                                            "read_only",
-                                            "false"
-                                        ) == "true",
-                                    };
-
-                                    whois_guard_contacts[contact_type.to_lowercase()] =
-                                        json!(whois_contact);
-                                }
-                            }
-                        }
-                    }
-
-                    // Add domain information and WhoisGuard contacts if available
-                    let mut result_json: Value =
-                        json!({
-                        "contacts": contacts,
-                        "domain": parse_string(result, "domain", ""),
-                        "domain_id": parse_string(result, "domainnameid", "")
-                    });
-
-                    if has_whois_guard {
-                        result_json["whois_guard_contacts"] = whois_guard_contacts;
-                    }
-
-                    return Ok(result_json);
-                }
-            }
+        if let Some(result_json) = parse_contacts(&response) {
+            return Ok(result_json);
         }
 
         // Return if no contacts found
@@ -179,12 +64,114 @@ impl NameCheapClient {
         })
         )
     }
+
+    /// Gets the typed registrant, tech, admin and billing contacts of a domain.
+    ///
+    /// Fails if the response does not contain all four contacts.
+    pub async fn domains_get_contact_set(&self, domain_name: &str) -> Result<ContactSet, Box<dyn Error>> {
+        let response: Value = self.domains_get_contacts(domain_name).await?;
+        let contacts: &Value = response
+            .get("contacts")
+            .ok_or_else(|| format!("No contacts returned for domain: {}", domain_name))?;
+
+        let role = |key: &str| -> Result<Contact, Box<dyn Error>> {
+            let contact: &Value = contacts
+                .get(key)
+                .ok_or_else(|| format!("Missing {} contact for domain: {}", key, domain_name))?;
+            Ok(serde_json::from_value(contact.clone())?)
+        };
+
+        Ok(ContactSet {
+            registrant: role("registrant")?,
+            tech: role("tech")?,
+            admin: role("admin")?,
+            aux_billing: role("auxbilling")?,
+        })
+    }
+}
+
+/// Parses a full `domains.getContacts` response.
+///
+/// `whois_guard_contacts` is only set when the response has a `WhoisGuardContact` section;
+/// missing contact types are left out.
+pub(crate) fn parse_contacts(response: &Value) -> Option<Value> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainContactsResult")?;
+    let mut contacts: Value = json!({});
+    let mut whois_guard_contacts: Value = json!({});
+    let whois_guard: Option<&Value> = result.get("WhoisGuardContact");
+
+    // Process each contact type
+    for contact_type in &["Registrant", "Tech", "Admin", "AuxBilling"] {
+        if let Some(contact_info) = result.get(contact_type) {
+            contacts[contact_type.to_lowercase()] = json!(parse_contact(contact_type, contact_info));
+        }
+
+        // Process WhoisGuard contacts if available
+        if let Some(whois_contact_info) = whois_guard.and_then(|w| w.get(contact_type)) {
+            whois_guard_contacts[contact_type.to_lowercase()] = json!(parse_contact(contact_type, whois_contact_info));
+        }
+    }
+
+    // Add domain information and WhoisGuard contacts if available
+    let mut result_json: Value =
+        json!({
+        "contacts": contacts,
+        "domain": parse_string(result, "domain", ""),
+        "domain_id": parse_string(result, "domainnameid", "")
+    });
+
+    if whois_guard.is_some() {
+        result_json["whois_guard_contacts"] = whois_guard_contacts;
+    }
+    Some(result_json)
+}
+
+/// Parses a single contact element (e.g. `Registrant`) of a `domains.getContacts` response.
+pub(crate) fn parse_contact(contact_type: &str, contact_info: &Value) -> Contact {
+    Contact {
+        type_: contact_type.to_string(),
+        first_name: parse_string(contact_info, "FirstName", ""),
+        last_name: parse_string(contact_info, "LastName", ""),
+        address_1: parse_string(contact_info, "Address1", ""),
+        address_2: parse_string(contact_info, "Address2", ""),
+        city: parse_string(contact_info, "City", ""),
+        state_province: parse_string(contact_info, "StateProvince", ""),
+        state_province_choice: parse_string(contact_info, "StateProvinceChoice", ""),
+        postal_code: parse_string(contact_info, "PostalCode", ""),
+        country: parse_string(contact_info, "Country", ""),
+        phone: parse_string(contact_info, "Phone", ""),
+        phone_ext: parse_string(contact_info, "PhoneExt", ""),
+        fax: parse_string(contact_info, "Fax", ""),
+        email_address: parse_string(contact_info, "EmailAddress", ""),
+        organization_name: parse_string(contact_info, "OrganizationName", ""),
+        job_title: parse_string(contact_info, "JobTitle", ""),
+        read_only: parse_string(contact_info, "read_only", "false") == "true",
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use dotenv::dotenv;
+    use tracing::info;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_contacts_without_whois_guard() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.getContacts">
+            <DomainContactsResult Domain="domain1.com" domainnameid="3152456">
+              <Registrant ReadOnly="false"><FirstName>John</FirstName><LastName>Smith</LastName><Country>US</Country></Registrant>
+            </DomainContactsResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let contacts: Value = parse_contacts(&response).unwrap();
+
+        assert_eq!(contacts["domain"], "domain1.com");
+        assert_eq!(contacts["contacts"]["registrant"]["firstName"], "John");
+        assert!(contacts["contacts"].get("tech").is_none());
+        assert!(contacts.get("whois_guard_contacts").is_none());
+    }
 
     #[tokio::test]
     async fn test_domains_get_contacts() {
@@ -0,0 +1,299 @@
+//! ### `domains.getInfo` Implementation
+//!
+//! This module provides the implementation for the `domains.getInfo` method of the NameCheap API.
+//!
+//! It retrieves detailed information about a domain, including its DNS details and
+//! PremiumDNS subscription.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+use chrono::{ DateTime, FixedOffset, Utc };
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, WhoisGuardStatus };
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_attr, get_node_at, get_nodes_at, parse_string, text_of };
+use crate::response::timestamp::{ server_offset, to_utc };
+
+/// ### DNS Details
+///
+/// The `DnsDetails` section of a `domains.getInfo` response.
+///
+/// #### Fields
+/// - `provider_type`: The DNS provider type, e.g. `FREE`, `CUSTOM` or `PREMIUM`.
+/// - `is_using_our_dns`: Whether the domain uses NameCheap's DNS.
+/// - `host_count`: The number of host records.
+/// - `email_type`: The email type, e.g. `FWD`, `MX` or `OX`.
+/// - `dynamic_dns_status`: Whether dynamic DNS is enabled.
+/// - `is_failover`: Whether DNS failover is enabled.
+/// - `nameservers`: The nameservers of the domain.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DnsDetails {
+    pub provider_type: String,
+    pub is_using_our_dns: bool,
+    pub host_count: i64,
+    pub email_type: String,
+    pub dynamic_dns_status: bool,
+    pub is_failover: bool,
+    pub nameservers: Vec<String>,
+}
+
+/// ### PremiumDNS Subscription
+///
+/// The `PremiumDnsSubscription` section of a `domains.getInfo` response.
+///
+/// #### Fields
+/// - `use_auto_renew`: Whether the subscription renews automatically.
+/// - `subscription_id`: The subscription ID, `-1` when there is no subscription.
+/// - `created_date`: The subscription creation date in UTC.
+/// - `expiration_date`: The subscription expiration date in UTC.
+/// - `is_active`: Whether the subscription is active.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PremiumDnsSubscription {
+    pub use_auto_renew: bool,
+    pub subscription_id: i64,
+    pub created_date: Option<DateTime<Utc>>,
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub is_active: bool,
+}
+
+/// ### Domain Info
+///
+/// The typed result of `domains.getInfo`.
+///
+/// #### Fields
+/// - `id`: The unique identifier for the domain.
+/// - `domain_name`: The name of the domain.
+/// - `owner_name`: The account owning the domain.
+/// - `is_owner`: Whether the API user owns the domain.
+/// - `is_premium`: Whether the domain is a premium domain.
+/// - `status`: The domain status, e.g. `Ok` or `Locked`.
+/// - `created`: The creation date in UTC.
+/// - `expires`: The expiration date in UTC.
+/// - `whois_guard`: The WHOIS guard status.
+/// - `dns_details`: The DNS details of the domain, when reported.
+/// - `premium_dns`: The PremiumDNS subscription, when reported.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainInfo {
+    pub id: i64,
+    pub domain_name: String,
+    pub owner_name: String,
+    pub is_owner: bool,
+    pub is_premium: bool,
+    pub status: String,
+    pub created: Option<DateTime<Utc>>,
+    pub expires: Option<DateTime<Utc>>,
+    pub whois_guard: WhoisGuardStatus,
+    pub dns_details: Option<DnsDetails>,
+    pub premium_dns: Option<PremiumDnsSubscription>,
+}
+
+impl DomainInfo {
+    /// Returns `true` if the domain is served by PremiumDNS.
+    pub fn is_premium_dns(&self) -> bool {
+        self.premium_dns.as_ref().is_some_and(|subscription| subscription.is_active) ||
+            self.dns_details.as_ref().is_some_and(|dns| dns.provider_type.eq_ignore_ascii_case("PREMIUM"))
+    }
+}
+
+impl NameCheapClient {
+    /// - `domains.getInfo`: Gets detailed information about the specified domain
+    ///
+    /// Gets detailed information about the specified domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use namecheap::NameCheapClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = NameCheapClient::new(
+    ///         "api_user".to_string(),
+    ///         "api_key".to_string(),
+    ///         "client_ip".to_string(),
+    ///         "user_name".to_string(),
+    ///         false
+    ///     );
+    ///
+    ///     let info = client.domains_get_info("example.com").await.unwrap();
+    ///     println!("PremiumDNS: {}", info.is_premium_dns());
+    /// }
+    /// ```
+    pub async fn domains_get_info(&self, domain_name: &str) -> Result<DomainInfo, Box<dyn Error>> {
+        let command: String = "namecheap.domains.getInfo".to_string();
+
+        let response: Value = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            None
+        ).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_domain_info(&response).ok_or_else(|| {
+            format!("Failed to extract domain info for domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Parses a full `domains.getInfo` response into a `DomainInfo`.
+pub(crate) fn parse_domain_info(response: &Value) -> Option<DomainInfo> {
+    let offset: FixedOffset = server_offset(response);
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainGetInfoResult")?;
+
+    let dns_details: Option<DnsDetails> = result
+        .get("DnsDetails")
+        .map(|dns| DnsDetails {
+            provider_type: parse_string(dns, "provider_type", ""),
+            is_using_our_dns: parse_string(dns, "is_using_our_dns", "false") == "true",
+            host_count: get_attr(dns, "host_count").unwrap_or(0),
+            email_type: parse_string(dns, "email_type", ""),
+            dynamic_dns_status: parse_string(dns, "dynamic_dnsstatus", "false") == "true",
+            is_failover: parse_string(dns, "is_failover", "false") == "true",
+            nameservers: get_nodes_at(dns, "/Nameserver")
+                .into_iter()
+                .filter_map(text_of)
+                .map(|nameserver| nameserver.to_string())
+                .collect(),
+        });
+
+    let premium_dns: Option<PremiumDnsSubscription> = result
+        .get("PremiumDnsSubscription")
+        .map(|premium| PremiumDnsSubscription {
+            use_auto_renew: parse_string(premium, "UseAutoRenew", "false") == "true",
+            subscription_id: get_attr(premium, "SubscriptionId").unwrap_or(-1),
+            created_date: to_utc(&parse_string(premium, "CreatedDate", ""), offset),
+            expiration_date: to_utc(&parse_string(premium, "ExpirationDate", ""), offset),
+            is_active: parse_string(premium, "IsActive", "false") == "true",
+        });
+
+    let whois_guard: WhoisGuardStatus = match result.get("Whoisguard") {
+        Some(whois_guard) if parse_string(whois_guard, "enabled", "").eq_ignore_ascii_case("true") =>
+            WhoisGuardStatus::Enabled,
+        Some(whois_guard) if parse_string(whois_guard, "enabled", "").eq_ignore_ascii_case("false") =>
+            WhoisGuardStatus::Disabled,
+        _ => WhoisGuardStatus::NotPresent,
+    };
+
+    Some(DomainInfo {
+        id: get_attr(result, "id").unwrap_or(0),
+        domain_name: parse_string(result, "domain_name", ""),
+        owner_name: parse_string(result, "owner_name", ""),
+        is_owner: parse_string(result, "is_owner", "false") == "true",
+        is_premium: parse_string(result, "is_premium", "false") == "true",
+        status: parse_string(result, "status", ""),
+        created: to_utc(
+            result
+                .pointer("/DomainDetails/CreatedDate")
+                .and_then(text_of)
+                .unwrap_or(""),
+            offset
+        ),
+        expires: to_utc(
+            result
+                .pointer("/DomainDetails/ExpiredDate")
+                .and_then(text_of)
+                .unwrap_or(""),
+            offset
+        ),
+        whois_guard,
+        dns_details,
+        premium_dns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json, parse_xml_to_json_with, ParseOptions };
+
+    const SAMPLE_RESPONSE: &str =
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<ApiResponse Status="OK" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+  <RequestedCommand>namecheap.domains.getInfo</RequestedCommand>
+  <CommandResponse Type="namecheap.domains.getInfo">
+    <DomainGetInfoResult Status="Ok" ID="1234" DomainName="domain1.com" OwnerName="owner" IsOwner="true" IsPremium="false">
+      <DomainDetails>
+        <CreatedDate>02/15/2016</CreatedDate>
+        <ExpiredDate>02/15/2022</ExpiredDate>
+        <NumYears>0</NumYears>
+      </DomainDetails>
+      <LockDetails />
+      <Whoisguard Enabled="True">
+        <ID>53536</ID>
+        <ExpiredDate>11/04/2022</ExpiredDate>
+      </Whoisguard>
+      <PremiumDnsSubscription>
+        <UseAutoRenew>false</UseAutoRenew>
+        <SubscriptionId>123</SubscriptionId>
+        <CreatedDate>2021-01-01T00:00:00</CreatedDate>
+        <ExpirationDate>2022-01-01T00:00:00</ExpirationDate>
+        <IsActive>true</IsActive>
+      </PremiumDnsSubscription>
+      <DnsDetails ProviderType="FREE" IsUsingOurDNS="true" HostCount="5" EmailType="FWD" DynamicDNSStatus="false" IsFailover="false">
+        <Nameserver>dns1.registrar-servers.com</Nameserver>
+        <Nameserver>dns2.registrar-servers.com</Nameserver>
+      </DnsDetails>
+      <Modificationrights All="true" />
+    </DomainGetInfoResult>
+  </CommandResponse>
+  <Server>WEB1-SANDBOX1</Server>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+  <ExecutionTime>0.008</ExecutionTime>
+</ApiResponse>"#;
+
+    #[test]
+    fn test_parse_domain_info() {
+        let response: Value = parse_xml_to_json_with(SAMPLE_RESPONSE, ParseOptions::flat()).unwrap();
+        let info: DomainInfo = parse_domain_info(&response).unwrap();
+
+        assert_eq!(info.id, 1234);
+        assert_eq!(info.domain_name, "domain1.com");
+        assert!(info.is_owner);
+        assert_eq!(info.whois_guard, WhoisGuardStatus::Enabled);
+        let dns: &DnsDetails = info.dns_details.as_ref().unwrap();
+        assert_eq!(dns.host_count, 5);
+        assert_eq!(dns.nameservers.len(), 2);
+        assert!(dns.is_using_our_dns);
+        assert_eq!(info.premium_dns.as_ref().map(|p| p.subscription_id), Some(123));
+        assert!(info.is_premium_dns());
+        assert_eq!(info.expires.unwrap().to_rfc3339(), "2022-02-15T05:00:00+00:00");
+
+        // Both shapes of the tree parse alike
+        let wrapped: Value = parse_xml_to_json(SAMPLE_RESPONSE).unwrap();
+        assert_eq!(parse_domain_info(&wrapped), Some(info));
+    }
+
+    #[test]
+    fn test_parse_minimal_domain_info() {
+        // Sandbox responses may leave out every optional section
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.getInfo">
+            <DomainGetInfoResult Status="Ok" ID="1234" DomainName="domain1.com" OwnerName="owner" IsOwner="true" IsPremium="false" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let info: DomainInfo = parse_domain_info(&response).unwrap();
+
+        assert_eq!(info.domain_name, "domain1.com");
+        assert_eq!(info.dns_details, None);
+        assert_eq!(info.premium_dns, None);
+        assert_eq!(info.whois_guard, WhoisGuardStatus::NotPresent);
+        assert!(info.expires.is_none());
+        assert!(!info.is_premium_dns());
+    }
+}
@@ -5,20 +5,45 @@
 //! It retrieves a list of domains associated with the user's account.
 //!
 //!
-use serde_json::{ Value, json, Map };
-use tracing::{ info, error, warn };
+use serde_json::{ Value, json };
+use tracing::warn;
 use std::error::Error;
+use chrono::FixedOffset;
+use futures_util::stream::{ self, StreamExt };
 
 // crate imports
-use crate::{ NameCheapClient, Domain };
+use crate::{ NameCheapClient, Domain, WhoisGuardStatus };
 use crate::utils::request_builder::Request;
-use crate::response::paging::extract_pagination_info;
-use crate::response::parse_value::{ parse_string, parse_bool, parse_i64 };
+use crate::response::paging::{ extract_pagination_info, PageSize, Pagination };
+use crate::domains::list_query::DomainListQuery;
+use crate::response::envelope::{ Environment, ResponseEnvelope };
+use crate::utils::cancellation::{ CancelHandle, Cancelled };
+use crate::utils::rate_limit::RateLimiter;
+use crate::utils::progress::{ ProgressHandle, ProgressTracker };
+use crate::response::parsed_page::ParsedPage;
+use crate::response::typed_xml::parse_domains_xml;
+use crate::response::timestamp::{ server_offset, to_utc };
+use crate::response::parse_value::{
+    get_node_at,
+    parse_string,
+    parse_bool,
+    parse_opt_bool,
+    parse_opt_string,
+    extra_fields,
+    Extra,
+};
+
+/// The number of `domains.getList` pages fetched concurrently by `domains_get_list_all`.
+pub const DEFAULT_PAGE_CONCURRENCY: usize = 4;
 
 impl NameCheapClient {
     /// - `domains.getList`: Gets a list of domains for the specified user
     /// Gets a list of domains for the specified user
     ///
+    /// Domain elements that fail to parse are not replaced by defaults, they are
+    /// returned under `skipped` as [`ParseIssue`](crate::response::parsed_page::ParseIssue)s.
+    /// `environment` tells whether the sandbox or production answered.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -52,131 +77,275 @@ impl NameCheapClient {
     ///         is_expired: false,
     ///         is_locked: false,
     ///         auto_renew: false,
-    ///         whois_guard: true,
+    ///         whois_guard: Enabled,
     ///         is_premium: false,
     ///         is_our_dns: true,
+    ///         is_dns_active: None,
+    ///         is_failover_active: None,
+    ///         whois_guard_expires: None,
+    ///         created_at: Some(2024-07-14T04:00:00Z),
+    ///         expires_at: Some(2025-07-14T04:00:00Z),
+    ///         extra: {},
     ///     }
     /// ]
     /// ```
     pub async fn domains_get_list(&self, page: i64) -> Result<Value, Box<dyn Error>> {
+        self.domains_get_list_page(page, PageSize::DEFAULT).await
+    }
+
+    /// Gets one page of domains with an explicit page size.
+    ///
+    /// Same result as [`domains_get_list`](Self::domains_get_list), with up to
+    /// `page_size` domains per page.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let first_page: Value = client.domains_get_list_page(1, PageSize::MAX).await.unwrap();
+    /// ```
+    pub async fn domains_get_list_page(&self, page: i64, page_size: PageSize) -> Result<Value, Box<dyn Error>> {
+        self.domains_get_list_query(&DomainListQuery::new().page(page).page_size(page_size)).await
+    }
+
+    /// Gets one page of domains with the options of a `DomainListQuery`.
+    ///
+    /// Same result as [`domains_get_list`](Self::domains_get_list).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let query = DomainListQuery::new().page_size(PageSize::MAX).sort_by(SortBy::ExpireDate);
+    /// let expiring_first: Value = client.domains_get_list_query(&query).await.unwrap();
+    /// ```
+    pub async fn domains_get_list_query(&self, query: &DomainListQuery) -> Result<Value, Box<dyn Error>> {
         let command: String = "namecheap.domains.getList".to_string();
-        let page: i64 = page.max(1);
-        let page: Option<i64> = Some(page);
+        let page: Option<i64> = Some(query.page.max(1));
+        let params: Value = query.params();
 
         let response: Value = Request::new(
-            self.clone(),
+            self,
             command,
             page,
             None,
-            None,
+            Some(params),
         
         ).send().await?;
 
+        let environment: Environment = ResponseEnvelope::from_response(&response, self.production).environment;
+
         // Extract domains from the response
-        if let Some(api_response) = response.get("ApiResponse") {
-            if let Some(command_response) = api_response.get("CommandResponse") {
-                let paging: Option<&Map<String, Value>> = command_response
-                    .get("Paging")
-                    .and_then(|p| p.as_object());
-
-                // Extract pagination information using the utility function
-                let (current_page, page_size, total_items, total_pages) =
-                    extract_pagination_info(paging);
-                if let Some(result) = command_response.get("DomainGetListResult") {
-                    // Extract pagination information
-                    // Extract pagination information from the Paging object
-
-                    if let Some(domains) = result.get("Domain") {
-                        if let Some(domains_array) = domains.as_array() {
-                            let mut domain_list: Vec<Domain> = Vec::new();
-
-                            for domain in domains_array {
-                                let id: i64 = parse_i64(domain, "id", 0);
-                                let name: String = parse_string(domain, "name", "");
-                                let user: String = parse_string(domain, "user", "");
-                                let created: String = parse_string(domain, "created", "");
-                                let expires: String = parse_string(domain, "expires", "");
-                                let is_expired: bool = parse_bool(
-                                    domain,
-                                    "is_expired",
-                                    "false",
-                                    "true"
-                                );
-                                let is_locked: bool = parse_bool(
-                                    domain,
-                                    "is_locked",
-                                    "false",
-                                    "true"
-                                );
-                                let auto_renew: bool = parse_bool(
-                                    domain,
-                                    "auto_renew",
-                                    "false",
-                                    "true"
-                                );
-                                let whois_guard: bool = parse_bool(
-                                    domain,
-                                    "whois_guard",
-                                    "NOTPRESENT",
-                                    "ENABLED"
-                                );
-                                let is_premium: bool = parse_bool(
-                                    domain,
-                                    "is_premium",
-                                    "false",
-                                    "true"
-                                );
-                                let is_our_dns: bool = parse_bool(
-                                    domain,
-                                    "is_our_dns",
-                                    "false",
-                                    "true"
-                                );
-
-                                domain_list.push(Domain {
-                                    id,
-                                    name,
-                                    user,
-                                    created,
-                                    expires,
-                                    is_expired,
-                                    is_locked,
-                                    auto_renew,
-                                    whois_guard,
-                                    is_premium,
-                                    is_our_dns,
-                                });
-                            }
-
-                            // Create a Value object with domains and pagination info
-                            let result_value: Value =
-                                json!({
-                                "domains": domain_list,
-                                "pagination": {
-                                    "currentPage": current_page,
-                                    "totalPages": total_pages
-                                }
-                            });
-
-                            return Ok(result_value);
-                        }
-                    }
-                }
+        let paging: Option<&Value> = get_node_at(&response, "/ApiResponse/CommandResponse/Paging");
+        let (current_page, page_size, total_items, total_pages) = extract_pagination_info(paging);
+
+        if
+            let Some(result) = get_node_at(
+                &response,
+                "/ApiResponse/CommandResponse/DomainGetListResult"
+            )
+        {
+            let offset: FixedOffset = server_offset(&response);
+            let page: ParsedPage<Domain> = ParsedPage::parse(result.get("Domain"), |domain| {
+                parse_domain(domain, offset)
+            });
+
+            for issue in &page.skipped {
+                warn!("Skipped domain at index {}: {}", issue.index, issue.reason);
+            }
+
+            // Safeguard against silently truncated pages
+            let expected: i64 = expected_page_items(current_page, page_size, total_items);
+            if paging.is_some() && (page.seen() as i64) != expected {
+                warn!("Expected {} domains on page {}, found {}", expected, current_page, page.seen());
             }
+
+            // Create a Value object with domains and pagination info
+            let result_value: Value =
+                json!({
+                "domains": page.items,
+                "skipped": page.skipped,
+                "pagination": Pagination { current_page, total_pages },
+                "environment": environment
+            });
+
+            return Ok(result_value);
         }
 
         // Return empty result with default pagination
         let empty_result: Value =
             json!({
             "domains": [],
-            "pagination": {
-                "currentPage": 1,
-                "totalPages": 1
-            }
+            "skipped": [],
+            "pagination": Pagination::default(),
+            "environment": environment
         });
 
         Ok(empty_result)
     }
+
+    /// Gets every domain of the account by walking all `domains.getList` pages.
+    ///
+    /// Pages are requested with [`PageSize::MAX`] to keep the number of calls low. Once the
+    /// first page reports the page count, the remaining pages are fetched concurrently, see
+    /// [`domains_get_list_all_with`](Self::domains_get_list_all_with).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let domains: Vec<Domain> = client.domains_get_list_all().await.unwrap();
+    /// println!("{} domains", domains.len());
+    /// ```
+    pub async fn domains_get_list_all(&self) -> Result<Vec<Domain>, Box<dyn Error>> {
+        self.domains_get_list_all_with(&RateLimiter::default(), DEFAULT_PAGE_CONCURRENCY).await
+    }
+
+    /// Gets every domain of the account, fetching pages after the first concurrently.
+    ///
+    /// # Parameters
+    ///
+    /// - `limiter`: Paces every page request, share it to keep other calls in the same budget
+    /// - `concurrency`: The maximum number of pages in flight
+    ///
+    /// Domains are returned in page order regardless of the order pages complete in.
+    pub async fn domains_get_list_all_with(
+        &self,
+        limiter: &RateLimiter,
+        concurrency: usize
+    ) -> Result<Vec<Domain>, Box<dyn Error>> {
+        self.domains_get_list_all_with_progress(limiter, concurrency, &ProgressHandle::default()).await
+    }
+
+    /// Gets every domain of the account like
+    /// [`domains_get_list_all_with`](Self::domains_get_list_all_with), reporting each fetched
+    /// page to `progress`.
+    pub async fn domains_get_list_all_with_progress(
+        &self,
+        limiter: &RateLimiter,
+        concurrency: usize,
+        progress: &ProgressHandle
+    ) -> Result<Vec<Domain>, Box<dyn Error>> {
+        let tracker: &ProgressTracker = &progress.tracker("domains.getList", None);
+        // The limiter may not be the client's own, so its waits are cancelled here
+        let cancellation: &CancelHandle = self.shared.cancellation();
+        cancellation.run(limiter.acquire()).await.ok_or_else(|| Cancelled::new("domains.getList", 0, None))?;
+        let (mut domains, total_pages) = self.domain_records_page(1).await?;
+        tracker.set_total(total_pages.max(1) as usize);
+        tracker.advance("page 1");
+
+        // Errors are kept as strings so the futures stay `Send`
+        let pages: Vec<Result<Vec<Domain>, String>> = stream
+            ::iter(2..=total_pages)
+            .map(|page| async move {
+                cancellation.run(limiter.acquire()).await.ok_or_else(|| format!("Page {}: cancelled", page))?;
+                let fetched = self.domain_records_page(page).await
+                    .map(|(domains, _)| domains)
+                    .map_err(|e| format!("Page {}: {}", page, e));
+                tracker.advance(&format!("page {}", page));
+                fetched
+            })
+            .buffered(concurrency.max(1))
+            .collect().await;
+
+        if cancellation.is_cancelled() {
+            let completed: usize = 1 + pages.iter().filter(|page| page.is_ok()).count();
+            if completed < total_pages.max(1) as usize {
+                return Err(Box::new(Cancelled::new("domains.getList", completed, Some(total_pages as usize))));
+            }
+        }
+
+        for page in pages {
+            domains.extend(page?);
+        }
+
+        Ok(domains)
+    }
+
+    /// Fetches one page of `PageSize::MAX` domains, parsed straight from the XML.
+    ///
+    /// Returns the domains of the page and the total number of pages.
+    async fn domain_records_page(&self, page: i64) -> Result<(Vec<Domain>, i64), Box<dyn Error>> {
+        let query: DomainListQuery = DomainListQuery::new().page(page).page_size(PageSize::MAX);
+        let response: String = Request::new(
+            self,
+            "namecheap.domains.getList".to_string(),
+            Some(query.page),
+            None,
+            Some(query.params()),
+        ).send_text().await?;
+
+        let (parsed, total_pages) = parse_domains_xml(&response)?;
+        for issue in &parsed.skipped {
+            warn!("Skipped domain at index {} of page {}: {}", issue.index, page, issue.reason);
+        }
+        Ok((parsed.items, total_pages))
+    }
+}
+
+/// The `Domain` attributes mapped by [`parse_domain`].
+const DOMAIN_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "user",
+    "created",
+    "expires",
+    "is_expired",
+    "is_locked",
+    "auto_renew",
+    "whois_guard",
+    "is_premium",
+    "is_our_dns",
+    "is_dns_active",
+    "is_failover_active",
+    "whois_guard_expired_date",
+];
+
+/// Parses a single `Domain` element of a `domains.getList` response.
+///
+/// Elements without a numeric `ID` or a `Name` are rejected instead of being
+/// filled in with defaults. Dates are normalized to UTC using the server `offset`.
+/// Attributes missing from `DOMAIN_FIELDS` are kept in `extra`, see [`extra_fields`].
+pub(crate) fn parse_domain(domain: &Value, offset: FixedOffset) -> Result<Domain, String> {
+    let id: i64 = parse_string(domain, "id", "")
+        .parse::<i64>()
+        .map_err(|_| "missing or invalid ID attribute".to_string())?;
+    let name: String = parse_string(domain, "name", "");
+    if name.is_empty() {
+        return Err(format!("domain {} has no Name attribute", id));
+    }
+
+    let created: String = parse_string(domain, "created", "");
+    let expires: String = parse_string(domain, "expires", "");
+    let extra: Extra = extra_fields(domain, "Domain", DOMAIN_FIELDS).map_err(|e| e.to_string())?;
+
+    Ok(Domain {
+        id,
+        name,
+        user: parse_string(domain, "user", ""),
+        created_at: to_utc(&created, offset),
+        expires_at: to_utc(&expires, offset),
+        created,
+        expires,
+        is_expired: parse_bool(domain, "is_expired", "false", "true"),
+        is_locked: parse_bool(domain, "is_locked", "false", "true"),
+        auto_renew: parse_bool(domain, "auto_renew", "false", "true"),
+        whois_guard: WhoisGuardStatus::from_api_str(
+            &parse_string(domain, "whois_guard", "NOTPRESENT")
+        ),
+        is_premium: parse_bool(domain, "is_premium", "false", "true"),
+        is_our_dns: parse_bool(domain, "is_our_dns", "false", "true"),
+        is_dns_active: parse_opt_bool(domain, "is_dns_active", "true"),
+        is_failover_active: parse_opt_bool(domain, "is_failover_active", "true"),
+        whois_guard_expires: parse_opt_string(domain, "whois_guard_expired_date"),
+        extra,
+    })
+}
+
+/// The number of items a page should hold according to its `Paging` node.
+///
+/// Corrupt values, e.g. a negative `PageSize`, give 0 rather than panicking.
+fn expected_page_items(current_page: i64, page_size: i64, total_items: i64) -> i64 {
+    let page_size: i64 = page_size.max(0);
+    let before: i64 = current_page.saturating_sub(1).saturating_mul(page_size);
+    total_items.saturating_sub(before).clamp(0, page_size)
 }
 
 #[cfg(test)]
@@ -184,6 +353,31 @@ mod tests {
     use super::*;
     use dotenv::dotenv;
     use tracing::info;
+    use serde_json::json;
+
+    #[test]
+    #[cfg(not(feature = "strict"))]
+    fn test_parse_domain_extra_fields() {
+        let offset: FixedOffset = FixedOffset::east_opt(0).unwrap();
+        let element: Value = json!({ "id": "1", "name": "example.com", "is_new_attribute": "true" });
+
+        let domain: Domain = parse_domain(&element, offset).unwrap();
+        assert_eq!(domain.extra.get("is_new_attribute").map(String::as_str), Some("true"));
+        let known: Domain = parse_domain(&json!({ "id": "1", "name": "example.com" }), offset).unwrap();
+        assert!(known.extra.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn test_parse_domain_extra_fields_strict() {
+        let offset: FixedOffset = FixedOffset::east_opt(0).unwrap();
+        let element: Value = json!({ "id": "1", "name": "example.com", "is_new_attribute": "true" });
+
+        let error: String = parse_domain(&element, offset).unwrap_err();
+        assert!(error.contains("is_new_attribute"));
+        let known: Domain = parse_domain(&json!({ "id": "1", "name": "example.com" }), offset).unwrap();
+        assert!(known.extra.is_empty());
+    }
 
     #[tokio::test]
     async fn test_domains_get_list() {
@@ -199,4 +393,30 @@ mod tests {
         assert!(domains.get("domains").is_some());
         assert!(domains.get("pagination").is_some());
     }
+
+    #[test]
+    fn test_expected_page_items() {
+        assert_eq!(expected_page_items(1, 20, 45), 20);
+        assert_eq!(expected_page_items(3, 20, 45), 5);
+        assert_eq!(expected_page_items(4, 20, 45), 0);
+
+        let paging: Value = json!({ "CurrentPage": { "$text": "1" }, "PageSize": { "$text": "-1" }, "TotalItems": { "$text": "45" } });
+        let (current_page, page_size, total_items, _) = extract_pagination_info(Some(&paging));
+        assert_eq!(page_size, -1);
+        assert_eq!(expected_page_items(current_page, page_size, total_items), 0);
+        assert_eq!(expected_page_items(i64::MAX, i64::MAX, 45), 0);
+    }
+
+    #[test]
+    fn test_parse_domain_rejects_missing_id() {
+        let valid: Value = json!({ "id": "127", "name": "example.com", "whois_guard": "ENABLED" });
+        let invalid: Value = json!({ "name": "example.com" });
+
+        let utc: FixedOffset = FixedOffset::east_opt(0).unwrap();
+
+        let domain: Domain = parse_domain(&valid, utc).unwrap();
+        assert_eq!(domain.id, 127);
+        assert_eq!(domain.whois_guard, WhoisGuardStatus::Enabled);
+        assert!(parse_domain(&invalid, utc).is_err());
+    }
 }
@@ -0,0 +1,129 @@
+//! ### `domains.getRegistrarLock` Implementation
+//!
+//! This module provides the implementation for the `domains.getRegistrarLock` method of the NameCheap API.
+//!
+//! It reads whether a domain is protected against transfers to another registrar. A domain
+//! has to be unlocked before it can be transferred out.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+
+/// The registrar lock status of a domain.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrarLockStatus {
+    /// Transfers to another registrar are refused.
+    Locked,
+    /// The domain can be transferred out.
+    Unlocked,
+}
+
+impl RegistrarLockStatus {
+    /// Parses the `RegistrarLockStatus` attribute, `true` meaning locked.
+    pub fn from_api_str(value: &str) -> Self {
+        if value.trim().eq_ignore_ascii_case("true") {
+            RegistrarLockStatus::Locked
+        } else {
+            RegistrarLockStatus::Unlocked
+        }
+    }
+
+    /// Returns `true` for `Locked`.
+    pub fn is_locked(&self) -> bool {
+        *self == RegistrarLockStatus::Locked
+    }
+}
+
+impl fmt::Display for RegistrarLockStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrarLockStatus::Locked => write!(f, "locked"),
+            RegistrarLockStatus::Unlocked => write!(f, "unlocked"),
+        }
+    }
+}
+
+/// ### Domain Registrar Lock
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `status`: Whether the domain is locked against transfers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainRegistrarLock {
+    pub domain: String,
+    pub status: RegistrarLockStatus,
+}
+
+impl NameCheapClient {
+    /// - `domains.getRegistrarLock`: Gets the registrar lock status of a domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let lock = client.domains_get_registrar_lock("example.com").await?;
+    /// if !lock.status.is_locked() {
+    ///     println!("{} can be transferred out", lock.domain);
+    /// }
+    /// ```
+    pub async fn domains_get_registrar_lock(&self, domain_name: &str) -> Result<DomainRegistrarLock, Box<dyn Error>> {
+        let command: String = "namecheap.domains.getRegistrarLock".to_string();
+
+        let response: Value = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            None
+        ).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_registrar_lock(&response).ok_or_else(|| {
+            format!("Failed to extract the registrar lock of domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Parses a full `domains.getRegistrarLock` response.
+pub(crate) fn parse_registrar_lock(response: &Value) -> Option<DomainRegistrarLock> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainGetRegistrarLockResult")?;
+
+    Some(DomainRegistrarLock {
+        domain: parse_string(result, "domain", ""),
+        status: RegistrarLockStatus::from_api_str(&parse_string(result, "registrar_lock_status", "false")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_registrar_lock() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.getRegistrarLock">
+            <DomainGetRegistrarLockResult Domain="example.com" RegistrarLockStatus="false" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let lock: DomainRegistrarLock = parse_registrar_lock(&response).unwrap();
+
+        assert_eq!(lock.domain, "example.com");
+        assert_eq!(lock.status, RegistrarLockStatus::Unlocked);
+        assert!(RegistrarLockStatus::from_api_str("True").is_locked());
+        assert_eq!(serde_json::to_string(&lock.status).unwrap(), "\"unlocked\"");
+    }
+}
@@ -12,6 +12,7 @@ use tracing::{ info, error };
 // crate imports
 use crate::NameCheapClient;
 use crate::utils::request_builder::Request;
+use crate::response::parse_value::get_node_at;
 
 impl NameCheapClient {
     /// - `domains.getTldList`: Gets a list of supported TLDs
@@ -43,7 +44,7 @@ impl NameCheapClient {
         let command: String = "namecheap.domains.getTldList".to_string();
 
         let response: Value = Request::new(
-            self.clone(),
+            self,
             command,
             None,
             None,
@@ -52,14 +53,8 @@ impl NameCheapClient {
         ).send().await?;
 
         // Extract TLDs from the response
-        if let Some(api_response) = response.get("ApiResponse") {
-            if let Some(command_response) = api_response.get("CommandResponse") {
-                if let Some(tlds) = command_response.get("Tlds") {
-                    if let Some(tld_list) = tlds.get("Tld") {
-                        return Ok(tld_list.clone());
-                    }
-                }
-            }
+        if let Some(tld_list) = get_node_at(&response, "/ApiResponse/CommandResponse/Tlds/Tld") {
+            return Ok(tld_list.clone());
         }
 
         error!("Failed to retrieve TLD list");
@@ -0,0 +1,284 @@
+//! ### Domain Labels
+//!
+//! Namecheap has no notion of groups, so a portfolio managed for several clients is one flat
+//! list. `Labels` attaches local labels to domains (`clientA`, `parked`, `prod`), persisted in
+//! a [`StateStore`] attached with [`NameCheapClient::with_label_store`].
+//!
+//! Bulk operations take a [`Selector`]: an explicit list of domains, a label resolved
+//! against the store when the operation runs, so "renew everything for client A" is
+//! `Selector::Label("clientA".into())`, or a [`DomainFilter`] expression evaluated against
+//! the account's domains.
+
+use serde::{ Serialize, Deserialize };
+use std::collections::{ BTreeMap, BTreeSet, HashMap };
+use std::error::Error;
+use std::sync::Arc;
+
+// crate imports
+use crate::{ NameCheapClient, Domain };
+use crate::batch::{ Batch, BatchOutcome, BatchReport };
+use crate::domains::filter::DomainFilter;
+use crate::domains::get_info::DomainInfo;
+use crate::utils::state_store::{ load_state, save_state, StateStore };
+
+/// The state store key holding the labels.
+pub const LABELS_KEY: &str = "labels";
+
+/// The labels of every labeled domain, keyed by lowercase domain name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Labels(BTreeMap<String, BTreeSet<String>>);
+
+impl Labels {
+    /// Creates an empty set of labels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the labels from `store`, empty if none were saved.
+    pub fn load(store: &dyn StateStore) -> Result<Self, Box<dyn Error>> {
+        Ok(load_state(store, LABELS_KEY)?.unwrap_or_default())
+    }
+
+    /// Saves the labels to `store`.
+    pub fn save(&self, store: &dyn StateStore) -> Result<(), Box<dyn Error>> {
+        save_state(store, LABELS_KEY, self)
+    }
+
+    /// Adds `label` to `domain`. Returns `false` if the domain already had it.
+    pub fn add(&mut self, domain: &str, label: &str) -> bool {
+        self.0.entry(domain.to_ascii_lowercase()).or_default().insert(label.to_string())
+    }
+
+    /// Removes `label` from `domain`. Returns `false` if the domain did not have it.
+    pub fn remove(&mut self, domain: &str, label: &str) -> bool {
+        let domain: String = domain.to_ascii_lowercase();
+        let Some(labels) = self.0.get_mut(&domain) else { return false };
+        let removed: bool = labels.remove(label);
+        if labels.is_empty() {
+            self.0.remove(&domain);
+        }
+        removed
+    }
+
+    /// Returns the labels of `domain`, in order.
+    pub fn labels_of(&self, domain: &str) -> Vec<String> {
+        self.0
+            .get(&domain.to_ascii_lowercase())
+            .map(|labels| labels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the domains carrying `label`, in order.
+    pub fn domains_with(&self, label: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(_, labels)| labels.contains(label))
+            .map(|(domain, _)| domain.clone())
+            .collect()
+    }
+
+    /// Returns every label in use, in order.
+    pub fn all_labels(&self) -> BTreeSet<String> {
+        self.0.values().flatten().cloned().collect()
+    }
+}
+
+/// The domains a bulk operation runs over.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Selector {
+    /// An explicit list, used as is.
+    Domains(Vec<String>),
+    /// Every domain carrying the label.
+    Label(String),
+    /// Every domain of the account matching the filter.
+    Filter(DomainFilter),
+}
+
+impl Selector {
+    /// Resolves the selector to domain names against `labels` and the account's `domains`.
+    ///
+    /// Filter predicates needing a `DomainInfo` do not match here, see
+    /// [`NameCheapClient::select`] which fetches them.
+    pub fn resolve(&self, labels: &Labels, domains: &[Domain]) -> Vec<String> {
+        match self {
+            Selector::Domains(domains) => domains.clone(),
+            Selector::Label(label) => labels.domains_with(label),
+            Selector::Filter(filter) => domains
+                .iter()
+                .filter(|domain| filter.matches(domain, None, labels))
+                .map(|domain| domain.name.clone())
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if resolving needs the labels.
+    pub fn needs_labels(&self) -> bool {
+        match self {
+            Selector::Domains(_) => false,
+            Selector::Label(_) => true,
+            Selector::Filter(filter) => filter.uses_labels(),
+        }
+    }
+}
+
+impl From<DomainFilter> for Selector {
+    fn from(filter: DomainFilter) -> Self {
+        Selector::Filter(filter)
+    }
+}
+
+impl From<Vec<String>> for Selector {
+    fn from(domains: Vec<String>) -> Self {
+        Selector::Domains(domains)
+    }
+}
+
+impl From<&[String]> for Selector {
+    fn from(domains: &[String]) -> Self {
+        Selector::Domains(domains.to_vec())
+    }
+}
+
+impl From<&Vec<String>> for Selector {
+    fn from(domains: &Vec<String>) -> Self {
+        Selector::Domains(domains.clone())
+    }
+}
+
+impl NameCheapClient {
+    /// Persists domain labels in `store` and resolves `Selector::Label` against it.
+    ///
+    /// Like `with_rate_limiter`, call this before cloning the client.
+    pub fn with_label_store(mut self, store: Arc<dyn StateStore>) -> Self {
+        self.shared = self.shared.with_labels(store);
+        self
+    }
+
+    /// Loads the current labels, empty without a label store.
+    pub fn labels(&self) -> Result<Labels, Box<dyn Error>> {
+        match self.shared.labels() {
+            Some(store) => Labels::load(store),
+            None => Ok(Labels::new()),
+        }
+    }
+
+    /// Changes the stored labels with `change`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// client.update_labels(|labels| { labels.add("example.com", "clientA"); })?;
+    /// ```
+    pub fn update_labels(&self, change: impl FnOnce(&mut Labels)) -> Result<Labels, Box<dyn Error>> {
+        let store: &dyn StateStore = self.shared.labels().ok_or("No label store, see with_label_store")?;
+        let mut labels: Labels = Labels::load(store)?;
+        change(&mut labels);
+        labels.save(store)?;
+        Ok(labels)
+    }
+
+    /// Resolves a selector to the domain names it covers.
+    ///
+    /// Selecting by label without a label store is an error rather than an empty selection. A
+    /// filter lists every domain of the account and fetches `domains.getInfo` only for the
+    /// domains the listing cannot decide.
+    pub async fn select(&self, selector: &Selector) -> Result<Vec<String>, Box<dyn Error>> {
+        let labels: Labels = if selector.needs_labels() {
+            let store: &dyn StateStore = self.shared.labels().ok_or("Selecting by label needs a label store, see with_label_store")?;
+            Labels::load(store)?
+        } else {
+            Labels::new()
+        };
+        let Selector::Filter(filter) = selector else {
+            return Ok(selector.resolve(&labels, &[]));
+        };
+
+        let domains: Vec<Domain> = self.domains_get_list_all().await?;
+        let undecided: Vec<String> = domains
+            .iter()
+            .filter(|domain| filter.evaluate(domain, None, &labels).is_none())
+            .map(|domain| domain.name.clone())
+            .collect();
+        let mut infos: HashMap<String, DomainInfo> = HashMap::new();
+        if !undecided.is_empty() {
+            let report: BatchReport<DomainInfo> = Batch::new().run(undecided, |domain| async move {
+                self.domains_get_info(&domain).await
+            }).await;
+            for item in report.results {
+                match item.outcome {
+                    BatchOutcome::Success(info) => {
+                        infos.insert(item.key, info);
+                    }
+                    BatchOutcome::Failed(error) => return Err(format!("Failed to get info of {}: {}", item.key, error).into()),
+                }
+            }
+        }
+
+        Ok(domains
+            .iter()
+            .filter(|domain| filter.matches(domain, infos.get(&domain.name), &labels))
+            .map(|domain| domain.name.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::state_store::MemoryStore;
+
+    #[test]
+    fn test_labels() {
+        let mut labels: Labels = Labels::new();
+        assert!(labels.add("Example.com", "clientA"));
+        assert!(!labels.add("example.com", "clientA"));
+        labels.add("example.net", "clientA");
+        labels.add("example.net", "parked");
+
+        assert_eq!(labels.domains_with("clientA"), ["example.com", "example.net"]);
+        assert_eq!(labels.labels_of("EXAMPLE.NET"), ["clientA", "parked"]);
+        assert!(labels.remove("example.com", "clientA"));
+        assert!(labels.labels_of("example.com").is_empty());
+        assert_eq!(labels.all_labels().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_select_by_label() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let explicit: Vec<String> = vec!["example.org".to_string()];
+        assert_eq!(client.select(&Selector::from(&explicit)).await.unwrap(), explicit);
+        assert!(client.select(&Selector::Label("clientA".to_string())).await.is_err());
+
+        let store: Arc<MemoryStore> = Arc::new(MemoryStore::new());
+        let client: NameCheapClient = client.with_label_store(store.clone());
+        client.update_labels(|labels| {
+            labels.add("example.com", "clientA");
+            labels.add("example.net", "clientB");
+        }).unwrap();
+
+        assert_eq!(client.clone().select(&Selector::Label("clientA".to_string())).await.unwrap(), ["example.com"]);
+        assert_eq!(Labels::load(store.as_ref()).unwrap().labels_of("example.net"), ["clientB"]);
+    }
+
+    #[test]
+    fn test_resolve_filter() {
+        let domains: Vec<Domain> = serde_json::from_value(serde_json::json!([
+            { "id": 1, "name": "example.com", "autoRenew": true },
+            { "id": 2, "name": "example.net" },
+        ])).unwrap();
+        let selector: Selector = Selector::from(!crate::domains::filter::auto_renew());
+
+        assert!(!selector.needs_labels());
+        assert_eq!(selector.resolve(&Labels::new(), &domains), ["example.net"]);
+    }
+}
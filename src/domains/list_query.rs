@@ -0,0 +1,173 @@
+//! ### `domains.getList` Query
+//!
+//! Typed options for `domains.getList`, so callers never pass NameCheap's magic strings.
+//! The SSL counterparts live in `ssl::get_list`.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+
+// crate imports
+use crate::response::paging::PageSize;
+
+/// The subsets of domains offered by `domains.getList`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[derive(PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ListType {
+    #[default]
+    All,
+    Expiring,
+    Expired,
+}
+
+impl ListType {
+    /// Every list type, in the order of `limits::LIST_TYPES`.
+    pub const ALL: [ListType; 3] = [ListType::All, ListType::Expiring, ListType::Expired];
+
+    /// Returns the value sent as `ListType`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListType::All => "ALL",
+            ListType::Expiring => "EXPIRING",
+            ListType::Expired => "EXPIRED",
+        }
+    }
+}
+
+/// The sort orders offered by `domains.getList`, matching the dashboard columns.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SortBy {
+    Name,
+    NameDesc,
+    #[serde(rename = "EXPIREDATE")]
+    ExpireDate,
+    #[serde(rename = "EXPIREDATE_DESC")]
+    ExpireDateDesc,
+    #[serde(rename = "CREATEDATE")]
+    CreateDate,
+    #[serde(rename = "CREATEDATE_DESC")]
+    CreateDateDesc,
+}
+
+impl SortBy {
+    /// Every sort order, in the order of `limits::SORT_ORDERS`.
+    pub const ALL: [SortBy; 6] = [
+        SortBy::Name,
+        SortBy::NameDesc,
+        SortBy::ExpireDate,
+        SortBy::ExpireDateDesc,
+        SortBy::CreateDate,
+        SortBy::CreateDateDesc,
+    ];
+
+    /// Returns the value sent as `SortBy`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortBy::Name => "NAME",
+            SortBy::NameDesc => "NAME_DESC",
+            SortBy::ExpireDate => "EXPIREDATE",
+            SortBy::ExpireDateDesc => "EXPIREDATE_DESC",
+            SortBy::CreateDate => "CREATEDATE",
+            SortBy::CreateDateDesc => "CREATEDATE_DESC",
+        }
+    }
+}
+
+/// ### Domain List Query
+///
+/// The options of a `domains.getList` call, see
+/// [`domains_get_list_query`](crate::NameCheapClient::domains_get_list_query).
+///
+/// #### Fields
+/// - `page`: The page to fetch, starting at 1.
+/// - `page_size`: The number of domains per page.
+/// - `sort_by`: The sort order, the API default (by name) when not set.
+/// - `list_type`: The subset of domains to list, every domain by default.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainListQuery {
+    pub page: i64,
+    pub page_size: PageSize,
+    pub sort_by: Option<SortBy>,
+    pub list_type: ListType,
+}
+
+impl Default for DomainListQuery {
+    fn default() -> Self {
+        DomainListQuery { page: 1, page_size: PageSize::DEFAULT, sort_by: None, list_type: ListType::All }
+    }
+}
+
+impl DomainListQuery {
+    /// Creates a query for the first page with the default page size and sort order.
+    pub fn new() -> Self {
+        DomainListQuery::default()
+    }
+
+    /// Sets the page to fetch, pages below 1 fetch the first page.
+    pub fn page(mut self, page: i64) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    /// Sets the number of domains per page.
+    pub fn page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the sort order.
+    pub fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    /// Sets the subset of domains to list.
+    pub fn list_type(mut self, list_type: ListType) -> Self {
+        self.list_type = list_type;
+        self
+    }
+
+    /// Returns the request parameters of the query, except `Page`.
+    pub fn params(&self) -> Value {
+        let mut params: Value = json!({ "PageSize": self.page_size.get() });
+        if self.list_type != ListType::All {
+            params["ListType"] = json!(self.list_type.as_str());
+        }
+        if let Some(sort_by) = self.sort_by {
+            params["SortBy"] = json!(sort_by.as_str());
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::{ LIST_TYPES, SORT_ORDERS };
+
+    #[test]
+    fn test_sort_by_matches_api_values() {
+        let values: Vec<&str> = SortBy::ALL.iter().map(SortBy::as_str).collect();
+        assert_eq!(values, SORT_ORDERS);
+        for sort_by in SortBy::ALL {
+            assert_eq!(serde_json::to_value(sort_by).unwrap(), json!(sort_by.as_str()));
+        }
+
+        let query: DomainListQuery = DomainListQuery::new().page(0).sort_by(SortBy::ExpireDateDesc);
+        assert_eq!(query.page, 1);
+        assert_eq!(query.params(), json!({ "PageSize": 20, "SortBy": "EXPIREDATE_DESC" }));
+    }
+
+    #[test]
+    fn test_list_type_matches_api_values() {
+        let values: Vec<&str> = ListType::ALL.iter().map(ListType::as_str).collect();
+        assert_eq!(values, LIST_TYPES);
+
+        let query: DomainListQuery = DomainListQuery::new().list_type(ListType::Expiring);
+        assert_eq!(query.params(), json!({ "PageSize": 20, "ListType": "EXPIRING" }));
+    }
+}
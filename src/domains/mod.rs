@@ -23,8 +23,37 @@ use crate::utils::request_builder::Request;
 
 /// - **domains.getList**
 pub mod get_list;
+/// - **domains.getList query options**
+pub mod list_query;
 /// - **domains.getContacts**
 pub mod get_contacts;
+/// - **domains.setContacts**
+pub mod set_contacts;
+/// - **contact consistency audit**
+pub mod audit_contacts;
+/// - **auto-renew status helpers**
+pub mod auto_renew;
+/// - **domains.getInfo**
+pub mod get_info;
+/// - **portfolio summary**
+pub mod portfolio;
+/// - **domain labels and selectors**
+pub mod labels;
+/// - **domain filter expressions**
+pub mod filter;
 /// - **domains.create**
+pub mod create;
 /// - **domains.getTldList**
-pub mod get_tld_list;
\ No newline at end of file
+pub mod get_tld_list;
+/// - **domains.check**
+pub mod check;
+/// - **domains.renew**
+pub mod renew;
+/// - **domains.reactivate**
+pub mod reactivate;
+/// - **domains.getRegistrarLock**
+pub mod get_registrar_lock;
+/// - **domains.setRegistrarLock**
+pub mod set_registrar_lock;
+/// - **cheapest TLD suggestions**
+pub mod suggest;
\ No newline at end of file
@@ -0,0 +1,143 @@
+//! ### Portfolio Summary
+//!
+//! This module aggregates the domains of an account into a `PortfolioSummary`, e.g. how many
+//! domains are expired, expire soon, renew automatically or are served by PremiumDNS.
+//!
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Domain };
+use crate::batch::{ Batch, BatchOutcome, BatchReport };
+use crate::domains::get_info::DomainInfo;
+
+/// The number of days considered "expiring soon" in a `PortfolioSummary`.
+pub const EXPIRING_SOON_DAYS: i64 = 30;
+
+/// ### Portfolio Summary
+///
+/// Aggregated counts over the domains of an account.
+///
+/// #### Fields
+/// - `total`: The number of domains.
+/// - `expired`: The number of expired domains.
+/// - `expiring_soon`: The number of domains expiring within `EXPIRING_SOON_DAYS`.
+/// - `auto_renew`: The number of domains with auto-renew enabled.
+/// - `locked`: The number of locked domains.
+/// - `whois_guard_enabled`: The number of domains with WHOIS guard enabled.
+/// - `premium`: The number of premium domains.
+/// - `our_dns`: The number of domains using NameCheap's DNS.
+/// - `premium_dns`: The number of domains on PremiumDNS, `None` when details were not fetched.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PortfolioSummary {
+    pub total: usize,
+    pub expired: usize,
+    pub expiring_soon: usize,
+    pub auto_renew: usize,
+    pub locked: usize,
+    pub whois_guard_enabled: usize,
+    pub premium: usize,
+    pub our_dns: usize,
+    pub premium_dns: Option<usize>,
+}
+
+impl PortfolioSummary {
+    /// Summarizes a list of domains, with optional `domains.getInfo` details.
+    ///
+    /// `premium_dns` is only counted when `infos` is provided.
+    pub fn summarize(domains: &[Domain], infos: Option<&[DomainInfo]>) -> Self {
+        let count = |predicate: fn(&Domain) -> bool| domains.iter().filter(|d| predicate(d)).count();
+
+        PortfolioSummary {
+            total: domains.len(),
+            expired: count(|d| d.is_expired),
+            expiring_soon: domains
+                .iter()
+                .filter(|d| !d.is_expired && d.expires_within(EXPIRING_SOON_DAYS))
+                .count(),
+            auto_renew: count(|d| d.auto_renew),
+            locked: count(|d| d.is_locked),
+            whois_guard_enabled: count(|d| d.whois_guard.is_enabled()),
+            premium: count(|d| d.is_premium),
+            our_dns: count(|d| d.is_our_dns),
+            premium_dns: infos.map(|infos| infos.iter().filter(|i| i.is_premium_dns()).count()),
+        }
+    }
+}
+
+impl NameCheapClient {
+    /// Builds a summary of every domain in the account.
+    ///
+    /// With `with_details` set, `domains.getInfo` is called for each domain to count the
+    /// domains on PremiumDNS. This costs one API call per domain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let summary = client.portfolio_summary(true).await.unwrap();
+    /// println!("{} of {} domains on PremiumDNS", summary.premium_dns.unwrap_or(0), summary.total);
+    /// ```
+    pub async fn portfolio_summary(
+        &self,
+        with_details: bool
+    ) -> Result<PortfolioSummary, Box<dyn Error>> {
+        let domains: Vec<Domain> = self.domains_get_list_all().await?;
+        if !with_details {
+            return Ok(PortfolioSummary::summarize(&domains, None));
+        }
+
+        let names: Vec<String> = domains
+            .iter()
+            .map(|domain| domain.name.clone())
+            .collect();
+        let report: BatchReport<DomainInfo> = Batch::new().run(names, |domain| async move {
+            self.domains_get_info(&domain).await
+        }).await;
+
+        let infos: Vec<DomainInfo> = report.results
+            .into_iter()
+            .filter_map(|item| {
+                match item.outcome {
+                    BatchOutcome::Success(info) => Some(info),
+                    BatchOutcome::Failed(_) => None,
+                }
+            })
+            .collect();
+
+        Ok(PortfolioSummary::summarize(&domains, Some(&infos)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::get_list::parse_domain;
+    use chrono::FixedOffset;
+    use serde_json::json;
+
+    #[test]
+    fn test_portfolio_summary() {
+        let utc: FixedOffset = FixedOffset::east_opt(0).unwrap();
+        let domains: Vec<Domain> = [
+            json!({ "id": "1", "name": "a.com", "auto_renew": "true", "whois_guard": "ENABLED" }),
+            json!({ "id": "2", "name": "b.com", "is_expired": "true", "is_our_dns": "true" }),
+            json!({ "id": "3", "name": "c.com", "is_locked": "true", "is_premium": "true" }),
+        ]
+            .iter()
+            .map(|domain| parse_domain(domain, utc).unwrap())
+            .collect();
+
+        let summary: PortfolioSummary = PortfolioSummary::summarize(&domains, None);
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.expired, 1);
+        assert_eq!(summary.auto_renew, 1);
+        assert_eq!(summary.locked, 1);
+        assert_eq!(summary.whois_guard_enabled, 1);
+        assert_eq!(summary.premium, 1);
+        assert_eq!(summary.our_dns, 1);
+        assert_eq!(summary.premium_dns, None);
+    }
+}
@@ -0,0 +1,182 @@
+//! ### `domains.reactivate` Implementation
+//!
+//! This module provides the implementation for the `domains.reactivate` method of the NameCheap API.
+//!
+//! It reactivates an expired domain (see `Domain::is_expired`) while it is still within its
+//! reactivation window, optionally with a promotion code and extra years.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value, json };
+use std::error::Error;
+use std::fmt;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::{ check_api_errors, ApiError };
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::{ get_attr, get_node_at, parse_string };
+
+/// The error number NameCheap returns for a domain that cannot be reactivated at this time.
+pub const OUTSIDE_REACTIVATION_WINDOW: &str = "2020166";
+
+/// ### Domain Reactivate Result
+///
+/// #### Fields
+/// - `domain`: The reactivated domain name.
+/// - `reactivated`: Whether the reactivation succeeded.
+/// - `charged_amount`: The amount charged to the account, in the account currency (optional).
+/// - `order_id`: The ID of the order.
+/// - `transaction_id`: The ID of the transaction.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainReactivateResult {
+    pub domain: String,
+    pub reactivated: bool,
+    pub charged_amount: Option<Money>,
+    pub order_id: i64,
+    pub transaction_id: i64,
+}
+
+/// A domain that is not expired, or whose reactivation window has closed.
+///
+/// #### Fields
+/// - `domain`: The domain that was to be reactivated.
+/// - `source`: The error reported by the API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutsideReactivationWindow {
+    pub domain: String,
+    pub source: ApiError,
+}
+
+impl fmt::Display for OutsideReactivationWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} cannot be reactivated: only expired domains still within their reactivation window can be, renew active domains instead ({})",
+            self.domain,
+            self.source
+        )
+    }
+}
+
+impl Error for OutsideReactivationWindow {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl NameCheapClient {
+    /// - `domains.reactivate`: Reactivates an expired domain
+    ///
+    /// Reactivates `domain_name`, applying `promo_code` if given and adding `years` years
+    /// on top of the reactivation, if given. Fails with [`OutsideReactivationWindow`] when the
+    /// domain is not expired or can no longer be reactivated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// for domain in client.domains_get_list_all().await?.iter().filter(|domain| domain.is_expired) {
+    ///     let result = client.domains_reactivate(&domain.name, None, None).await?;
+    ///     println!("{}: charged {:?}", result.domain, result.charged_amount);
+    /// }
+    /// ```
+    pub async fn domains_reactivate(
+        &self,
+        domain_name: &str,
+        promo_code: Option<&str>,
+        years: Option<u8>
+    ) -> Result<DomainReactivateResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.reactivate".to_string();
+        let mut params: Map<String, Value> = Map::new();
+        if let Some(promo_code) = promo_code.map(str::trim).filter(|code| !code.is_empty()) {
+            params.insert("PromotionCode".to_string(), json!(promo_code));
+        }
+        if let Some(years) = years.filter(|years| *years > 0) {
+            params.insert("YearsToAdd".to_string(), json!(years));
+        }
+
+        let request: Request = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            Some(Value::Object(params))
+        ).flatten_text();
+        self.check_policies(&Mutation::ReactivateDomain { domain: domain_name.to_string(), years: years.unwrap_or(0) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response).map_err(|error| reactivation_error(domain_name, error))?;
+        info!("Response: {:#?}", response);
+
+        parse_reactivate_result(&response).ok_or_else(|| {
+            format!("Failed to extract the reactivation of domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Explains the API errors of a reactivation that is not possible at this time.
+fn reactivation_error(domain_name: &str, error: ApiError) -> Box<dyn Error> {
+    if error.number == OUTSIDE_REACTIVATION_WINDOW {
+        Box::new(OutsideReactivationWindow { domain: domain_name.to_string(), source: error })
+    } else {
+        Box::new(error)
+    }
+}
+
+/// Parses a full `domains.reactivate` response.
+pub(crate) fn parse_reactivate_result(response: &Value) -> Option<DomainReactivateResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainReactivateResult")?;
+    // Falls back to USD, the default account currency, when the response carries none
+    let currency: Currency = detect_currency(response).unwrap_or_default();
+
+    Some(DomainReactivateResult {
+        domain: parse_string(result, "domain", ""),
+        reactivated: parse_string(result, "is_success", "false") == "true",
+        charged_amount: parse_money(result, "charged_amount", &currency),
+        order_id: get_attr(result, "order_id").unwrap_or(0),
+        transaction_id: get_attr(result, "transaction_id").unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_reactivate_result() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.reactivate">
+            <DomainReactivateResult Domain="example.com" IsSuccess="true" ChargedAmount="650.0000" OrderID="23569" TransactionID="25080" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let result: DomainReactivateResult = parse_reactivate_result(&response).unwrap();
+
+        assert_eq!(result.domain, "example.com");
+        assert!(result.reactivated);
+        assert_eq!((result.order_id, result.transaction_id), (23569, 25080));
+        assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("650.0000 USD"));
+    }
+
+    #[test]
+    fn test_reactivation_error() {
+        let error: Box<dyn Error> = reactivation_error("example.com", ApiError::new(OUTSIDE_REACTIVATION_WINDOW, "Domain is not expired"));
+        let window: &OutsideReactivationWindow = error.downcast_ref::<OutsideReactivationWindow>().unwrap();
+        assert_eq!(window.domain, "example.com");
+        assert!(error.to_string().contains("renew active domains instead"));
+        assert!(error.source().is_some());
+
+        let error: Box<dyn Error> = reactivation_error("example.com", ApiError::new("2019166", "Domain not found"));
+        assert!(error.is::<ApiError>());
+    }
+}
@@ -0,0 +1,154 @@
+//! ### `domains.renew` Implementation
+//!
+//! This module provides the implementation for the `domains.renew` method of the NameCheap API.
+//!
+//! It renews a domain registration, optionally with a promotion code, and reports the charge
+//! and the new expiration date.
+//!
+
+use chrono::{ DateTime, Utc };
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::{ get_attr, get_node_at, get_text_at, parse_string };
+use crate::response::timestamp::{ server_offset, to_utc };
+
+/// ### Domain Renew Result
+///
+/// #### Fields
+/// - `domain`: The renewed domain name.
+/// - `renewed`: Whether the renewal succeeded.
+/// - `charged_amount`: The amount charged to the account, in the account currency (optional).
+/// - `domain_id`: The ID of the domain.
+/// - `order_id`: The ID of the renewal order.
+/// - `transaction_id`: The ID of the transaction.
+/// - `expires_at`: The new expiration date, in UTC (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainRenewResult {
+    pub domain: String,
+    pub renewed: bool,
+    pub charged_amount: Option<Money>,
+    pub domain_id: i64,
+    pub order_id: i64,
+    pub transaction_id: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl NameCheapClient {
+    /// - `domains.renew`: Renews a domain registration
+    ///
+    /// Renews `domain_name` for `years` years, applying `promo_code` if given. In dry-run mode
+    /// nothing is charged, the call fails with the
+    /// [`PlannedRequest`](crate::utils::request_builder::PlannedRequest) it would have sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_renew("example.com", 1, None).await.unwrap();
+    /// println!("Charged {:?}, now expires {:?}", result.charged_amount, result.expires_at);
+    /// ```
+    pub async fn domains_renew(
+        &self,
+        domain_name: &str,
+        years: u8,
+        promo_code: Option<&str>
+    ) -> Result<DomainRenewResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.renew".to_string();
+        let mut params: Map<String, Value> = Map::new();
+        params.insert("Years".to_string(), json!(years.max(1)));
+        if let Some(promo_code) = promo_code.map(str::trim).filter(|code| !code.is_empty()) {
+            params.insert("PromotionCode".to_string(), json!(promo_code));
+        }
+
+        let request: Request = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            Some(Value::Object(params))
+        ).flatten_text();
+        self.check_policies(&Mutation::RenewDomain { domain: domain_name.to_string(), years: years.max(1) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_renew_result(&response).ok_or_else(|| {
+            format!("Failed to extract the renewal of domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Parses a full `domains.renew` response.
+pub(crate) fn parse_renew_result(response: &Value) -> Option<DomainRenewResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainRenewResult")?;
+    // Falls back to USD, the default account currency, when the response carries none
+    let currency: Currency = detect_currency(response).unwrap_or_default();
+
+    Some(DomainRenewResult {
+        domain: parse_string(result, "domain_name", ""),
+        renewed: parse_string(result, "renew", "false") == "true",
+        charged_amount: parse_money(result, "charged_amount", &currency),
+        domain_id: get_attr(result, "domain_id").unwrap_or(0),
+        order_id: get_attr(result, "order_id").unwrap_or(0),
+        transaction_id: get_attr(result, "transaction_id").unwrap_or(0),
+        expires_at: get_text_at::<String>(result, "/DomainDetails/ExpiredDate")
+            .and_then(|text| to_utc(&text, server_offset(response))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::request_builder::PlannedRequest;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_renew_result() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.renew">
+            <DomainRenewResult DomainName="example.com" DomainID="151378" Renew="true" OrderID="82511" TransactionID="150826" ChargedAmount="13.9800">
+              <DomainDetails><ExpiredDate>10/21/2026 8:41:21 AM</ExpiredDate><NumYears>0</NumYears></DomainDetails>
+            </DomainRenewResult>
+            </CommandResponse><GMTTimeDifference>--5:00</GMTTimeDifference></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let result: DomainRenewResult = parse_renew_result(&response).unwrap();
+
+        assert_eq!(result.domain, "example.com");
+        assert!(result.renewed);
+        assert_eq!((result.domain_id, result.order_id, result.transaction_id), (151378, 82511, 150826));
+        assert_eq!(result.charged_amount.map(|amount| amount.to_string()).as_deref(), Some("13.9800 USD"));
+        assert_eq!(result.expires_at.map(|expires_at| expires_at.to_rfc3339()).as_deref(), Some("2026-10-21T13:41:21+00:00"));
+    }
+
+    #[tokio::test]
+    async fn test_domains_renew_dry_run() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+
+        let error: Box<dyn Error> = client.domains_renew("example.com", 2, Some("SAVE10")).await.unwrap_err();
+        let planned: &PlannedRequest = error.downcast_ref::<PlannedRequest>().unwrap();
+        assert_eq!(planned.command, "namecheap.domains.renew");
+        assert_eq!(planned.params["Years"], 2);
+    }
+}
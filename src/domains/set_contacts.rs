@@ -0,0 +1,196 @@
+//! ### `domains.setContacts` Implementation
+//!
+//! This module provides the implementation for the `domains.setContacts` method of the NameCheap API.
+//!
+//! It sets the registrant, tech, admin and billing contacts of a domain, either for a
+//! single domain or across a portfolio with [`Batch`].
+//!
+
+use serde_json::Value;
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, ContactSet };
+use crate::batch::{ Batch, BatchReport };
+use crate::changes::ChangeSummary;
+use crate::domains::labels::Selector;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::get_node_at;
+use crate::policy::Mutation;
+
+impl NameCheapClient {
+    /// - `domains.setContacts`: Sets contact information for the specified domain
+    ///
+    /// Sets contact information for the specified domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use namecheap::{ NameCheapClient, ContactSet };
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = NameCheapClient::new(
+    ///         "api_user".to_string(),
+    ///         "api_key".to_string(),
+    ///         "client_ip".to_string(),
+    ///         "user_name".to_string(),
+    ///         false
+    ///     );
+    ///
+    ///     let contacts: ContactSet = ContactSet::uniform(contact);
+    ///     let result = client.domains_set_contacts("example.com", &contacts).await.unwrap();
+    ///     println!("Set Contacts Result: {:?}", result);
+    /// }
+    /// ```
+    pub async fn domains_set_contacts(
+        &self,
+        domain_name: &str,
+        contacts: &ContactSet
+    ) -> Result<Value, Box<dyn Error>> {
+        let command: String = "namecheap.domains.setContacts".to_string();
+
+        let request: Request = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            Some(Value::Object(contacts.to_params()))
+        );
+        self.check_policies(&Mutation::Contacts { domain: domain_name.to_string() })?;
+        if request.is_dry_run() {
+            return Ok(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+
+        let result: Value = get_node_at(&response, "/ApiResponse/CommandResponse/DomainSetContactResult")
+            .cloned()
+            .ok_or("Failed to set contact information")?;
+        info!("Set Contacts Result: {:#?}", result);
+
+        Ok(result)
+    }
+
+    /// Sets contact information like [`domains_set_contacts`](Self::domains_set_contacts),
+    /// returning the fields changed on each contact.
+    ///
+    /// The live contacts are read first, nothing is sent when they already match. With
+    /// [`PrivacyMode::Minimized`](crate::utils::privacy::PrivacyMode) the summary holds masked
+    /// values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let summary: ChangeSummary = client.domains_set_contacts_with_summary("example.com", &contacts).await?;
+    /// println!("{}", summary);
+    /// ```
+    pub async fn domains_set_contacts_with_summary(
+        &self,
+        domain_name: &str,
+        contacts: &ContactSet
+    ) -> Result<ChangeSummary, Box<dyn Error>> {
+        let before: ContactSet = self.domains_get_contact_set(domain_name).await?;
+        let mut summary: ChangeSummary = ChangeSummary::from_contacts(domain_name, &before, contacts);
+
+        if !summary.is_empty() {
+            let result: Value = self.domains_set_contacts(domain_name, contacts).await?;
+            summary.applied = result.get("is_success").and_then(Value::as_str) == Some("true");
+        }
+        if self.privacy.is_minimized() {
+            summary = summary.minimized();
+        }
+        Ok(summary)
+    }
+
+    /// Sets the same contact information on many domains.
+    ///
+    /// The domains are an explicit list or a [`Selector`], e.g. every domain labeled `clientA`.
+    /// Each domain is updated independently, a failure on one domain does not stop the others.
+    /// With `dry_run` set (or the client in dry-run mode), nothing is sent and each result
+    /// holds the `PlannedRequest` for that domain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let domains: Vec<String> = vec!["example.com".to_string(), "example.org".to_string()];
+    /// let report = client.domains_set_contacts_bulk(&domains, &contacts, true).await?;
+    /// let report = client.domains_set_contacts_bulk(Selector::Label("clientA".into()), &contacts, true).await?;
+    ///
+    /// for item in report.failed() {
+    ///     println!("{} failed: {:?}", item.key, item.outcome);
+    /// }
+    /// ```
+    pub async fn domains_set_contacts_bulk(
+        &self,
+        domains: impl Into<Selector>,
+        contacts: &ContactSet,
+        dry_run: bool
+    ) -> Result<BatchReport<Value>, Box<dyn Error>> {
+        let domains: Vec<String> = self.select(&domains.into()).await?;
+        let client: NameCheapClient = self.clone().with_dry_run(self.dry_run || dry_run);
+        let client: &NameCheapClient = &client;
+        Ok(Batch::new().run(domains, |domain| async move {
+            client.domains_set_contacts(&domain, contacts).await
+        }).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::BatchOutcome;
+    use crate::Contact;
+
+    fn sample_contact() -> Contact {
+        Contact {
+            type_: String::new(),
+            address_1: "8939 S. Cross Blvd".to_string(),
+            address_2: String::new(),
+            city: "Phoenix".to_string(),
+            country: "US".to_string(),
+            email_address: "admin@example.com".to_string(),
+            fax: String::new(),
+            first_name: "John".to_string(),
+            job_title: String::new(),
+            last_name: "Smith".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.6613102107".to_string(),
+            phone_ext: String::new(),
+            postal_code: "85044".to_string(),
+            state_province: "AZ".to_string(),
+            state_province_choice: String::new(),
+            read_only: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_domains_set_contacts_bulk_dry_run() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        );
+        let contacts: ContactSet = ContactSet::uniform(sample_contact());
+        let domains: Vec<String> = vec!["example.com".to_string(), "example.org".to_string()];
+
+        let report = client.domains_set_contacts_bulk(&domains, &contacts, true).await.unwrap();
+
+        assert!(report.is_success());
+        assert_eq!(report.results[1].key, "example.org");
+        match &report.results[0].outcome {
+            BatchOutcome::Success(planned) => {
+                assert_eq!(planned["params"]["RegistrantFirstName"], "John");
+                assert_eq!(planned["params"]["AuxBillingEmailAddress"], "admin@example.com");
+                assert!(planned["params"].get("TechFax").is_none());
+            }
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+}
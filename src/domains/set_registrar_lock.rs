@@ -0,0 +1,151 @@
+//! ### `domains.setRegistrarLock` Implementation
+//!
+//! This module provides the implementation for the `domains.setRegistrarLock` method of the NameCheap API.
+//!
+//! It locks or unlocks a domain against transfers to another registrar, e.g. to prepare a
+//! transfer out without going through the dashboard. The current status is read with
+//! `domains_get_registrar_lock`.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use std::fmt;
+use tracing::{ info, warn };
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::domains::get_registrar_lock::RegistrarLockStatus;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+
+/// The change to make to the registrar lock of a domain.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum LockAction {
+    /// Refuse transfers to another registrar.
+    Lock,
+    /// Allow the domain to be transferred out.
+    Unlock,
+}
+
+impl LockAction {
+    /// Returns the `LockAction` parameter value, `LOCK` or `UNLOCK`.
+    pub fn as_api_str(&self) -> &'static str {
+        match self {
+            LockAction::Lock => "LOCK",
+            LockAction::Unlock => "UNLOCK",
+        }
+    }
+
+    /// Returns the status of a domain once the action is applied.
+    pub fn status(&self) -> RegistrarLockStatus {
+        match self {
+            LockAction::Lock => RegistrarLockStatus::Locked,
+            LockAction::Unlock => RegistrarLockStatus::Unlocked,
+        }
+    }
+}
+
+impl fmt::Display for LockAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_api_str())
+    }
+}
+
+/// ### Domain Set Registrar Lock Result
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `updated`: Whether the change succeeded.
+/// - `status`: The status requested, in effect when `updated` is `true`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainSetRegistrarLockResult {
+    pub domain: String,
+    pub updated: bool,
+    pub status: RegistrarLockStatus,
+}
+
+impl NameCheapClient {
+    /// - `domains.setRegistrarLock`: Locks or unlocks a domain against transfers
+    ///
+    /// Unlocking is logged as a warning, an unlocked domain can be transferred away with its
+    /// EPP code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_set_registrar_lock("example.com", LockAction::Unlock).await?;
+    /// assert_eq!(result.status, RegistrarLockStatus::Unlocked);
+    /// ```
+    pub async fn domains_set_registrar_lock(
+        &self,
+        domain_name: &str,
+        action: LockAction
+    ) -> Result<DomainSetRegistrarLockResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.setRegistrarLock".to_string();
+        let params: Value = json!({ "LockAction": action.as_api_str() });
+
+        let request: Request = Request::new(
+            self,
+            command,
+            None,
+            Some(domain_name.to_string()),
+            Some(params)
+        ).flatten_text();
+        self.check_policies(&Mutation::RegistrarLock { domain: domain_name.to_string(), locked: action == LockAction::Lock })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        if action == LockAction::Unlock {
+            warn!("Unlocking {}: the domain loses its transfer protection", domain_name);
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_set_registrar_lock_result(&response, action).ok_or_else(|| {
+            format!("Failed to extract the registrar lock change of domain: {}", domain_name).into()
+        })
+    }
+}
+
+/// Parses a full `domains.setRegistrarLock` response for `action`.
+pub(crate) fn parse_set_registrar_lock_result(response: &Value, action: LockAction) -> Option<DomainSetRegistrarLockResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainSetRegistrarLockResult")?;
+
+    Some(DomainSetRegistrarLockResult {
+        domain: parse_string(result, "domain", ""),
+        updated: parse_string(result, "is_success", "false") == "true",
+        status: action.status(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_set_registrar_lock_result() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.setRegistrarLock">
+            <DomainSetRegistrarLockResult Domain="example.com" IsSuccess="true" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let result: DomainSetRegistrarLockResult = parse_set_registrar_lock_result(&response, LockAction::Unlock).unwrap();
+
+        assert_eq!(result.domain, "example.com");
+        assert!(result.updated);
+        assert_eq!(result.status, RegistrarLockStatus::Unlocked);
+        assert_eq!(LockAction::Lock.to_string(), "LOCK");
+    }
+}
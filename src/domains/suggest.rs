@@ -0,0 +1,181 @@
+//! ### Cheapest TLD Suggestions
+//!
+//! Finds the cheapest available registrations of a keyword: the keyword is checked under every
+//! candidate TLD (given, or every TLD of the `domains.getTldList` catalogue registerable
+//! through the API) and the available names are ranked by first-year price, then renewal
+//! price, from the account's `users.getPricing` matrix. Premium names are ranked by their
+//! premium prices.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::domains::check::DomainCheckResult;
+use crate::response::money::Money;
+use crate::response::parse_value::{ get_nodes_at, parse_string, Decimal };
+use crate::users::get_pricing::{ Action, PricingMatrix, ProductType };
+
+/// ### TLD Suggestion
+///
+/// #### Fields
+/// - `domain`: The available domain name.
+/// - `tld`: The TLD of the domain.
+/// - `first_year`: The price of registering it for one year.
+/// - `renewal`: The price of renewing it for one year (optional).
+/// - `is_premium`: Whether the domain is a premium name, priced as such.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct TldSuggestion {
+    pub domain: String,
+    pub tld: String,
+    pub first_year: Money,
+    pub renewal: Option<Money>,
+    pub is_premium: bool,
+}
+
+/// Returns the TLDs of a `domains.getTldList` result registerable through the API.
+pub fn registerable_tlds(tld_list: &Value) -> Vec<String> {
+    get_nodes_at(tld_list, "")
+        .into_iter()
+        .filter(|tld| parse_string(tld, "is_api_registerable", "false") == "true")
+        .map(|tld| parse_string(tld, "name", "").to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Returns the amount of `money` as a float, for ranking only.
+fn amount(money: &Money) -> f64 {
+    money.amount.to_string().parse().unwrap_or(f64::MAX)
+}
+
+/// Ranks the available results of `checks` by first-year then renewal price.
+///
+/// Names without a registration price in `matrix`, or costing more than `max_price` the first
+/// year, are left out.
+pub fn rank_suggestions(checks: &[DomainCheckResult], matrix: &PricingMatrix, max_price: Option<&Decimal>) -> Vec<TldSuggestion> {
+    let max_price: Option<f64> = max_price.and_then(|max| max.to_string().parse().ok());
+    let mut suggestions: Vec<TldSuggestion> = checks
+        .iter()
+        .filter(|check| check.available)
+        .filter_map(|check| {
+            let tld: String = check.domain.split_once('.')?.1.to_ascii_lowercase();
+            let (first_year, renewal) = if check.is_premium_name {
+                (check.premium_registration_price.clone()?, check.premium_renewal_price.clone())
+            } else {
+                (
+                    matrix.price_for(&tld, Action::Register, 1)?.effective_price().clone(),
+                    matrix.price_for(&tld, Action::Renew, 1).map(|price| price.effective_price().clone()),
+                )
+            };
+            Some(TldSuggestion { domain: check.domain.to_ascii_lowercase(), tld, first_year, renewal, is_premium: check.is_premium_name })
+        })
+        .filter(|suggestion| max_price.is_none_or(|max| amount(&suggestion.first_year) <= max))
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        let renewal = |suggestion: &TldSuggestion| suggestion.renewal.as_ref().map(amount).unwrap_or(f64::MAX);
+        amount(&a.first_year)
+            .partial_cmp(&amount(&b.first_year))
+            .unwrap_or(Ordering::Equal)
+            .then(renewal(a).partial_cmp(&renewal(b)).unwrap_or(Ordering::Equal))
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    suggestions
+}
+
+impl NameCheapClient {
+    /// Suggests the cheapest available registrations of `keyword`.
+    ///
+    /// `tlds` limits the candidates (with or without the leading dot), otherwise every TLD
+    /// registerable through the API is checked. With `max_price`, names costing more the first
+    /// year are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let suggestions = client.suggest_cheapest("example", Some(vec!["com".into(), "io".into(), "dev".into()]), None).await?;
+    /// for suggestion in suggestions {
+    ///     println!("{}: {} then {:?}", suggestion.domain, suggestion.first_year, suggestion.renewal);
+    /// }
+    /// ```
+    pub async fn suggest_cheapest(
+        &self,
+        keyword: &str,
+        tlds: Option<Vec<String>>,
+        max_price: Option<Decimal>
+    ) -> Result<Vec<TldSuggestion>, Box<dyn Error>> {
+        let keyword: String = keyword.trim().to_ascii_lowercase();
+        if keyword.is_empty() || keyword.contains('.') {
+            return Err(format!("Invalid keyword: {}", keyword).into());
+        }
+        let tlds: Vec<String> = match tlds {
+            Some(tlds) => tlds.iter().map(|tld| tld.trim().trim_start_matches('.').to_ascii_lowercase()).collect(),
+            None => registerable_tlds(&self.domains_get_tld_list().await?),
+        };
+        let names: Vec<String> = tlds.iter().map(|tld| format!("{}.{}", keyword, tld)).collect();
+
+        let matrix: PricingMatrix = self.users_get_pricing(ProductType::Domain, None).await?;
+        let checks: Vec<DomainCheckResult> = self.domains_check(&names).await?;
+        Ok(rank_suggestions(&checks, &matrix, max_price.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::check::parse_check_results;
+    use crate::users::get_pricing::parse_pricing;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_registerable_tlds() {
+        let tlds: Value = serde_json::json!([
+            { "name": "com", "is_api_registerable": "true" },
+            { "name": "bank", "is_api_registerable": "false" },
+        ]);
+        assert_eq!(registerable_tlds(&tlds), ["com"]);
+        assert_eq!(registerable_tlds(&tlds[0]), ["com"]);
+    }
+
+    #[test]
+    fn test_rank_suggestions() {
+        let pricing: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.getPricing">
+            <UserGetPricingResult><ProductType Name="domains">
+              <ProductCategory Name="register">
+                <Product Name="com"><Price Duration="1" DurationType="YEAR" YourPrice="9.58" Currency="USD" /></Product>
+                <Product Name="xyz"><Price Duration="1" DurationType="YEAR" YourPrice="1.00" Currency="USD" /></Product>
+                <Product Name="io"><Price Duration="1" DurationType="YEAR" YourPrice="32.98" Currency="USD" /></Product>
+              </ProductCategory>
+              <ProductCategory Name="renew">
+                <Product Name="com"><Price Duration="1" DurationType="YEAR" YourPrice="14.98" Currency="USD" /></Product>
+                <Product Name="xyz"><Price Duration="1" DurationType="YEAR" YourPrice="12.98" Currency="USD" /></Product>
+              </ProductCategory>
+            </ProductType></UserGetPricingResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let checks: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.check">
+            <DomainCheckResult Domain="example.com" Available="true" IsPremiumName="false" />
+            <DomainCheckResult Domain="example.xyz" Available="true" IsPremiumName="false" />
+            <DomainCheckResult Domain="example.io" Available="true" IsPremiumName="false" />
+            <DomainCheckResult Domain="example.net" Available="false" IsPremiumName="false" />
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let matrix: PricingMatrix = parse_pricing(&pricing, ProductType::Domain).unwrap();
+        let checks: Vec<DomainCheckResult> = parse_check_results(&checks).unwrap();
+
+        let ranked: Vec<TldSuggestion> = rank_suggestions(&checks, &matrix, None);
+        let domains: Vec<&str> = ranked.iter().map(|suggestion| suggestion.domain.as_str()).collect();
+        assert_eq!(domains, ["example.xyz", "example.com", "example.io"]);
+        assert!(ranked[2].renewal.is_none());
+
+        let max_price: Decimal = "10".parse().unwrap();
+        assert_eq!(rank_suggestions(&checks, &matrix, Some(&max_price)).len(), 2);
+    }
+}
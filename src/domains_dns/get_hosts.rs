@@ -12,10 +12,18 @@ use tracing::{ info, error };
 // crate imports
 use crate::{ NameCheapClient, Host };
 use crate::utils::request_builder::Request;
+use crate::response::parse_value::{ get_attr, get_node_at, parse_string };
+use crate::response::typed_xml::parse_hosts_xml;
+use crate::limits::DEFAULT_TTL;
+use crate::zone::{ RecordKey, sort_hosts };
+#[cfg(feature = "audit_log")]
+use crate::zone::history::ZoneState;
 
 impl NameCheapClient {
     /// Retrieves DNS host records for a given domain.
     ///
+    /// Records are in canonical order, see [`sort_hosts`](crate::zone::sort_hosts).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -44,7 +52,7 @@ impl NameCheapClient {
         let params = json!({ "SLD": sld, "TLD": tld });
 
         let response = Request::new(
-            self.clone(),
+            self,
             command.to_string(),
             Some(1),
             None,
@@ -53,15 +61,79 @@ impl NameCheapClient {
         ).send().await?;
         info!("Response: {:#?}", response);
 
-        let hosts = response
-            .pointer("/ApiResponse/CommandResponse/DomainDNSGetHostsResult/host")
+        let mut hosts = get_node_at(&response, "/ApiResponse/CommandResponse/DomainDNSGetHostsResult/host")
             .cloned()
             .unwrap_or_else(|| json!([])); // Return an empty array if no host records are found
+        if let Value::Array(hosts) = &mut hosts {
+            hosts.sort_by_cached_key(|host| RecordKey::from(&parse_host(host)));
+        }
 
+        #[cfg(feature = "audit_log")]
+        self.record_zone_state(|| {
+            let hosts: Vec<Host> = match &hosts {
+                Value::Array(hosts) => hosts.iter().map(parse_host).collect(),
+                host => vec![parse_host(host)],
+            };
+            ZoneState::observed(&format!("{}.{}", sld, tld), &hosts)
+        });
+        Ok(hosts)
+    }
+
+    /// Retrieves the DNS host records of a domain as typed `Host`s.
+    ///
+    /// Records are in canonical order, see [`sort_hosts`](crate::zone::sort_hosts).
+    ///
+    /// API errors are returned as [`ApiError`](crate::response::errors::ApiError)s.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let hosts: Vec<Host> = client.domains_dns_get_host_records("domain", "com").await.unwrap();
+    /// for host in &hosts {
+    ///     println!("{} {} {}", host.name, host.type_, host.address);
+    /// }
+    /// ```
+    pub async fn domains_dns_get_host_records(
+        &self,
+        sld: &str,
+        tld: &str
+    ) -> Result<Vec<Host>, Box<dyn Error>> {
+        let command = "namecheap.domains.dns.getHosts";
+        let params = json!({ "SLD": sld, "TLD": tld });
+
+        // Parsed straight from the XML, without building the intermediate JSON tree
+        let response: String = Request::new(
+            self,
+            command.to_string(),
+            Some(1),
+            None,
+            Some(params),
+        ).send_text().await?;
+
+        let mut hosts: Vec<Host> = parse_hosts_xml(&response)?;
+        sort_hosts(&mut hosts);
+        #[cfg(feature = "audit_log")]
+        self.record_zone_state(|| ZoneState::observed(&format!("{}.{}", sld, tld), &hosts));
         Ok(hosts)
     }
 }
 
+/// Parses a single `host` element of a `domains.dns.getHosts` response.
+pub(crate) fn parse_host(host: &Value) -> Host {
+    Host {
+        host_id: parse_string(host, "host_id", ""),
+        name: parse_string(host, "name", ""),
+        address: parse_string(host, "address", ""),
+        type_: parse_string(host, "type", ""),
+        is_active: parse_string(host, "is_active", "true") == "true",
+        ttl: get_attr(host, "ttl").unwrap_or(DEFAULT_TTL),
+        mx_pref: parse_string(host, "mxpref", ""),
+        is_ddns_enabled: parse_string(host, "is_ddnsenabled", "false") == "true",
+        friendly_name: parse_string(host, "friendly_name", ""),
+        associated_app_title: parse_string(host, "associated_app_title", ""),
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -69,6 +141,10 @@ mod tests {
     use dotenv::dotenv;
     use serde_json::json;
     use tracing::info;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::domains_dns::set_hosts::HostRequest;
+    use crate::simulation::{ Latency, SimulatedTransport, SimulationConfig };
 
     #[tokio::test]
     async fn test_domains_dns_get_hosts() {
@@ -82,4 +158,32 @@ mod tests {
         // Check if host_records is an array with at least two items
         assert!(host_records.as_array().map_or(false, |arr| arr.len() >= 2), "Expected at least two host records");
     }
+
+    #[tokio::test]
+    async fn test_domains_dns_get_hosts_canonical_order() {
+        let config: SimulationConfig = SimulationConfig {
+            latency: Latency::Fixed(Duration::ZERO),
+            propagation_delay: Duration::ZERO,
+            ..SimulationConfig::default()
+        };
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None),
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, None, None, None)
+        ];
+        let simulation: Arc<SimulatedTransport> = Arc::new(SimulatedTransport::new(config).with_zone("example.com", &hosts));
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_transport(simulation);
+
+        let raw: Value = client.domains_dns_get_hosts("example", "com").await.unwrap();
+        let names: Vec<String> = raw.as_array().unwrap().iter().map(|host| parse_host(host).name).collect();
+        assert_eq!(names, vec!["@", "www"]);
+
+        let typed: Vec<Host> = client.domains_dns_get_host_records("example", "com").await.unwrap();
+        assert_eq!(typed.iter().map(|host| host.name.as_str()).collect::<Vec<_>>(), vec!["@", "www"]);
+    }
 }
@@ -12,6 +12,7 @@ use tracing::{ info, error };
 // crate imports
 use crate::NameCheapClient;
 use crate::utils::request_builder::Request;
+use crate::response::parse_value::{ get_node_at, get_nodes_at };
 
 impl NameCheapClient {
     /// - `domains.dns.getList`: Gets a list of DNS servers for the specified domain
@@ -48,7 +49,7 @@ impl NameCheapClient {
         });
 
         let response: Value = Request::new(
-            self.clone(),
+            self,
             command,
             Some(1),
             None,
@@ -57,17 +58,8 @@ impl NameCheapClient {
         ).send().await?;
 
         // Extract DNS servers from the response
-        if let Some(api_response) = response.get("ApiResponse") {
-            if let Some(command_response) = api_response.get("CommandResponse") {
-                if
-                    let Some(domain_dns_get_list_result) =
-                        command_response.get("DomainDNSGetListResult")
-                {
-                    if let Some(nameservers) = domain_dns_get_list_result.get("Nameserver") {
-                        return Ok(nameservers.clone());
-                    }
-                }
-            }
+        if let Some(nameservers) = parse_nameservers(&response) {
+            return Ok(nameservers);
         }
 
         error!("Failed to retrieve DNS server list");
@@ -75,11 +67,41 @@ impl NameCheapClient {
     }
 }
 
+/// Extracts the nameservers of a `domains.dns.getList` response as an array.
+///
+/// Domains on the NameCheap DNS may report no `Nameserver` element, an empty array is
+/// returned then; `None` only when the result itself is missing.
+pub(crate) fn parse_nameservers(response: &Value) -> Option<Value> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainDNSGetListResult")?;
+    Some(Value::Array(get_nodes_at(result, "/Nameserver").into_iter().cloned().collect()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use dotenv::dotenv;
     use serde_json::json;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_nameservers() {
+        let parse = |xml: &str| parse_nameservers(&parse_xml_to_json(xml).unwrap());
+
+        let custom: Option<Value> = parse(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.dns.getList">
+            <DomainDNSGetListResult Domain="domain.com" IsUsingOurDNS="false"><Nameserver>ns1.example.net</Nameserver></DomainDNSGetListResult>
+            </CommandResponse></ApiResponse>"#
+        );
+        assert_eq!(custom, Some(json!([{ "$text": "ns1.example.net" }])));
+
+        let minimal: Option<Value> = parse(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.dns.getList">
+            <DomainDNSGetListResult Domain="domain.com" IsUsingOurDNS="true" />
+            </CommandResponse></ApiResponse>"#
+        );
+        assert_eq!(minimal, Some(json!([])));
+        assert_eq!(parse(r#"<ApiResponse Status="OK"><Errors /><CommandResponse /></ApiResponse>"#), None);
+    }
 
     #[tokio::test]
     async fn test_domains_dns_get_list() {
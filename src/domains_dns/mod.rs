@@ -14,4 +14,5 @@
 
 pub mod get_list;
 pub mod get_hosts;
-pub mod set_hosts;
\ No newline at end of file
+pub mod set_hosts;
+pub mod set_custom;
\ No newline at end of file
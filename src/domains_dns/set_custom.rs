@@ -0,0 +1,128 @@
+//! ### `domains.dns.setCustom` Implementation
+//!
+//! This module provides the implementation for the `domains.dns.setCustom` method of the NameCheap API.
+//!
+//! It delegates a domain to external nameservers, e.g. those of Cloudflare or Route53. Host
+//! records managed by NameCheap stop being served once the change is applied.
+//!
+
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::get_node_at;
+use crate::limits::validate_nameserver_count;
+use crate::policy::Mutation;
+
+/// Normalizes and validates the nameservers of a `domains.dns.setCustom` call.
+///
+/// Nameservers are trimmed, lowercased and stripped of a trailing dot, duplicates are
+/// dropped. Fails on a nameserver that is not a hostname, or on fewer than 2 or more than 12
+/// nameservers, see [`validate_nameserver_count`].
+pub fn normalize_nameservers(nameservers: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for nameserver in nameservers {
+        let nameserver: String = nameserver.trim().trim_end_matches('.').to_lowercase();
+        let is_hostname: bool =
+            nameserver.contains('.') &&
+            nameserver
+                .split('.')
+                .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        if !is_hostname {
+            return Err(format!("Invalid nameserver: {:?}", nameserver));
+        }
+        if !normalized.contains(&nameserver) {
+            normalized.push(nameserver);
+        }
+    }
+    validate_nameserver_count(normalized.len())?;
+    Ok(normalized)
+}
+
+impl NameCheapClient {
+    /// - `domains.dns.setCustom`: Sets custom nameservers for the specified domain
+    ///
+    /// Between 2 and 12 nameservers are required, they are checked with
+    /// [`normalize_nameservers`] before anything is sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let nameservers: Vec<String> = vec!["ada.ns.cloudflare.com".to_string(), "bob.ns.cloudflare.com".to_string()];
+    /// let result = client.domains_dns_set_custom("domain", "com", nameservers).await.unwrap();
+    /// println!("Set Custom Result: {:?}", result);
+    /// ```
+    pub async fn domains_dns_set_custom(
+        &self,
+        sld: &str,
+        tld: &str,
+        nameservers: Vec<String>
+    ) -> Result<Value, Box<dyn Error>> {
+        let command: String = "namecheap.domains.dns.setCustom".to_string();
+        let nameservers: Vec<String> = normalize_nameservers(&nameservers)?;
+        let params: Value = json!({
+            "SLD": sld,
+            "TLD": tld,
+            "Nameservers": nameservers.join(","),
+        });
+
+        let request: Request = Request::new(self, command, None, None, Some(params));
+        self.check_policies(&Mutation::Nameservers { domain: format!("{}.{}", sld, tld), nameservers })?;
+        if request.is_dry_run() {
+            return Ok(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        let result: Value = get_node_at(&response, "/ApiResponse/CommandResponse/DomainDNSSetCustomResult")
+            .cloned()
+            .ok_or("Failed to set custom nameservers")?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nameservers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_nameservers() {
+        let normalized: Vec<String> = normalize_nameservers(
+            &nameservers(&[" Ada.NS.Cloudflare.com. ", "bob.ns.cloudflare.com", "ada.ns.cloudflare.com"])
+        ).unwrap();
+        assert_eq!(normalized, ["ada.ns.cloudflare.com", "bob.ns.cloudflare.com"]);
+
+        assert!(normalize_nameservers(&nameservers(&["ada.ns.cloudflare.com"])).is_err());
+        assert!(normalize_nameservers(&nameservers(&["ada.ns.cloudflare.com", "not a host"])).is_err());
+        let thirteen: Vec<String> = (1..=13).map(|n| format!("ns{}.example.net", n)).collect();
+        assert!(normalize_nameservers(&thirteen).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_set_custom_dry_run() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+
+        let planned: Value = client
+            .domains_dns_set_custom("example", "com", nameservers(&["ns-1.awsdns-01.org", "ns-2.awsdns-02.com"])).await
+            .unwrap();
+        assert_eq!(planned["command"], "namecheap.domains.dns.setCustom");
+        assert_eq!(planned["params"]["Nameservers"], "ns-1.awsdns-01.org,ns-2.awsdns-02.com");
+    }
+}
@@ -1,14 +1,26 @@
-use serde_json::{ Value, json };
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value, json };
 use std::error::Error;
+use std::fmt;
 use tracing::{ info, error };
 
 // crate imports
-use crate::{ NameCheapClient };
+use crate::{ NameCheapClient, Host };
 use crate::utils::request_builder::Request;
-use crate::utils::xml_parser::parse_xml_to_json;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::get_node_at;
+use crate::limits::validate_host_count;
+use crate::policy::Mutation;
+use crate::changes::ChangeSummary;
+use crate::zone::diff::ZonePlan;
+use crate::zone::sort_host_requests;
+#[cfg(feature = "audit_log")]
+use crate::zone::history::ZoneState;
 
 /// Represents the parameters required for setting DNS host records.
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostRequest {
     pub host_name: String,
     pub record_type: String,
@@ -44,9 +56,224 @@ impl HostRequest {
     }
 }
 
+/// Checks that every record sets a host name, a record type and an address.
+pub fn validate_host_requests(hosts: &[HostRequest]) -> Result<(), HostRecordError> {
+    for (index, host) in hosts.iter().enumerate() {
+        for (field, value) in [
+            ("HostName", &host.host_name),
+            ("RecordType", &host.record_type),
+            ("Address", &host.address),
+        ] {
+            if value.trim().is_empty() {
+                return Err(HostRecordError { index, field: field.to_string(), reason: "is missing".to_string() });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds the numbered `setHosts` query parameters (`HostName1`, `RecordType1`, ...) for
+/// a list of host records.
+///
+/// `EmailType` is taken from the first record that sets it.
+pub fn host_params(hosts: &[HostRequest]) -> Value {
+    let mut params: Map<String, Value> = Map::new();
+    for (index, host) in hosts.iter().enumerate() {
+        let idx: usize = index + 1;
+        params.insert(format!("HostName{}", idx), json!(host.host_name));
+        params.insert(format!("RecordType{}", idx), json!(host.record_type));
+        params.insert(format!("Address{}", idx), json!(host.address));
+
+        let optional = [
+            ("MXPref", &host.mx_pref),
+            ("TTL", &host.ttl),
+            ("Flag", &host.flag),
+            ("Tag", &host.tag),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                params.insert(format!("{}{}", key, idx), json!(value));
+            }
+        }
+    }
+
+    if let Some(email_type) = hosts.iter().find_map(|host| host.email_type.clone()) {
+        params.insert("EmailType".to_string(), json!(email_type));
+    }
+
+    Value::Object(params)
+}
+
+/// A host record that cannot be serialized for `setHosts`.
+///
+/// #### Fields
+/// - `index`: The position of the record in its list, starting at 0.
+/// - `field`: The offending field, e.g. `Address`.
+/// - `reason`: Why the field was rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostRecordError {
+    pub index: usize,
+    pub field: String,
+    pub reason: String,
+}
+
+impl fmt::Display for HostRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Host record {}: {} {}", self.index, self.field, self.reason)
+    }
+}
+
+impl std::error::Error for HostRecordError {}
+
+/// Reads host records from JSON into `HostRequest`s.
+///
+/// Accepts an array, a single record (as returned by `domains_dns_get_hosts` for one-record
+/// zones) or `null`. Each record may use the `setHosts` keys (`HostName`, `RecordType`, ...),
+/// the `getHosts` keys (`name`, `type`, ...) or the `HostRequest` field names. Numbers and
+/// booleans are accepted wherever a string is expected, e.g. a numeric `TTL`.
+///
+/// Records without a name, type or address, or with a non-scalar field, are rejected with a
+/// [`HostRecordError`] instead of being sent with empty values.
+pub fn host_requests_from_value(hosts: &Value) -> Result<Vec<HostRequest>, HostRecordError> {
+    let records: Vec<&Value> = match hosts {
+        Value::Null => Vec::new(),
+        Value::Array(records) => records.iter().collect(),
+        record => vec![record],
+    };
+
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| host_request_from_value(index, record))
+        .collect()
+}
+
+fn host_request_from_value(index: usize, record: &Value) -> Result<HostRequest, HostRecordError> {
+    let error = |field: &str, reason: &str| HostRecordError {
+        index,
+        field: field.to_string(),
+        reason: reason.to_string(),
+    };
+    if !record.is_object() {
+        return Err(error("record", "is not an object"));
+    }
+
+    let field = |field: &str, keys: &[&str]| -> Result<Option<String>, HostRecordError> {
+        match keys.iter().find_map(|key| record.get(*key)) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(value @ (Value::Number(_) | Value::Bool(_))) => Ok(Some(value.to_string())),
+            Some(_) => Err(error(field, "is not a scalar value")),
+        }
+    };
+    let required = |name: &str, keys: &[&str]| -> Result<String, HostRecordError> {
+        match field(name, keys)? {
+            Some(value) if !value.trim().is_empty() => Ok(value),
+            _ => Err(error(name, "is missing")),
+        }
+    };
+
+    let record_type: String = required("RecordType", &["RecordType", "record_type", "type"])?.to_uppercase();
+    let mx_pref: Option<String> = field("MXPref", &["MXPref", "mx_pref", "mxpref"])?
+        .filter(|_| record_type == "MX");
+
+    Ok(HostRequest {
+        host_name: required("HostName", &["HostName", "host_name", "name"])?,
+        address: required("Address", &["Address", "address"])?,
+        mx_pref,
+        email_type: field("EmailType", &["EmailType", "email_type"])?,
+        ttl: field("TTL", &["TTL", "ttl"])?,
+        flag: field("Flag", &["Flag", "flag"])?,
+        tag: field("Tag", &["Tag", "tag"])?,
+        record_type,
+    })
+}
+
 impl NameCheapClient {
+    /// Replaces all DNS host records of a domain with exactly `hosts`.
+    ///
+    /// Unlike `domains_dns_set_hosts`, the live records are not merged in: any record missing
+    /// from `hosts` is removed. Client-level defaults are applied to each record. With policies
+    /// attached, the live zone is read and the plan checked against them first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let hosts: Vec<HostRequest> = client.domains_dns_get_host_records("domain", "com").await.unwrap()
+    ///     .iter()
+    ///     .map(|host| host.to_host_request())
+    ///     .collect();
+    /// client.domains_dns_replace_hosts("domain", "com", &hosts).await.unwrap();
+    /// ```
+    pub async fn domains_dns_replace_hosts(
+        &self,
+        sld: &str,
+        tld: &str,
+        hosts: &[HostRequest]
+    ) -> Result<Value, Box<dyn Error>> {
+        let command: String = "namecheap.domains.dns.setHosts".to_string();
+        let mut hosts: Vec<HostRequest> = hosts
+            .iter()
+            .map(|host| self.apply_host_defaults(host))
+            .collect();
+        // Submitted in canonical order, so the same zone always gives the same request
+        sort_host_requests(&mut hosts);
+        validate_host_count(hosts.len())?;
+        if self.has_policies() {
+            let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+            let plan: ZonePlan = ZonePlan::new(&format!("{}.{}", sld, tld), &current, &hosts);
+            self.check_policies(&Mutation::Zone(plan))?;
+        }
+
+        let mut params: Value = host_params(&hosts);
+        params["SLD"] = json!(sld);
+        params["TLD"] = json!(tld);
+
+        let request: Request = Request::new(self, command, None, None, Some(params));
+        if request.is_dry_run() {
+            return Ok(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        let result: Value = get_node_at(
+            &response,
+            "/ApiResponse/CommandResponse/DomainDNSSetHostsResult"
+        )
+            .ok_or("Failed to set host records")?
+            .clone();
+
+        #[cfg(feature = "audit_log")]
+        if result.get("is_success").and_then(Value::as_str) == Some("true") {
+            self.record_zone_state(|| ZoneState::applied(&format!("{}.{}", sld, tld), &hosts));
+        }
+        Ok(result)
+    }
+
+    /// Applies the client-level defaults to a `HostRequest`.
+    ///
+    /// `ttl` falls back to `default_ttl` and `email_type` falls back to `default_email_type`,
+    /// fields that are set on the request are left untouched.
+    pub fn apply_host_defaults(&self, host: &HostRequest) -> HostRequest {
+        let mut host: HostRequest = host.clone();
+        if host.ttl.is_none() {
+            host.ttl = self.default_ttl.map(|ttl| ttl.to_string());
+        }
+        if host.email_type.is_none() {
+            host.email_type = self.default_email_type.clone();
+        }
+        host
+    }
+
     /// Sets DNS host records for a given domain.
     ///
+    /// The records are added to the live zone: existing records are read back and submitted
+    /// together with `new_hosts`. Incomplete records, new or existing, are rejected with a
+    /// [`HostRecordError`] before anything is sent.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -95,191 +322,95 @@ impl NameCheapClient {
         tld: &str,
         new_hosts: Vec<HostRequest>
     ) -> Result<Value, Box<dyn Error>> {
-        // Retrieve existing hosts
-        let existing_hosts = self.domains_dns_get_hosts(sld, tld).await?;
-        info!("Existing Hosts: {:#?}", existing_hosts);
-
-        // Check if there are existing hosts
-        let combined_hosts: Vec<Value> = if
-            let Some(existing_hosts_array) = existing_hosts.as_array()
-        {
-            // Combine existing and new hosts
-            existing_hosts_array
-                .iter()
-                .cloned()
-                .chain(
-                    new_hosts
-                        .iter()
-                        .map(|host| {
-                            json!({
-                        "HostName": host.host_name,
-                        "RecordType": host.record_type,
-                        "Address": host.address,
-                        "MXPref": host.mx_pref,
-                        "EmailType": host.email_type,
-                        "TTL": host.ttl,
-                        "Flag": host.flag,
-                        "Tag": host.tag
-                    })
-                        })
-                )
-                .collect()
-        } else {
-            // If no existing hosts, use only new hosts
-            new_hosts
-                .iter()
-                .map(|host| {
-                    json!({
-                    "HostName": host.host_name,
-                    "RecordType": host.record_type,
-                    "Address": host.address,
-                    "MXPref": host.mx_pref,
-                    "EmailType": host.email_type,
-                    "TTL": host.ttl,
-                    "Flag": host.flag,
-                    "Tag": host.tag
-                })
-                })
-                .collect()
-        };
-
-        info!("Combined Hosts: {:#?}", combined_hosts);
-
-        let request_values: Vec<Value> = combined_hosts
+        // Fill in client-level defaults for omitted fields
+        let new_hosts: Vec<HostRequest> = new_hosts
             .iter()
-            .enumerate()
-            .flat_map(|(index, host)| {
-                let idx = index + 1;
-                vec![
-                    json!({
-                        "Key": format!("HostName{}", idx),
-                        "Value": host.get("HostName").unwrap_or(&Value::String("".to_string())).as_str().unwrap().to_string()
-                    }),
-                    json!({
-                        "Key": format!("RecordType{}", idx),
-                        "Value": host.get("RecordType").unwrap_or(&Value::String("".to_string())).as_str().unwrap().to_string()
-                    }),
-                    json!({
-                        "Key": format!("Address{}", idx),
-                        "Value": host.get("Address").unwrap_or(&Value::String("".to_string())).as_str().unwrap().to_string()
-                    }),
-                    json!({
-                        "Key": format!("TTL{}", idx),
-                        "Value": host.get("TTL").unwrap_or(&Value::String("".to_string())).as_str().unwrap().to_string()
-                    })
-                ]
-            })
+            .map(|host| self.apply_host_defaults(host))
             .collect();
 
-        let body: Value =
-            json!({
-            "authDetails": {
-                "ParentUserType": "",
-                "ParentUserId": 0,
-                "UserId": "",
-                "UserName": self.user_name,
-                "ClientIp": self.client_ip,
-                "EndUserIp": "",
-                "AdminUserName": self.user_name,
-                "DisableSecurityNotification": true,
-                "AllowWhenDomainLocked": true,
-                "ProceedWhenDomainLockedFlag": true,
-                "DefaultChargeForUserName": "",
-                "Roles": ["User"]
-            },
-            "request": {
-                "RequestValues": request_values,
-                "SLD": sld,
-                "TLD": tld
-            }
-        });
-        info!("Request Body: {:#?}", body);
-
-        let xml_body =
-            r#"
-            <?xml version="1.0" encoding="UTF-8"?>
-            <ApiRequest>
-              <Command>namecheap.domains.dns.setHosts</Command>
-              <ClientIp>{client_ip}</ClientIp>
-              <UserName>{user_name}</UserName>
-              <ApiUser>{api_user}</ApiUser>
-              <ApiKey>{api_key}</ApiKey>
-              <SLD>{sld}</SLD>
-              <TLD>{tld}</TLD>
-              {hosts}
-            </ApiRequest>
-        "#;
-
-        let hosts_xml: String = combined_hosts
-            .iter()
-            .enumerate()
-            .map(|(index, host)| {
-                format!(
-                    r#"<Host HostId="{}" Name="{}" Type="{}" Address="{}" TTL="{}" />"#,
-                    index + 1,
-                    host
-                        .get("HostName")
-                        .unwrap_or(&Value::String("".to_string()))
-                        .as_str()
-                        .unwrap(),
-                    host
-                        .get("RecordType")
-                        .unwrap_or(&Value::String("".to_string()))
-                        .as_str()
-                        .unwrap(),
-                    host.get("Address").unwrap_or(&Value::String("".to_string())).as_str().unwrap(),
-                    host.get("TTL").unwrap_or(&Value::String("".to_string())).as_str().unwrap()
-                )
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        let xml_body = xml_body
-            .replace("{client_ip}", &self.client_ip)
-            .replace("{user_name}", &self.user_name)
-            .replace("{api_user}", &self.api_user)
-            .replace("{api_key}", &self.api_key)
-            .replace("{sld}", sld)
-            .replace("{tld}", tld)
-            .replace("{hosts}", &hosts_xml);
-
-        let client = reqwest::Client::new();
-        let url = format!(
-            "https://api.namecheap.com/xml.response?ApiUser={api_user}&ApiKey={api_key}&UserName={user_name}&Command={command}&ClientIp={client_ip}&SLD={sld}&TLD={tld}",
-            api_user = self.api_user,
-            api_key = self.api_key,
-            user_name = self.user_name,
-            command = "namecheap.domains.dns.setHosts",
-            client_ip = self.client_ip,
-            sld = sld,
-            tld = tld
-        );
-        let response = client
-            .post(&url)
-            .header("Content-Type", "application/xml")
-            .body(xml_body)
-            .send().await?;
-
-        let response_text = response.text().await?;
-        let json_value: Value = parse_xml_to_json(&response_text)?;
-        info!("Response: {:#?}", json_value);
-
-        let result = json_value
-            .pointer("/ApiResponse/CommandResponse/DomainDNSSetHostsResult")
-            .ok_or("Failed to set host records")?
-            .clone();
+        // Reject incomplete records before touching the zone
+        validate_host_requests(&new_hosts)?;
 
-        // Check if the operation was successful
-        if result.get("IsSuccess").and_then(Value::as_bool).unwrap_or(false) {
+        // Hold the domain lock across the read-modify-write of the zone
+        let _lock = self.lock_domain(&format!("{}.{}", sld, tld)).await;
+
+        // Retrieve existing hosts, a zone with a single record comes back as an object
+        let existing_hosts: Value = self.domains_dns_get_hosts(sld, tld).await?;
+        let mut combined_hosts: Vec<HostRequest> = host_requests_from_value(&existing_hosts)?;
+        combined_hosts.extend(new_hosts);
+        info!("Combined Hosts: {:#?}", combined_hosts);
+
+        let result: Value = self.domains_dns_replace_hosts(sld, tld, &combined_hosts).await?;
+        if result.get("is_success").and_then(Value::as_str) == Some("true") {
             info!("Set Hosts operation was successful.");
-        } else {
+        } else if !self.dry_run {
             error!("Set Hosts operation failed.");
         }
 
-        info!("Set Hosts Result: {:#?}", result);
-
         Ok(result)
     }
+
+    /// Replaces the DNS host records of a domain like
+    /// [`domains_dns_replace_hosts`](Self::domains_dns_replace_hosts), returning what changed.
+    ///
+    /// The live zone is read first and the records are submitted as planned by a
+    /// [`ZonePlan`], nothing is sent when the zone already matches.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let summary: ChangeSummary = client.domains_dns_replace_hosts_with_summary("domain", "com", &hosts).await?;
+    /// println!("{} records removed", summary.records_removed.len());
+    /// ```
+    pub async fn domains_dns_replace_hosts_with_summary(
+        &self,
+        sld: &str,
+        tld: &str,
+        hosts: &[HostRequest]
+    ) -> Result<ChangeSummary, Box<dyn Error>> {
+        let _lock = self.lock_domain(&format!("{}.{}", sld, tld)).await;
+        let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+        self.apply_zone_summary(sld, tld, &current, hosts).await
+    }
+
+    /// Adds DNS host records to a domain like
+    /// [`domains_dns_set_hosts`](Self::domains_dns_set_hosts), returning what changed.
+    pub async fn domains_dns_set_hosts_with_summary(
+        &self,
+        sld: &str,
+        tld: &str,
+        new_hosts: Vec<HostRequest>
+    ) -> Result<ChangeSummary, Box<dyn Error>> {
+        validate_host_requests(&new_hosts)?;
+
+        let _lock = self.lock_domain(&format!("{}.{}", sld, tld)).await;
+        let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+        let mut combined_hosts: Vec<HostRequest> = current.iter().map(Host::to_host_request).collect();
+        combined_hosts.extend(new_hosts);
+        self.apply_zone_summary(sld, tld, &current, &combined_hosts).await
+    }
+
+    /// Plans `hosts` against the `current` records and submits the plan if it changes the zone.
+    async fn apply_zone_summary(
+        &self,
+        sld: &str,
+        tld: &str,
+        current: &[Host],
+        hosts: &[HostRequest]
+    ) -> Result<ChangeSummary, Box<dyn Error>> {
+        let hosts: Vec<HostRequest> = hosts
+            .iter()
+            .map(|host| self.apply_host_defaults(host))
+            .collect();
+        let plan: ZonePlan = ZonePlan::new(&format!("{}.{}", sld, tld), current, &hosts);
+
+        let mut summary: ChangeSummary = ChangeSummary::from_plan(&plan);
+        if plan.has_changes() {
+            let result: Value = self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+            summary.applied = result.get("is_success").and_then(Value::as_str) == Some("true");
+        }
+        Ok(summary)
+    }
 }
 
 #[cfg(test)]
@@ -288,6 +419,224 @@ mod tests {
     use dotenv::dotenv;
     use serde_json::json;
     use tracing::info;
+    use std::time::Duration;
+    use crate::utils::shutdown::{ Shutdown, ShuttingDown };
+    use crate::utils::xml_parser::parse_xml_to_json;
+    use crate::simulation::{ Latency, SimulatedTransport, SimulationConfig };
+    use crate::zone::RecordKey;
+
+    #[test]
+    fn test_host_params() {
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new(
+                "@".to_string(),
+                "MX".to_string(),
+                "mail.example.com.".to_string(),
+                Some("10".to_string()),
+                Some("MX".to_string()),
+                Some("1800".to_string()),
+                None,
+                None
+            ),
+            HostRequest::new(
+                "www".to_string(),
+                "CNAME".to_string(),
+                "example.com.".to_string(),
+                None,
+                None,
+                None,
+                None,
+                None
+            )
+        ];
+
+        let params: Value = host_params(&hosts);
+        assert_eq!(params["HostName1"], "@");
+        assert_eq!(params["MXPref1"], "10");
+        assert_eq!(params["TTL1"], "1800");
+        assert_eq!(params["RecordType2"], "CNAME");
+        assert!(params.get("TTL2").is_none());
+        assert_eq!(params["EmailType"], "MX");
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_replace_hosts_dry_run() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, None, None, None)
+        ];
+
+        let planned: Value = client.domains_dns_replace_hosts("example", "com", &hosts).await.unwrap();
+        assert_eq!(planned["dryRun"], true);
+        assert_eq!(planned["command"], "namecheap.domains.dns.setHosts");
+        assert_eq!(planned["params"]["Address1"], "203.0.113.10");
+        assert!(planned.get("apiKey").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_replace_hosts_canonical_order() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None),
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, None, None, None)
+        ];
+
+        let planned: Value = client.domains_dns_replace_hosts("example", "com", &hosts).await.unwrap();
+        assert_eq!(planned["params"]["HostName1"], "@");
+        assert_eq!(planned["params"]["HostName2"], "www");
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_hosts_with_summary() {
+        let config: SimulationConfig = SimulationConfig {
+            latency: Latency::Fixed(Duration::ZERO),
+            propagation_delay: Duration::ZERO,
+            ..SimulationConfig::default()
+        };
+        let apex: HostRequest = HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, Some("1800".to_string()), None, None);
+        let simulation = std::sync::Arc::new(SimulatedTransport::new(config).with_zone("example.com", std::slice::from_ref(&apex)));
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_transport(simulation);
+        let www: HostRequest = HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None);
+
+        let summary: ChangeSummary = client.domains_dns_set_hosts_with_summary("example", "com", vec![www.clone()]).await.unwrap();
+        assert!(summary.applied);
+        assert_eq!(summary.records_added, [RecordKey::from(&www)]);
+        assert!(summary.records_removed.is_empty());
+
+        let summary: ChangeSummary = client.domains_dns_replace_hosts_with_summary("example", "com", std::slice::from_ref(&www)).await.unwrap();
+        assert_eq!(summary.records_removed, [RecordKey::from(&apex)]);
+
+        let summary: ChangeSummary = client.domains_dns_replace_hosts_with_summary("example", "com", &[www]).await.unwrap();
+        assert!(summary.is_empty() && !summary.applied);
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_replace_hosts_refused_after_shutdown() {
+        let shutdown: Shutdown = Shutdown::new();
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_shutdown(shutdown.clone());
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, None, None, None)
+        ];
+
+        assert!(shutdown.shutdown(Duration::from_millis(10)).await);
+        let error: Box<dyn Error> = client.domains_dns_replace_hosts("example", "com", &hosts).await.unwrap_err();
+        assert!(error.downcast_ref::<ShuttingDown>().is_some());
+    }
+
+    #[test]
+    fn test_host_requests_from_value() {
+        // A single-record zone as returned by `domains_dns_get_hosts`, with numeric fields
+        let zone: Value = parse_xml_to_json(
+            r#"<ApiResponse><CommandResponse><DomainDNSGetHostsResult>
+            <host HostId="1" Name="@" Type="MX" Address="mail.example.com." MXPref="10" TTL="1800" />
+            </DomainDNSGetHostsResult></CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let hosts: &Value = &zone["ApiResponse"]["CommandResponse"]["DomainDNSGetHostsResult"]["host"];
+        let requests: Vec<HostRequest> = host_requests_from_value(hosts).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].mx_pref.as_deref(), Some("10"));
+
+        let requests: Vec<HostRequest> = host_requests_from_value(
+            &json!([{ "HostName": "www", "RecordType": "a", "Address": "203.0.113.10", "TTL": 300, "MXPref": 10 }])
+        ).unwrap();
+        assert_eq!(requests[0].ttl.as_deref(), Some("300"));
+        assert_eq!(requests[0].record_type, "A");
+        assert_eq!(requests[0].mx_pref, None);
+        assert!(host_requests_from_value(&Value::Null).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_host_requests_from_value_rejects_incomplete_records() {
+        let error: HostRecordError = host_requests_from_value(
+            &json!([
+                { "name": "@", "type": "A", "address": "203.0.113.10" },
+                { "name": "www", "type": "CNAME" }
+            ])
+        ).unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(error.field, "Address");
+
+        let error: HostRecordError = host_requests_from_value(
+            &json!({ "name": "@", "type": "A", "address": "203.0.113.10", "ttl": { "value": 300 } })
+        ).unwrap_err();
+        assert_eq!(error.field, "TTL");
+        assert!(host_requests_from_value(&json!(["@ A 203.0.113.10"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_domains_dns_set_hosts_rejects_missing_address() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        );
+        let hosts: Vec<HostRequest> = vec![
+            HostRequest::new("@".to_string(), "A".to_string(), String::new(), None, None, None, None, None)
+        ];
+
+        // Rejected before any call is made
+        let error: Box<dyn Error> = client.domains_dns_set_hosts("example", "com", hosts).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<HostRecordError>().unwrap().field, "Address");
+    }
+
+    #[test]
+    fn test_apply_host_defaults() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        )
+            .with_default_ttl(300)
+            .with_default_email_type("MX");
+
+        let omitted = HostRequest::new(
+            "@".to_string(),
+            "A".to_string(),
+            "203.0.113.10".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None
+        );
+        let explicit = HostRequest {
+            ttl: Some("60".to_string()),
+            ..omitted.clone()
+        };
+
+        let applied = client.apply_host_defaults(&omitted);
+        assert_eq!(applied.ttl.as_deref(), Some("300"));
+        assert_eq!(applied.email_type.as_deref(), Some("MX"));
+        assert_eq!(client.apply_host_defaults(&explicit).ttl.as_deref(), Some("60"));
+    }
 
     #[tokio::test]
     async fn test_domains_dns_set_hosts() {
@@ -0,0 +1,95 @@
+//! ### `domains.ns.create` Implementation
+//!
+//! This module provides the implementation for the `domains.ns.create` method of the NameCheap API.
+//!
+//! It registers a nameserver under a domain with its glue record, e.g. `ns1.example.com` at
+//! `192.0.2.1`.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::domains_ns::{ normalize_child_nameserver, validate_glue_ip };
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+
+/// ### Domain NS Create Result
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `nameserver`: The nameserver registered.
+/// - `ip`: The IP address of its glue record.
+/// - `created`: Whether the nameserver was registered.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainNsCreateResult {
+    pub domain: String,
+    pub nameserver: String,
+    pub ip: String,
+    pub created: bool,
+}
+
+impl NameCheapClient {
+    /// - `domains.ns.create`: Registers a nameserver under the specified domain
+    ///
+    /// The nameserver must be a subdomain of the domain, see
+    /// [`normalize_child_nameserver`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_ns_create("example", "com", "ns1.example.com", "192.0.2.1").await?;
+    /// assert!(result.created);
+    /// ```
+    pub async fn domains_ns_create(
+        &self,
+        sld: &str,
+        tld: &str,
+        nameserver: &str,
+        ip: &str
+    ) -> Result<DomainNsCreateResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.ns.create".to_string();
+        let nameserver: String = normalize_child_nameserver(sld, tld, nameserver)?;
+        let ip: String = validate_glue_ip(ip)?;
+        let params: Value = json!({
+            "SLD": sld,
+            "TLD": tld,
+            "Nameserver": nameserver,
+            "IP": ip,
+        });
+
+        let request: Request = Request::new(self, command, None, None, Some(params)).flatten_text();
+        self.check_policies(&Mutation::GlueRecord { domain: format!("{}.{}", sld, tld), nameserver: nameserver.clone(), ip: Some(ip) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_ns_create_result(&response).ok_or_else(|| {
+            format!("Failed to extract the registration of nameserver: {}", nameserver).into()
+        })
+    }
+}
+
+/// Parses a full `domains.ns.create` response.
+pub(crate) fn parse_ns_create_result(response: &Value) -> Option<DomainNsCreateResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainNSCreateResult")?;
+
+    Some(DomainNsCreateResult {
+        domain: parse_string(result, "domain", ""),
+        nameserver: parse_string(result, "nameserver", ""),
+        ip: parse_string(result, "ip", ""),
+        created: parse_string(result, "is_success", "false") == "true",
+    })
+}
@@ -0,0 +1,86 @@
+//! ### `domains.ns.delete` Implementation
+//!
+//! This module provides the implementation for the `domains.ns.delete` method of the NameCheap API.
+//!
+//! It deletes a nameserver registered under a domain. The registry refuses the deletion while
+//! domains are still delegated to the nameserver.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::domains_ns::normalize_child_nameserver;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+
+/// ### Domain NS Delete Result
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `nameserver`: The nameserver deleted.
+/// - `deleted`: Whether the nameserver was deleted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainNsDeleteResult {
+    pub domain: String,
+    pub nameserver: String,
+    pub deleted: bool,
+}
+
+impl NameCheapClient {
+    /// - `domains.ns.delete`: Deletes a nameserver registered under the specified domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_ns_delete("example", "com", "ns1.example.com").await?;
+    /// assert!(result.deleted);
+    /// ```
+    pub async fn domains_ns_delete(
+        &self,
+        sld: &str,
+        tld: &str,
+        nameserver: &str
+    ) -> Result<DomainNsDeleteResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.ns.delete".to_string();
+        let nameserver: String = normalize_child_nameserver(sld, tld, nameserver)?;
+        let params: Value = json!({
+            "SLD": sld,
+            "TLD": tld,
+            "Nameserver": nameserver,
+        });
+
+        let request: Request = Request::new(self, command, None, None, Some(params)).flatten_text();
+        self.check_policies(&Mutation::GlueRecord { domain: format!("{}.{}", sld, tld), nameserver: nameserver.clone(), ip: None })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_ns_delete_result(&response).ok_or_else(|| {
+            format!("Failed to extract the deletion of nameserver: {}", nameserver).into()
+        })
+    }
+}
+
+/// Parses a full `domains.ns.delete` response.
+pub(crate) fn parse_ns_delete_result(response: &Value) -> Option<DomainNsDeleteResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainNSDeleteResult")?;
+
+    Some(DomainNsDeleteResult {
+        domain: parse_string(result, "domain", ""),
+        nameserver: parse_string(result, "nameserver", ""),
+        deleted: parse_string(result, "is_success", "false") == "true",
+    })
+}
@@ -0,0 +1,92 @@
+//! ### `domains.ns.getInfo` Implementation
+//!
+//! This module provides the implementation for the `domains.ns.getInfo` method of the NameCheap API.
+//!
+//! It retrieves the IP address and registry statuses of a nameserver registered under a domain.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::domains_ns::normalize_child_nameserver;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, get_nodes_at, parse_string, text_of };
+
+/// ### Domain NS Info
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `nameserver`: The registered nameserver.
+/// - `ip`: The IP address of its glue record.
+/// - `statuses`: The registry statuses of the nameserver, e.g. `OK` or `Linked`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainNsInfo {
+    pub domain: String,
+    pub nameserver: String,
+    pub ip: String,
+    pub statuses: Vec<String>,
+}
+
+impl DomainNsInfo {
+    /// Returns `true` if domains are delegated to the nameserver, it cannot be deleted then.
+    pub fn is_linked(&self) -> bool {
+        self.statuses.iter().any(|status| status.eq_ignore_ascii_case("Linked"))
+    }
+}
+
+impl NameCheapClient {
+    /// - `domains.ns.getInfo`: Gets the details of a nameserver registered under the specified domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let info = client.domains_ns_get_info("example", "com", "ns1.example.com").await?;
+    /// println!("{} is at {}", info.nameserver, info.ip);
+    /// ```
+    pub async fn domains_ns_get_info(
+        &self,
+        sld: &str,
+        tld: &str,
+        nameserver: &str
+    ) -> Result<DomainNsInfo, Box<dyn Error>> {
+        let command: String = "namecheap.domains.ns.getInfo".to_string();
+        let nameserver: String = normalize_child_nameserver(sld, tld, nameserver)?;
+        let params: Value = json!({
+            "SLD": sld,
+            "TLD": tld,
+            "Nameserver": nameserver,
+        });
+
+        let response: Value = Request::new(self, command, None, None, Some(params)).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_ns_info(&response).ok_or_else(|| {
+            format!("Failed to extract the details of nameserver: {}", nameserver).into()
+        })
+    }
+}
+
+/// Parses a full `domains.ns.getInfo` response.
+pub(crate) fn parse_ns_info(response: &Value) -> Option<DomainNsInfo> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainNSInfoResult")?;
+    let statuses: Vec<String> = get_nodes_at(result, "/NameserverStatuses/Status")
+        .into_iter()
+        .filter_map(text_of)
+        .map(|status| status.trim().to_string())
+        .collect();
+
+    Some(DomainNsInfo {
+        domain: parse_string(result, "domain", ""),
+        nameserver: parse_string(result, "nameserver", ""),
+        ip: parse_string(result, "ip", ""),
+        statuses,
+    })
+}
@@ -0,0 +1,87 @@
+//! ## Domains NS API
+//! The Domains NS API manages the nameservers registered under a domain at the registry, with
+//! their glue records, so the domain can serve as its own vanity nameservers (e.g.
+//! `ns1.example.com`).
+//!
+//! ### Available Methods
+//! - `namecheap.domains.ns.create`: Register a nameserver with its IP address.
+//! - `namecheap.domains.ns.delete`: Delete a registered nameserver.
+//! - `namecheap.domains.ns.getInfo`: Retrieve the IP address and statuses of a registered nameserver.
+//! - `namecheap.domains.ns.update`: Change the IP address of a registered nameserver.
+//!
+//! Registering a nameserver does not delegate the domain to it, see `domains_dns_set_custom`.
+//!
+
+use std::net::IpAddr;
+
+/// - **domains.ns.create**
+pub mod create;
+/// - **domains.ns.delete**
+pub mod delete;
+/// - **domains.ns.getInfo**
+pub mod get_info;
+/// - **domains.ns.update**
+pub mod update;
+
+/// Normalizes a nameserver registered under `sld`.`tld`.
+///
+/// The nameserver is trimmed, lowercased and stripped of a trailing dot. Fails if it is not a
+/// subdomain of the domain, the registry only accepts glue records for those.
+pub fn normalize_child_nameserver(sld: &str, tld: &str, nameserver: &str) -> Result<String, String> {
+    let nameserver: String = nameserver.trim().trim_end_matches('.').to_lowercase();
+    let suffix: String = format!(".{}.{}", sld, tld).to_lowercase();
+    let host: Option<&str> = nameserver.strip_suffix(&suffix);
+    let is_child: bool = host.is_some_and(|host| {
+        !host.is_empty() &&
+            host.split('.').all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    });
+    if !is_child {
+        return Err(format!("Nameserver {:?} is not a subdomain of {}.{}", nameserver, sld, tld));
+    }
+    Ok(nameserver)
+}
+
+/// Validates the IP address of a glue record, returning it in canonical form.
+pub fn validate_glue_ip(ip: &str) -> Result<String, String> {
+    ip.trim()
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| format!("Invalid IP address: {:?}", ip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use crate::NameCheapClient;
+    use crate::utils::request_builder::PlannedRequest;
+
+    #[test]
+    fn test_normalize_child_nameserver() {
+        assert_eq!(normalize_child_nameserver("example", "com", " NS1.Example.com. ").unwrap(), "ns1.example.com");
+        assert!(normalize_child_nameserver("example", "com", "ns1.other.com").is_err());
+        assert!(normalize_child_nameserver("example", "com", "example.com").is_err());
+        assert!(normalize_child_nameserver("example", "com", "ns_1.example.com").is_err());
+
+        assert_eq!(validate_glue_ip(" 192.0.2.1 ").unwrap(), "192.0.2.1");
+        assert!(validate_glue_ip("192.0.2.256").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_glue_records_dry_run() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+
+        let error: Box<dyn Error> = client.domains_ns_create("example", "com", "ns1.example.com", "192.0.2.1").await.unwrap_err();
+        assert_eq!(error.downcast_ref::<PlannedRequest>().unwrap().params["IP"], "192.0.2.1");
+        let error: Box<dyn Error> = client.domains_ns_update("example", "com", "ns1.example.com", "192.0.2.1", "192.0.2.2").await.unwrap_err();
+        assert!(error.downcast_ref::<PlannedRequest>().is_some());
+        let error: Box<dyn Error> = client.domains_ns_delete("example", "com", "ns1.example.com").await.unwrap_err();
+        assert_eq!(error.downcast_ref::<PlannedRequest>().unwrap().command, "namecheap.domains.ns.delete");
+    }
+}
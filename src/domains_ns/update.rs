@@ -0,0 +1,95 @@
+//! ### `domains.ns.update` Implementation
+//!
+//! This module provides the implementation for the `domains.ns.update` method of the NameCheap API.
+//!
+//! It moves a nameserver registered under a domain to a new IP address. The registry checks
+//! the current address, read it with `domains_ns_get_info` when it is not known.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::domains_ns::{ normalize_child_nameserver, validate_glue_ip };
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+
+/// ### Domain NS Update Result
+///
+/// #### Fields
+/// - `domain`: The domain name.
+/// - `nameserver`: The nameserver updated.
+/// - `ip`: The IP address requested, in effect when `updated` is `true`.
+/// - `updated`: Whether the change succeeded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DomainNsUpdateResult {
+    pub domain: String,
+    pub nameserver: String,
+    pub ip: String,
+    pub updated: bool,
+}
+
+impl NameCheapClient {
+    /// - `domains.ns.update`: Changes the IP address of a nameserver registered under the specified domain
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = client.domains_ns_update("example", "com", "ns1.example.com", "192.0.2.1", "192.0.2.2").await?;
+    /// assert!(result.updated);
+    /// ```
+    pub async fn domains_ns_update(
+        &self,
+        sld: &str,
+        tld: &str,
+        nameserver: &str,
+        old_ip: &str,
+        ip: &str
+    ) -> Result<DomainNsUpdateResult, Box<dyn Error>> {
+        let command: String = "namecheap.domains.ns.update".to_string();
+        let nameserver: String = normalize_child_nameserver(sld, tld, nameserver)?;
+        let old_ip: String = validate_glue_ip(old_ip)?;
+        let ip: String = validate_glue_ip(ip)?;
+        let params: Value = json!({
+            "SLD": sld,
+            "TLD": tld,
+            "Nameserver": nameserver,
+            "OldIP": old_ip,
+            "IP": ip,
+        });
+
+        let request: Request = Request::new(self, command, None, None, Some(params)).flatten_text();
+        self.check_policies(&Mutation::GlueRecord { domain: format!("{}.{}", sld, tld), nameserver: nameserver.clone(), ip: Some(ip.clone()) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_ns_update_result(&response, &ip).ok_or_else(|| {
+            format!("Failed to extract the update of nameserver: {}", nameserver).into()
+        })
+    }
+}
+
+/// Parses a full `domains.ns.update` response for a change to `ip`.
+pub(crate) fn parse_ns_update_result(response: &Value, ip: &str) -> Option<DomainNsUpdateResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainNSUpdateResult")?;
+
+    Some(DomainNsUpdateResult {
+        domain: parse_string(result, "domain", ""),
+        nameserver: parse_string(result, "nameserver", ""),
+        ip: ip.to_string(),
+        updated: parse_string(result, "is_success", "false") == "true",
+    })
+}
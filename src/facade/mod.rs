@@ -0,0 +1,345 @@
+//! ## Client Facades
+//! `NameCheapClient` carries every API method of the crate, which makes it hard to browse and
+//! hard to hand out with limited power. The facades scope it by capability:
+//! - `client.domains()`: registration, contacts and the domain list.
+//! - `client.dns()`: zones, addressed by full domain name (`example.com`).
+//! - `client.ssl()`: certificates.
+//! - `client.users()`: balances and the address book.
+//!
+//! Each facade owns a clone of the client (sharing its connection pool, rate limiter and
+//! policies), so it can be moved into a task or stored by a subsystem. `read_only()` narrows
+//! the domains and DNS facades to handles without any mutating method:
+//!
+//! ```rust
+//! let records = client.dns().get_hosts("example.com").await?;
+//!
+//! // A reporting subsystem can read zones but not change them
+//! async fn report(zones: DnsReader) -> Result<(), Box<dyn Error>> {
+//!     println!("{}", zones.snapshot("example.com").await?.hash);
+//!     Ok(())
+//! }
+//! report(client.dns().read_only()).await?;
+//! ```
+//!
+//! The facades only delegate: the `NameCheapClient` methods stay available and behave the
+//! same.
+
+use serde_json::Value;
+use std::error::Error;
+use std::ops::Deref;
+
+// crate imports
+use crate::{ NameCheapClient, ContactSet, Domain, Host };
+use crate::batch::BatchReport;
+use crate::domains::audit_contacts::ContactAuditReport;
+use crate::domains::check::DomainCheckResult;
+use crate::domains::create::DomainCreateResult;
+use crate::domains::get_info::DomainInfo;
+use crate::domains::labels::Selector;
+use crate::domains::list_query::DomainListQuery;
+use crate::domains::portfolio::PortfolioSummary;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::response::money::Currency;
+use crate::response::paging::Pagination;
+use crate::ssl::activate::{ SslActivateRequest, SslActivateResult };
+use crate::ssl::get_info::SslInfo;
+use crate::ssl::get_list::{ SslCertificate, SslListQuery };
+use crate::users::get_balances::AccountBalances;
+use crate::users_address::create::ContactProfile;
+use crate::users_address::get_list::AddressSummary;
+use crate::users_address::sync::SyncedAddress;
+use crate::zone::diff::ZonePlan;
+use crate::zone::snapshot::ZoneSnapshot;
+use crate::zone::split_domain;
+
+/// Read-only access to domains.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DomainsReader {
+    client: NameCheapClient,
+}
+
+impl DomainsReader {
+    /// See [`NameCheapClient::domains_get_info`].
+    pub async fn get_info(&self, domain_name: &str) -> Result<DomainInfo, Box<dyn Error>> {
+        self.client.domains_get_info(domain_name).await
+    }
+
+    /// See [`NameCheapClient::domains_get_list_query`].
+    pub async fn get_list(&self, query: &DomainListQuery) -> Result<Value, Box<dyn Error>> {
+        self.client.domains_get_list_query(query).await
+    }
+
+    /// See [`NameCheapClient::domains_get_list_all`].
+    pub async fn get_list_all(&self) -> Result<Vec<Domain>, Box<dyn Error>> {
+        self.client.domains_get_list_all().await
+    }
+
+    /// See [`NameCheapClient::domains_find`].
+    pub async fn find(&self, domain_name: &str) -> Result<Option<Domain>, Box<dyn Error>> {
+        self.client.domains_find(domain_name).await
+    }
+
+    /// See [`NameCheapClient::domains_get_contact_set`].
+    pub async fn get_contacts(&self, domain_name: &str) -> Result<ContactSet, Box<dyn Error>> {
+        self.client.domains_get_contact_set(domain_name).await
+    }
+
+    /// See [`NameCheapClient::domains_check`].
+    pub async fn check<S: AsRef<str>>(&self, domain_names: &[S]) -> Result<Vec<DomainCheckResult>, Box<dyn Error>> {
+        self.client.domains_check(domain_names).await
+    }
+
+    /// See [`NameCheapClient::domains_get_tld_list`].
+    pub async fn get_tld_list(&self) -> Result<Value, Box<dyn Error>> {
+        self.client.domains_get_tld_list().await
+    }
+
+    /// See [`NameCheapClient::domains_without_auto_renew`].
+    pub async fn without_auto_renew(&self) -> Result<Vec<Domain>, Box<dyn Error>> {
+        self.client.domains_without_auto_renew().await
+    }
+
+    /// See [`NameCheapClient::portfolio_summary`].
+    pub async fn portfolio_summary(&self, with_details: bool) -> Result<PortfolioSummary, Box<dyn Error>> {
+        self.client.portfolio_summary(with_details).await
+    }
+
+    /// See [`NameCheapClient::audit_contacts`].
+    pub async fn audit_contacts(&self, reference: &ContactSet) -> Result<ContactAuditReport, Box<dyn Error>> {
+        self.client.audit_contacts(reference).await
+    }
+
+    /// See [`NameCheapClient::select`].
+    pub async fn select(&self, selector: &Selector) -> Result<Vec<String>, Box<dyn Error>> {
+        self.client.select(selector).await
+    }
+}
+
+/// Access to domains, reads included (see [`DomainsReader`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DomainsApi {
+    reader: DomainsReader,
+}
+
+impl DomainsApi {
+    /// Narrows the handle to reads.
+    pub fn read_only(self) -> DomainsReader {
+        self.reader
+    }
+
+    /// See [`NameCheapClient::domains_create`].
+    pub async fn create(&self, domain_name: &str, years: u8, contacts: &ContactSet) -> Result<DomainCreateResult, Box<dyn Error>> {
+        self.reader.client.domains_create(domain_name, years, contacts).await
+    }
+
+    /// See [`NameCheapClient::domains_set_contacts`].
+    pub async fn set_contacts(&self, domain_name: &str, contacts: &ContactSet) -> Result<Value, Box<dyn Error>> {
+        self.reader.client.domains_set_contacts(domain_name, contacts).await
+    }
+
+    /// See [`NameCheapClient::domains_set_contacts_bulk`].
+    pub async fn set_contacts_bulk(
+        &self,
+        domains: impl Into<Selector>,
+        contacts: &ContactSet,
+        dry_run: bool
+    ) -> Result<BatchReport<Value>, Box<dyn Error>> {
+        self.reader.client.domains_set_contacts_bulk(domains, contacts, dry_run).await
+    }
+}
+
+impl Deref for DomainsApi {
+    type Target = DomainsReader;
+
+    fn deref(&self) -> &DomainsReader {
+        &self.reader
+    }
+}
+
+/// Read-only access to zones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DnsReader {
+    client: NameCheapClient,
+}
+
+/// Splits `domain`, failing on names without a TLD.
+fn parts(domain: &str) -> Result<(&str, &str), Box<dyn Error>> {
+    split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain).into())
+}
+
+impl DnsReader {
+    /// Returns the host records of `domain`, see [`NameCheapClient::domains_dns_get_host_records`].
+    pub async fn get_hosts(&self, domain: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.client.domains_dns_get_host_records(sld, tld).await
+    }
+
+    /// Returns the name servers of `domain`, see [`NameCheapClient::domains_dns_get_list`].
+    pub async fn get_list(&self, domain: &str) -> Result<Value, Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.client.domains_dns_get_list(sld, tld).await
+    }
+
+    /// See [`NameCheapClient::zone_snapshot`].
+    pub async fn snapshot(&self, domain: &str) -> Result<ZoneSnapshot, Box<dyn Error>> {
+        self.client.zone_snapshot(domain).await
+    }
+}
+
+/// Access to zones, reads included (see [`DnsReader`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DnsApi {
+    reader: DnsReader,
+}
+
+impl DnsApi {
+    /// Narrows the handle to reads.
+    pub fn read_only(self) -> DnsReader {
+        self.reader
+    }
+
+    /// Adds `hosts` to the zone, see [`NameCheapClient::domains_dns_set_hosts`].
+    pub async fn set_hosts(&self, domain: &str, hosts: Vec<HostRequest>) -> Result<Value, Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.reader.client.domains_dns_set_hosts(sld, tld, hosts).await
+    }
+
+    /// Replaces the zone with `hosts`, see [`NameCheapClient::domains_dns_replace_hosts`].
+    pub async fn replace_hosts(&self, domain: &str, hosts: &[HostRequest]) -> Result<Value, Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.reader.client.domains_dns_replace_hosts(sld, tld, hosts).await
+    }
+
+    /// See [`NameCheapClient::ensure_records`].
+    pub async fn ensure_records(&self, domain: &str, records: &[HostRequest]) -> Result<ZonePlan, Box<dyn Error>> {
+        self.reader.client.ensure_records(domain, records).await
+    }
+
+    /// See [`NameCheapClient::replace_records`].
+    pub async fn replace_records(&self, domain: &str, records: &[HostRequest]) -> Result<ZonePlan, Box<dyn Error>> {
+        self.reader.client.replace_records(domain, records).await
+    }
+
+    /// See [`NameCheapClient::set_hosts_if_unchanged`].
+    pub async fn set_hosts_if_unchanged(&self, domain: &str, expected_hash: &str, hosts: &[HostRequest]) -> Result<ZonePlan, Box<dyn Error>> {
+        self.reader.client.set_hosts_if_unchanged(domain, expected_hash, hosts).await
+    }
+}
+
+impl Deref for DnsApi {
+    type Target = DnsReader;
+
+    fn deref(&self) -> &DnsReader {
+        &self.reader
+    }
+}
+
+/// Access to certificates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SslApi {
+    client: NameCheapClient,
+}
+
+impl SslApi {
+    /// See [`NameCheapClient::ssl_get_info`].
+    pub async fn get_info(&self, certificate_id: i64, with_certificate: bool) -> Result<SslInfo, Box<dyn Error>> {
+        self.client.ssl_get_info(certificate_id, with_certificate).await
+    }
+
+    /// See [`NameCheapClient::ssl_get_list`].
+    pub async fn get_list(&self, query: &SslListQuery) -> Result<(Vec<SslCertificate>, Pagination), Box<dyn Error>> {
+        self.client.ssl_get_list(query).await
+    }
+
+    /// See [`NameCheapClient::ssl_activate`].
+    pub async fn activate(&self, request: &SslActivateRequest) -> Result<SslActivateResult, Box<dyn Error>> {
+        self.client.ssl_activate(request).await
+    }
+}
+
+/// Access to the account: balances and the address book.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UsersApi {
+    client: NameCheapClient,
+}
+
+impl UsersApi {
+    /// See [`NameCheapClient::users_get_balances`].
+    pub async fn get_balances(&self) -> Result<AccountBalances, Box<dyn Error>> {
+        self.client.users_get_balances().await
+    }
+
+    /// See [`NameCheapClient::account_currency`].
+    pub async fn currency(&self) -> Result<Currency, Box<dyn Error>> {
+        self.client.account_currency().await
+    }
+
+    /// See [`NameCheapClient::users_address_get_list`].
+    pub async fn addresses(&self) -> Result<Vec<AddressSummary>, Box<dyn Error>> {
+        self.client.users_address_get_list().await
+    }
+
+    /// See [`NameCheapClient::users_address_get_info`].
+    pub async fn address(&self, address_id: i64) -> Result<ContactProfile, Box<dyn Error>> {
+        self.client.users_address_get_info(address_id).await
+    }
+
+    /// See [`NameCheapClient::users_address_create`].
+    pub async fn create_address(&self, profile: &ContactProfile) -> Result<i64, Box<dyn Error>> {
+        self.client.users_address_create(profile).await
+    }
+
+    /// See [`NameCheapClient::users_address_update`].
+    pub async fn update_address(&self, address_id: i64, profile: &ContactProfile) -> Result<i64, Box<dyn Error>> {
+        self.client.users_address_update(address_id, profile).await
+    }
+
+    /// See [`NameCheapClient::sync_address_book`].
+    pub async fn sync_address_book(&self, profiles: Vec<ContactProfile>) -> Result<Vec<SyncedAddress>, Box<dyn Error>> {
+        self.client.sync_address_book(profiles).await
+    }
+}
+
+impl NameCheapClient {
+    /// Returns a handle scoped to domains.
+    pub fn domains(&self) -> DomainsApi {
+        DomainsApi { reader: DomainsReader { client: self.clone() } }
+    }
+
+    /// Returns a handle scoped to zones.
+    pub fn dns(&self) -> DnsApi {
+        DnsApi { reader: DnsReader { client: self.clone() } }
+    }
+
+    /// Returns a handle scoped to certificates.
+    pub fn ssl(&self) -> SslApi {
+        SslApi { client: self.clone() }
+    }
+
+    /// Returns a handle scoped to the account.
+    pub fn users(&self) -> UsersApi {
+        UsersApi { client: self.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_facades_share_client_state() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let dns: DnsReader = client.dns().read_only();
+        assert!(dns.client.shared.ptr_eq(&client.shared));
+        assert!(client.domains().reader.client.shared.ptr_eq(&client.shared));
+
+        let error: Box<dyn Error> = dns.get_hosts("localhost").await.unwrap_err();
+        assert_eq!(error.to_string(), "Invalid domain: localhost");
+    }
+}
@@ -0,0 +1,269 @@
+//! ## DNS Failover
+//! A health-checked updater for NameCheap-hosted DNS: it monitors a primary address through
+//! an HTTP health check and points an `A` record at a backup address when the primary is
+//! down, then back once it recovers.
+//!
+//! Switching requires several consecutive failed (or successful) checks, so a single slow
+//! response does not flap the record. Every check and switch is reported as a
+//! `FailoverEvent` on a channel.
+//!
+//! This module is behind the `failover` feature.
+//!
+//! DNS caches keep serving the old address until the record TTL expires, so keep the TTL
+//! of the failover record low (`FailoverConfig::ttl`).
+
+use serde::{ Serialize, Deserialize };
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tracing::{ info, warn };
+
+// crate imports
+use crate::NameCheapClient;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::shutdown::Shutdown;
+
+/// The configuration of a `Failover`.
+///
+/// #### Fields
+/// - `domain`: The domain holding the record, e.g. `example.com`.
+/// - `host_name`: The host name of the `A` record, e.g. `@` or `api`.
+/// - `primary`: The primary IP address.
+/// - `backup`: The backup IP address.
+/// - `health_url`: The URL checked on the primary, a 2xx response is healthy.
+/// - `interval`: The time between health checks.
+/// - `timeout`: The health check timeout.
+/// - `failure_threshold`: Consecutive failed checks before switching to the backup.
+/// - `recovery_threshold`: Consecutive successful checks before switching back.
+/// - `ttl`: The TTL of the `A` record.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct FailoverConfig {
+    pub domain: String,
+    pub host_name: String,
+    pub primary: String,
+    pub backup: String,
+    pub health_url: String,
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub failure_threshold: u32,
+    pub recovery_threshold: u32,
+    pub ttl: i64,
+}
+
+impl FailoverConfig {
+    /// Creates a configuration with a 30 second interval, a 5 second timeout, 3 failures to
+    /// switch to the backup, 5 successes to switch back and a 60 second TTL.
+    pub fn new(domain: &str, host_name: &str, primary: &str, backup: &str, health_url: &str) -> Self {
+        FailoverConfig {
+            domain: domain.to_string(),
+            host_name: host_name.to_string(),
+            primary: primary.to_string(),
+            backup: backup.to_string(),
+            health_url: health_url.to_string(),
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5),
+            failure_threshold: 3,
+            recovery_threshold: 5,
+            ttl: 60,
+        }
+    }
+}
+
+/// The address the record currently points at.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum FailoverTarget {
+    Primary,
+    Backup,
+}
+
+/// An event emitted by a running `Failover`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "event")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum FailoverEvent {
+    /// A health check of the primary succeeded.
+    CheckPassed { consecutive: u32 },
+    /// A health check of the primary failed.
+    CheckFailed { consecutive: u32, reason: String },
+    /// The record was pointed at the given target.
+    Switched { target: FailoverTarget, address: String },
+    /// Updating the record failed, the switch is retried after the next check.
+    UpdateFailed { target: FailoverTarget, reason: String },
+}
+
+/// The hysteresis state of a failover.
+#[derive(Debug, Clone)]
+struct Hysteresis {
+    target: FailoverTarget,
+    failures: u32,
+    successes: u32,
+}
+
+impl Hysteresis {
+    /// Records a check result and returns the target to switch to, if any.
+    fn observe(&mut self, healthy: bool, config: &FailoverConfig) -> Option<FailoverTarget> {
+        if healthy {
+            self.successes += 1;
+            self.failures = 0;
+        } else {
+            self.failures += 1;
+            self.successes = 0;
+        }
+
+        match self.target {
+            FailoverTarget::Primary if self.failures >= config.failure_threshold =>
+                Some(FailoverTarget::Backup),
+            FailoverTarget::Backup if self.successes >= config.recovery_threshold =>
+                Some(FailoverTarget::Primary),
+            _ => None,
+        }
+    }
+}
+
+/// A health-checked DNS failover.
+///
+/// # Example
+///
+/// ```rust
+/// let config = FailoverConfig::new("example.com", "@", "203.0.113.10", "198.51.100.10", "http://203.0.113.10/health");
+/// let (sender, mut events) = tokio::sync::mpsc::channel(16);
+/// tokio::spawn(Failover::new(client, config).run(sender));
+/// while let Some(event) = events.recv().await {
+///     println!("{:?}", event);
+/// }
+/// ```
+pub struct Failover {
+    client: NameCheapClient,
+    config: FailoverConfig,
+    state: Hysteresis,
+    shutdown: Shutdown,
+}
+
+impl Failover {
+    /// Creates a failover, assuming the record points at the primary.
+    pub fn new(client: NameCheapClient, config: FailoverConfig) -> Self {
+        Failover {
+            client,
+            config,
+            state: Hysteresis {
+                target: FailoverTarget::Primary,
+                failures: 0,
+                successes: 0,
+            },
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Stops `run` once `shutdown` begins, a switch in progress completes first.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Returns the target the record points at.
+    pub fn target(&self) -> FailoverTarget {
+        self.state.target
+    }
+
+    /// Checks the primary once, switching the record when a threshold is reached.
+    pub async fn check(&mut self, http: &reqwest::Client) -> Vec<FailoverEvent> {
+        let mut events: Vec<FailoverEvent> = Vec::new();
+
+        let result: Result<(), String> = match
+            http.get(&self.config.health_url).timeout(self.config.timeout).send().await
+        {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(format!("status {}", response.status())),
+            Err(error) => Err(error.to_string()),
+        };
+
+        let switch: Option<FailoverTarget> = self.state.observe(result.is_ok(), &self.config);
+        events.push(match result {
+            Ok(()) => FailoverEvent::CheckPassed { consecutive: self.state.successes },
+            Err(reason) => FailoverEvent::CheckFailed { consecutive: self.state.failures, reason },
+        });
+
+        if let Some(target) = switch {
+            events.push(self.switch_to(target).await);
+        }
+        events
+    }
+
+    /// Points the record at `target`.
+    pub async fn switch_to(&mut self, target: FailoverTarget) -> FailoverEvent {
+        let address: String = match target {
+            FailoverTarget::Primary => self.config.primary.clone(),
+            FailoverTarget::Backup => self.config.backup.clone(),
+        };
+        let record: HostRequest = HostRequest::new(
+            self.config.host_name.clone(),
+            "A".to_string(),
+            address.clone(),
+            None,
+            None,
+            Some(self.config.ttl.to_string()),
+            None,
+            None
+        );
+
+        match self.client.replace_records(&self.config.domain, &[record]).await {
+            Ok(_) => {
+                info!("Failover for {} switched to {}", self.config.domain, address);
+                self.state.target = target;
+                FailoverEvent::Switched { target, address }
+            }
+            Err(error) => {
+                warn!("Failover for {} failed to switch: {}", self.config.domain, error);
+                FailoverEvent::UpdateFailed { target, reason: error.to_string() }
+            }
+        }
+    }
+
+    /// Runs health checks every `interval` until the event receiver is dropped or shutdown begins.
+    pub async fn run(mut self, events: Sender<FailoverEvent>) {
+        let http: reqwest::Client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(self.config.interval);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = self.shutdown.cancelled() => return,
+            }
+            for event in self.check(&http).await {
+                if events.send(event).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hysteresis() {
+        let config: FailoverConfig = FailoverConfig::new("example.com", "@", "1.1.1.1", "2.2.2.2", "http://1.1.1.1/");
+        let mut state: Hysteresis = Hysteresis {
+            target: FailoverTarget::Primary,
+            failures: 0,
+            successes: 0,
+        };
+
+        assert_eq!(state.observe(false, &config), None);
+        assert_eq!(state.observe(true, &config), None);
+        assert_eq!(state.observe(false, &config), None);
+        assert_eq!(state.observe(false, &config), None);
+        assert_eq!(state.observe(false, &config), Some(FailoverTarget::Backup));
+
+        state.target = FailoverTarget::Backup;
+        for _ in 0..4 {
+            assert_eq!(state.observe(true, &config), None);
+        }
+        assert_eq!(state.observe(true, &config), Some(FailoverTarget::Primary));
+    }
+}
@@ -0,0 +1,285 @@
+//! ### FFI Bindings
+//!
+//! Available with the `ffi` feature. This module exposes the main operations as a C ABI so
+//! services written in other languages (Kotlin/JNA, Python/ctypes, Go/cgo, ...) can reuse the
+//! client. The functions follow cbindgen conventions, a header can be generated with
+//! `cbindgen --crate namecheap --output namecheap.h`.
+//!
+//! Build the shared library with
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! #### Conventions
+//! - Every argument and result is a NUL terminated UTF-8 string, structured values are JSON.
+//! - Results are JSON objects, either `{"ok": <result>}` or `{"error": "<message>"}`.
+//! - Strings returned by this module are owned by the caller and must be released with
+//!   [`namecheap_string_free`], clients with [`namecheap_client_free`].
+//! - Calls block the calling thread, async work runs on a runtime shared by all clients. They
+//!   must not be called from an async context, e.g. a thread driving a tokio runtime: such a
+//!   call fails with an error result instead of blocking.
+//! - A panic never unwinds into the caller, it becomes an error result (or a null pointer).
+
+use serde::Serialize;
+use serde_json::{ json, Value };
+use std::error::Error;
+use std::ffi::{ c_char, CStr, CString };
+use std::any::Any;
+use std::future::Future;
+use std::panic::{ catch_unwind, AssertUnwindSafe };
+use std::sync::LazyLock;
+use tokio::runtime::Runtime;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::domains_dns::set_hosts::{ host_requests_from_value, HostRequest };
+
+/// The runtime driving every FFI call.
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread().enable_all().build().expect("Failed to build the FFI runtime")
+});
+
+/// Reads a borrowed C string argument.
+///
+/// # Safety
+/// `value` must be null or point to a NUL terminated string valid for the duration of the call.
+unsafe fn read_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, Box<dyn Error>> {
+    if value.is_null() {
+        return Err(format!("{} is null", name).into());
+    }
+    Ok(unsafe { CStr::from_ptr(value) }.to_str()?)
+}
+
+/// Converts a result into an owned `{"ok": ...}` or `{"error": ...}` C string.
+fn into_c_string<T: Serialize>(result: Result<T, Box<dyn Error>>) -> *mut c_char {
+    let envelope: Value = match result.and_then(|value| Ok(serde_json::to_value(value)?)) {
+        Ok(value) => json!({ "ok": value }),
+        Err(error) => json!({ "error": error.to_string() }),
+    };
+    // serde_json escapes control characters, the output never contains a NUL byte
+    CString::new(envelope.to_string()).unwrap_or_default().into_raw()
+}
+
+/// Runs an async operation to completion on the shared runtime.
+///
+/// Panics when called from an async context, exported functions run it inside [`catch_panic`].
+fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+/// Returns the message of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Runs the body of an exported function, a panic is returned as `{"error": ...}`.
+fn catch_panic(body: impl FnOnce() -> *mut c_char) -> *mut c_char {
+    catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        into_c_string::<()>(Err(format!("panicked: {}", panic_message(payload.as_ref())).into()))
+    })
+}
+
+/// Creates a client from a JSON configuration.
+///
+/// The configuration uses the serialized form of [`NameCheapClient`], e.g.
+/// `{"apiUser": "...", "apiKey": "...", "clientIp": "...", "userName": "...", "production": false, "apiUrl": null}`.
+/// Returns null if the configuration is invalid.
+///
+/// # Safety
+/// `config_json` must be null or a valid NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_client_new(config_json: *const c_char) -> *mut NameCheapClient {
+    catch_unwind(|| {
+        let client: Result<NameCheapClient, Box<dyn Error>> = unsafe { read_str(config_json, "config_json") }.and_then(
+            |config| Ok(serde_json::from_str(config)?)
+        );
+        match client {
+            Ok(client) => Box::into_raw(Box::new(client)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    }).unwrap_or(std::ptr::null_mut())
+}
+
+/// Creates a client from the `NAMECHEAP_*` environment variables, see `NameCheapClient::new_from_env`.
+///
+/// Returns null if a variable is missing.
+#[unsafe(no_mangle)]
+pub extern "C" fn namecheap_client_from_env() -> *mut NameCheapClient {
+    catch_unwind(|| match NameCheapClient::new_from_env() {
+        Ok(client) => Box::into_raw(Box::new(client)),
+        Err(_) => std::ptr::null_mut(),
+    }).unwrap_or(std::ptr::null_mut())
+}
+
+/// Releases a client created by this module.
+///
+/// # Safety
+/// `client` must be null or a pointer returned by `namecheap_client_new` or
+/// `namecheap_client_from_env` that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_client_free(client: *mut NameCheapClient) {
+    if !client.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(unsafe { Box::from_raw(client) })));
+    }
+}
+
+/// Releases a string returned by this module.
+///
+/// # Safety
+/// `value` must be null or a string returned by this module that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(unsafe { CString::from_raw(value) })));
+    }
+}
+
+/// Lists every domain of the account, the result is an array of `Domain`.
+///
+/// # Safety
+/// `client` must be a live pointer returned by `namecheap_client_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_domains_get_list(client: *const NameCheapClient) -> *mut c_char {
+    catch_panic(|| {
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            return into_c_string::<()>(Err("client is null".into()));
+        };
+        into_c_string(block_on(client.domains_get_list_all()))
+    })
+}
+
+/// Checks the availability of domains, `domains_json` being an array of domain names, e.g.
+/// `["example.com", "example.net"]`. The result is an array of `DomainCheckResult`.
+///
+/// # Safety
+/// `client` must be a live pointer returned by `namecheap_client_new`, `domains_json` must be a
+/// valid NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_domains_check(
+    client: *const NameCheapClient,
+    domains_json: *const c_char
+) -> *mut c_char {
+    catch_panic(|| {
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            return into_c_string::<()>(Err("client is null".into()));
+        };
+        let result = (|| {
+            let domains: Vec<String> = serde_json::from_str(unsafe { read_str(domains_json, "domains_json") }?)?;
+            block_on(client.domains_check(&domains))
+        })();
+        into_c_string(result)
+    })
+}
+
+/// Returns the host records of a domain, the result is an array of `Host`.
+///
+/// # Safety
+/// `client` must be a live pointer returned by `namecheap_client_new`, `sld` and `tld` must be
+/// valid NUL terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_dns_get_hosts(
+    client: *const NameCheapClient,
+    sld: *const c_char,
+    tld: *const c_char
+) -> *mut c_char {
+    catch_panic(|| {
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            return into_c_string::<()>(Err("client is null".into()));
+        };
+        let result = (|| {
+            let sld: &str = unsafe { read_str(sld, "sld") }?;
+            let tld: &str = unsafe { read_str(tld, "tld") }?;
+            block_on(client.domains_dns_get_host_records(sld, tld))
+        })();
+        into_c_string(result)
+    })
+}
+
+/// Replaces the host records of a domain with `hosts_json`, an array of `HostRequest`.
+///
+/// The zone is replaced exactly, records missing from `hosts_json` are removed.
+///
+/// # Safety
+/// `client` must be a live pointer returned by `namecheap_client_new`, the other arguments
+/// must be valid NUL terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn namecheap_dns_set_hosts(
+    client: *const NameCheapClient,
+    sld: *const c_char,
+    tld: *const c_char,
+    hosts_json: *const c_char
+) -> *mut c_char {
+    catch_panic(|| {
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            return into_c_string::<()>(Err("client is null".into()));
+        };
+        let result = (|| {
+            let sld: &str = unsafe { read_str(sld, "sld") }?;
+            let tld: &str = unsafe { read_str(tld, "tld") }?;
+            let hosts: Value = serde_json::from_str(unsafe { read_str(hosts_json, "hosts_json") }?)?;
+            let hosts: Vec<HostRequest> = host_requests_from_value(&hosts)?;
+            let _lock = block_on(client.lock_domain(&format!("{}.{}", sld, tld)));
+            block_on(client.domains_dns_replace_hosts(sld, tld, &hosts))
+        })();
+        into_c_string(result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(value: *mut c_char) -> Value {
+        let text: String = unsafe { CStr::from_ptr(value) }.to_str().unwrap().to_string();
+        unsafe { namecheap_string_free(value) };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let config: CString = CString::new(
+            r#"{"apiUser":"user","apiKey":"key","clientIp":"127.0.0.1","userName":"user","production":false,"apiUrl":null,"dryRun":true}"#
+        ).unwrap();
+        let client: *mut NameCheapClient = unsafe { namecheap_client_new(config.as_ptr()) };
+        assert!(!client.is_null());
+
+        let sld: CString = CString::new("example").unwrap();
+        let tld: CString = CString::new("com").unwrap();
+        let hosts: CString = CString::new(
+            r#"[{"host_name":"@","record_type":"A","address":"203.0.113.10","mx_pref":null,"email_type":null,"ttl":"1800","flag":null,"tag":null}]"#
+        ).unwrap();
+        let planned: Value = take(unsafe { namecheap_dns_set_hosts(client, sld.as_ptr(), tld.as_ptr(), hosts.as_ptr()) });
+        assert_eq!(planned["ok"]["dryRun"], true);
+        assert_eq!(planned["ok"]["params"]["Address1"], "203.0.113.10");
+
+        let invalid: Value = take(unsafe { namecheap_dns_set_hosts(client, sld.as_ptr(), std::ptr::null(), hosts.as_ptr()) });
+        assert_eq!(invalid["error"], "tld is null");
+
+        let invalid: Value = take(unsafe { namecheap_domains_check(client, std::ptr::null()) });
+        assert_eq!(invalid["error"], "domains_json is null");
+        let domains: CString = CString::new("example.com").unwrap();
+        assert!(take(unsafe { namecheap_domains_check(client, domains.as_ptr()) })["error"].is_string());
+
+        unsafe { namecheap_client_free(client) };
+        assert!(unsafe { namecheap_client_new(std::ptr::null()) }.is_null());
+    }
+
+    #[tokio::test]
+    async fn test_ffi_call_from_async_context_does_not_unwind() {
+        let config: CString = CString::new(
+            r#"{"apiUser":"user","apiKey":"key","clientIp":"127.0.0.1","userName":"user","production":false,"apiUrl":null}"#
+        ).unwrap();
+        let client: *mut NameCheapClient = unsafe { namecheap_client_new(config.as_ptr()) };
+
+        let domains: CString = CString::new(r#"["example.com"]"#).unwrap();
+        let result: Value = take(unsafe { namecheap_domains_check(client, domains.as_ptr()) });
+        assert!(result["error"].as_str().unwrap().starts_with("panicked: "));
+
+        unsafe { namecheap_client_free(client) };
+    }
+}
@@ -0,0 +1,200 @@
+//! ### gRPC Facade
+//!
+//! Available with the `grpc` feature. This module serves the daemon's operations over gRPC for
+//! service meshes, using the service definition in `proto/namecheap.proto` (package
+//! `namecheap.v1`). It shares the `ServeState` of the HTTP daemon, so both facades use the same
+//! cache and rate limit budget when served side by side.
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{ Request, Response, Status };
+use tracing::info;
+
+// crate imports
+use crate::{ Domain, Host };
+use crate::domains::check::DomainCheckResult;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::serve::ServeState;
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+use crate::zone::snapshot::snapshot_hash;
+
+/// The types and service traits generated from `proto/namecheap.proto`.
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("namecheap.v1");
+}
+
+use proto::name_cheap_server::{ NameCheap, NameCheapServer };
+
+impl From<&DomainCheckResult> for proto::Availability {
+    fn from(result: &DomainCheckResult) -> Self {
+        proto::Availability {
+            domain: result.domain.clone(),
+            available: result.available,
+            is_premium: result.is_premium_name,
+        }
+    }
+}
+
+impl From<&Domain> for proto::Domain {
+    fn from(domain: &Domain) -> Self {
+        proto::Domain {
+            name: domain.name.clone(),
+            created: domain.created.clone(),
+            expires: domain.expires.clone(),
+            is_expired: domain.is_expired,
+            is_locked: domain.is_locked,
+            auto_renew: domain.auto_renew,
+            is_premium: domain.is_premium,
+            is_our_dns: domain.is_our_dns,
+        }
+    }
+}
+
+impl From<&Host> for proto::HostRecord {
+    fn from(host: &Host) -> Self {
+        proto::HostRecord {
+            name: host.name.clone(),
+            r#type: host.type_.clone(),
+            address: host.address.clone(),
+            ttl: host.ttl,
+            mx_pref: host.mx_pref.clone(),
+        }
+    }
+}
+
+impl From<&proto::HostRecord> for HostRequest {
+    fn from(record: &proto::HostRecord) -> Self {
+        let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        HostRequest {
+            host_name: record.name.clone(),
+            record_type: record.r#type.to_uppercase(),
+            address: record.address.clone(),
+            mx_pref: optional(&record.mx_pref),
+            email_type: None,
+            ttl: (record.ttl > 0).then(|| record.ttl.to_string()),
+            flag: None,
+            tag: None,
+        }
+    }
+}
+
+impl From<&ZonePlan> for Vec<proto::PlannedChange> {
+    fn from(plan: &ZonePlan) -> Self {
+        plan.changes
+            .iter()
+            .filter(|change| change.kind != ChangeKind::Keep)
+            .map(|change| proto::PlannedChange {
+                kind: format!("{:?}", change.kind).to_lowercase(),
+                name: change.key.name.clone(),
+                r#type: change.key.record_type.clone(),
+                address: change.key.address.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The gRPC service, backed by a shared [`ServeState`].
+pub struct GrpcService {
+    state: Arc<ServeState>,
+}
+
+impl GrpcService {
+    /// Creates the service over a daemon state.
+    pub fn new(state: Arc<ServeState>) -> Self {
+        GrpcService { state }
+    }
+
+    /// Wraps the service for `tonic::transport::Server::add_service`.
+    pub fn into_server(self) -> NameCheapServer<Self> {
+        NameCheapServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl NameCheap for GrpcService {
+    async fn list_domains(
+        &self,
+        _request: Request<proto::ListDomainsRequest>
+    ) -> Result<Response<proto::ListDomainsResponse>, Status> {
+        let domains: Vec<Domain> = self.state.domains().await.map_err(Status::unavailable)?;
+        Ok(Response::new(proto::ListDomainsResponse { domains: domains.iter().map(Into::into).collect() }))
+    }
+
+    async fn get_zone(&self, request: Request<proto::GetZoneRequest>) -> Result<Response<proto::Zone>, Status> {
+        let domain: String = request.into_inner().domain;
+        let hosts: Vec<Host> = self.state.zone(&domain).await.map_err(Status::unavailable)?;
+        Ok(
+            Response::new(proto::Zone {
+                hash: snapshot_hash(&hosts),
+                records: hosts.iter().map(Into::into).collect(),
+                domain,
+            })
+        )
+    }
+
+    async fn apply_zone_plan(
+        &self,
+        request: Request<proto::ApplyZonePlanRequest>
+    ) -> Result<Response<proto::ApplyZonePlanResponse>, Status> {
+        let request: proto::ApplyZonePlanRequest = request.into_inner();
+        let records: Vec<HostRequest> = request.records.iter().map(Into::into).collect();
+        let plan: ZonePlan = self.state
+            .apply_zone(&request.domain, &records, request.expected_hash.as_deref(), request.dry_run).await
+            .map_err(Status::aborted)?;
+        Ok(
+            Response::new(proto::ApplyZonePlanResponse {
+                changes: (&plan).into(),
+                applied: !request.dry_run && plan.has_changes(),
+            })
+        )
+    }
+
+    async fn check_availability(
+        &self,
+        request: Request<proto::CheckAvailabilityRequest>
+    ) -> Result<Response<proto::CheckAvailabilityResponse>, Status> {
+        let domains: Vec<String> = request.into_inner().domains;
+        if domains.is_empty() {
+            return Err(Status::invalid_argument("No domains to check"));
+        }
+        let results: Vec<DomainCheckResult> = self.state.check_availability(&domains).await.map_err(Status::unavailable)?;
+        Ok(Response::new(proto::CheckAvailabilityResponse { results: results.iter().map(Into::into).collect() }))
+    }
+}
+
+/// Serves the gRPC facade on `addr` until the process stops.
+///
+/// # Example
+///
+/// ```rust
+/// let state = Arc::new(ServeState::new(NameCheapClient::new_from_env()?));
+/// namecheap::grpc::serve(state, "127.0.0.1:50053".parse()?).await?;
+/// ```
+pub async fn serve(state: Arc<ServeState>, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    info!("Serving the NameCheap gRPC facade on {}", addr);
+    tonic::transport::Server::builder().add_service(GrpcService::new(state).into_server()).serve(addr).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_record_conversion() {
+        let record: proto::HostRecord = proto::HostRecord {
+            name: "@".to_string(),
+            r#type: "mx".to_string(),
+            address: "mail.example.com.".to_string(),
+            ttl: 0,
+            mx_pref: "10".to_string(),
+        };
+        let request: HostRequest = (&record).into();
+
+        assert_eq!(request.record_type, "MX");
+        assert_eq!(request.mx_pref.as_deref(), Some("10"));
+        assert_eq!(request.ttl, None);
+    }
+}
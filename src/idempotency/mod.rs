@@ -0,0 +1,391 @@
+//! ## Idempotency Ledger
+//! Purchases (`domains.create`, `domains.renew`, `domains.reactivate`, ...) charge the account.
+//! When a call times out the purchase may still have gone through, and a blind retry would
+//! charge twice.
+//!
+//! The `IdempotencyLedger` records every purchase under a fingerprint of its command and
+//! parameters:
+//! - Before the call, a `Pending` entry is reserved, in the same store operation that checks
+//!   for a previous one, so concurrent calls with the same parameters run the purchase once.
+//! - After a successful call, the entry becomes `Completed` with the API result.
+//! - When the API rejects the call (an `ApiError`), the entry is removed, nothing was charged.
+//!
+//! A repeated call within the window returns the recorded result instead of calling the API
+//! again. A call whose previous attempt is still `Pending` (outcome unknown) is refused until
+//! the account is checked and the entry cleared with `forget`.
+//!
+//! Entries are kept by a `LedgerStore`, `FileLedgerStore` (a local JSON file) by default.
+
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::future::Future;
+use std::path::{ Path, PathBuf };
+use std::sync::Mutex;
+use tracing::{ info, warn };
+
+// crate imports
+use crate::response::errors::ApiError;
+use crate::utils::correlation::current_correlation_id;
+use crate::utils::hash::fnv1a_hex;
+
+/// The state of a ledger entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum LedgerStatus {
+    /// The call was started, its outcome is unknown.
+    Pending,
+    /// The call succeeded, `result` holds its response.
+    Completed,
+}
+
+/// A recorded operation.
+///
+/// #### Fields
+/// - `key`: The operation fingerprint.
+/// - `command`: The API command.
+/// - `status`: Whether the call is pending or completed.
+/// - `recorded_at`: When the entry was last written.
+/// - `result`: The API result of a completed call.
+/// - `correlation_id`: The operation that made the call (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub key: String,
+    pub command: String,
+    pub status: LedgerStatus,
+    pub recorded_at: DateTime<Utc>,
+    #[serde(default)]
+    pub result: Value,
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+}
+
+/// Where ledger entries are kept.
+///
+/// Implement this to keep the ledger in a database or shared cache so that several
+/// processes are protected.
+pub trait LedgerStore: Send + Sync {
+    /// Returns the entry for `key`, if any.
+    fn get(&self, key: &str) -> Result<Option<LedgerEntry>, Box<dyn Error>>;
+    /// Inserts `entry` unless its key has an entry recorded at or after `since`, as one atomic
+    /// step so two concurrent callers cannot both reserve the same key.
+    ///
+    /// Returns the entry that prevented the insert, `None` once `entry` is inserted.
+    fn reserve(&self, entry: LedgerEntry, since: DateTime<Utc>) -> Result<Option<LedgerEntry>, Box<dyn Error>>;
+    /// Inserts or replaces an entry.
+    fn put(&self, entry: LedgerEntry) -> Result<(), Box<dyn Error>>;
+    /// Removes the entry for `key`.
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>>;
+}
+
+/// A ledger store kept in memory, for tests and short-lived processes.
+#[derive(Debug, Default)]
+pub struct MemoryLedgerStore {
+    entries: Mutex<BTreeMap<String, LedgerEntry>>,
+}
+
+impl LedgerStore for MemoryLedgerStore {
+    fn get(&self, key: &str) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        Ok(self.entries.lock().map_err(|e| e.to_string())?.get(key).cloned())
+    }
+
+    fn reserve(&self, entry: LedgerEntry, since: DateTime<Utc>) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        if let Some(live) = entries.get(&entry.key).filter(|live| live.recorded_at >= since) {
+            return Ok(Some(live.clone()));
+        }
+        entries.insert(entry.key.clone(), entry);
+        Ok(None)
+    }
+
+    fn put(&self, entry: LedgerEntry) -> Result<(), Box<dyn Error>> {
+        self.entries.lock().map_err(|e| e.to_string())?.insert(entry.key.clone(), entry);
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>> {
+        self.entries.lock().map_err(|e| e.to_string())?.remove(key);
+        Ok(())
+    }
+}
+
+/// A ledger store backed by a local JSON file, rewritten on every change.
+#[derive(Debug)]
+pub struct FileLedgerStore {
+    path: PathBuf,
+    // Serializes read-modify-write cycles within the process
+    lock: Mutex<()>,
+}
+
+impl FileLedgerStore {
+    /// Creates a store backed by `path`, the file is created on the first write.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileLedgerStore {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, LedgerEntry>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(&self.path)?)?)
+    }
+
+    fn write(&self, entries: &BTreeMap<String, LedgerEntry>) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(entries)?)?;
+        Ok(())
+    }
+}
+
+impl LedgerStore for FileLedgerStore {
+    fn get(&self, key: &str) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        Ok(self.read()?.remove(key))
+    }
+
+    fn reserve(&self, entry: LedgerEntry, since: DateTime<Utc>) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let mut entries: BTreeMap<String, LedgerEntry> = self.read()?;
+        if let Some(live) = entries.get(&entry.key).filter(|live| live.recorded_at >= since) {
+            return Ok(Some(live.clone()));
+        }
+        entries.insert(entry.key.clone(), entry);
+        self.write(&entries)?;
+        Ok(None)
+    }
+
+    fn put(&self, entry: LedgerEntry) -> Result<(), Box<dyn Error>> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let mut entries: BTreeMap<String, LedgerEntry> = self.read()?;
+        entries.insert(entry.key.clone(), entry);
+        self.write(&entries)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let mut entries: BTreeMap<String, LedgerEntry> = self.read()?;
+        if entries.remove(key).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a stable fingerprint of a command and its parameters.
+///
+/// Object keys are sorted, so the order parameters were built in does not matter. The
+/// fingerprint is stable across processes and versions (64-bit FNV-1a).
+pub fn fingerprint(command: &str, params: &Value) -> String {
+    fnv1a_hex(&format!("{}\n{}", command.to_lowercase(), params))
+}
+
+/// Detects and short-circuits repeated purchases.
+///
+/// # Example
+///
+/// ```rust
+/// let ledger = IdempotencyLedger::new(FileLedgerStore::new(".namecheap/ledger.json"));
+/// let params = json!({ "DomainName": "example.com", "Years": 1 });
+/// let result = ledger.run("namecheap.domains.renew", &params, || async {
+///     let renewal: DomainRenewResult = client.domains_renew("example.com", 1, None).await?;
+///     Ok(serde_json::to_value(renewal)?)
+/// }).await?;
+/// ```
+pub struct IdempotencyLedger {
+    store: Box<dyn LedgerStore>,
+    window: Duration,
+}
+
+impl IdempotencyLedger {
+    /// The default window in which a repeated call is considered a replay.
+    pub const DEFAULT_WINDOW_HOURS: i64 = 24;
+
+    /// Creates a ledger with a 24 hour window.
+    pub fn new(store: impl LedgerStore + 'static) -> Self {
+        IdempotencyLedger {
+            store: Box::new(store),
+            window: Duration::hours(Self::DEFAULT_WINDOW_HOURS),
+        }
+    }
+
+    /// Sets the window in which a repeated call is considered a replay.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Returns the live entry for a command and parameters, if recorded within the window.
+    pub fn lookup(&self, command: &str, params: &Value) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        let entry: Option<LedgerEntry> = self.store.get(&fingerprint(command, params))?;
+        Ok(entry.filter(|entry| Utc::now() - entry.recorded_at < self.window))
+    }
+
+    /// Clears the entry for a command and parameters, e.g. after checking that a pending
+    /// purchase did not go through.
+    pub fn forget(&self, command: &str, params: &Value) -> Result<(), Box<dyn Error>> {
+        self.store.remove(&fingerprint(command, params))
+    }
+
+    /// Runs `operation` unless the same command and parameters ran within the window.
+    ///
+    /// Returns the recorded result of a completed replay, and an error for a replay whose
+    /// previous attempt has an unknown outcome.
+    pub async fn run<F, Fut>(
+        &self,
+        command: &str,
+        params: &Value,
+        operation: F
+    ) -> Result<Value, Box<dyn Error>>
+        where F: FnOnce() -> Fut, Fut: Future<Output = Result<Value, Box<dyn Error>>>
+    {
+        let key: String = fingerprint(command, params);
+        let mut entry: LedgerEntry = LedgerEntry {
+            key: key.clone(),
+            command: command.to_string(),
+            status: LedgerStatus::Pending,
+            recorded_at: Utc::now(),
+            result: Value::Null,
+            correlation_id: current_correlation_id(),
+        };
+
+        // Checked and reserved in one step, a concurrent call with the same key sees `Pending`
+        if let Some(existing) = self.store.reserve(entry.clone(), Utc::now() - self.window)? {
+            return match existing.status {
+                LedgerStatus::Completed => {
+                    info!("Replay of {} ({}) short-circuited with the recorded result", command, key);
+                    Ok(existing.result)
+                }
+                LedgerStatus::Pending =>
+                    Err(
+                        format!(
+                            "A previous {} ({}) started at {} has an unknown outcome, check the account and call forget before retrying",
+                            command,
+                            key,
+                            existing.recorded_at
+                        ).into()
+                    ),
+            };
+        }
+
+        match operation().await {
+            Ok(result) => {
+                entry.status = LedgerStatus::Completed;
+                entry.recorded_at = Utc::now();
+                entry.result = result.clone();
+                self.store.put(entry)?;
+                Ok(result)
+            }
+            Err(error) => {
+                // The API answered with an error: nothing was charged, the call can be retried
+                if error.downcast_ref::<ApiError>().is_some() {
+                    self.store.remove(&key)?;
+                } else {
+                    warn!("{} ({}) failed with an unknown outcome: {}", command, key, error);
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{ AtomicUsize, Ordering };
+
+    #[test]
+    fn test_fingerprint_ignores_key_order() {
+        let a: Value = json!({ "DomainName": "example.com", "Years": 1 });
+        let b: Value = json!({ "Years": 1, "DomainName": "example.com" });
+        assert_eq!(fingerprint("namecheap.domains.renew", &a), fingerprint("namecheap.domains.renew", &b));
+        assert_ne!(
+            fingerprint("namecheap.domains.renew", &a),
+            fingerprint("namecheap.domains.renew", &json!({ "DomainName": "example.com", "Years": 2 }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ledger_short_circuits_replays() {
+        let ledger: IdempotencyLedger = IdempotencyLedger::new(MemoryLedgerStore::default());
+        let calls: AtomicUsize = AtomicUsize::new(0);
+        let params: Value = json!({ "DomainName": "example.com", "Years": 1 });
+
+        for _ in 0..2 {
+            let result: Value = ledger
+                .run("namecheap.domains.renew", &params, || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(json!({ "OrderID": "42" }))
+                }).await
+                .unwrap();
+            assert_eq!(result["OrderID"], "42");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ledger_reserves_concurrent_calls() {
+        let ledger: IdempotencyLedger = IdempotencyLedger::new(MemoryLedgerStore::default());
+        let calls: AtomicUsize = AtomicUsize::new(0);
+        let params: Value = json!({ "DomainName": "example.com", "Years": 1 });
+        let renew = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(json!({ "OrderID": "42" }))
+        };
+
+        let (first, second) = tokio::join!(
+            ledger.run("namecheap.domains.renew", &params, renew),
+            ledger.run("namecheap.domains.renew", &params, renew)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(first.is_ok() != second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ledger_entries_carry_the_correlation_id() {
+        use crate::utils::correlation::{ with_correlation_id, CorrelationId };
+
+        let ledger: IdempotencyLedger = IdempotencyLedger::new(MemoryLedgerStore::default());
+        let params: Value = json!({ "DomainName": "example.com", "Years": 1 });
+        let id: CorrelationId = CorrelationId::from_string("0123456789abcdef");
+        with_correlation_id(id, "renew", ledger.run("namecheap.domains.renew", &params, || async { Ok(Value::Null) }))
+            .await
+            .unwrap();
+
+        let entry: LedgerEntry = ledger.lookup("namecheap.domains.renew", &params).unwrap().unwrap();
+        assert_eq!(entry.correlation_id.as_deref(), Some("0123456789abcdef"));
+    }
+
+    #[tokio::test]
+    async fn test_ledger_refuses_unknown_outcome() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-ledger-{}.json", std::process::id()));
+        let ledger: IdempotencyLedger = IdempotencyLedger::new(FileLedgerStore::new(&path));
+        let params: Value = json!({ "DomainName": "example.com" });
+
+        let timeout = ledger.run("namecheap.domains.create", &params, || async {
+            Err("operation timed out".into())
+        }).await;
+        assert!(timeout.is_err());
+        assert!(ledger.run("namecheap.domains.create", &params, || async { Ok(Value::Null) }).await.is_err());
+
+        ledger.forget("namecheap.domains.create", &params).unwrap();
+        let rejected = ledger.run("namecheap.domains.create", &params, || async {
+            Err(Box::new(ApiError::new("2011166", "Invalid domain")) as Box<dyn Error>)
+        }).await;
+        assert!(rejected.is_err());
+        assert!(ledger.lookup("namecheap.domains.create", &params).unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
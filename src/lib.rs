@@ -1,14 +1,56 @@
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::{ Map, Value };
 use dotenv::dotenv;
+use chrono::{ DateTime, Duration, Utc };
+
+// crate imports
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::credential_helper::CredentialHelper;
+use crate::utils::shared::SharedState;
+use crate::utils::rate_limit::Priority;
+use crate::utils::privacy::PrivacyMode;
+use crate::response::parse_value::Extra;
 
 pub mod utils;
 pub mod domains;
 pub mod response;
 pub mod domains_dns;
+pub mod domains_ns;
+pub mod batch;
+pub mod zone;
+pub mod scheduler;
+pub mod idempotency;
+pub mod wire;
+#[cfg(test)]
+mod contracts;
+pub mod limits;
+pub mod policy;
+pub mod changes;
+pub mod accounts;
+pub mod simulation;
+pub mod facade;
+pub mod provider;
+pub mod ssl;
+pub mod users;
+pub mod users_address;
+pub mod watcher;
+pub mod cli;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "failover")]
+pub mod failover;
 
 pub const NAMECHEAP_API_URL: &str = "https://api.namecheap.com";
 pub const NAMECHEAP_SANDBOX_API_URL: &str = "https://api.sandbox.namecheap.com";
+/// The default largest response body accepted, 32 MiB is far above any legitimate response.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 32 * 1024 * 1024;
 
 /// ### NameCheap API Client
 ///
@@ -22,6 +64,14 @@ pub const NAMECHEAP_SANDBOX_API_URL: &str = "https://api.sandbox.namecheap.com";
 /// - `client_ip`: The client IP address.
 /// - `user_name`: The user name.
 /// - `production`: A boolean indicating whether to use the production environment.
+/// - `default_ttl`: TTL applied to host records that do not specify one (optional).
+/// - `default_email_type`: Email type applied to `setHosts` calls that do not specify one (optional).
+/// - `dry_run`: When `true`, mutating methods return the request they would send instead of sending it.
+/// - `skip_domain_locks`: When `true`, zone changes are not serialized per domain (see `utils::domain_lock`).
+/// - `max_response_bytes`: The largest response body accepted, `DEFAULT_MAX_RESPONSE_BYTES` when not set.
+/// - `priority`: The class the calls of this client queue with on a saturated rate limiter.
+/// - `privacy`: How contact personal data appears in logs and reports (see `utils::privacy`).
+/// - `shared`: The HTTP connection pool and rate limiter shared by all clones (see `utils::shared`).
 ///
 /// #### Note
 /// `production` is a boolean defaulted to `false`. If set to `true`, the client will
@@ -38,6 +88,22 @@ pub struct NameCheapClient {
     pub user_name: String,
     pub production: bool,
     pub api_url: Option<String>,
+    #[serde(default)]
+    pub default_ttl: Option<i64>,
+    #[serde(default)]
+    pub default_email_type: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub skip_domain_locks: bool,
+    #[serde(default)]
+    pub max_response_bytes: Option<usize>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub privacy: PrivacyMode,
+    #[serde(skip)]
+    pub shared: SharedState,
 }
 
 /// ### Domain
@@ -54,30 +120,120 @@ pub struct NameCheapClient {
 /// - `is_expired`: A boolean indicating whether the domain is expired.
 /// - `is_locked`: A boolean indicating whether the domain is locked.
 /// - `auto_renew`: A boolean indicating whether auto-renew is enabled.
-/// - `who_is_guard`: A boolean indicating whether WHOIS guard is enabled.
+/// - `whois_guard`: The WHOIS guard status of the domain, see [`WhoisGuardStatus`].
 /// - `is_premium`: A boolean indicating whether the domain is premium.
 /// - `is_our_dns`: A boolean indicating whether the domain uses NameCheap's DNS.
+/// - `is_dns_active`: Whether DNS is active for the domain, when reported.
+/// - `is_failover_active`: Whether DNS failover is active for the domain, when reported.
+/// - `whois_guard_expires`: The WHOIS guard expiration date, when reported.
+/// - `created_at`: The creation date normalized to UTC using the server's `GMTTimeDifference`.
+/// - `expires_at`: The expiration date normalized to UTC using the server's `GMTTimeDifference`.
+/// - `extra`: The attributes the API returned that this struct does not model yet.
+///
+/// The optional fields are only present on accounts and environments where the
+/// API includes them, they are `None` otherwise. Only `id` and `name` are required when
+/// deserializing, the sandbox omits some of the other fields.
+///
+/// #### Wire format
+/// By default `Domain` serializes with camelCase keys (`isOurDns`, `autoRenew`).
+/// Enabling the `snake_case` feature switches serialization to snake_case keys
+/// (`is_our_dns`, `auto_renew`). Deserialization accepts both shapes regardless
+/// of the feature, so payloads produced by either build can be read back.
 ///
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "snake_case"), serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "snake_case", serde(rename_all = "snake_case"))]
 #[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Domain {
     pub id: i64,
     pub name: String,
+    #[serde(default)]
     pub user: String,
+    #[serde(default)]
     pub created: String,
+    #[serde(default)]
     pub expires: String,
+    #[serde(default, alias = "isExpired", alias = "is_expired")]
     pub is_expired: bool,
+    #[serde(default, alias = "isLocked", alias = "is_locked")]
     pub is_locked: bool,
+    #[serde(default, alias = "autoRenew", alias = "auto_renew")]
     pub auto_renew: bool,
-    pub whois_guard: bool,
+    #[serde(default, alias = "whoisGuard", alias = "whois_guard")]
+    pub whois_guard: WhoisGuardStatus,
+    #[serde(default, alias = "isPremium", alias = "is_premium")]
     pub is_premium: bool,
+    #[serde(default, alias = "isOurDns", alias = "is_our_dns")]
     pub is_our_dns: bool,
+    #[serde(default, alias = "isDnsActive", alias = "is_dns_active")]
+    pub is_dns_active: Option<bool>,
+    #[serde(default, alias = "isFailoverActive", alias = "is_failover_active")]
+    pub is_failover_active: Option<bool>,
+    #[serde(default, alias = "whoisGuardExpires", alias = "whois_guard_expires")]
+    pub whois_guard_expires: Option<String>,
+    #[serde(default, alias = "createdAt", alias = "created_at")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, alias = "expiresAt", alias = "expires_at")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Extra::is_empty")]
+    pub extra: Extra,
+}
+
+impl Domain {
+    /// Returns `true` if the domain expires within the given number of days from now.
+    ///
+    /// Uses the UTC normalized `expires_at`, domains without an expiry date never match.
+    pub fn expires_within(&self, days: i64) -> bool {
+        self.expires_at.is_some_and(|expires| expires <= Utc::now() + Duration::days(days))
+    }
+}
+
+/// ### WhoisGuard Status
+///
+/// The WHOIS guard state of a domain as reported by the NameCheap API.
+///
+/// #### Variants
+/// - `Enabled`: WHOIS guard is allotted and enabled.
+/// - `Disabled`: WHOIS guard is allotted but disabled.
+/// - `NotPresent`: No WHOIS guard is allotted to the domain.
+/// - `Expired`: The WHOIS guard subscription has expired.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "UPPERCASE")]
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WhoisGuardStatus {
+    Enabled,
+    Disabled,
+    #[default]
+    NotPresent,
+    Expired,
+}
+
+impl WhoisGuardStatus {
+    /// Parses the raw value returned by the API (e.g. `ENABLED`, `NOTPRESENT`).
+    ///
+    /// The comparison is case-insensitive, unknown values map to `NotPresent`.
+    pub fn from_api_str(value: &str) -> Self {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "ENABLED" => WhoisGuardStatus::Enabled,
+            "DISABLED" => WhoisGuardStatus::Disabled,
+            "EXPIRED" => WhoisGuardStatus::Expired,
+            _ => WhoisGuardStatus::NotPresent,
+        }
+    }
+
+    /// Returns `true` if WHOIS guard is currently protecting the domain.
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, WhoisGuardStatus::Enabled)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Contact {
     pub type_: String,
     pub address_1: String,
@@ -98,30 +254,118 @@ pub struct Contact {
     pub read_only: bool,
 }
 
+impl Contact {
+    /// Builds the request parameters for this contact, prefixed with the contact role.
+    ///
+    /// For example with `prefix = "Registrant"` the first name is sent as `RegistrantFirstName`.
+    /// Empty optional fields are omitted.
+    pub fn to_params(&self, prefix: &str) -> Map<String, Value> {
+        let fields: [(&str, &String); 15] = [
+            ("FirstName", &self.first_name),
+            ("LastName", &self.last_name),
+            ("Address1", &self.address_1),
+            ("Address2", &self.address_2),
+            ("City", &self.city),
+            ("StateProvince", &self.state_province),
+            ("StateProvinceChoice", &self.state_province_choice),
+            ("PostalCode", &self.postal_code),
+            ("Country", &self.country),
+            ("Phone", &self.phone),
+            ("PhoneExt", &self.phone_ext),
+            ("Fax", &self.fax),
+            ("EmailAddress", &self.email_address),
+            ("OrganizationName", &self.organization_name),
+            ("JobTitle", &self.job_title),
+        ];
+
+        fields
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| (format!("{}{}", prefix, key), Value::String(value.clone())))
+            .collect()
+    }
+}
+
+/// ### Contact Set
+///
+/// The four contacts Namecheap requires for a domain.
+///
+/// #### Fields
+/// - `registrant`: The registrant (owner) contact.
+/// - `tech`: The technical contact.
+/// - `admin`: The administrative contact.
+/// - `aux_billing`: The billing contact.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ContactSet {
+    pub registrant: Contact,
+    pub tech: Contact,
+    pub admin: Contact,
+    pub aux_billing: Contact,
+}
+
+impl ContactSet {
+    /// Creates a `ContactSet` using the same contact for all four roles.
+    pub fn uniform(contact: Contact) -> Self {
+        ContactSet {
+            registrant: Contact { type_: "Registrant".to_string(), ..contact.clone() },
+            tech: Contact { type_: "Tech".to_string(), ..contact.clone() },
+            admin: Contact { type_: "Admin".to_string(), ..contact.clone() },
+            aux_billing: Contact { type_: "AuxBilling".to_string(), ..contact },
+        }
+    }
+
+    /// Builds the request parameters for all four contacts, as expected by `domains.setContacts`.
+    pub fn to_params(&self) -> Map<String, Value> {
+        let mut params: Map<String, Value> = Map::new();
+        params.extend(self.registrant.to_params("Registrant"));
+        params.extend(self.tech.to_params("Tech"));
+        params.extend(self.admin.to_params("Admin"));
+        params.extend(self.aux_billing.to_params("AuxBilling"));
+        params
+    }
+}
+
 /// ### Host
+///
+/// Only `name`, `address` and `type` are required when deserializing, the other fields fall
+/// back to the API defaults since the sandbox omits some of them.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Host {
+    #[serde(default)]
     pub host_id: String,
     pub name: String,
     pub address: String,
     #[serde(rename = "type")]
     pub type_: String,
-    #[serde(rename = "is_active")]
+    #[serde(rename = "is_active", default = "default_true")]
     pub is_active: bool,
-    #[serde(rename = "ttl")]
+    #[serde(rename = "ttl", default = "default_host_ttl")]
     pub ttl: i64,
-    #[serde(rename = "mxpref")]
+    #[serde(rename = "mxpref", default)]
     pub mx_pref: String,
-    #[serde(rename = "is_ddnsenabled")]
+    #[serde(rename = "is_ddnsenabled", default)]
     pub is_ddns_enabled: bool,
-    #[serde(rename = "friendly_name")]
+    #[serde(rename = "friendly_name", default)]
     pub friendly_name: String,
-    #[serde(rename = "associated_app_title")]
+    #[serde(rename = "associated_app_title", default)]
     pub associated_app_title: String,
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_host_ttl() -> i64 {
+    limits::DEFAULT_TTL
+}
+
 // Impl of host
 
 impl Host {
@@ -156,6 +400,23 @@ impl Host {
             associated_app_title: String::new(),
         }
     }
+
+    /// Converts the host record into a `HostRequest`, e.g. to resubmit it with `setHosts`.
+    ///
+    /// The MX preference is only carried over for `MX` records.
+    pub fn to_host_request(&self) -> HostRequest {
+        let is_mx: bool = self.type_.eq_ignore_ascii_case("MX");
+        HostRequest::new(
+            self.name.clone(),
+            self.type_.clone(),
+            self.address.clone(),
+            if is_mx && !self.mx_pref.is_empty() { Some(self.mx_pref.clone()) } else { None },
+            None,
+            Some(self.ttl.to_string()),
+            None,
+            None
+        )
+    }
 }
 
 impl NameCheapClient {
@@ -199,14 +460,89 @@ impl NameCheapClient {
             } else {
                 Some(NAMECHEAP_SANDBOX_API_URL.to_string())
             },
+            default_ttl: None,
+            default_email_type: None,
+            dry_run: false,
+            skip_domain_locks: false,
+            max_response_bytes: None,
+            priority: Priority::default(),
+            privacy: PrivacyMode::default(),
+            shared: SharedState::default(),
         }
     }
 
+    /// Sets the TTL applied to host records that are submitted without one.
+    ///
+    /// #### Example
+    /// ```rust
+    /// let client: NameCheapClient = NameCheapClient::new_from_env()?
+    ///     .with_default_ttl(300)
+    ///     .with_default_email_type("MX");
+    /// ```
+    ///
+    pub fn with_default_ttl(mut self, ttl: i64) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the email type (e.g. `MX`, `MXE`, `FWD`, `OX`) applied to `setHosts` calls
+    /// that are submitted without one.
+    pub fn with_default_email_type(mut self, email_type: &str) -> Self {
+        self.default_email_type = Some(email_type.to_string());
+        self
+    }
+
+    /// Enables or disables dry-run mode.
+    ///
+    /// In dry-run mode every mutating method (`setHosts`, `setContacts`, ...) skips the HTTP
+    /// call. Methods returning JSON return the `PlannedRequest` they would have sent, serialized
+    /// with `dryRun: true`; methods returning a typed result (`domains_renew`, `ssl_activate`,
+    /// ...) fail with the `PlannedRequest` as their error. Any command that is not a read
+    /// command is held back by `Request::send`, see `request_builder::is_read_command`.
+    /// Read-only calls are still sent, so zone helpers return an accurate `ZonePlan`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client: NameCheapClient = NameCheapClient::new_from_env()?.with_dry_run(true);
+    /// let plan = client.setup_hosting("example.com", &HostingProvider::Vercel).await?;
+    /// println!("{}", plan);
+    /// ```
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the largest response body accepted, larger responses fail with a
+    /// [`ResponseTooLarge`](crate::response::errors::ResponseTooLarge) error.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = Some(max_response_bytes);
+        self
+    }
+
+    /// Sets the priority the calls of this client queue with once the rate limiter is saturated.
+    ///
+    /// The limiter is shared by all clones, so a clone with another priority competes with the
+    /// original for the same budget, see [`Priority`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let interactive: NameCheapClient = client.clone().with_priority(Priority::High);
+    /// let domains = interactive.domains_get_list().await?;
+    /// ```
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Creates a new `NameCheapClient` instance from environment variables.
     ///
     /// This method expects the following environment variables to be set:
     /// - `NAMECHEAP_USER_NAME`: Your NameCheap account username
-    /// - `NAMECHEAP_API_KEY`: Your NameCheap API key
+    /// - `NAMECHEAP_API_KEY`: Your NameCheap API key, or
+    ///   `NAMECHEAP_CREDENTIAL_HELPER`: A command printing it, e.g. `op read op://vault/namecheap/key`
+    ///   (see [`CredentialHelper`](crate::utils::credential_helper::CredentialHelper))
     /// - `NAMECHEAP_CLIENT_IP`: Your client IP address
     /// - `NAMECHEAP_PRODUCTION`: Boolean indicating whether to use production environment (defaults to false)
     ///
@@ -223,7 +559,13 @@ impl NameCheapClient {
         use std::env::var;
 
         let user_name = var("NAMECHEAP_USER_NAME")?;
-        let api_key = var("NAMECHEAP_API_KEY")?;
+        let api_key = match var("NAMECHEAP_API_KEY") {
+            Ok(api_key) => api_key,
+            Err(err) => match var("NAMECHEAP_CREDENTIAL_HELPER") {
+                Ok(command) => CredentialHelper::new(&command).fetch()?,
+                Err(_) => return Err(err.into()),
+            },
+        };
         let client_ip = var("NAMECHEAP_CLIENT_IP")?;
         let production = var("NAMECHEAP_PRODUCTION")
             .unwrap_or_else(|_| "false".to_string())
@@ -233,3 +575,80 @@ impl NameCheapClient {
         Ok(Self::new(user_name.clone(), api_key, client_ip, user_name, production))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whois_guard_status_from_api_str() {
+        assert_eq!(WhoisGuardStatus::from_api_str("ENABLED"), WhoisGuardStatus::Enabled);
+        assert_eq!(WhoisGuardStatus::from_api_str("disabled"), WhoisGuardStatus::Disabled);
+        assert_eq!(WhoisGuardStatus::from_api_str("NOTPRESENT"), WhoisGuardStatus::NotPresent);
+        assert_eq!(WhoisGuardStatus::from_api_str("EXPIRED"), WhoisGuardStatus::Expired);
+        assert_eq!(WhoisGuardStatus::from_api_str(""), WhoisGuardStatus::NotPresent);
+    }
+
+    fn sample_domain() -> Domain {
+        Domain {
+            id: 127,
+            name: "example.com".to_string(),
+            user: "owner".to_string(),
+            created: "02/15/2016".to_string(),
+            expires: "02/15/2022".to_string(),
+            is_expired: false,
+            is_locked: true,
+            auto_renew: false,
+            whois_guard: WhoisGuardStatus::Enabled,
+            is_premium: false,
+            is_our_dns: true,
+            is_dns_active: None,
+            is_failover_active: None,
+            whois_guard_expires: None,
+            created_at: None,
+            expires_at: None,
+            extra: Extra::new(),
+        }
+    }
+
+    #[cfg(not(feature = "snake_case"))]
+    #[test]
+    fn test_domain_serializes_camel_case() {
+        let value: serde_json::Value = serde_json::to_value(sample_domain()).unwrap();
+
+        assert_eq!(value["isOurDns"], true);
+        assert_eq!(value["isLocked"], true);
+        assert_eq!(value["whoisGuard"], "ENABLED");
+        assert!(value.get("is_our_dns").is_none());
+    }
+
+    #[cfg(feature = "snake_case")]
+    #[test]
+    fn test_domain_serializes_snake_case() {
+        let value: serde_json::Value = serde_json::to_value(sample_domain()).unwrap();
+
+        assert_eq!(value["is_our_dns"], true);
+        assert_eq!(value["is_locked"], true);
+        assert_eq!(value["whois_guard"], "ENABLED");
+        assert!(value.get("isOurDns").is_none());
+    }
+
+    #[test]
+    fn test_domain_deserializes_both_shapes() {
+        let camel: serde_json::Value = serde_json::json!({
+            "id": 127, "name": "example.com", "user": "owner",
+            "created": "02/15/2016", "expires": "02/15/2022",
+            "isExpired": false, "isLocked": true, "autoRenew": false,
+            "whoisGuard": "ENABLED", "isPremium": false, "isOurDns": true
+        });
+        let snake: serde_json::Value = serde_json::json!({
+            "id": 127, "name": "example.com", "user": "owner",
+            "created": "02/15/2016", "expires": "02/15/2022",
+            "is_expired": false, "is_locked": true, "auto_renew": false,
+            "whois_guard": "ENABLED", "is_premium": false, "is_our_dns": true
+        });
+
+        assert_eq!(serde_json::from_value::<Domain>(camel).unwrap(), sample_domain());
+        assert_eq!(serde_json::from_value::<Domain>(snake).unwrap(), sample_domain());
+    }
+}
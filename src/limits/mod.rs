@@ -0,0 +1,128 @@
+//! ### Limits
+//!
+//! The documented limits and enumerated values of the NameCheap API, in one place.
+//!
+//! Validation throughout the crate (`PageSize`, `zone::template::validate_record`,
+//! `domains_dns_replace_hosts`, the rate limiter) reads its bounds from here, so a limit
+//! changed by NameCheap only needs to be updated once.
+
+/// The smallest page size accepted by paged list commands, e.g. `domains.getList`.
+pub const MIN_PAGE_SIZE: u8 = 10;
+/// The largest page size accepted by paged list commands.
+pub const MAX_PAGE_SIZE: u8 = 100;
+/// The page size the API uses when none is sent.
+pub const DEFAULT_PAGE_SIZE: u8 = 20;
+
+/// The smallest TTL accepted on a host record, in seconds.
+pub const MIN_TTL: i64 = 60;
+/// The largest TTL accepted on a host record, in seconds.
+pub const MAX_TTL: i64 = 60000;
+/// The TTL NameCheap applies to host records that do not set one, in seconds.
+pub const DEFAULT_TTL: i64 = 1800;
+
+/// The largest number of host records a zone can hold.
+pub const MAX_HOST_RECORDS: usize = 150;
+
+/// The smallest number of nameservers accepted by `domains.dns.setCustom`.
+pub const MIN_NAMESERVERS: usize = 2;
+/// The largest number of nameservers accepted by `domains.dns.setCustom`.
+pub const MAX_NAMESERVERS: usize = 12;
+
+/// The largest number of domains checked by a single `domains.check` call.
+pub const MAX_CHECK_DOMAINS: usize = 50;
+
+/// The record types accepted by `domains.dns.setHosts`.
+pub const RECORD_TYPES: [&str; 10] = [
+    "A",
+    "AAAA",
+    "ALIAS",
+    "CAA",
+    "CNAME",
+    "MX",
+    "MXE",
+    "NS",
+    "TXT",
+    "URL",
+];
+
+/// The email types accepted by `domains.dns.setHosts`.
+pub const EMAIL_TYPES: [&str; 5] = ["MXE", "MX", "FWD", "OX", "GMAIL"];
+
+/// The list types accepted by `domains.getList`.
+pub const LIST_TYPES: [&str; 3] = ["ALL", "EXPIRING", "EXPIRED"];
+
+/// The list types accepted by `ssl.getList`.
+pub const SSL_LIST_TYPES: [&str; 11] = [
+    "ALL",
+    "Processing",
+    "EmailSent",
+    "TechnicalProblem",
+    "InProgress",
+    "Completed",
+    "Deactivated",
+    "Active",
+    "Cancelled",
+    "NewPurchase",
+    "NewRenewal",
+];
+
+/// The sort orders accepted by `domains.getList`.
+pub const SORT_ORDERS: [&str; 6] = [
+    "NAME",
+    "NAME_DESC",
+    "EXPIREDATE",
+    "EXPIREDATE_DESC",
+    "CREATEDATE",
+    "CREATEDATE_DESC",
+];
+
+/// The largest number of API calls accepted per account per minute.
+pub const MAX_CALLS_PER_MINUTE: u32 = 20;
+/// The largest number of API calls accepted per account per hour.
+pub const MAX_CALLS_PER_HOUR: u32 = 700;
+/// The largest number of API calls accepted per account per day.
+pub const MAX_CALLS_PER_DAY: u32 = 8000;
+
+/// Checks that a TTL is within `MIN_TTL..=MAX_TTL`.
+pub fn validate_ttl(ttl: i64) -> Result<(), String> {
+    if (MIN_TTL..=MAX_TTL).contains(&ttl) {
+        Ok(())
+    } else {
+        Err(format!("TTL {} is not between {} and {}", ttl, MIN_TTL, MAX_TTL))
+    }
+}
+
+/// Checks that a zone does not hold more than `MAX_HOST_RECORDS` records.
+pub fn validate_host_count(count: usize) -> Result<(), String> {
+    if count <= MAX_HOST_RECORDS {
+        Ok(())
+    } else {
+        Err(format!("{} host records exceed the limit of {}", count, MAX_HOST_RECORDS))
+    }
+}
+
+/// Checks that between `MIN_NAMESERVERS` and `MAX_NAMESERVERS` nameservers are set.
+pub fn validate_nameserver_count(count: usize) -> Result<(), String> {
+    if (MIN_NAMESERVERS..=MAX_NAMESERVERS).contains(&count) {
+        Ok(())
+    } else {
+        Err(format!("{} nameservers given, between {} and {} are required", count, MIN_NAMESERVERS, MAX_NAMESERVERS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_validation() {
+        assert!(validate_ttl(MIN_TTL).is_ok());
+        assert!(validate_ttl(MAX_TTL).is_ok());
+        assert_eq!(validate_ttl(59).unwrap_err(), "TTL 59 is not between 60 and 60000");
+        assert!(validate_host_count(MAX_HOST_RECORDS).is_ok());
+        assert!(validate_host_count(MAX_HOST_RECORDS + 1).is_err());
+        assert!(validate_nameserver_count(MIN_NAMESERVERS).is_ok());
+        assert!(validate_nameserver_count(MAX_NAMESERVERS + 1).is_err());
+        assert!((MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&DEFAULT_PAGE_SIZE));
+    }
+}
@@ -1,15 +1,6 @@
-// utils
-use namecheap::NameCheapClient;
-use namecheap::utils::tracer::init_tracing;
-use namecheap::domains_dns::set_hosts::HostRequest;
-
-use dotenv::dotenv;
-use std::env::var;
-use serde_json::Value;
-use tracing::info;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() {
-
-    // Example usage of the client
+async fn main() -> ExitCode {
+    namecheap::cli::main().await
 }
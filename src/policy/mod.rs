@@ -0,0 +1,340 @@
+//! ## Mutation Policies
+//! Guards evaluated before any change is sent: a `Policy` looks at a [`Mutation`] (a zone plan,
+//! a nameserver or contact change, a registration, renewal or reactivation, a registrar lock or
+//! WHOIS guard change, a certificate activation or an address book change) and reports the rules it breaks. Policies attached with [`NameCheapClient::with_policy`] apply to this
+//! client and all its clones; a mutation breaking any of them fails with [`PolicyViolations`]
+//! before anything is sent, dry runs included.
+//!
+//! Built-in policies:
+//! - `NoMxDeletion`: forbids deleting MX records.
+//! - `MinTtl`: forbids created or updated records with a TTL below a minimum.
+//! - `KeepWhoisGuard`: forbids disabling WHOIS guard.
+//!
+//! Any closure becomes a policy with [`policy_fn`]:
+//!
+//! ```rust
+//! let client = client
+//!     .with_policy(NoMxDeletion)
+//!     .with_policy(MinTtl(300))
+//!     .with_policy(policy_fn("no-contact-changes", |mutation| match mutation {
+//!         Mutation::Contacts { .. } => Some("Contacts are managed by the registrar team".to_string()),
+//!         _ => None,
+//!     }));
+//! ```
+//!
+//! Zone policies need the live records to tell deletions apart, so with policies attached
+//! `domains_dns_replace_hosts` reads the zone before replacing it.
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::zone::RecordKey;
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+
+/// A change about to be sent, as seen by policies.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Mutation {
+    /// The zone of a domain is replaced, see `domains_dns_replace_hosts`.
+    Zone(ZonePlan),
+    /// The domain is delegated to external nameservers, see `domains_dns_set_custom`.
+    Nameservers { domain: String, nameservers: Vec<String> },
+    /// A nameserver is registered under a domain at `ip`, or deleted when `ip` is `None`, see
+    /// `domains_ns_create`, `domains_ns_update` and `domains_ns_delete`.
+    GlueRecord { domain: String, nameserver: String, ip: Option<String> },
+    /// The contacts of a domain are replaced, see `domains_set_contacts`.
+    Contacts { domain: String },
+    /// A domain is registered, see `domains_create`.
+    CreateDomain { domain: String, years: u8 },
+    /// A domain is renewed, see `domains_renew`.
+    RenewDomain { domain: String, years: u8 },
+    /// An expired domain is reactivated, `years` being added on top, see `domains_reactivate`.
+    ReactivateDomain { domain: String, years: u8 },
+    /// A domain is locked or unlocked against transfers, see `domains_set_registrar_lock`.
+    RegistrarLock { domain: String, locked: bool },
+    /// WHOIS guard is enabled or disabled on a domain.
+    WhoisGuard { domain: String, enabled: bool },
+    /// A purchased certificate is activated with a CSR, see `ssl_activate`.
+    SslActivation { certificate_id: i64 },
+    /// A stored address is created, or updated when `address_id` is set, see
+    /// `users_address_create` and `users_address_update`.
+    Address { address_name: String, address_id: Option<i64> },
+}
+
+impl Mutation {
+    /// Returns the domain the mutation applies to, empty for account level mutations such as
+    /// certificate activations and address book changes.
+    pub fn domain(&self) -> &str {
+        match self {
+            Mutation::Zone(plan) => &plan.domain,
+            Mutation::Nameservers { domain, .. } |
+            Mutation::GlueRecord { domain, .. } |
+            Mutation::Contacts { domain } |
+            Mutation::CreateDomain { domain, .. } |
+            Mutation::RenewDomain { domain, .. } |
+            Mutation::ReactivateDomain { domain, .. } |
+            Mutation::RegistrarLock { domain, .. } |
+            Mutation::WhoisGuard { domain, .. } => domain,
+            Mutation::SslActivation { .. } | Mutation::Address { .. } => "",
+        }
+    }
+}
+
+/// ### Policy Violation
+///
+/// #### Fields
+/// - `policy`: The name of the broken policy.
+/// - `domain`: The domain of the mutation.
+/// - `record`: The record at fault, for zone policies (optional).
+/// - `reason`: Why the mutation is refused.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PolicyViolation {
+    pub policy: String,
+    pub domain: String,
+    pub record: Option<RecordKey>,
+    pub reason: String,
+}
+
+impl PolicyViolation {
+    /// Creates a violation of `policy` by `mutation`.
+    pub fn new(policy: &str, mutation: &Mutation, reason: impl Into<String>) -> Self {
+        PolicyViolation {
+            policy: policy.to_string(),
+            domain: mutation.domain().to_string(),
+            record: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Sets the record at fault.
+    pub fn with_record(mut self, record: RecordKey) -> Self {
+        self.record = Some(record);
+        self
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.policy, self.domain)?;
+        if let Some(record) = &self.record {
+            write!(f, " {} {} {}", record.name, record.record_type, record.address)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// The error of a mutation refused by policies, with every violation found.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PolicyViolations {
+    pub violations: Vec<PolicyViolation>,
+}
+
+impl fmt::Display for PolicyViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Refused by policy")?;
+        for violation in &self.violations {
+            write!(f, "\n- {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for PolicyViolations {}
+
+/// A rule checked before every mutation.
+pub trait Policy: Send + Sync {
+    /// The name reported in violations.
+    fn name(&self) -> &str;
+
+    /// Returns the violations of the rule by `mutation`, none if it is allowed.
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation>;
+}
+
+/// Forbids deleting MX records.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct NoMxDeletion;
+
+impl Policy for NoMxDeletion {
+    fn name(&self) -> &str {
+        "no-mx-deletion"
+    }
+
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation> {
+        let Mutation::Zone(plan) = mutation else { return Vec::new() };
+        plan.changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::Delete && change.key.record_type == "MX")
+            .map(|change| PolicyViolation::new(self.name(), mutation, "MX records must not be deleted").with_record(change.key.clone()))
+            .collect()
+    }
+}
+
+/// Forbids created or updated records with a TTL below the given number of seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct MinTtl(pub i64);
+
+impl Policy for MinTtl {
+    fn name(&self) -> &str {
+        "min-ttl"
+    }
+
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation> {
+        let Mutation::Zone(plan) = mutation else { return Vec::new() };
+        plan.changes
+            .iter()
+            .filter(|change| matches!(change.kind, ChangeKind::Create | ChangeKind::Update))
+            .filter_map(|change| {
+                let ttl: i64 = change.ttl_after.filter(|ttl| *ttl < self.0)?;
+                let reason: String = format!("TTL {} is below the minimum of {}", ttl, self.0);
+                Some(PolicyViolation::new(self.name(), mutation, reason).with_record(change.key.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Forbids disabling WHOIS guard.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct KeepWhoisGuard;
+
+impl Policy for KeepWhoisGuard {
+    fn name(&self) -> &str {
+        "keep-whois-guard"
+    }
+
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation> {
+        match mutation {
+            Mutation::WhoisGuard { enabled: false, .. } => {
+                vec![PolicyViolation::new(self.name(), mutation, "WHOIS guard must stay enabled")]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A policy made of a closure, see [`policy_fn`].
+pub struct FnPolicy<F> {
+    name: String,
+    check: F,
+}
+
+impl<F: Fn(&Mutation) -> Option<String> + Send + Sync> Policy for FnPolicy<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation> {
+        (self.check)(mutation)
+            .map(|reason| vec![PolicyViolation::new(&self.name, mutation, reason)])
+            .unwrap_or_default()
+    }
+}
+
+/// Makes a policy of `check`, which returns the reason a mutation is refused.
+pub fn policy_fn<F: Fn(&Mutation) -> Option<String> + Send + Sync>(name: &str, check: F) -> FnPolicy<F> {
+    FnPolicy { name: name.to_string(), check }
+}
+
+/// Checks `mutation` against every policy, failing with all the violations found.
+pub fn check_policies(policies: &[Arc<dyn Policy>], mutation: &Mutation) -> Result<(), PolicyViolations> {
+    let violations: Vec<PolicyViolation> = policies
+        .iter()
+        .flat_map(|policy| policy.check(mutation))
+        .collect();
+    if violations.is_empty() { Ok(()) } else { Err(PolicyViolations { violations }) }
+}
+
+impl NameCheapClient {
+    /// Checks every mutation of this client and its clones against `policy`.
+    ///
+    /// Policies add up. Like `with_rate_limiter`, call this before cloning the client.
+    pub fn with_policy(mut self, policy: impl Policy + 'static) -> Self {
+        self.shared = self.shared.with_policy(Arc::new(policy));
+        self
+    }
+
+    /// Returns `true` if policies are attached.
+    pub fn has_policies(&self) -> bool {
+        !self.shared.policies().is_empty()
+    }
+
+    /// Checks `mutation` against the attached policies.
+    ///
+    /// Called by the mutating methods of the crate; call it before changes made by other means.
+    pub fn check_policies(&self, mutation: &Mutation) -> Result<(), PolicyViolations> {
+        check_policies(self.shared.policies(), mutation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Host;
+    use crate::domains_dns::set_hosts::HostRequest;
+
+    fn host(name: &str, record_type: &str, address: &str, ttl: i64) -> Host {
+        let mut host: Host = Host::new();
+        host.name = name.to_string();
+        host.type_ = record_type.to_string();
+        host.address = address.to_string();
+        host.ttl = ttl;
+        host
+    }
+
+    fn zone_change() -> Mutation {
+        let current: Vec<Host> = vec![
+            host("@", "MX", "mx1.example.net", 1800),
+            host("@", "A", "192.0.2.1", 1800),
+        ];
+        let mut a: HostRequest = current[1].to_host_request();
+        a.ttl = Some("30".to_string());
+        Mutation::Zone(ZonePlan::new("example.com", &current, &[a]))
+    }
+
+    #[test]
+    fn test_builtin_policies() {
+        let mutation: Mutation = zone_change();
+        let policies: Vec<Arc<dyn Policy>> = vec![Arc::new(NoMxDeletion), Arc::new(MinTtl(60)), Arc::new(KeepWhoisGuard)];
+        let error: PolicyViolations = check_policies(&policies, &mutation).unwrap_err();
+
+        assert_eq!(error.violations.len(), 2);
+        assert_eq!(error.violations[0].policy, "no-mx-deletion");
+        assert_eq!(error.violations[0].record.as_ref().map(|key| key.record_type.as_str()), Some("MX"));
+        assert_eq!(error.violations[1].reason, "TTL 30 is below the minimum of 60");
+        assert_eq!(error.violations[1].domain, "example.com");
+
+        let disable: Mutation = Mutation::WhoisGuard { domain: "example.com".to_string(), enabled: false };
+        assert_eq!(KeepWhoisGuard.check(&disable).len(), 1);
+        assert!(check_policies(&policies, &Mutation::Contacts { domain: "example.com".to_string() }).is_ok());
+    }
+
+    #[test]
+    fn test_closure_policy_on_client() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_policy(policy_fn("no-registrations", |mutation| match mutation {
+            Mutation::CreateDomain { domain, .. } => Some(format!("{} needs approval", domain)),
+            _ => None,
+        }));
+        assert!(client.clone().has_policies());
+
+        let create: Mutation = Mutation::CreateDomain { domain: "example.com".to_string(), years: 1 };
+        let error: PolicyViolations = client.check_policies(&create).unwrap_err();
+        assert_eq!(error.to_string(), "Refused by policy\n- no-registrations: example.com: example.com needs approval");
+    }
+}
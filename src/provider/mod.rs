@@ -0,0 +1,289 @@
+//! ## Provider Traits
+//! Applications coding against `NameCheapClient` directly are tied to NameCheap. The traits
+//! of this module describe what they need instead:
+//! - `DnsProvider`: list zones, get and set their records.
+//! - `Registrar`: check availability, register and renew domains.
+//!
+//! `NameCheapClient` implements both; `MemoryProvider` implements both in memory, for tests and
+//! local development. Code generic over the traits swaps one for the other, or drives several
+//! providers at once:
+//!
+//! ```rust
+//! async fn ensure_a_record(dns: &impl DnsProvider, domain: &str, address: &str) -> Result<(), Box<dyn Error>> {
+//!     let mut records: Vec<HostRequest> = dns.get_records(domain).await?
+//!         .iter()
+//!         .map(Host::to_host_request)
+//!         .collect();
+//!     records.push(HostRequest::new("@".into(), "A".into(), address.into(), None, None, None, None, None));
+//!     dns.set_records(domain, &records).await
+//! }
+//! ```
+
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Serialize, Deserialize };
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+// crate imports
+use crate::{ NameCheapClient, ContactSet, Domain, Host };
+use crate::domains::check::DomainCheckResult;
+use crate::domains::create::DomainCreateResult;
+use crate::domains::renew::DomainRenewResult;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::response::money::Money;
+use crate::zone::split_domain;
+
+/// ### Renewal
+///
+/// #### Fields
+/// - `domain`: The renewed domain name.
+/// - `years`: The number of years added.
+/// - `charged_amount`: The amount charged (optional).
+/// - `expires_at`: The new expiration date (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Renewal {
+    pub domain: String,
+    pub years: u8,
+    pub charged_amount: Option<Money>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A service hosting DNS zones.
+// Used through generics (`impl DnsProvider`), the futures need no `Send` bound
+#[allow(async_fn_in_trait)]
+pub trait DnsProvider {
+    /// Returns the domains whose zone the provider hosts.
+    async fn list_zones(&self) -> Result<Vec<String>, Box<dyn Error>>;
+
+    /// Returns the records of the zone of `domain`.
+    async fn get_records(&self, domain: &str) -> Result<Vec<Host>, Box<dyn Error>>;
+
+    /// Replaces the records of the zone of `domain` with exactly `records`.
+    async fn set_records(&self, domain: &str, records: &[HostRequest]) -> Result<(), Box<dyn Error>>;
+}
+
+/// A service registering domains.
+#[allow(async_fn_in_trait)]
+pub trait Registrar {
+    /// Returns the availability of each domain.
+    async fn check(&self, domains: &[String]) -> Result<Vec<DomainCheckResult>, Box<dyn Error>>;
+
+    /// Registers `domain` for `years` years.
+    async fn register(&self, domain: &str, years: u8, contacts: &ContactSet) -> Result<DomainCreateResult, Box<dyn Error>>;
+
+    /// Renews `domain` for `years` years.
+    async fn renew(&self, domain: &str, years: u8) -> Result<Renewal, Box<dyn Error>>;
+}
+
+/// Splits `domain`, failing on names without a TLD.
+fn parts(domain: &str) -> Result<(&str, &str), Box<dyn Error>> {
+    split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain).into())
+}
+
+impl DnsProvider for NameCheapClient {
+    /// Returns the domains using the NameCheap name servers.
+    async fn list_zones(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let domains: Vec<Domain> = self.domains_get_list_all().await?;
+        Ok(domains.into_iter().filter(|domain| domain.is_our_dns).map(|domain| domain.name).collect())
+    }
+
+    async fn get_records(&self, domain: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.domains_dns_get_host_records(sld, tld).await
+    }
+
+    async fn set_records(&self, domain: &str, records: &[HostRequest]) -> Result<(), Box<dyn Error>> {
+        let (sld, tld) = parts(domain)?;
+        self.domains_dns_replace_hosts(sld, tld, records).await.map(|_| ())
+    }
+}
+
+impl Registrar for NameCheapClient {
+    async fn check(&self, domains: &[String]) -> Result<Vec<DomainCheckResult>, Box<dyn Error>> {
+        self.domains_check(domains).await
+    }
+
+    async fn register(&self, domain: &str, years: u8, contacts: &ContactSet) -> Result<DomainCreateResult, Box<dyn Error>> {
+        self.domains_create(domain, years, contacts).await
+    }
+
+    async fn renew(&self, domain: &str, years: u8) -> Result<Renewal, Box<dyn Error>> {
+        let result: DomainRenewResult = self.domains_renew(domain, years, None).await?;
+        Ok(Renewal {
+            domain: result.domain,
+            years: years.max(1),
+            charged_amount: result.charged_amount,
+            expires_at: result.expires_at,
+        })
+    }
+}
+
+/// An in-memory DNS provider and registrar.
+///
+/// Registered domains get an empty zone and expire after the registered years. Domains
+/// already taken elsewhere are added with [`MemoryProvider::take`].
+#[derive(Debug, Default)]
+pub struct MemoryProvider {
+    zones: Mutex<BTreeMap<String, Vec<Host>>>,
+    expiries: Mutex<BTreeMap<String, DateTime<Utc>>>,
+}
+
+impl MemoryProvider {
+    /// Creates a provider without domains.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks `domain` as registered, with an empty zone.
+    pub fn take(&self, domain: &str) {
+        let domain: String = domain.to_ascii_lowercase();
+        if let Ok(mut expiries) = self.expiries.lock() {
+            expiries.entry(domain.clone()).or_insert_with(|| Utc::now() + Duration::days(365));
+        }
+        if let Ok(mut zones) = self.zones.lock() {
+            zones.entry(domain).or_default();
+        }
+    }
+
+    /// Returns the expiration date of `domain`, if registered.
+    pub fn expires_at(&self, domain: &str) -> Option<DateTime<Utc>> {
+        self.expiries.lock().ok()?.get(&domain.to_ascii_lowercase()).copied()
+    }
+}
+
+impl DnsProvider for MemoryProvider {
+    async fn list_zones(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(self.zones.lock().map_err(|_| "Zones lock poisoned")?.keys().cloned().collect())
+    }
+
+    async fn get_records(&self, domain: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+        let zones = self.zones.lock().map_err(|_| "Zones lock poisoned")?;
+        zones.get(&domain.to_ascii_lowercase()).cloned().ok_or_else(|| format!("No zone for {}", domain).into())
+    }
+
+    async fn set_records(&self, domain: &str, records: &[HostRequest]) -> Result<(), Box<dyn Error>> {
+        let mut zones = self.zones.lock().map_err(|_| "Zones lock poisoned")?;
+        let zone: &mut Vec<Host> = zones.get_mut(&domain.to_ascii_lowercase()).ok_or_else(|| format!("No zone for {}", domain))?;
+        *zone = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let mut host: Host = Host::new();
+                host.host_id = (index + 1).to_string();
+                host.name = record.host_name.clone();
+                host.type_ = record.record_type.clone();
+                host.address = record.address.clone();
+                if let Some(ttl) = record.ttl.as_deref().and_then(|ttl| ttl.parse().ok()) {
+                    host.ttl = ttl;
+                }
+                host.mx_pref = record.mx_pref.clone().unwrap_or_default();
+                host
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+impl Registrar for MemoryProvider {
+    async fn check(&self, domains: &[String]) -> Result<Vec<DomainCheckResult>, Box<dyn Error>> {
+        let expiries = self.expiries.lock().map_err(|_| "Expiries lock poisoned")?;
+        Ok(domains
+            .iter()
+            .map(|domain| DomainCheckResult {
+                domain: domain.clone(),
+                available: !expiries.contains_key(&domain.to_ascii_lowercase()),
+                error_no: "0".to_string(),
+                description: None,
+                is_premium_name: false,
+                premium_registration_price: None,
+                premium_renewal_price: None,
+                premium_transfer_price: None,
+                premium_restore_price: None,
+                eap_fee: None,
+                icann_fee: None,
+                extra: Default::default(),
+            })
+            .collect())
+    }
+
+    async fn register(&self, domain: &str, years: u8, _contacts: &ContactSet) -> Result<DomainCreateResult, Box<dyn Error>> {
+        let name: String = domain.to_ascii_lowercase();
+        {
+            let mut expiries = self.expiries.lock().map_err(|_| "Expiries lock poisoned")?;
+            if expiries.contains_key(&name) {
+                return Err(format!("{} is not available", domain).into());
+            }
+            expiries.insert(name.clone(), Utc::now() + Duration::days(365 * i64::from(years.max(1))));
+        }
+        self.take(&name);
+        let id: i64 = self.expiries.lock().map(|expiries| expiries.len() as i64).unwrap_or(0);
+        Ok(DomainCreateResult {
+            domain: name,
+            registered: true,
+            charged_amount: None,
+            domain_id: id,
+            order_id: id,
+            transaction_id: id,
+            whois_guard_enabled: false,
+        })
+    }
+
+    async fn renew(&self, domain: &str, years: u8) -> Result<Renewal, Box<dyn Error>> {
+        let name: String = domain.to_ascii_lowercase();
+        let mut expiries = self.expiries.lock().map_err(|_| "Expiries lock poisoned")?;
+        let expires_at: &mut DateTime<Utc> = expiries.get_mut(&name).ok_or_else(|| format!("{} is not registered", domain))?;
+        *expires_at += Duration::days(365 * i64::from(years.max(1)));
+        Ok(Renewal { domain: name, years: years.max(1), charged_amount: None, expires_at: Some(*expires_at) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::users_address::create::tests::sample_contact;
+
+    async fn register_with_mail(
+        provider: &(impl DnsProvider + Registrar),
+        domain: &str,
+        contacts: &ContactSet
+    ) -> Result<(), Box<dyn Error>> {
+        let available: bool = provider.check(&[domain.to_string()]).await?.iter().all(|result| result.available);
+        if !available {
+            return Err(format!("{} is taken", domain).into());
+        }
+        provider.register(domain, 1, contacts).await?;
+        let mx: HostRequest = HostRequest::new(
+            "@".to_string(),
+            "MX".to_string(),
+            "mx.example.net".to_string(),
+            Some("10".to_string()),
+            None,
+            None,
+            None,
+            None
+        );
+        provider.set_records(domain, &[mx]).await
+    }
+
+    #[tokio::test]
+    async fn test_memory_provider() {
+        let provider: MemoryProvider = MemoryProvider::new();
+        let contacts: ContactSet = ContactSet::uniform(sample_contact());
+        provider.take("example.org");
+
+        register_with_mail(&provider, "Example.com", &contacts).await.unwrap();
+        assert!(register_with_mail(&provider, "example.org", &contacts).await.is_err());
+        assert_eq!(provider.list_zones().await.unwrap(), ["example.com", "example.org"]);
+
+        let records: Vec<Host> = provider.get_records("example.com").await.unwrap();
+        assert_eq!((records[0].type_.as_str(), records[0].mx_pref.as_str()), ("MX", "10"));
+
+        let expires_at: DateTime<Utc> = provider.expires_at("example.com").unwrap();
+        let renewal: Renewal = provider.renew("example.com", 2).await.unwrap();
+        assert_eq!(renewal.expires_at, Some(expires_at + Duration::days(730)));
+        assert!(provider.renew("example.net", 1).await.is_err());
+    }
+}
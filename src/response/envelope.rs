@@ -0,0 +1,112 @@
+//! This module reads the envelope of a Namecheap API response: the fields of `ApiResponse`
+//! around `CommandResponse`, which describe where and how the command ran.
+//!
+//! Sandbox responses do not always match production ones (some fields are omitted), the
+//! `environment` marker lets downstream code branch when it needs to.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+
+// crate imports
+use crate::response::parse_value::{ get_node_at, parse_string, text_of };
+
+/// The NameCheap environment that answered a call.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Environment {
+    Production,
+    Sandbox,
+}
+
+impl Environment {
+    /// Returns the environment a client is configured for.
+    pub fn from_production(production: bool) -> Self {
+        if production { Environment::Production } else { Environment::Sandbox }
+    }
+
+    /// Returns the environment of a `Server` name, e.g. `WEB1-SANDBOX1` for the sandbox.
+    pub fn from_server(server: &str) -> Self {
+        if server.to_uppercase().contains("SANDBOX") { Environment::Sandbox } else { Environment::Production }
+    }
+}
+
+/// ### Response Envelope
+///
+/// #### Fields
+/// - `status`: The `Status` attribute, `OK` or `ERROR`.
+/// - `command`: The `RequestedCommand`, e.g. `namecheap.domains.getlist` (optional).
+/// - `server`: The server that answered, e.g. `WEB1-SANDBOX1` (optional).
+/// - `environment`: The environment that answered, from `server` when reported.
+/// - `gmt_time_difference`: The server's `GMTTimeDifference`, e.g. `--5:00` (optional).
+/// - `execution_time`: The server side execution time in seconds, as reported (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ResponseEnvelope {
+    pub status: String,
+    pub command: Option<String>,
+    pub server: Option<String>,
+    pub environment: Environment,
+    pub gmt_time_difference: Option<String>,
+    pub execution_time: Option<String>,
+}
+
+impl ResponseEnvelope {
+    /// Reads the envelope of a full JSON response.
+    ///
+    /// When the response does not name its server, the environment is the one the client
+    /// was configured for (`production`).
+    pub fn from_response(response: &Value, production: bool) -> Self {
+        let text = |element: &str| {
+            get_node_at(response, &format!("/ApiResponse/{}", element))
+                .and_then(text_of)
+                .map(|text| text.to_string())
+        };
+        let server: Option<String> = text("Server");
+
+        ResponseEnvelope {
+            status: get_node_at(response, "/ApiResponse")
+                .map(|envelope| parse_string(envelope, "status", ""))
+                .unwrap_or_default(),
+            command: text("RequestedCommand"),
+            environment: server
+                .as_deref()
+                .map(Environment::from_server)
+                .unwrap_or_else(|| Environment::from_production(production)),
+            server,
+            gmt_time_difference: text("GMTTimeDifference"),
+            execution_time: text("ExecutionTime"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Domain, Host };
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_envelope_environment() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><RequestedCommand>namecheap.domains.getinfo</RequestedCommand><Server>WEB1-SANDBOX1</Server><GMTTimeDifference>--5:00</GMTTimeDifference><ExecutionTime>0.008</ExecutionTime></ApiResponse>"#
+        ).unwrap();
+        let envelope: ResponseEnvelope = ResponseEnvelope::from_response(&response, true);
+        assert_eq!(envelope.status, "OK");
+        assert_eq!(envelope.environment, Environment::Sandbox);
+        assert_eq!(envelope.execution_time.as_deref(), Some("0.008"));
+
+        let response: Value = parse_xml_to_json(r#"<ApiResponse Status="OK"><Errors /></ApiResponse>"#).unwrap();
+        assert_eq!(ResponseEnvelope::from_response(&response, true).environment, Environment::Production);
+    }
+
+    #[test]
+    fn test_models_tolerate_omitted_fields() {
+        let domain: Domain = serde_json::from_str(r#"{ "id": 1, "name": "example.com" }"#).unwrap();
+        assert!(!domain.auto_renew);
+        let host: Host = serde_json::from_str(r#"{ "name": "@", "address": "203.0.113.10", "type": "A" }"#).unwrap();
+        assert_eq!(host.ttl, 1800);
+        assert!(host.is_active);
+    }
+}
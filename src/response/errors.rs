@@ -0,0 +1,143 @@
+//! This module provides extraction of the `Errors` section of a Namecheap API response.
+//!
+//! Failed commands are returned with `Status="ERROR"` and one or more `Error` elements
+//! carrying a numeric error code, e.g. `<Error Number="2019166">Domain not found</Error>`.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::fmt;
+
+// crate imports
+use crate::response::parse_value::{ get_attr, get_nodes_at, text_of };
+use crate::utils::correlation::current_correlation_id;
+
+/// A single error reported by the NameCheap API.
+///
+/// #### Fields
+/// - `number`: The Namecheap error number, e.g. `2019166`.
+/// - `message`: The human readable error message.
+/// - `correlation_id`: The operation the error occurred in, see [`correlation`](crate::utils::correlation) (optional).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiError {
+    pub number: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+impl ApiError {
+    /// Creates an error, stamped with the correlation ID of the current operation.
+    pub fn new(number: &str, message: &str) -> Self {
+        ApiError {
+            number: number.to_string(),
+            message: message.to_string(),
+            correlation_id: current_correlation_id(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Namecheap API error {}: {}", self.number, self.message)?;
+        if let Some(id) = &self.correlation_id {
+            write!(f, " (correlation id {})", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// A response body larger than the client's `max_response_bytes`.
+///
+/// #### Fields
+/// - `limit`: The configured maximum, in bytes.
+/// - `received`: The size that exceeded it, either the announced `Content-Length` or the bytes read so far.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResponseTooLarge {
+    pub limit: usize,
+    pub received: usize,
+}
+
+impl fmt::Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Response body of at least {} bytes exceeds the limit of {} bytes", self.received, self.limit)
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// Fields of a response element no typed model knows, only an error with the `strict` feature.
+///
+/// #### Fields
+/// - `element`: The element holding the fields, e.g. `Domain`.
+/// - `fields`: The unknown keys, as named in the parsed JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownFields {
+    pub element: String,
+    pub fields: Vec<String>,
+}
+
+impl fmt::Display for UnknownFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown fields on {}: {}", self.element, self.fields.join(", "))
+    }
+}
+
+impl std::error::Error for UnknownFields {}
+
+/// Extracts all errors reported in a response envelope
+///
+/// # Parameters
+///
+/// - `response`: The full JSON response, starting at `ApiResponse`
+///
+/// # Returns
+///
+/// The reported errors, empty if the command succeeded
+pub fn extract_api_errors(response: &Value) -> Vec<ApiError> {
+    get_nodes_at(response, "/ApiResponse/Errors/Error")
+        .into_iter()
+        .map(|error| {
+            ApiError::new(&get_attr::<String>(error, "number").unwrap_or_default(), text_of(error).unwrap_or(""))
+        })
+        .collect()
+}
+
+/// Checks the status of a response envelope
+///
+/// # Parameters
+///
+/// - `response`: The full JSON response, starting at `ApiResponse`
+///
+/// # Returns
+///
+/// `Ok(())` if no errors were reported, otherwise the first `ApiError`
+pub fn check_api_errors(response: &Value) -> Result<(), ApiError> {
+    match extract_api_errors(response).into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_api_errors() {
+        let response: Value =
+            json!({
+            "ApiResponse": {
+                "status": "ERROR",
+                "Errors": { "Error": { "number": "2019166", "$text": "Domain not found" } }
+            }
+        });
+
+        let error: ApiError = check_api_errors(&response).unwrap_err();
+        assert_eq!(error.number, "2019166");
+        assert_eq!(error.message, "Domain not found");
+        assert!(check_api_errors(&json!({ "ApiResponse": { "status": "OK", "Errors": {} } })).is_ok());
+    }
+}
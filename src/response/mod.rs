@@ -1,2 +1,9 @@
+pub mod envelope;
+pub mod errors;
+pub mod money;
 pub mod paging;
-pub mod parse_value;
\ No newline at end of file
+pub mod parse_value;
+pub mod parsed_page;
+pub mod timestamp;
+pub mod typed_xml;
+pub mod views;
@@ -0,0 +1,294 @@
+//! ### Money Values
+//!
+//! Balances, prices and charged amounts are returned in the account currency. The currency is
+//! only carried by some responses (e.g. the `Currency` attribute of `users.getBalances`), this
+//! module pairs every amount with its [`Currency`] so values of different accounts never mix.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+// crate imports
+use crate::response::parse_value::{ parse_decimal, text_of, Decimal };
+
+/// The currency of an account, serialized as its ISO 4217 code.
+///
+/// Namecheap accounts are billed in USD unless configured otherwise, which is the default.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(from = "String", into = "String")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Gbp,
+    Cad,
+    Aud,
+    Inr,
+    /// Any other currency code, kept uppercased.
+    Other(String),
+}
+
+impl Currency {
+    /// Returns the ISO 4217 code of the currency.
+    pub fn code(&self) -> &str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Cad => "CAD",
+            Currency::Aud => "AUD",
+            Currency::Inr => "INR",
+            Currency::Other(code) => code,
+        }
+    }
+}
+
+impl From<&str> for Currency {
+    fn from(code: &str) -> Self {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "USD" => Currency::Usd,
+            "EUR" => Currency::Eur,
+            "GBP" => Currency::Gbp,
+            "CAD" => Currency::Cad,
+            "AUD" => Currency::Aud,
+            "INR" => Currency::Inr,
+            other => Currency::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for Currency {
+    fn from(code: String) -> Self {
+        Currency::from(code.as_str())
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().to_string()
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let code: &str = code.trim();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("Invalid currency code: {:?}", code));
+        }
+        Ok(Currency::from(code))
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// ### Money
+///
+/// An exact amount in a given currency.
+///
+/// #### Fields
+/// - `amount`: The amount, never converted through `f64`.
+/// - `currency`: The currency of the amount.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Creates an amount in the given currency.
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Money { amount, currency }
+    }
+
+    /// Returns the exact sum of two amounts, `None` when their currencies differ or the sum
+    /// overflows.
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        self.try_add(other).ok()
+    }
+
+    /// Returns the exact sum of two amounts, or an error when their currencies differ or the
+    /// sum overflows.
+    pub fn try_add(&self, other: &Money) -> Result<Money, String> {
+        if self.currency != other.currency {
+            return Err(format!("Cannot add {} to {}", other, self));
+        }
+        add(&self.amount, &other.amount)
+            .map(|amount| Money::new(amount, self.currency.clone()))
+            .ok_or_else(|| format!("Overflow adding {} to {}", other, self))
+    }
+
+    /// Returns the amount with the opposite sign.
+    pub fn negated(&self) -> Money {
+        Money::new(negate(&self.amount), self.currency.clone())
+    }
+
+    /// Returns `true` if the amount is above zero.
+    pub fn is_positive(&self) -> bool {
+        is_positive(&self.amount)
+    }
+}
+
+#[cfg(feature = "decimal")]
+fn add(a: &Decimal, b: &Decimal) -> Option<Decimal> {
+    a.checked_add(*b)
+}
+
+#[cfg(feature = "decimal")]
+fn is_positive(amount: &Decimal) -> bool {
+    *amount > Decimal::ZERO
+}
+
+#[cfg(feature = "decimal")]
+fn negate(amount: &Decimal) -> Decimal {
+    -amount
+}
+
+/// Splits a validated decimal string into its unscaled value and scale, `None` on overflow.
+#[cfg(not(feature = "decimal"))]
+fn split(text: &str) -> Option<(i128, usize)> {
+    let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+    let negative: bool = int.starts_with('-');
+    let digits: String = format!("{}{}", int.trim_start_matches('-'), frac);
+    let value: i128 = digits.parse().ok()?;
+    Some((if negative { -value } else { value }, frac.len()))
+}
+
+/// Adds two validated decimal strings exactly, `None` on overflow.
+#[cfg(not(feature = "decimal"))]
+fn add(a: &Decimal, b: &Decimal) -> Option<Decimal> {
+    let ((a, a_scale), (b, b_scale)) = (split(a)?, split(b)?);
+    let scale: usize = a_scale.max(b_scale);
+    let rescale = |value: i128, from: usize| -> Option<i128> {
+        10i128.checked_pow(u32::try_from(scale - from).ok()?)?.checked_mul(value)
+    };
+    let total: i128 = rescale(a, a_scale)?.checked_add(rescale(b, b_scale)?)?;
+    let digits: String = format!("{:0>width$}", total.unsigned_abs(), width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    let sign: &str = if total < 0 { "-" } else { "" };
+    Some(if scale == 0 { format!("{}{}", sign, int) } else { format!("{}{}.{}", sign, int, frac) })
+}
+
+/// Amounts too large for an `i128` are not positive, like every amount `add` refuses.
+#[cfg(not(feature = "decimal"))]
+fn is_positive(amount: &Decimal) -> bool {
+    split(amount).is_some_and(|(value, _)| value > 0)
+}
+
+#[cfg(not(feature = "decimal"))]
+fn negate(amount: &Decimal) -> Decimal {
+    match amount.strip_prefix('-') {
+        Some(positive) => positive.to_string(),
+        None => format!("-{}", amount),
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Parses a money value from a JSON object in the given currency
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `key`: The key (attribute or child element) holding the amount
+/// - `currency`: The currency of the amount
+///
+/// # Returns
+///
+/// The money value or `None` if the key is not found or is not a decimal number
+pub fn parse_money(json: &Value, key: &str, currency: &Currency) -> Option<Money> {
+    parse_decimal(json, key).map(|amount| Money::new(amount, currency.clone()))
+}
+
+/// Finds the first `Currency` attribute of a parsed response
+///
+/// The search is depth first, so the currency of the outermost result wins.
+///
+/// # Parameters
+///
+/// - `json`: The parsed response, or any node of it
+///
+/// # Returns
+///
+/// The currency or `None` if the response does not carry one
+pub fn detect_currency(json: &Value) -> Option<Currency> {
+    match json {
+        Value::Object(map) => {
+            if let Some(currency) = map.get("currency").and_then(text_of).and_then(|code| code.parse().ok()) {
+                return Some(currency);
+            }
+            map.iter()
+                .filter(|(key, _)| key.as_str() != "$text")
+                .find_map(|(_, value)| detect_currency(value))
+        }
+        Value::Array(values) => values.iter().find_map(detect_currency),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::response::parse_value::parse_decimal_str;
+
+    /// The largest amount of the `Decimal` type.
+    #[cfg(feature = "decimal")]
+    const HUGE: &str = "79228162514264337593543950335";
+    #[cfg(not(feature = "decimal"))]
+    const HUGE: &str = "170141183460469231731687303715884105727";
+
+    #[test]
+    fn test_currency_codes() {
+        assert_eq!(Currency::from("usd"), Currency::Usd);
+        assert_eq!("CHF".parse::<Currency>(), Ok(Currency::Other("CHF".to_string())));
+        assert!("dollars".parse::<Currency>().is_err());
+        assert_eq!(serde_json::to_value(Currency::Eur).unwrap(), json!("EUR"));
+        assert_eq!(serde_json::from_value::<Currency>(json!("gbp")).unwrap(), Currency::Gbp);
+    }
+
+    #[test]
+    fn test_parse_and_detect() {
+        let response: Value = json!({
+            "ApiResponse": { "CommandResponse": {
+                "UserGetBalancesResult": { "currency": "EUR", "available_balance": "4932.96" }
+            } }
+        });
+        let currency: Currency = detect_currency(&response).unwrap();
+        let result: &Value = &response["ApiResponse"]["CommandResponse"]["UserGetBalancesResult"];
+        let money: Money = parse_money(result, "available_balance", &currency).unwrap();
+
+        assert_eq!(currency, Currency::Eur);
+        assert_eq!(money.to_string(), "4932.96 EUR");
+        assert_eq!(detect_currency(&json!({ "status": "OK" })), None);
+    }
+
+    #[test]
+    fn test_money_arithmetic() {
+        let usd = |amount: &str| Money::new(parse_decimal_str(amount).unwrap(), Currency::Usd);
+
+        assert_eq!(usd("10.50").checked_add(&usd("0.25")).unwrap().to_string(), "10.75 USD");
+        assert!(usd("10.50").checked_add(&Money::new(parse_decimal_str("1").unwrap(), Currency::Eur)).is_none());
+        assert!(usd("1").is_positive());
+        assert!(!usd("0.00").is_positive());
+        assert!(!usd("-0.01").is_positive());
+        assert!(!usd("1.00").negated().is_positive());
+
+        let huge: Money = usd(HUGE);
+        assert!(huge.try_add(&huge).unwrap_err().contains("Overflow"));
+    }
+}
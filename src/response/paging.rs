@@ -1,54 +1,38 @@
 //! This module provides functionality to extract pagination information from a Namecheap API response.
 //!
 
-use serde_json::{ Value, Map };
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
 use tracing::warn;
 
+// crate imports
+use crate::response::parse_value::get_text_at;
+use crate::limits::{ DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, MIN_PAGE_SIZE };
+
 /// Extracts pagination information from a Namecheap API response
 ///
 /// # Parameters
 ///
-/// - `paging`: Optional reference to a JSON Map containing paging information
+/// - `paging`: Optional reference to the JSON `Paging` node of the response
 ///
 /// # Returns
 ///
-/// A tuple containing (current_page, page_size, total_items, total_pages)
-pub fn extract_pagination_info(paging: Option<&Map<String, Value>>) -> (i64, i64, i64, i64) {
+/// A tuple containing (current_page, page_size, total_items, total_pages). Without a `Paging`
+/// node, e.g. in minimal sandbox responses, the response is taken as the only page.
+pub fn extract_pagination_info(paging: Option<&Value>) -> (i64, i64, i64, i64) {
     // Default pagination values
     let mut current_page: i64 = 1;
-    let mut page_size: i64 = 21;
+    let mut page_size: i64 = DEFAULT_PAGE_SIZE as i64;
     let mut total_items: i64 = 0;
 
     // Extract values if Paging exists
-    if let Some(paging_obj) = paging {
-        if
-            let Some(current) = paging_obj
-                .get("CurrentPage")
-                .and_then(|v| v.get("$text"))
-                .and_then(|v| v.as_str())
-        {
-            current_page = current.parse::<i64>().unwrap_or(1);
-        }
-
-        if
-            let Some(size) = paging_obj
-                .get("PageSize")
-                .and_then(|v| v.get("$text"))
-                .and_then(|v| v.as_str())
-        {
-            page_size = size.parse::<i64>().unwrap_or(21);
-        }
-
-        if
-            let Some(total) = paging_obj
-                .get("TotalItems")
-                .and_then(|v| v.get("$text"))
-                .and_then(|v| v.as_str())
-        {
-            total_items = total.parse::<i64>().unwrap_or(0);
-        }
+    if let Some(paging) = paging {
+        current_page = get_text_at(paging, "/CurrentPage").unwrap_or(current_page);
+        page_size = get_text_at(paging, "/PageSize").unwrap_or(page_size);
+        total_items = get_text_at(paging, "/TotalItems").unwrap_or(total_items);
     } else {
         warn!("Paging information not found in response");
+        return (current_page, page_size, total_items, 1);
     }
 
     // Calculate total pages based on total items and page size
@@ -60,3 +44,100 @@ pub fn extract_pagination_info(paging: Option<&Map<String, Value>>) -> (i64, i64
 
     (current_page, page_size, total_items, total_pages)
 }
+
+/// ### Page Size
+///
+/// The number of items per page requested from paged list commands, e.g. `domains.getList`.
+///
+/// NameCheap accepts page sizes between 10 and 100, and uses 20 when none is sent. Fetching
+/// a whole account with [`PageSize::MAX`] needs five times fewer calls than the default.
+/// Deserializing goes through [`PageSize::new`], out of range values are rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(try_from = "u8")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PageSize(u8);
+
+impl PageSize {
+    /// The smallest page size accepted by the API.
+    pub const MIN: PageSize = PageSize(MIN_PAGE_SIZE);
+    /// The largest page size accepted by the API.
+    pub const MAX: PageSize = PageSize(MAX_PAGE_SIZE);
+    /// The page size the API uses when none is sent.
+    pub const DEFAULT: PageSize = PageSize(DEFAULT_PAGE_SIZE);
+
+    /// Creates a page size, returning an error outside of 10 to 100.
+    pub fn new(size: u8) -> Result<Self, String> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&size) {
+            Ok(PageSize(size))
+        } else {
+            Err(format!("Page size {} is not between {} and {}", size, Self::MIN.0, Self::MAX.0))
+        }
+    }
+
+    /// Returns the number of items per page.
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for PageSize {
+    type Error = String;
+
+    fn try_from(size: u8) -> Result<Self, Self::Error> {
+        PageSize::new(size)
+    }
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        PageSize::DEFAULT
+    }
+}
+
+/// ### Pagination
+///
+/// The pagination summary returned alongside list results, e.g. by `domains_get_list`.
+///
+/// #### Fields
+/// - `current_page`: The page that was returned, starting at 1.
+/// - `total_pages`: The number of pages available.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Pagination {
+    pub current_page: i64,
+    pub total_pages: i64,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination { current_page: 1, total_pages: 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_paging() {
+        assert_eq!(extract_pagination_info(None), (1, DEFAULT_PAGE_SIZE as i64, 0, 1));
+
+        let paging: Value = serde_json::json!({ "TotalItems": { "$text": "45" }, "PageSize": { "$text": "20" } });
+        assert_eq!(extract_pagination_info(Some(&paging)), (1, 20, 45, 3));
+    }
+
+    #[test]
+    fn test_page_size_bounds() {
+        assert_eq!(PageSize::new(100).unwrap(), PageSize::MAX);
+        assert_eq!(PageSize::new(10).unwrap().get(), 10);
+        assert!(PageSize::new(9).is_err());
+        assert!(PageSize::new(101).is_err());
+        assert_eq!(PageSize::default().get(), 20);
+
+        assert_eq!(serde_json::from_str::<PageSize>("50").unwrap().get(), 50);
+        assert!(serde_json::from_str::<PageSize>("5").is_err());
+        assert_eq!(serde_json::to_string(&PageSize::MAX).unwrap(), "100");
+    }
+}
@@ -3,6 +3,62 @@
 //! It contains functions to safely extract and convert values from JSON responses.
 
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::debug;
+
+// crate imports
+use crate::response::errors::UnknownFields;
+
+/// Decimal type used for money fields (prices, balances, charged amounts).
+///
+/// With the `decimal` feature (enabled by default) this is `rust_decimal::Decimal`.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+
+/// Decimal type used for money fields (prices, balances, charged amounts).
+///
+/// Without the `decimal` feature money is kept as the validated decimal string
+/// returned by the API, so it never goes through a lossy `f64`.
+#[cfg(not(feature = "decimal"))]
+pub type Decimal = String;
+
+/// The fields of a response element not mapped by its typed model, keyed like the parsed JSON.
+///
+/// Namecheap adds attributes over time; tolerant parsing keeps them here instead of dropping
+/// them, so they can be used before the crate models them.
+pub type Extra = BTreeMap<String, String>;
+
+/// Collects the fields of `node` missing from `known`
+///
+/// Attributes keep their text, child elements are kept as JSON.
+///
+/// # Parameters
+///
+/// - `node`: The element, e.g. a `Domain` of `domains.getList`
+/// - `element`: The element name, for error messages
+/// - `known`: The keys read by the typed model
+///
+/// # Returns
+///
+/// The unknown fields, or with the `strict` feature an `UnknownFields` error when there are any
+pub fn extra_fields(node: &Value, element: &str, known: &[&str]) -> Result<Extra, UnknownFields> {
+    let extra: Extra = node
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.as_str() != "$text" && !known.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), text_of(value).map(str::to_string).unwrap_or_else(|| value.to_string())))
+        .collect();
+
+    if !extra.is_empty() {
+        debug!("Unknown fields on {}: {:?}", element, extra.keys());
+        if cfg!(feature = "strict") {
+            return Err(UnknownFields { element: element.to_string(), fields: extra.into_keys().collect() });
+        }
+    }
+    Ok(extra)
+}
 
 /// Parses a string value from a JSON object
 ///
@@ -65,3 +121,231 @@ pub fn parse_i64(json: &Value, key: &str, default: i64) -> i64 {
         .parse::<i64>()
         .unwrap_or(default)
 }
+
+/// Parses an optional string value from a JSON object
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `key`: The key to look for in the JSON object
+///
+/// # Returns
+///
+/// `Some` with the string value, or `None` if the key is not present
+pub fn parse_opt_string(json: &Value, key: &str) -> Option<String> {
+    json.get(key)
+        .and_then(|v| {
+            if let Some(text) = v.get("$text") {
+                text.as_str()
+            } else {
+                v.as_str()
+            }
+        })
+        .map(|v| v.to_string())
+}
+
+/// Parses an optional boolean value from a JSON object
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `key`: The key to look for in the JSON object
+/// - `true_value`: The string value that represents true (compared case-insensitively)
+///
+/// # Returns
+///
+/// `Some` with the boolean value, or `None` if the key is not present
+pub fn parse_opt_bool(json: &Value, key: &str, true_value: &str) -> Option<bool> {
+    parse_opt_string(json, key).map(|v| v.eq_ignore_ascii_case(true_value))
+}
+
+/// Returns the text content of a JSON node produced by the XML parser
+///
+/// Elements with text content are represented as `{ "$text": "..." }`, attributes
+/// and already flattened values are plain strings. Both are handled.
+///
+/// # Parameters
+///
+/// - `node`: The JSON node to read the text from
+///
+/// # Returns
+///
+/// The text content or `None` if the node has no text
+pub fn text_of(node: &Value) -> Option<&str> {
+    match node.get("$text") {
+        Some(text) => text.as_str(),
+        None => node.as_str(),
+    }
+}
+
+/// Returns the JSON node at the given pointer path
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `path`: A JSON pointer path, e.g. `/ApiResponse/CommandResponse`
+///
+/// # Returns
+///
+/// The node or `None` if any segment of the path is missing
+pub fn get_node_at<'a>(json: &'a Value, path: &str) -> Option<&'a Value> {
+    json.pointer(path)
+}
+
+/// Returns every element found at the given pointer path
+///
+/// The XML parser collapses a single repeated element into an object rather than an
+/// array, this helper always returns a list regardless of the element count.
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `path`: A JSON pointer path, e.g. `/ApiResponse/CommandResponse/Tlds/Tld`
+///
+/// # Returns
+///
+/// The elements, or an empty list if the path is missing
+pub fn get_nodes_at<'a>(json: &'a Value, path: &str) -> Vec<&'a Value> {
+    match json.pointer(path) {
+        Some(Value::Array(array)) => array.iter().collect(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(value) => vec![value],
+    }
+}
+
+/// Parses the text found at the given pointer path into a typed value
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `path`: A JSON pointer path, e.g. `/ApiResponse/CommandResponse/Paging/TotalItems`
+///
+/// # Returns
+///
+/// The parsed value or `None` if the path is missing or parsing fails
+pub fn get_text_at<T: FromStr>(json: &Value, path: &str) -> Option<T> {
+    json.pointer(path)
+        .and_then(text_of)
+        .and_then(|text| text.trim().parse::<T>().ok())
+}
+
+/// Parses an attribute of a JSON node into a typed value
+///
+/// Attribute names are the snake_case names produced by the XML parser, e.g. `is_our_dns`.
+///
+/// # Parameters
+///
+/// - `json`: The JSON node holding the attribute
+/// - `key`: The attribute name
+///
+/// # Returns
+///
+/// The parsed value or `None` if the attribute is missing or parsing fails
+pub fn get_attr<T: FromStr>(json: &Value, key: &str) -> Option<T> {
+    json.get(key)
+        .and_then(text_of)
+        .and_then(|text| text.trim().parse::<T>().ok())
+}
+
+/// Parses a decimal string (e.g. `"12.50"`) into a [`Decimal`]
+///
+/// # Parameters
+///
+/// - `text`: The decimal string, surrounding whitespace is ignored
+///
+/// # Returns
+///
+/// The decimal value or `None` if the string is not a plain decimal number
+pub fn parse_decimal_str(text: &str) -> Option<Decimal> {
+    let text: &str = text.trim();
+    let digits: &str = text.strip_prefix('-').unwrap_or(text);
+    let mut parts = digits.splitn(2, '.');
+    let whole: &str = parts.next().unwrap_or("");
+    let fraction: &str = parts.next().unwrap_or("");
+
+    let is_plain_decimal: bool =
+        !(whole.is_empty() && fraction.is_empty()) &&
+        whole.chars().all(|c| c.is_ascii_digit()) &&
+        fraction.chars().all(|c| c.is_ascii_digit()) &&
+        !digits.ends_with('.');
+    if !is_plain_decimal {
+        return None;
+    }
+
+    #[cfg(feature = "decimal")]
+    {
+        Decimal::from_str(text).ok()
+    }
+    #[cfg(not(feature = "decimal"))]
+    {
+        Some(text.to_string())
+    }
+}
+
+/// Parses a money value from a JSON object without going through `f64`
+///
+/// # Parameters
+///
+/// - `json`: The JSON object to extract from
+/// - `key`: The key (attribute or child element) to look for in the JSON object
+///
+/// # Returns
+///
+/// The decimal value or `None` if the key is not found or is not a decimal number
+pub fn parse_decimal(json: &Value, key: &str) -> Option<Decimal> {
+    json.get(key).and_then(text_of).and_then(parse_decimal_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_path_helpers_unwrap_text() {
+        let json: Value =
+            json!({
+            "ApiResponse": {
+                "status": "OK",
+                "CommandResponse": {
+                    "Paging": { "TotalItems": { "$text": "42" } },
+                    "Tlds": { "Tld": { "name": "com" } }
+                }
+            }
+        });
+
+        assert_eq!(get_text_at::<i64>(&json, "/ApiResponse/CommandResponse/Paging/TotalItems"), Some(42));
+        assert_eq!(get_text_at::<i64>(&json, "/ApiResponse/CommandResponse/Paging/PageSize"), None);
+        assert_eq!(get_attr::<String>(&json["ApiResponse"], "status"), Some("OK".to_string()));
+        assert_eq!(get_nodes_at(&json, "/ApiResponse/CommandResponse/Tlds/Tld").len(), 1);
+        assert!(get_nodes_at(&json, "/ApiResponse/Errors").is_empty());
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let json: Value = json!({ "charged_amount": "10.8700", "Price": { "$text": "-3.5" }, "bad": "1e5" });
+
+        assert_eq!(parse_decimal(&json, "charged_amount").map(|d| d.to_string()), Some("10.8700".to_string()));
+        assert_eq!(parse_decimal(&json, "Price").map(|d| d.to_string()), Some("-3.5".to_string()));
+        assert_eq!(parse_decimal(&json, "bad"), None);
+        assert_eq!(parse_decimal(&json, "missing"), None);
+        assert_eq!(parse_decimal_str("."), None);
+        assert_eq!(parse_decimal_str("12."), None);
+    }
+
+    #[test]
+    fn test_extra_fields() {
+        let json: Value = json!({ "id": "1", "name": "example.com", "is_new_attr": "yes", "Child": { "a": "b" }, "$text": "x" });
+        let extra: Result<Extra, UnknownFields> = extra_fields(&json, "Domain", &["id", "name"]);
+
+        if cfg!(feature = "strict") {
+            assert_eq!(extra.unwrap_err().fields, ["Child", "is_new_attr"]);
+        } else {
+            let extra: Extra = extra.unwrap();
+            assert_eq!(extra.get("is_new_attr").map(String::as_str), Some("yes"));
+            assert_eq!(extra.get("Child").map(String::as_str), Some(r#"{"a":"b"}"#));
+            assert_eq!(extra.len(), 2);
+        }
+        assert_eq!(extra_fields(&json, "Domain", &["id", "name", "is_new_attr", "Child"]), Ok(Extra::new()));
+    }
+}
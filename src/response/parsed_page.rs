@@ -0,0 +1,106 @@
+//! This module provides the `ParsedPage` type used to report partially parsed list responses.
+//!
+//! List commands return many elements per page. Instead of silently filling in defaults when
+//! an element is malformed, the element is skipped and recorded as a `ParseIssue`.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+
+/// A single list element that could not be parsed.
+///
+/// #### Fields
+/// - `index`: The position of the element within the page.
+/// - `reason`: Why the element was skipped.
+/// - `raw`: The raw JSON element as returned by the XML parser.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParseIssue {
+    pub index: usize,
+    pub reason: String,
+    pub raw: Value,
+}
+
+/// A page of parsed items alongside the elements that were skipped.
+///
+/// #### Fields
+/// - `items`: The successfully parsed items.
+/// - `skipped`: The elements that failed to parse.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParsedPage<T> {
+    pub items: Vec<T>,
+    pub skipped: Vec<ParseIssue>,
+}
+
+impl<T> ParsedPage<T> {
+    /// Parses every element of a list node with the given parser.
+    ///
+    /// The XML parser collapses a single child element into an object rather than an array,
+    /// both shapes are handled here. A missing node yields an empty page.
+    ///
+    /// # Parameters
+    ///
+    /// - `elements`: The list node, e.g. `DomainGetListResult.Domain`
+    /// - `parse`: The per-element parser returning the reason on failure
+    pub fn parse<F>(elements: Option<&Value>, parse: F) -> Self where F: Fn(&Value) -> Result<T, String> {
+        let elements: Vec<&Value> = match elements {
+            Some(Value::Array(array)) => array.iter().collect(),
+            Some(value @ Value::Object(_)) => vec![value],
+            _ => Vec::new(),
+        };
+
+        let mut items: Vec<T> = Vec::new();
+        let mut skipped: Vec<ParseIssue> = Vec::new();
+
+        for (index, element) in elements.into_iter().enumerate() {
+            match parse(element) {
+                Ok(item) => items.push(item),
+                Err(reason) =>
+                    skipped.push(ParseIssue {
+                        index,
+                        reason,
+                        raw: element.clone(),
+                    }),
+            }
+        }
+
+        ParsedPage { items, skipped }
+    }
+
+    /// Returns the number of elements seen on the page, parsed or not.
+    pub fn seen(&self) -> usize {
+        self.items.len() + self.skipped.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_id(value: &Value) -> Result<i64, String> {
+        value
+            .get("id")
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.parse::<i64>().ok())
+            .ok_or_else(|| "missing or invalid id".to_string())
+    }
+
+    #[test]
+    fn test_parsed_page_collects_issues() {
+        let elements: Value = json!([{ "id": "1" }, { "id": "abc" }, { "id": "3" }]);
+        let page: ParsedPage<i64> = ParsedPage::parse(Some(&elements), parse_id);
+
+        assert_eq!(page.items, vec![1, 3]);
+        assert_eq!(page.skipped.len(), 1);
+        assert_eq!(page.skipped[0].index, 1);
+        assert_eq!(page.seen(), 3);
+    }
+
+    #[test]
+    fn test_parsed_page_single_element() {
+        let element: Value = json!({ "id": "42" });
+        let page: ParsedPage<i64> = ParsedPage::parse(Some(&element), parse_id);
+
+        assert_eq!(page.items, vec![42]);
+        assert!(page.skipped.is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+//! This module provides time zone aware timestamp handling for Namecheap API responses.
+//!
+//! Every response envelope carries a `GMTTimeDifference` element (e.g. `--5:00`) describing
+//! the offset of the server clock. Dates in the response are local to that offset, this module
+//! normalizes them to `DateTime<Utc>`.
+//!
+//! Date formats differ between endpoints: most use `MM/DD/YYYY`, SSL and transfer responses
+//! also use 12-hour times, ISO 8601 and RFC 3339 timestamps. [`DATETIME_FORMATS`] and
+//! [`DATE_FORMATS`] list every accepted format; parsing never depends on the system locale.
+
+use chrono::{ DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc };
+use serde_json::Value;
+
+// crate imports
+use crate::response::parse_value::get_text_at;
+
+/// The date and time formats accepted by [`to_utc`], tried in order.
+pub const DATETIME_FORMATS: [&str; 7] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%m/%d/%Y %I:%M %p",
+    "%m/%d/%Y %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// The date formats accepted by [`parse_date`] and [`to_utc`], tried in order.
+pub const DATE_FORMATS: [&str; 3] = ["%m/%d/%Y", "%Y-%m-%d", "%Y/%m/%d"];
+
+/// Parses a server-local date and time in one of [`DATETIME_FORMATS`], or a date in one of
+/// [`DATE_FORMATS`] at midnight
+///
+/// # Parameters
+///
+/// - `text`: The raw date text, surrounding whitespace is ignored
+///
+/// # Returns
+///
+/// The local date and time or `None` if the text is empty or not a known format
+pub fn parse_local_datetime(text: &str) -> Option<NaiveDateTime> {
+    let text: &str = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| parse_date(text).and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
+
+/// Parses a date in one of [`DATE_FORMATS`], ignoring a trailing time if present
+///
+/// # Parameters
+///
+/// - `text`: The raw date text, e.g. `07/14/2025` or `2025-07-14`
+///
+/// # Returns
+///
+/// The date or `None` if the text is empty or not a known format
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text: &str = text.trim();
+    let date: &str = text.split([' ', 'T']).next().unwrap_or(text);
+    DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+}
+
+/// Parses a `GMTTimeDifference` value into a fixed offset
+///
+/// Namecheap formats negative offsets with a doubled sign (`--5:00`), plain
+/// `-5:00`, `+5:30` and unsigned `5:00` values are accepted as well.
+///
+/// # Parameters
+///
+/// - `text`: The raw offset text
+///
+/// # Returns
+///
+/// The offset or `None` if the text is not a valid offset
+pub fn parse_gmt_offset(text: &str) -> Option<FixedOffset> {
+    let text: &str = text.trim();
+    let (negative, rest): (bool, &str) = if let Some(rest) = text.strip_prefix("--") {
+        (true, rest)
+    } else if let Some(rest) = text.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, text.strip_prefix('+').unwrap_or(text))
+    };
+
+    let (hours, minutes): (&str, &str) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+
+    let seconds: i32 = hours * 3600 + minutes * 60;
+    FixedOffset::east_opt(if negative { -seconds } else { seconds })
+}
+
+/// Extracts the server offset from a parsed response envelope
+///
+/// # Parameters
+///
+/// - `response`: The full JSON response, starting at `ApiResponse`
+///
+/// # Returns
+///
+/// The server offset, or UTC if the envelope does not report one
+pub fn server_offset(response: &Value) -> FixedOffset {
+    get_text_at::<String>(response, "/ApiResponse/GMTTimeDifference")
+        .and_then(|text| parse_gmt_offset(&text))
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
+/// Normalizes a server-local date or timestamp to UTC
+///
+/// Dates without a time (`MM/DD/YYYY`) are interpreted as midnight server time, RFC 3339
+/// timestamps keep their own offset.
+///
+/// # Parameters
+///
+/// - `text`: The raw date text, e.g. `07/14/2025` or `2025-07-14T10:30:00`
+/// - `offset`: The server offset taken from the response envelope
+///
+/// # Returns
+///
+/// The UTC timestamp or `None` if the text is empty or not a known format
+pub fn to_utc(text: &str, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    // Timestamps carrying their own offset do not depend on the server clock
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text.trim()) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    let naive: NaiveDateTime = parse_local_datetime(text)?;
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_gmt_offset() {
+        assert_eq!(parse_gmt_offset("--5:00"), FixedOffset::west_opt(5 * 3600));
+        assert_eq!(parse_gmt_offset("-4:00"), FixedOffset::west_opt(4 * 3600));
+        assert_eq!(parse_gmt_offset("+5:30"), FixedOffset::east_opt(5 * 3600 + 30 * 60));
+        assert_eq!(parse_gmt_offset("0:00"), FixedOffset::east_opt(0));
+        assert_eq!(parse_gmt_offset("abc"), None);
+    }
+
+    #[test]
+    fn test_to_utc_applies_server_offset() {
+        let response: Value = json!({ "ApiResponse": { "GMTTimeDifference": { "$text": "--5:00" } } });
+        let offset: FixedOffset = server_offset(&response);
+
+        let expires: DateTime<Utc> = to_utc("07/14/2025", offset).unwrap();
+        assert_eq!(expires.to_rfc3339(), "2025-07-14T05:00:00+00:00");
+
+        let created: DateTime<Utc> = to_utc("2025-07-14T22:30:00", offset).unwrap();
+        assert_eq!(created.to_rfc3339(), "2025-07-15T03:30:00+00:00");
+
+        assert_eq!(to_utc("", offset), None);
+    }
+
+    #[test]
+    fn test_date_formats() {
+        let offset: FixedOffset = FixedOffset::east_opt(0).unwrap();
+        let expected: &str = "2025-07-14T15:30:00+00:00";
+
+        for text in [
+            "07/14/2025 15:30:00",
+            "07/14/2025 3:30:00 PM",
+            "07/14/2025 03:30 PM",
+            "2025-07-14 15:30:00",
+            "2025-07-14T15:30:00.000",
+            "2025-07-14T17:30:00+02:00",
+        ] {
+            assert_eq!(to_utc(text, offset).map(|date| date.to_rfc3339()).as_deref(), Some(expected), "{}", text);
+        }
+
+        assert_eq!(parse_date("2025-07-14"), NaiveDate::from_ymd_opt(2025, 7, 14));
+        assert_eq!(parse_date("07/14/2025 3:30:00 PM"), NaiveDate::from_ymd_opt(2025, 7, 14));
+        assert_eq!(parse_date("14/07/2025"), None);
+        assert_eq!(to_utc("July 14th", offset), None);
+    }
+}
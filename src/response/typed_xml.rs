@@ -0,0 +1,221 @@
+//! This module parses the responses of hot path commands straight from XML into typed structs.
+//!
+//! The generic pipeline converts the whole XML document into a JSON `Value` tree and then
+//! extracts typed values from it. For large responses (`domains.dns.getHosts`,
+//! `domains.getList`) the tree is pure overhead: here the XML is scanned once as a stream and
+//! only the attributes of the wanted elements are kept, one element at a time. Each element is
+//! parsed with the same parser as the generic pipeline, so both paths produce equal results.
+
+use serde_json::{ json, Map, Value };
+use std::collections::HashMap;
+use std::error::Error;
+use chrono::FixedOffset;
+use xml::ParserConfig;
+use xml::reader::{ EventReader, XmlEvent };
+
+// crate imports
+use crate::{ Domain, Host };
+use crate::domains::get_list::parse_domain;
+use crate::domains_dns::get_hosts::parse_host;
+use crate::response::errors::ApiError;
+use crate::response::paging::extract_pagination_info;
+use crate::response::parsed_page::ParsedPage;
+use crate::response::timestamp::parse_gmt_offset;
+use crate::utils::xml_parser::convert_camel_to_snake;
+
+/// The parts of a response kept by [`scan_elements`].
+///
+/// #### Fields
+/// - `elements`: The attributes of every wanted element, in document order, keyed like the JSON pipeline.
+/// - `errors`: The errors reported in the `Errors` section.
+/// - `texts`: The text content of the wanted text elements, keyed by element name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmlScan {
+    pub elements: Vec<Value>,
+    pub errors: Vec<ApiError>,
+    pub texts: HashMap<String, String>,
+}
+
+impl XmlScan {
+    /// Returns the first reported error, if any.
+    pub fn check_errors(&self) -> Result<(), ApiError> {
+        match self.errors.first() {
+            Some(error) => Err(error.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Scans a response, keeping the attributes of every `element` and the text of `text_elements`.
+///
+/// # Parameters
+///
+/// - `xml`: The raw response body
+/// - `element`: The name of the repeated element to collect, e.g. `host`
+/// - `text_elements`: The names of elements whose text is kept, e.g. `TotalItems`
+pub fn scan_elements(xml: &str, element: &str, text_elements: &[&str]) -> Result<XmlScan, String> {
+    let config: ParserConfig = ParserConfig::new()
+        .trim_whitespace(true)
+        .ignore_comments(true)
+        .coalesce_characters(true);
+    let reader: EventReader<&[u8]> = EventReader::new_with_config(xml.as_bytes(), config);
+
+    let mut scan: XmlScan = XmlScan::default();
+    // The element whose text is being read: a wanted text element or an `Error`
+    let mut capturing: Option<String> = None;
+    let mut error_number: String = String::new();
+
+    for event in reader {
+        match event.map_err(|e| format!("XML parsing error: {}", e))? {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                let local_name: &str = name.local_name.as_str();
+                if local_name == element {
+                    let attributes: Map<String, Value> = attributes
+                        .into_iter()
+                        .map(|attr| (convert_camel_to_snake(&attr.name.local_name), Value::String(attr.value)))
+                        .collect();
+                    scan.elements.push(Value::Object(attributes));
+                } else if local_name == "Error" {
+                    error_number = attributes
+                        .into_iter()
+                        .find(|attr| attr.name.local_name == "Number")
+                        .map(|attr| attr.value)
+                        .unwrap_or_default();
+                    capturing = Some(local_name.to_string());
+                } else if text_elements.contains(&local_name) {
+                    capturing = Some(local_name.to_string());
+                }
+            }
+            XmlEvent::Characters(text) => {
+                if let Some(name) = capturing.take() {
+                    if name == "Error" {
+                        scan.errors.push(ApiError::new(&std::mem::take(&mut error_number), &text));
+                    } else {
+                        scan.texts.insert(name, text);
+                    }
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                // An `Error` without text is still an error
+                if capturing.as_deref() == Some("Error") && name.local_name == "Error" {
+                    scan.errors.push(ApiError::new(&std::mem::take(&mut error_number), ""));
+                }
+                capturing = None;
+            }
+            XmlEvent::EndDocument => {
+                return Ok(scan);
+            }
+            _ => {}
+        }
+    }
+
+    Err("Unexpected end of XML document".to_string())
+}
+
+/// Parses a `domains.dns.getHosts` response into `Host`s.
+pub fn parse_hosts_xml(xml: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+    let scan: XmlScan = scan_elements(xml, "host", &[])?;
+    scan.check_errors()?;
+    Ok(scan.elements.iter().map(parse_host).collect())
+}
+
+/// Parses a `domains.getList` response into a page of `Domain`s and the total number of pages.
+pub fn parse_domains_xml(xml: &str) -> Result<(ParsedPage<Domain>, i64), Box<dyn Error>> {
+    let scan: XmlScan = scan_elements(xml, "Domain", &[
+        "GMTTimeDifference",
+        "CurrentPage",
+        "PageSize",
+        "TotalItems",
+    ])?;
+    scan.check_errors()?;
+
+    let offset: FixedOffset = scan.texts
+        .get("GMTTimeDifference")
+        .and_then(|text| parse_gmt_offset(text))
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+
+    let paging: Option<Value> = scan.texts.contains_key("TotalItems").then(|| {
+        let mut paging: Map<String, Value> = Map::new();
+        for key in ["CurrentPage", "PageSize", "TotalItems"] {
+            if let Some(text) = scan.texts.get(key) {
+                paging.insert(key.to_string(), json!({ "$text": text }));
+            }
+        }
+        Value::Object(paging)
+    });
+    let (_, _, _, total_pages) = extract_pagination_info(paging.as_ref());
+
+    let elements: Value = Value::Array(scan.elements);
+    let page: ParsedPage<Domain> = ParsedPage::parse(Some(&elements), |domain| parse_domain(domain, offset));
+    Ok((page, total_pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::parse_xml_to_json;
+    use crate::response::parse_value::get_node_at;
+    use crate::response::timestamp::server_offset;
+
+    const HOSTS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ApiResponse Status="OK" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+  <RequestedCommand>namecheap.domains.dns.getHosts</RequestedCommand>
+  <CommandResponse Type="namecheap.domains.dns.getHosts">
+    <DomainDNSGetHostsResult Domain="example.com" IsUsingOurDNS="true">
+      <host HostId="1" Name="@" Type="A" Address="203.0.113.10" MXPref="10" TTL="1800" IsActive="true" IsDDNSEnabled="false" />
+      <host HostId="2" Name="www" Type="CNAME" Address="example.com." MXPref="10" TTL="60" IsActive="true" IsDDNSEnabled="false" />
+    </DomainDNSGetHostsResult>
+  </CommandResponse>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+</ApiResponse>"#;
+
+    const DOMAINS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ApiResponse Status="OK" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+  <CommandResponse Type="namecheap.domains.getList">
+    <DomainGetListResult>
+      <Domain ID="1" Name="example.com" User="owner" Created="07/14/2024" Expires="07/14/2025" IsExpired="false" IsLocked="false" AutoRenew="true" WhoisGuard="ENABLED" IsPremium="false" IsOurDNS="true" />
+      <Domain ID="2" Name="example.net" User="owner" Created="01/02/2023" Expires="01/02/2026" IsExpired="false" IsLocked="true" AutoRenew="false" WhoisGuard="NOTPRESENT" IsPremium="false" IsOurDNS="false" />
+    </DomainGetListResult>
+    <Paging>
+      <TotalItems>250</TotalItems>
+      <CurrentPage>1</CurrentPage>
+      <PageSize>100</PageSize>
+    </Paging>
+  </CommandResponse>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+</ApiResponse>"#;
+
+    #[test]
+    fn test_direct_parsing_matches_json_pipeline() {
+        let json: Value = parse_xml_to_json(HOSTS).unwrap();
+        let hosts: Vec<Host> = get_node_at(&json, "/ApiResponse/CommandResponse/DomainDNSGetHostsResult/host")
+            .and_then(Value::as_array)
+            .unwrap()
+            .iter()
+            .map(parse_host)
+            .collect();
+        assert_eq!(parse_hosts_xml(HOSTS).unwrap(), hosts);
+
+        let json: Value = parse_xml_to_json(DOMAINS).unwrap();
+        let offset: FixedOffset = server_offset(&json);
+        let expected: ParsedPage<Domain> = ParsedPage::parse(
+            get_node_at(&json, "/ApiResponse/CommandResponse/DomainGetListResult/Domain"),
+            |domain| parse_domain(domain, offset)
+        );
+        let (page, total_pages) = parse_domains_xml(DOMAINS).unwrap();
+        assert_eq!(page, expected);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(total_pages, 3);
+    }
+
+    #[test]
+    fn test_direct_parsing_reports_api_errors() {
+        let xml: &str = r#"<ApiResponse Status="ERROR"><Errors><Error Number="2019166">Domain not found</Error></Errors></ApiResponse>"#;
+        let error: Box<dyn Error> = parse_hosts_xml(xml).unwrap_err();
+        let error: &ApiError = error.downcast_ref::<ApiError>().unwrap();
+        assert_eq!(error.number, "2019166");
+        assert_eq!(error.message, "Domain not found");
+    }
+}
@@ -0,0 +1,155 @@
+//! ### Borrowed Response Views
+//!
+//! Services proxying NameCheap data often parse a response only to serialize it again. The
+//! typed models own their strings, so every record costs one allocation per field. The views
+//! of this module borrow their strings from the parsed response instead: they live as long as
+//! the JSON tree and serialize exactly like the typed model they mirror.
+//!
+//! ```rust
+//! let response: Value = parse_xml_to_json(&body)?;
+//! let hosts: Vec<HostView<'_>> = host_views(&response);
+//! let out: String = serde_json::to_string(&hosts)?;
+//! ```
+//!
+
+use serde::Serialize;
+use serde_json::Value;
+
+// crate imports
+use crate::Host;
+use crate::limits::DEFAULT_TTL;
+use crate::response::parse_value::{ get_attr, get_node_at, get_nodes_at, text_of };
+
+/// ### Host View
+///
+/// A `host` element of a `domains.dns.getHosts` response, borrowed from the parsed response.
+/// Serializes like [`Host`].
+///
+/// #### Fields
+/// - `host_id`: The unique identifier of the record.
+/// - `name`: The host name, e.g. `@` or `www`.
+/// - `address`: The value of the record.
+/// - `type_`: The record type, e.g. `A` or `MX`.
+/// - `is_active`: Whether the record is active.
+/// - `ttl`: The time to live, in seconds.
+/// - `mx_pref`: The MX preference.
+/// - `is_ddns_enabled`: Whether dynamic DNS is enabled for the record.
+/// - `friendly_name`: The friendly name of the record.
+/// - `associated_app_title`: The title of the app the record belongs to.
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct HostView<'a> {
+    pub host_id: &'a str,
+    pub name: &'a str,
+    pub address: &'a str,
+    #[serde(rename = "type")]
+    pub type_: &'a str,
+    #[serde(rename = "is_active")]
+    pub is_active: bool,
+    #[serde(rename = "ttl")]
+    pub ttl: i64,
+    #[serde(rename = "mxpref")]
+    pub mx_pref: &'a str,
+    #[serde(rename = "is_ddnsenabled")]
+    pub is_ddns_enabled: bool,
+    #[serde(rename = "friendly_name")]
+    pub friendly_name: &'a str,
+    #[serde(rename = "associated_app_title")]
+    pub associated_app_title: &'a str,
+}
+
+impl<'a> HostView<'a> {
+    /// Borrows a single `host` element, with the same defaults as the typed parser.
+    pub fn from_value(host: &'a Value) -> Self {
+        let text = |key: &str| host.get(key).and_then(text_of).unwrap_or("");
+        HostView {
+            host_id: text("host_id"),
+            name: text("name"),
+            address: text("address"),
+            type_: text("type"),
+            is_active: host.get("is_active").and_then(text_of).unwrap_or("true") == "true",
+            ttl: get_attr(host, "ttl").unwrap_or(DEFAULT_TTL),
+            mx_pref: text("mxpref"),
+            is_ddns_enabled: text("is_ddnsenabled") == "true",
+            friendly_name: text("friendly_name"),
+            associated_app_title: text("associated_app_title"),
+        }
+    }
+
+    /// Copies the view into an owned `Host`.
+    pub fn to_host(&self) -> Host {
+        Host {
+            host_id: self.host_id.to_string(),
+            name: self.name.to_string(),
+            address: self.address.to_string(),
+            type_: self.type_.to_string(),
+            is_active: self.is_active,
+            ttl: self.ttl,
+            mx_pref: self.mx_pref.to_string(),
+            is_ddns_enabled: self.is_ddns_enabled,
+            friendly_name: self.friendly_name.to_string(),
+            associated_app_title: self.associated_app_title.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a Host> for HostView<'a> {
+    fn from(host: &'a Host) -> Self {
+        HostView {
+            host_id: &host.host_id,
+            name: &host.name,
+            address: &host.address,
+            type_: &host.type_,
+            is_active: host.is_active,
+            ttl: host.ttl,
+            mx_pref: &host.mx_pref,
+            is_ddns_enabled: host.is_ddns_enabled,
+            friendly_name: &host.friendly_name,
+            associated_app_title: &host.associated_app_title,
+        }
+    }
+}
+
+/// Borrows the host records of a `domains.dns.getHosts` response.
+///
+/// Accepts the full response or the `host` node returned by
+/// [`domains_dns_get_hosts`](crate::NameCheapClient::domains_dns_get_hosts).
+pub fn host_views(response: &Value) -> Vec<HostView<'_>> {
+    let hosts: &Value = get_node_at(response, "/ApiResponse/CommandResponse/DomainDNSGetHostsResult/host").unwrap_or(response);
+    get_nodes_at(hosts, "").into_iter().map(HostView::from_value).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains_dns::get_hosts::parse_host;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_host_views() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.domains.dns.getHosts">
+            <DomainDNSGetHostsResult Domain="example.com" IsUsingOurDNS="true">
+              <host HostId="1" Name="@" Type="A" Address="203.0.113.10" MXPref="10" TTL="1800" IsActive="true" IsDDNSEnabled="false" />
+              <host HostId="2" Name="www" Type="CNAME" Address="example.com." TTL="60" />
+            </DomainDNSGetHostsResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let views: Vec<HostView<'_>> = host_views(&response);
+        let hosts: Vec<Host> = get_nodes_at(&response, "/ApiResponse/CommandResponse/DomainDNSGetHostsResult/host")
+            .into_iter()
+            .map(parse_host)
+            .collect();
+
+        assert_eq!(views.len(), 2);
+        assert_eq!((views[1].name, views[1].ttl, views[1].is_active), ("www", 60, true));
+        assert_eq!(serde_json::to_value(&views).unwrap(), serde_json::to_value(&hosts).unwrap());
+        assert_eq!(views.iter().map(HostView::to_host).collect::<Vec<Host>>(), hosts);
+        assert_eq!(HostView::from(&hosts[0]), views[0]);
+
+        let node: &Value = &response["ApiResponse"]["CommandResponse"]["DomainDNSGetHostsResult"]["host"];
+        assert_eq!(host_views(node), views);
+        assert_eq!(host_views(&node[0]).len(), 1);
+    }
+}
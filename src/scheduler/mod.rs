@@ -0,0 +1,498 @@
+//! ## Scheduler
+//! A lightweight tokio-based runner for recurring operations inside a long-running process,
+//! e.g. a drift check every hour, an expiry watch every morning or a DDNS update every five
+//! minutes.
+//!
+//! Each job has a `Schedule`, either a fixed interval or a 5-field cron expression evaluated
+//! in UTC, an optional random jitter added to every run, and overlap protection: a run that
+//! comes due while the previous run of the same job is still going is skipped.
+//!
+//! ### Schedule Syntax
+//! - `*/5 * * * *`: cron fields `minute hour day-of-month month day-of-week`, each `*`, a
+//!   number, a range `a-b`, a list `a,b` or a step `*/n` / `a-b/n`. Day-of-week `0` and `7` are Sunday.
+//! - `@hourly`, `@daily`, `@weekly`, `@monthly`: the usual cron shorthands.
+//! - `@every 30s`: a fixed interval, with `s`, `m`, `h` or `d` units.
+//!
+//! With [`Scheduler::with_shutdown`], no run starts once shutdown begins; runs in progress
+//! complete, their changes are awaited by `Shutdown::shutdown`.
+//!
+//! With [`Scheduler::in_window`], a job only runs inside its maintenance windows; runs coming
+//! due outside them are deferred to the next opening or skipped.
+
+use chrono::{ DateTime, Datelike, Duration as ChronoDuration, Timelike, Utc };
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::Duration;
+use tokio::task::{ JoinHandle, JoinSet };
+use tracing::{ info, warn, error };
+
+// crate imports
+use crate::utils::shutdown::Shutdown;
+use crate::scheduler::window::{ ChangeWindows, OutsideWindow };
+
+/// - **window**
+pub mod window;
+
+/// A 5-field cron expression, stored as the allowed values of each field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CronExpr {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+    // cron matches either day field when both are restricted
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+impl CronExpr {
+    /// Parses a 5-field cron expression.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 cron fields, got {}: {}", fields.len(), expression));
+        }
+
+        let mut days_of_week: Vec<u32> = parse_field(fields[4], 0, 7)?
+            .into_iter()
+            .map(|day| day % 7)
+            .collect();
+        days_of_week.sort_unstable();
+        days_of_week.dedup();
+
+        Ok(CronExpr {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            day_of_month_any: fields[2] == "*",
+            day_of_week_any: fields[4] == "*",
+        })
+    }
+
+    fn matches(&self, time: &DateTime<Utc>) -> bool {
+        let day_of_month: bool = self.days_of_month.contains(&time.day());
+        let day_of_week: bool = self.days_of_week.contains(&time.weekday().num_days_from_sunday());
+        let day: bool = match (self.day_of_month_any, self.day_of_week_any) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        };
+
+        day &&
+            self.minutes.contains(&time.minute()) &&
+            self.hours.contains(&time.hour()) &&
+            self.months.contains(&time.month())
+    }
+
+    /// Returns the first matching minute strictly after `after`, within four years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time: DateTime<Utc> = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let limit: DateTime<Utc> = after + ChronoDuration::days(4 * 366);
+
+        while time < limit {
+            if !self.months.contains(&time.month()) {
+                // Skip to the first minute of the next day
+                time = (time + ChronoDuration::days(1)).with_hour(0)?.with_minute(0)?;
+                continue;
+            }
+            if self.matches(&time) {
+                return Some(time);
+            }
+            time += ChronoDuration::minutes(1);
+        }
+        None
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>, String> {
+    let mut values: Vec<u32> = Vec::new();
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Invalid cron step: {}", part))?;
+                if step == 0 {
+                    return Err(format!("Invalid cron step: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start: u32 = start.parse().map_err(|_| format!("Invalid cron range: {}", part))?;
+            let end: u32 = end.parse().map_err(|_| format!("Invalid cron range: {}", part))?;
+            (start, end)
+        } else {
+            let value: u32 = range.parse().map_err(|_| format!("Invalid cron value: {}", part))?;
+            // `5/15` means "from 5 every 15"
+            if step > 1 { (value, max) } else { (value, value) }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("Cron value out of range {}-{}: {}", min, max, part));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// When a job runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Schedule {
+    /// Every `Duration`, starting one interval after the scheduler starts.
+    Every(Duration),
+    /// At the minutes matching a cron expression, in UTC.
+    Cron(CronExpr),
+}
+
+impl Schedule {
+    /// Parses a cron expression, a cron shorthand or `@every <n><unit>`.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression: &str = expression.trim();
+        match expression {
+            "@hourly" => return Schedule::parse("0 * * * *"),
+            "@daily" | "@midnight" => return Schedule::parse("0 0 * * *"),
+            "@weekly" => return Schedule::parse("0 0 * * 0"),
+            "@monthly" => return Schedule::parse("0 0 1 * *"),
+            _ => {}
+        }
+
+        match expression.strip_prefix("@every") {
+            Some(interval) => parse_interval(interval.trim()).map(Schedule::Every),
+            None => CronExpr::parse(expression).map(Schedule::Cron),
+        }
+    }
+
+    /// Returns the time until the next run after `now`.
+    pub fn delay_from(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self {
+            Schedule::Every(interval) => Some(*interval),
+            Schedule::Cron(cron) => cron.next_after(now).and_then(|next| (next - now).to_std().ok()),
+        }
+    }
+}
+
+/// Parses an interval such as `30s`, `15m`, `6h` or `1d`.
+pub fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let split: usize = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Missing interval unit: {}", interval))?;
+    let (amount, unit) = interval.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| format!("Invalid interval: {}", interval))?;
+
+    let seconds: u64 = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 3600,
+        "d" => amount * 86400,
+        _ => return Err(format!("Invalid interval unit: {}", interval)),
+    };
+    if seconds == 0 {
+        return Err(format!("Interval must be positive: {}", interval));
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type JobOperation = Arc<dyn Fn() -> JobFuture + Send + Sync>;
+
+struct Job {
+    name: String,
+    schedule: Schedule,
+    jitter: Duration,
+    window: Option<(ChangeWindows, OutsideWindow)>,
+    operation: JobOperation,
+}
+
+/// Runs registered jobs on their schedules.
+///
+/// # Example
+///
+/// ```rust
+/// let mut scheduler = Scheduler::new();
+/// let watcher = client.clone();
+/// scheduler.register("expiry-watch", Schedule::parse("0 8 * * *")?, Duration::from_secs(60), move || {
+///     let client = watcher.clone();
+///     async move {
+///         let summary = client.portfolio_summary(false).await.map_err(|e| anyhow::anyhow!(e.to_string()))?;
+///         println!("{} domains expiring soon", summary.expiring_soon);
+///         Ok(())
+///     }
+/// });
+/// let handle = scheduler.start();
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    shutdown: Shutdown,
+}
+
+impl Scheduler {
+    /// Creates an empty scheduler.
+    pub fn new() -> Self {
+        Scheduler::default()
+    }
+
+    /// Stops starting runs once `shutdown` begins.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Registers a job.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The job name, used in logs
+    /// - `schedule`: When the job runs
+    /// - `jitter`: The maximum random delay added to each run, spreading load across jobs
+    /// - `operation`: The async operation to run
+    pub fn register<F, Fut>(&mut self, name: &str, schedule: Schedule, jitter: Duration, operation: F) -> &mut Self
+        where F: Fn() -> Fut + Send + Sync + 'static, Fut: Future<Output = anyhow::Result<()>> + Send + 'static
+    {
+        self.jobs.push(Job {
+            name: name.to_string(),
+            schedule,
+            jitter,
+            window: None,
+            operation: Arc::new(move || Box::pin(operation())),
+        });
+        self
+    }
+
+    /// Restricts the last registered job to `windows`, deferring or skipping runs outside them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let windows = ChangeWindows::new(vec![MaintenanceWindow::parse("0 22 * * 1-5", "4h")?]);
+    /// scheduler.register("apply-zones", Schedule::parse("@hourly")?, Duration::ZERO, apply)
+    ///     .in_window(windows, OutsideWindow::Defer);
+    /// ```
+    pub fn in_window(&mut self, windows: ChangeWindows, outside: OutsideWindow) -> &mut Self {
+        if let Some(job) = self.jobs.last_mut() {
+            job.window = Some((windows, outside));
+        }
+        self
+    }
+
+    /// Returns the names of the registered jobs.
+    pub fn job_names(&self) -> Vec<&str> {
+        self.jobs
+            .iter()
+            .map(|job| job.name.as_str())
+            .collect()
+    }
+
+    /// Starts every job on the tokio runtime.
+    pub fn start(self) -> SchedulerHandle {
+        let tasks: Vec<JoinHandle<()>> = self.jobs
+            .into_iter()
+            .map(|job| tokio::spawn(run_job(job, self.shutdown.clone())))
+            .collect();
+        SchedulerHandle { tasks }
+    }
+}
+
+/// A handle on a started `Scheduler`.
+pub struct SchedulerHandle {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl SchedulerHandle {
+    /// Stops scheduling new runs, runs already in progress are aborted.
+    ///
+    /// The runs of a job belong to its task, aborting the task drops and aborts them too.
+    pub fn abort(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Returns a random delay between zero and `max`.
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let random: u64 = RandomState::new().hash_one(Utc::now().timestamp_nanos_opt());
+    Duration::from_millis(random % (max.as_millis() as u64).max(1))
+}
+
+async fn run_job(job: Job, shutdown: Shutdown) {
+    let running: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    // Aborted with the job task when dropped, see `SchedulerHandle::abort`
+    let mut runs: JoinSet<()> = JoinSet::new();
+
+    loop {
+        while runs.try_join_next().is_some() {}
+
+        let Some(delay) = job.schedule.delay_from(Utc::now()) else {
+            warn!("Job {} has no upcoming run, stopping", job.name);
+            break;
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(delay + jitter(job.jitter)) => {}
+            _ = shutdown.cancelled() => {
+                info!("Job {} stopped by shutdown", job.name);
+                break;
+            }
+        }
+
+        if let Some((windows, outside)) = &job.window && !windows.is_open(Utc::now()) {
+            let delay: Option<Duration> = windows.delay_from(Utc::now());
+            match (outside, delay) {
+                (OutsideWindow::Defer, Some(delay)) => {
+                    info!("Job {} is outside its maintenance windows, deferred by {:?}", job.name, delay);
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = shutdown.cancelled() => {
+                            info!("Job {} stopped by shutdown", job.name);
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    info!("Job {} is outside its maintenance windows, skipping this run", job.name);
+                    continue;
+                }
+            }
+        }
+
+        if running.swap(true, Ordering::SeqCst) {
+            warn!("Job {} is still running, skipping this run", job.name);
+            continue;
+        }
+
+        let name: String = job.name.clone();
+        let operation: JobOperation = job.operation.clone();
+        let running: Arc<AtomicBool> = running.clone();
+        runs.spawn(async move {
+            info!("Running job {}", name);
+            if let Err(err) = operation().await {
+                error!("Job {} failed: {}", name, err);
+            }
+            running.store(false, Ordering::SeqCst);
+        });
+    }
+
+    // Runs in progress complete when the job stops on its own or by shutdown
+    runs.detach_all();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_cron_next_after() {
+        let now: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 1, 10, 7, 30).unwrap();
+
+        let every_five = CronExpr::parse("*/5 * * * *").unwrap();
+        assert_eq!(every_five.next_after(now).unwrap(), Utc.with_ymd_and_hms(2024, 1, 1, 10, 10, 0).unwrap());
+
+        let weekday_morning = CronExpr::parse("30 8 * * 1-5").unwrap();
+        // 2024-01-01 is a Monday
+        assert_eq!(
+            weekday_morning.next_after(now).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 8, 30, 0).unwrap()
+        );
+
+        let first_of_march = CronExpr::parse("0 0 1 3 *").unwrap();
+        assert_eq!(first_of_march.next_after(now).unwrap(), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_schedule_parse() {
+        assert_eq!(Schedule::parse("@every 5m").unwrap(), Schedule::Every(Duration::from_secs(300)));
+        assert_eq!(Schedule::parse("@daily").unwrap(), Schedule::parse("0 0 * * *").unwrap());
+        assert!(Schedule::parse("61 * * * *").is_err());
+        assert!(Schedule::parse("* * *").is_err());
+        assert!(Schedule::parse("@every 0s").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_skips_overlapping_runs() {
+        let runs: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let counter: Arc<AtomicUsize> = runs.clone();
+
+        let mut scheduler: Scheduler = Scheduler::new();
+        scheduler.register("slow", Schedule::Every(Duration::from_millis(10)), Duration::ZERO, move || {
+            let counter: Arc<AtomicUsize> = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok(())
+            }
+        });
+
+        let handle: SchedulerHandle = scheduler.start();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.abort();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_abort_stops_runs_in_progress() {
+        let finished: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let flag: Arc<AtomicBool> = finished.clone();
+
+        let mut scheduler: Scheduler = Scheduler::new();
+        scheduler.register("slow", Schedule::Every(Duration::from_millis(10)), Duration::ZERO, move || {
+            let flag: Arc<AtomicBool> = flag.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                flag.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        let handle: SchedulerHandle = scheduler.start();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        handle.abort();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_stops_on_shutdown() {
+        let runs: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let shutdown: Shutdown = Shutdown::new();
+        let mut scheduler: Scheduler = Scheduler::new().with_shutdown(shutdown.clone());
+        let counter: Arc<AtomicUsize> = runs.clone();
+        scheduler.register("tick", Schedule::Every(Duration::from_millis(10)), Duration::ZERO, move || {
+            let counter: Arc<AtomicUsize> = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        let _handle: SchedulerHandle = scheduler.start();
+
+        tokio::time::sleep(Duration::from_millis(35)).await;
+        assert!(shutdown.shutdown(Duration::from_millis(10)).await);
+        let stopped_at: usize = runs.load(Ordering::SeqCst);
+        assert!(stopped_at > 0);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+    }
+}
@@ -0,0 +1,156 @@
+//! ### Maintenance Windows
+//!
+//! Organisations with change-management rules only allow changes at agreed times. A
+//! `MaintenanceWindow` opens at the minutes matching a cron expression (UTC) and stays open
+//! for a fixed length, e.g. weeknights from 22:00 for four hours:
+//!
+//! ```rust
+//! let windows = ChangeWindows::new(vec![MaintenanceWindow::parse("0 22 * * 1-5", "4h")?]);
+//! ```
+//!
+//! `ChangeWindows` is a [`Policy`]: attached with `with_policy`, every mutation outside the
+//! windows is rejected. Scheduled jobs restricted with [`Scheduler::in_window`] are deferred
+//! to the next opening instead, or skipped.
+//!
+//! [`Scheduler::in_window`]: crate::scheduler::Scheduler::in_window
+
+use chrono::{ DateTime, Duration as ChronoDuration, Utc };
+use std::time::Duration;
+
+// crate imports
+use crate::policy::{ Mutation, Policy, PolicyViolation };
+use crate::scheduler::{ parse_interval, CronExpr, Schedule };
+
+/// A recurring window during which changes are allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MaintenanceWindow {
+    pub opens: CronExpr,
+    pub length: Duration,
+}
+
+impl MaintenanceWindow {
+    /// Creates a window opening at the minutes matching `opens` and lasting `length`.
+    pub fn new(opens: CronExpr, length: Duration) -> Self {
+        MaintenanceWindow { opens, length }
+    }
+
+    /// Parses a window from a cron expression or shorthand and a length such as `4h`.
+    pub fn parse(opens: &str, length: &str) -> Result<Self, String> {
+        let Schedule::Cron(opens) = Schedule::parse(opens)? else {
+            return Err(format!("A maintenance window opens on a cron expression, not an interval: {}", opens));
+        };
+        Ok(MaintenanceWindow::new(opens, parse_interval(length)?))
+    }
+
+    /// Returns `true` if the window is open at `time`.
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.current_opening(time).is_some()
+    }
+
+    /// Returns the opening of the window containing `time`, if any.
+    fn current_opening(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let length: ChronoDuration = ChronoDuration::from_std(self.length).ok()?;
+        // The first opening after `time - length` is open at `time` unless it is still ahead
+        self.opens.next_after(time - length).filter(|opening| *opening <= time)
+    }
+
+    /// Returns `time` if the window is open then, otherwise its next opening.
+    pub fn next_open(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.contains(time) { Some(time) } else { self.opens.next_after(time) }
+    }
+}
+
+/// The windows during which changes are allowed; changes are allowed in any of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChangeWindows(Vec<MaintenanceWindow>);
+
+impl ChangeWindows {
+    /// Creates the set of allowed windows.
+    pub fn new(windows: Vec<MaintenanceWindow>) -> Self {
+        ChangeWindows(windows)
+    }
+
+    /// Returns `true` if any window is open at `time`.
+    pub fn is_open(&self, time: DateTime<Utc>) -> bool {
+        self.0.iter().any(|window| window.contains(time))
+    }
+
+    /// Returns `time` if a window is open then, otherwise the earliest next opening.
+    pub fn next_open(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.0.iter().filter_map(|window| window.next_open(time)).min()
+    }
+
+    /// Returns the time until a window is open, zero if one is open at `time`.
+    pub fn delay_from(&self, time: DateTime<Utc>) -> Option<Duration> {
+        self.next_open(time).map(|open| (open - time).to_std().unwrap_or_default())
+    }
+}
+
+impl Policy for ChangeWindows {
+    fn name(&self) -> &str {
+        "maintenance-window"
+    }
+
+    fn check(&self, mutation: &Mutation) -> Vec<PolicyViolation> {
+        let now: DateTime<Utc> = Utc::now();
+        if self.is_open(now) {
+            return Vec::new();
+        }
+        let reason: String = match self.next_open(now) {
+            Some(open) => format!("Outside the maintenance windows, the next one opens at {}", open.to_rfc3339()),
+            None => "Outside the maintenance windows, none opens again".to_string(),
+        };
+        vec![PolicyViolation::new(self.name(), mutation, reason)]
+    }
+}
+
+/// What a scheduled job does when it comes due outside its windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutsideWindow {
+    /// Waits for the next window, then runs.
+    Defer,
+    /// Skips the run.
+    Skip,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // 2024-01-01 is a Monday
+        Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let window: MaintenanceWindow = MaintenanceWindow::parse("0 22 * * 1-5", "4h").unwrap();
+
+        assert!(window.contains(at(1, 22, 0)));
+        assert!(window.contains(at(2, 1, 59)));
+        assert!(!window.contains(at(2, 2, 0)));
+        assert!(!window.contains(at(1, 21, 59)));
+        // Saturday night is not a weeknight
+        assert!(!window.contains(at(6, 23, 0)));
+        assert_eq!(window.next_open(at(6, 23, 0)), Some(at(8, 22, 0)));
+        assert!(MaintenanceWindow::parse("@every 1h", "4h").is_err());
+    }
+
+    #[test]
+    fn test_change_windows() {
+        let windows: ChangeWindows = ChangeWindows::new(vec![
+            MaintenanceWindow::parse("0 22 * * *", "2h").unwrap(),
+            MaintenanceWindow::parse("0 12 * * 0", "1h").unwrap(),
+        ]);
+
+        assert!(windows.is_open(at(7, 12, 30)));
+        assert_eq!(windows.delay_from(at(7, 12, 30)), Some(Duration::ZERO));
+        assert_eq!(windows.next_open(at(7, 13, 0)), Some(at(7, 22, 0)));
+        assert_eq!(windows.delay_from(at(7, 21, 0)), Some(Duration::from_secs(3600)));
+
+        let never: ChangeWindows = ChangeWindows::new(Vec::new());
+        let violations: Vec<PolicyViolation> = never.check(&Mutation::Contacts { domain: "example.com".to_string() });
+        assert_eq!(violations[0].reason, "Outside the maintenance windows, none opens again");
+    }
+}
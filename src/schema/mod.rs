@@ -0,0 +1,59 @@
+//! ### JSON Schema
+//!
+//! Available with the `schemars` feature. This module exports JSON Schemas of the public
+//! models, so consumers of the crate's JSON output in other languages can validate payloads
+//! and generate types from them.
+//!
+//! The schemas follow the active wire format, so a build with the `snake_case` feature
+//! produces snake_case property names.
+
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use serde_json::{ Map, Value };
+
+// crate imports
+use crate::{ Contact, ContactSet, Domain, Host, WhoisGuardStatus };
+use crate::domains_dns::set_hosts::HostRequest;
+
+/// Returns the schema of every exported model, keyed by type name.
+pub fn schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("Domain", schema_for!(Domain)),
+        ("WhoisGuardStatus", schema_for!(WhoisGuardStatus)),
+        ("Contact", schema_for!(Contact)),
+        ("ContactSet", schema_for!(ContactSet)),
+        ("Host", schema_for!(Host)),
+        ("HostRequest", schema_for!(HostRequest))
+    ]
+}
+
+/// Returns the schemas of [`schemas`] as a single JSON object, keyed by type name.
+///
+/// # Example
+///
+/// ```rust
+/// std::fs::write("namecheap.schema.json", serde_json::to_string_pretty(&namecheap::schema::schemas_json())?)?;
+/// ```
+pub fn schemas_json() -> Value {
+    let schemas: Map<String, Value> = schemas()
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), serde_json::to_value(schema).unwrap_or_default()))
+        .collect();
+    Value::Object(schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas_json() {
+        let schemas: Value = schemas_json();
+
+        assert_eq!(schemas["Domain"]["title"], "Domain");
+        assert!(schemas["Domain"]["properties"]["name"].is_object());
+        assert!(schemas["Host"]["properties"]["type"].is_object());
+        assert!(schemas["ContactSet"]["definitions"]["Contact"].is_object());
+        assert!(schemas["HostRequest"]["required"].as_array().is_some_and(|required| !required.is_empty()));
+    }
+}
@@ -0,0 +1,289 @@
+//! ### Local HTTP Daemon
+//!
+//! Available with the `serve` feature. NameCheap only accepts calls from whitelisted IPs and
+//! throttles each account, so running one client per internal tool multiplies both problems.
+//! This module runs a small HTTP facade backed by a single client, so tools share one
+//! whitelisted IP, one rate limit budget and one cache.
+//!
+//! #### Routes
+//! - `GET /domains`: Every domain of the account, as `Domain`s.
+//! - `GET /zones/{domain}`: The host records of a domain, as `Host`s.
+//! - `POST /zones/{domain}/records`: Ensures the `HostRequest`s of the body exist in the zone,
+//!   leaving other records alone, and returns the applied `ZonePlan`.
+//! - `GET /healthz`: `200` while the process is up, for liveness probes.
+//! - `GET /readyz`: `200` once a cheap API call succeeds, `503` otherwise, for readiness probes.
+//!   This checks the credentials, the API endpoint and the IP whitelist at once. The outcome is
+//!   reused for `READY_CACHE_TTL` so frequent probes do not eat the rate limit budget.
+//!
+//! Reads are cached for `cache_ttl`, every API call goes through the same `RateLimiter`.
+//! Errors are returned as `{"error": "<message>"}` with status 502.
+//!
+//! The daemon has no authentication, bind it to a loopback or private address.
+
+use axum::extract::{ Path, State };
+use axum::http::StatusCode;
+use axum::response::{ IntoResponse, Response };
+use axum::routing::{ get, post };
+use axum::{ Json, Router };
+use serde_json::json;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, Domain, Host };
+use crate::domains::get_list::DEFAULT_PAGE_CONCURRENCY;
+use crate::domains_dns::set_hosts::HostRequest;
+#[cfg(feature = "grpc")]
+use crate::domains::check::DomainCheckResult;
+use crate::utils::rate_limit::RateLimiter;
+use crate::zone::diff::ZonePlan;
+use crate::zone::reverse::ReverseLookup;
+#[cfg(feature = "grpc")]
+use crate::zone::split_domain;
+
+/// The state shared by every request of the daemon.
+pub struct ServeState {
+    client: NameCheapClient,
+    zones: ReverseLookup,
+    cache_ttl: Duration,
+    domains: Mutex<Option<(Instant, Vec<Domain>)>>,
+    ready: Mutex<Option<(Instant, Result<(), String>)>>,
+}
+
+/// How long the outcome of a readiness check is reused.
+pub const READY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+impl ServeState {
+    /// Creates the state with the default rate limit and a 5 minute cache.
+    pub fn new(client: NameCheapClient) -> Self {
+        ServeState {
+            zones: ReverseLookup::new(client.clone()),
+            client,
+            cache_ttl: ReverseLookup::DEFAULT_CACHE_TTL,
+            domains: Mutex::new(None),
+            ready: Mutex::new(None),
+        }
+    }
+
+    /// Sets the rate limiter shared by every API call of the daemon.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.zones = self.zones.with_rate_limiter(limiter);
+        self
+    }
+
+    /// Sets how long domain lists and zones are reused.
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.zones = self.zones.with_cache_ttl(cache_ttl);
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// Returns every domain of the account, from the cache when fresh.
+    pub(crate) async fn domains(&self) -> Result<Vec<Domain>, String> {
+        let mut cached = self.domains.lock().await;
+        if let Some((fetched_at, domains)) = cached.as_ref() && fetched_at.elapsed() < self.cache_ttl {
+            return Ok(domains.clone());
+        }
+
+        let domains: Vec<Domain> = self.client
+            .domains_get_list_all_with(self.zones.limiter(), DEFAULT_PAGE_CONCURRENCY).await
+            .map_err(|e| e.to_string())?;
+        *cached = Some((Instant::now(), domains.clone()));
+        Ok(domains)
+    }
+
+    /// Checks that the API accepts calls from this daemon, from the cache when fresh.
+    ///
+    /// See `NameCheapClient::validate`, which fails on invalid credentials, an unreachable
+    /// endpoint or a client IP missing from the whitelist.
+    pub(crate) async fn ready(&self) -> Result<(), String> {
+        let mut cached = self.ready.lock().await;
+        if let Some((checked_at, ready)) = cached.as_ref() && checked_at.elapsed() < READY_CACHE_TTL {
+            return ready.clone();
+        }
+
+        self.zones.limiter().acquire().await;
+        let ready: Result<(), String> = match self.client.validate().await.failure {
+            Some(failure) => Err(failure.to_string()),
+            None => Ok(()),
+        };
+        *cached = Some((Instant::now(), ready.clone()));
+        ready
+    }
+
+    /// Returns the host records of a domain, from the cache when fresh.
+    pub(crate) async fn zone(&self, domain: &str) -> Result<Vec<Host>, String> {
+        self.zones.host_records(domain).await.map_err(|e| e.to_string())
+    }
+
+    /// Ensures records exist in a zone and drops its cached records.
+    pub(crate) async fn ensure_records(&self, domain: &str, records: &[HostRequest]) -> Result<ZonePlan, String> {
+        self.zones.limiter().acquire().await;
+        let plan: Result<ZonePlan, String> = self.client
+            .ensure_records(domain, records).await
+            .map_err(|e| e.to_string());
+        self.zones.invalidate(domain).await;
+        plan
+    }
+
+    /// Checks the availability of domains, see `NameCheapClient::domains_check`.
+    #[cfg(feature = "grpc")]
+    pub(crate) async fn check_availability(&self, domains: &[String]) -> Result<Vec<DomainCheckResult>, String> {
+        self.zones.limiter().acquire().await;
+        self.client.domains_check(domains).await.map_err(|e| e.to_string())
+    }
+
+    /// Replaces the records of a zone, only if it still has `expected_hash` when one is given.
+    ///
+    /// With `dry_run` the plan is computed against the live zone but not submitted.
+    #[cfg(feature = "grpc")]
+    pub(crate) async fn apply_zone(
+        &self,
+        domain: &str,
+        records: &[HostRequest],
+        expected_hash: Option<&str>,
+        dry_run: bool
+    ) -> Result<ZonePlan, String> {
+        self.zones.limiter().acquire().await;
+        let client: NameCheapClient = self.client.clone().with_dry_run(self.client.dry_run || dry_run);
+        let plan: Result<ZonePlan, String> = match expected_hash {
+            Some(hash) => client.set_hosts_if_unchanged(domain, hash, records).await.map_err(|e| e.to_string()),
+            None => replace_zone(&client, domain, records).await.map_err(|e| e.to_string()),
+        };
+        self.zones.invalidate(domain).await;
+        plan
+    }
+}
+
+/// Replaces the records of a zone with `records`, submitting only when something changes.
+#[cfg(feature = "grpc")]
+async fn replace_zone(
+    client: &NameCheapClient,
+    domain: &str,
+    records: &[HostRequest]
+) -> Result<ZonePlan, Box<dyn Error>> {
+    let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+    let _lock = client.lock_domain(domain).await;
+    let current: Vec<Host> = client.domains_dns_get_host_records(sld, tld).await?;
+    let plan: ZonePlan = ZonePlan::new(domain, &current, records);
+    if plan.has_changes() {
+        client.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+    }
+    Ok(plan)
+}
+
+/// Converts a result into a JSON response, errors become `502 Bad Gateway`.
+fn respond<T: serde::Serialize>(result: Result<T, String>) -> Response {
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(error) => (StatusCode::BAD_GATEWAY, Json(json!({ "error": error }))).into_response(),
+    }
+}
+
+async fn get_healthz() -> Response {
+    Json(json!({ "status": "ok" })).into_response()
+}
+
+async fn get_readyz(State(state): State<Arc<ServeState>>) -> Response {
+    match state.ready().await {
+        Ok(()) => Json(json!({ "status": "ready" })).into_response(),
+        Err(error) =>
+            (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "status": "unavailable", "error": error }))).into_response(),
+    }
+}
+
+async fn get_domains(State(state): State<Arc<ServeState>>) -> Response {
+    respond(state.domains().await)
+}
+
+async fn get_zone(State(state): State<Arc<ServeState>>, Path(domain): Path<String>) -> Response {
+    respond(state.zone(&domain).await)
+}
+
+async fn post_records(
+    State(state): State<Arc<ServeState>>,
+    Path(domain): Path<String>,
+    Json(records): Json<Vec<HostRequest>>
+) -> Response {
+    respond(state.ensure_records(&domain, &records).await)
+}
+
+/// Returns the router of the daemon, to be served or nested in another application.
+pub fn router(state: Arc<ServeState>) -> Router {
+    Router::new()
+        .route("/healthz", get(get_healthz))
+        .route("/readyz", get(get_readyz))
+        .route("/domains", get(get_domains))
+        .route("/zones/{domain}", get(get_zone))
+        .route("/zones/{domain}/records", post(post_records))
+        .with_state(state)
+}
+
+/// Serves the daemon on `addr` until the process stops.
+///
+/// # Example
+///
+/// ```rust
+/// let client = NameCheapClient::new_from_env()?;
+/// namecheap::serve::serve(ServeState::new(client), "127.0.0.1:8053".parse()?).await?;
+/// ```
+pub async fn serve(state: ServeState, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    let listener: tokio::net::TcpListener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Serving the NameCheap API facade on {}", listener.local_addr()?);
+    axum::serve(listener, router(Arc::new(state))).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_reports_errors_as_json() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let state: ServeState = ServeState::new(client).with_rate_limiter(RateLimiter::new(Duration::ZERO));
+        let listener: tokio::net::TcpListener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(Arc::new(state))).await });
+
+        let response: reqwest::Response = reqwest::get(format!("http://{}/zones/invalid", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_GATEWAY);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "Invalid domain: invalid");
+
+        let response: reqwest::Response = reqwest::get(format!("http://{}/healthz", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_reports_rejected_credentials() {
+        // Rejected by the API, or unreachable when offline
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let state: ServeState = ServeState::new(client).with_rate_limiter(RateLimiter::new(Duration::ZERO));
+        let listener: tokio::net::TcpListener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(Arc::new(state))).await });
+
+        let response: reqwest::Response = reqwest::get(format!("http://{}/readyz", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "unavailable");
+    }
+}
@@ -0,0 +1,480 @@
+//! ## Simulation
+//! A `SimulatedTransport` answers requests like the NameCheap API would, without any network
+//! call, so applications embedding the client can load-test their orchestration logic (retries,
+//! cooldowns, read-after-write assumptions) against realistic behavior:
+//! - Latency: every call takes a duration drawn from a [`Latency`] distribution.
+//! - Rate limits: calls above `calls_per_minute` in any sliding minute fail with the
+//!   `Too many requests` error (`500000`) the API returns.
+//! - Eventual consistency: records written with `domains.dns.setHosts` are only returned by
+//!   `domains.dns.getHosts` once `propagation_delay` has passed, earlier reads see the
+//!   previous records.
+//!
+//! Simulated commands are `domains.getList`, `domains.dns.getHosts` and
+//! `domains.dns.setHosts`, other commands fail with an API error. Randomness comes from
+//! `seed`, so a run can be replayed.
+//!
+//! # Example
+//!
+//! ```rust
+//! let simulation = Arc::new(SimulatedTransport::new(SimulationConfig::default())
+//!     .with_zone("example.com", &records));
+//! let client = NameCheapClient::new_from_env()?.with_transport(simulation.clone());
+//! run_orchestration(&client).await?;
+//! println!("{:?}", simulation.stats());
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use std::collections::{ BTreeMap, VecDeque };
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+// crate imports
+use crate::limits::MAX_CALLS_PER_MINUTE;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::transport::{ Transport, TransportFuture, TransportResponse };
+
+/// A distribution of call latencies.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq)]
+pub enum Latency {
+    /// Every call takes the same time.
+    Fixed(Duration),
+    /// Calls take between `min` and `max`, evenly spread.
+    Uniform { min: Duration, max: Duration },
+    /// Calls take up to `typical`, except a `tail_ratio` share taking between `typical` and
+    /// `tail`, like the slow calls of the real API.
+    LongTail { typical: Duration, tail: Duration, tail_ratio: f64 },
+}
+
+impl Default for Latency {
+    fn default() -> Self {
+        Latency::LongTail {
+            typical: Duration::from_millis(400),
+            tail: Duration::from_secs(3),
+            tail_ratio: 0.02,
+        }
+    }
+}
+
+impl Latency {
+    /// Draws a latency with the random number generator `rng`.
+    fn sample(&self, rng: &mut Rng) -> Duration {
+        match *self {
+            Latency::Fixed(latency) => latency,
+            Latency::Uniform { min, max } => between(rng, min, max),
+            Latency::LongTail { typical, tail, tail_ratio } => {
+                if rng.next_f64() < tail_ratio {
+                    between(rng, typical, tail)
+                } else {
+                    between(rng, Duration::ZERO, typical)
+                }
+            }
+        }
+    }
+}
+
+/// Returns a duration between `min` and `max`.
+fn between(rng: &mut Rng, min: Duration, max: Duration) -> Duration {
+    min + max.saturating_sub(min).mul_f64(rng.next_f64())
+}
+
+/// A small deterministic random number generator (SplitMix64).
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z: u64 = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// ### Simulation Config
+///
+/// #### Fields
+/// - `latency`: The distribution of call latencies.
+/// - `calls_per_minute`: The calls allowed in any sliding minute, `None` for no limit.
+/// - `propagation_delay`: How long `getHosts` keeps returning the previous records after a `setHosts`.
+/// - `seed`: The seed of the random latencies.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq)]
+pub struct SimulationConfig {
+    pub latency: Latency,
+    pub calls_per_minute: Option<u32>,
+    pub propagation_delay: Duration,
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            latency: Latency::default(),
+            calls_per_minute: Some(MAX_CALLS_PER_MINUTE),
+            propagation_delay: Duration::from_secs(5),
+            seed: 0,
+        }
+    }
+}
+
+/// ### Simulation Stats
+///
+/// #### Fields
+/// - `requests`: The number of requests received.
+/// - `throttled`: The number of requests refused by the rate limit.
+/// - `stale_reads`: The number of `getHosts` calls answered with records about to be replaced.
+/// - `writes`: The number of accepted `setHosts` calls.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SimulationStats {
+    pub requests: u64,
+    pub throttled: u64,
+    pub stale_reads: u64,
+    pub writes: u64,
+}
+
+/// A record of a simulated zone.
+#[derive(Debug, Clone)]
+struct SimulatedHost {
+    host_id: u64,
+    name: String,
+    type_: String,
+    address: String,
+    mx_pref: String,
+    ttl: String,
+}
+
+/// The records of a simulated zone, and the write not visible yet.
+#[derive(Debug, Default)]
+struct Zone {
+    visible: Vec<SimulatedHost>,
+    pending: Option<(Vec<SimulatedHost>, Instant)>,
+}
+
+impl Zone {
+    /// Makes the pending write visible once its time has come.
+    fn settle(&mut self, now: Instant) {
+        if let Some((_, visible_at)) = &self.pending && *visible_at <= now
+            && let Some((hosts, _)) = self.pending.take()
+        {
+            self.visible = hosts;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SimulationState {
+    rng: Rng,
+    calls: VecDeque<Instant>,
+    zones: BTreeMap<String, Zone>,
+    next_host_id: u64,
+    stats: SimulationStats,
+}
+
+impl SimulationState {
+    fn hosts(&mut self, hosts: impl IntoIterator<Item = HostRequest>) -> Vec<SimulatedHost> {
+        hosts
+            .into_iter()
+            .map(|host| {
+                self.next_host_id += 1;
+                SimulatedHost {
+                    host_id: self.next_host_id,
+                    name: host.host_name,
+                    type_: host.record_type.to_uppercase(),
+                    address: host.address,
+                    mx_pref: host.mx_pref.unwrap_or_else(|| "10".to_string()),
+                    ttl: host.ttl.unwrap_or_else(|| "1800".to_string()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A `Transport` emulating the NameCheap API, see the [module documentation](self).
+#[derive(Debug)]
+pub struct SimulatedTransport {
+    config: SimulationConfig,
+    state: Mutex<SimulationState>,
+}
+
+impl SimulatedTransport {
+    /// Creates a simulation without any domain.
+    pub fn new(config: SimulationConfig) -> Self {
+        let rng: Rng = Rng(config.seed);
+        SimulatedTransport {
+            config,
+            state: Mutex::new(SimulationState {
+                rng,
+                calls: VecDeque::new(),
+                zones: BTreeMap::new(),
+                next_host_id: 0,
+                stats: SimulationStats::default(),
+            }),
+        }
+    }
+
+    /// Adds `domain` to the account, its zone holding `hosts`.
+    pub fn with_zone(self, domain: &str, hosts: &[HostRequest]) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            let visible: Vec<SimulatedHost> = state.hosts(hosts.iter().cloned());
+            state.zones.insert(domain.to_lowercase(), Zone { visible, pending: None });
+        }
+        self
+    }
+
+    /// Returns the counters of the simulation so far.
+    pub fn stats(&self) -> SimulationStats {
+        self.state.lock().map(|state| state.stats.clone()).unwrap_or_default()
+    }
+
+    /// Counts a call against the rate limit, returns `false` if it is refused.
+    fn admit(&self, state: &mut SimulationState, now: Instant) -> bool {
+        let Some(limit) = self.config.calls_per_minute else { return true };
+        while state.calls.front().is_some_and(|call| now.duration_since(*call) >= Duration::from_secs(60)) {
+            state.calls.pop_front();
+        }
+        if state.calls.len() >= limit as usize {
+            return false;
+        }
+        state.calls.push_back(now);
+        true
+    }
+
+    /// Answers a request once its latency has passed.
+    async fn respond(&self, url: &str) -> TransportResponse {
+        let params: BTreeMap<String, String> = url
+            .split_once('?')
+            .map(|(_, query)| form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+            .unwrap_or_default();
+        let command: &str = params.get("Command").map(String::as_str).unwrap_or("");
+
+        let (admitted, latency) = {
+            let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.stats.requests += 1;
+            let admitted: bool = self.admit(&mut state, Instant::now());
+            if !admitted {
+                state.stats.throttled += 1;
+            }
+            let latency: Duration = self.config.latency.sample(&mut state.rng);
+            (admitted, latency)
+        };
+        tokio::time::sleep(latency).await;
+
+        if !admitted {
+            return TransportResponse::ok(error_response(command, "500000", "Too many requests"));
+        }
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let body: String = match command.trim_start_matches("namecheap.") {
+            "domains.getList" => get_list(&state, &params),
+            "domains.dns.getHosts" => get_hosts(&mut state, &params),
+            "domains.dns.setHosts" => set_hosts(&mut state, &params, self.config.propagation_delay),
+            _ => error_response(command, "3050900", "Command is not simulated"),
+        };
+        TransportResponse::ok(body)
+    }
+}
+
+impl Transport for SimulatedTransport {
+    fn get<'a>(&'a self, url: &'a str) -> TransportFuture<'a> {
+        Box::pin(async move { Ok(self.respond(url).await) })
+    }
+}
+
+/// Escapes a value for an XML attribute.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Wraps the command response in the API envelope.
+fn envelope(command: &str, status: &str, errors: &str, response: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><ApiResponse Status="{status}" xmlns="http://api.namecheap.com/xml.response"><Errors>{errors}</Errors><Warnings /><RequestedCommand>{command}</RequestedCommand>{response}<Server>SIMULATION</Server><GMTTimeDifference>--5:00</GMTTimeDifference><ExecutionTime>0.01</ExecutionTime></ApiResponse>"#,
+        command = escape(command),
+    )
+}
+
+fn ok_response(command: &str, result: &str) -> String {
+    let response: String = format!(r#"<CommandResponse Type="{}">{}</CommandResponse>"#, escape(command), result);
+    envelope(command, "OK", "", &response)
+}
+
+fn error_response(command: &str, number: &str, message: &str) -> String {
+    envelope(command, "ERROR", &format!(r#"<Error Number="{}">{}</Error>"#, number, escape(message)), "")
+}
+
+/// Returns the domain named by the `SLD` and `TLD` parameters.
+fn zone_name(params: &BTreeMap<String, String>) -> String {
+    let part = |key: &str| params.get(key).map(|value| value.to_lowercase()).unwrap_or_default();
+    format!("{}.{}", part("SLD"), part("TLD"))
+}
+
+fn get_list(state: &SimulationState, params: &BTreeMap<String, String>) -> String {
+    let command: &str = "namecheap.domains.getList";
+    let page_size: usize = params.get("PageSize").and_then(|size| size.parse().ok()).unwrap_or(20).max(1);
+    let page: usize = params.get("Page").and_then(|page| page.parse().ok()).unwrap_or(1).max(1);
+
+    let domains: String = state.zones
+        .keys()
+        .enumerate()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .map(|(index, name)| format!(
+            r#"<Domain ID="{}" Name="{}" User="simulation" Created="01/01/2024" Expires="01/01/2030" IsExpired="false" IsLocked="false" AutoRenew="true" WhoisGuard="ENABLED" IsPremium="false" IsOurDNS="true" />"#,
+            index + 1,
+            escape(name)
+        ))
+        .collect();
+    ok_response(command, &format!(
+        "<DomainGetListResult>{}</DomainGetListResult><Paging><TotalItems>{}</TotalItems><CurrentPage>{}</CurrentPage><PageSize>{}</PageSize></Paging>",
+        domains,
+        state.zones.len(),
+        page,
+        page_size
+    ))
+}
+
+fn get_hosts(state: &mut SimulationState, params: &BTreeMap<String, String>) -> String {
+    let command: &str = "namecheap.domains.dns.getHosts";
+    let domain: String = zone_name(params);
+    let Some(zone) = state.zones.get_mut(&domain) else {
+        return error_response(command, "2019166", "Domain not found");
+    };
+
+    zone.settle(Instant::now());
+    let stale: bool = zone.pending.is_some();
+    let hosts: String = zone.visible
+        .iter()
+        .map(|host| format!(
+            r#"<host HostId="{}" Name="{}" Type="{}" Address="{}" MXPref="{}" TTL="{}" AssociatedAppTitle="" FriendlyName="" IsActive="true" IsDDNSEnabled="false" />"#,
+            host.host_id,
+            escape(&host.name),
+            escape(&host.type_),
+            escape(&host.address),
+            escape(&host.mx_pref),
+            escape(&host.ttl)
+        ))
+        .collect();
+    if stale {
+        state.stats.stale_reads += 1;
+    }
+    ok_response(command, &format!(
+        r#"<DomainDNSGetHostsResult Domain="{}" IsUsingOurDNS="true">{}</DomainDNSGetHostsResult>"#,
+        escape(&domain),
+        hosts
+    ))
+}
+
+fn set_hosts(state: &mut SimulationState, params: &BTreeMap<String, String>, propagation_delay: Duration) -> String {
+    let command: &str = "namecheap.domains.dns.setHosts";
+    let domain: String = zone_name(params);
+    if !state.zones.contains_key(&domain) {
+        return error_response(command, "2019166", "Domain not found");
+    }
+
+    let requests: Vec<HostRequest> = (1..)
+        .map_while(|index| {
+            let field = |key: &str| params.get(&format!("{}{}", key, index)).cloned();
+            Some(HostRequest::new(field("HostName")?, field("RecordType")?, field("Address")?, field("MXPref"), None, field("TTL"), None, None))
+        })
+        .collect();
+    let hosts: Vec<SimulatedHost> = state.hosts(requests);
+    if let Some(zone) = state.zones.get_mut(&domain) {
+        zone.settle(Instant::now());
+        zone.pending = Some((hosts, Instant::now() + propagation_delay));
+    }
+    state.stats.writes += 1;
+
+    ok_response(command, &format!(r#"<DomainDNSSetHostsResult Domain="{}" IsSuccess="true" />"#, escape(&domain)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ NameCheapClient, Host };
+
+    fn client(config: SimulationConfig) -> (NameCheapClient, std::sync::Arc<SimulatedTransport>) {
+        let record = HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, Some("300".to_string()), None, None);
+        let simulation = std::sync::Arc::new(SimulatedTransport::new(config).with_zone("example.com", &[record]));
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_transport(simulation.clone());
+        (client, simulation)
+    }
+
+    fn instant() -> SimulationConfig {
+        SimulationConfig { latency: Latency::Fixed(Duration::ZERO), ..SimulationConfig::default() }
+    }
+
+    #[tokio::test]
+    async fn test_get_list_and_hosts() {
+        let (client, _) = client(instant());
+
+        let domains = client.domains_get_list_all().await.unwrap();
+        assert_eq!(domains.len(), 1);
+        assert_eq!(domains[0].name, "example.com");
+
+        let hosts: Vec<Host> = client.domains_dns_get_host_records("example", "com").await.unwrap();
+        assert_eq!((hosts[0].address.as_str(), hosts[0].ttl), ("203.0.113.10", 300));
+        assert!(client.domains_dns_get_host_records("missing", "com").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_eventual_consistency() {
+        let (client, simulation) = client(SimulationConfig { propagation_delay: Duration::from_millis(50), ..instant() });
+        let www = HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None);
+
+        client.domains_dns_replace_hosts("example", "com", &[www]).await.unwrap();
+        let stale: Vec<Host> = client.domains_dns_get_host_records("example", "com").await.unwrap();
+        assert_eq!(stale[0].name, "@");
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let fresh: Vec<Host> = client.domains_dns_get_host_records("example", "com").await.unwrap();
+        assert_eq!((fresh.len(), fresh[0].name.as_str()), (1, "www"));
+        assert_eq!((simulation.stats().writes, simulation.stats().stale_reads), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_starts_cooldown() {
+        let (client, simulation) = client(SimulationConfig { calls_per_minute: Some(2), ..instant() });
+
+        for _ in 0..2 {
+            client.domains_dns_get_host_records("example", "com").await.unwrap();
+        }
+        let error = client.domains_dns_get_host_records("example", "com").await.unwrap_err();
+        assert!(error.to_string().contains("500000"));
+        assert!(client.shared.cooldown().remaining().is_some());
+        assert_eq!(simulation.stats().throttled, 1);
+    }
+
+    #[test]
+    fn test_latency_distribution() {
+        let mut rng: Rng = Rng(7);
+        let latency: Latency = Latency::LongTail { typical: Duration::from_millis(100), tail: Duration::from_secs(1), tail_ratio: 0.1 };
+        let samples: Vec<Duration> = (0..1000).map(|_| latency.sample(&mut rng)).collect();
+
+        let slow: usize = samples.iter().filter(|sample| **sample > Duration::from_millis(100)).count();
+        assert!((50..150).contains(&slow), "{} slow calls", slow);
+        assert!(samples.iter().all(|sample| *sample <= Duration::from_secs(1)));
+        // The same seed replays the same latencies
+        assert_eq!(latency.sample(&mut Rng(7)), samples[0]);
+    }
+}
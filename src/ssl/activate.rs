@@ -0,0 +1,228 @@
+//! ### `ssl.activate` Implementation
+//!
+//! This module provides the implementation for the `ssl.activate` method of the NameCheap API.
+//!
+//! It submits the CSR of a purchased certificate. With DNS validation the response carries the
+//! CNAME records the certificate authority checks for (`DcvRecord`), which can be created with
+//! the zone helpers, e.g. `NameCheapClient::ensure_records`.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_attr, get_node_at, get_nodes_at, parse_string, text_of };
+
+/// How domain control is validated for a certificate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DcvMethod {
+    /// An email to one of the approver addresses of the domain.
+    Email(String),
+    /// A CNAME record in the zone of the domain.
+    Dns,
+    /// A file served over HTTP.
+    Http,
+}
+
+/// ### SSL Activate Request
+///
+/// #### Fields
+/// - `certificate_id`: The ID of the purchased certificate.
+/// - `csr`: The PEM encoded certificate signing request.
+/// - `admin_email`: The email address the issued certificate is sent to.
+/// - `web_server_type`: The server type the certificate is issued for, e.g. `nginx` (optional).
+/// - `dcv_method`: How domain control is validated.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SslActivateRequest {
+    pub certificate_id: i64,
+    pub csr: String,
+    pub admin_email: String,
+    pub web_server_type: Option<String>,
+    pub dcv_method: DcvMethod,
+}
+
+impl SslActivateRequest {
+    /// Returns the request parameters.
+    pub fn params(&self) -> Value {
+        let mut params: Value = json!({
+            "CertificateID": self.certificate_id,
+            "CSR": self.csr.trim(),
+            "AdminEmailAddress": self.admin_email,
+        });
+        if let Some(web_server_type) = &self.web_server_type {
+            params["WebServerType"] = json!(web_server_type);
+        }
+        match &self.dcv_method {
+            DcvMethod::Email(approver) => params["ApproverEmail"] = json!(approver),
+            DcvMethod::Dns => params["DNSDCValidation"] = json!("true"),
+            DcvMethod::Http => params["HTTPDCValidation"] = json!("true"),
+        }
+        params
+    }
+}
+
+/// ### DCV Record
+///
+/// A CNAME record to create for DNS domain control validation.
+///
+/// #### Fields
+/// - `domain`: The domain being validated.
+/// - `host_name`: The fully qualified name of the record.
+/// - `target`: The target of the CNAME.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DcvRecord {
+    pub domain: String,
+    pub host_name: String,
+    pub target: String,
+}
+
+/// ### SSL Activate Result
+///
+/// #### Fields
+/// - `certificate_id`: The ID of the certificate.
+/// - `is_success`: Whether the activation was accepted.
+/// - `dcv_records`: The records to create for DNS validation, empty for other methods.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SslActivateResult {
+    pub certificate_id: i64,
+    pub is_success: bool,
+    pub dcv_records: Vec<DcvRecord>,
+}
+
+impl NameCheapClient {
+    /// - `ssl.activate`: Activates a purchased certificate
+    ///
+    /// Checked against the client policies as [`Mutation::SslActivation`]. In dry-run mode
+    /// nothing is submitted, the call fails with the
+    /// [`PlannedRequest`](crate::utils::request_builder::PlannedRequest) it would have sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let request = SslActivateRequest {
+    ///     certificate_id: 52556,
+    ///     csr: std::fs::read_to_string("example.com.csr")?,
+    ///     admin_email: "admin@example.com".to_string(),
+    ///     web_server_type: Some("nginx".to_string()),
+    ///     dcv_method: DcvMethod::Dns,
+    /// };
+    /// let result = client.ssl_activate(&request).await?;
+    /// for record in &result.dcv_records {
+    ///     println!("{} CNAME {}", record.host_name, record.target);
+    /// }
+    /// ```
+    pub async fn ssl_activate(&self, request: &SslActivateRequest) -> Result<SslActivateResult, Box<dyn Error>> {
+        let command: String = "namecheap.ssl.activate".to_string();
+
+        let certificate_id: i64 = request.certificate_id;
+        let request: Request = Request::new(self, command, None, None, Some(request.params()));
+        self.check_policies(&Mutation::SslActivation { certificate_id })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_activate_result(&response).ok_or_else(|| {
+            format!("Failed to extract the activation of certificate: {}", certificate_id).into()
+        })
+    }
+}
+
+/// Parses a full `ssl.activate` response.
+pub(crate) fn parse_activate_result(response: &Value) -> Option<SslActivateResult> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/SSLActivateResult")?;
+
+    let dcv_records: Vec<DcvRecord> = get_nodes_at(result, "/DNSDCValidation/DNS")
+        .into_iter()
+        .filter_map(|dns| {
+            Some(DcvRecord {
+                domain: parse_string(dns, "domain", ""),
+                host_name: dns.get("HostName").and_then(text_of)?.trim().to_string(),
+                target: dns.get("Target").and_then(text_of)?.trim().to_string(),
+            })
+        })
+        .collect();
+
+    Some(SslActivateResult {
+        certificate_id: get_attr(result, "id").unwrap_or(0),
+        is_success: parse_string(result, "is_success", "false") == "true",
+        dcv_records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{ policy_fn, PolicyViolations };
+    use crate::utils::request_builder::PlannedRequest;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_activate_result() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.ssl.activate">
+            <SSLActivateResult ID="52556" IsSuccess="true">
+              <DNSDCValidation ValueAvailable="true">
+                <DNS domain="example.com">
+                  <HostName><![CDATA[_0a1b2c.example.com]]></HostName>
+                  <Target><![CDATA[3d4e5f.6a7b8c.comodoca.com]]></Target>
+                </DNS>
+              </DNSDCValidation>
+            </SSLActivateResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let result: SslActivateResult = parse_activate_result(&response).unwrap();
+
+        assert_eq!(result.certificate_id, 52556);
+        assert!(result.is_success);
+        assert_eq!(result.dcv_records, vec![DcvRecord {
+            domain: "example.com".to_string(),
+            host_name: "_0a1b2c.example.com".to_string(),
+            target: "3d4e5f.6a7b8c.comodoca.com".to_string(),
+        }]);
+    }
+
+    #[tokio::test]
+    async fn test_ssl_activate_guards() {
+        let request: SslActivateRequest = SslActivateRequest {
+            certificate_id: 52556,
+            csr: "-----BEGIN CERTIFICATE REQUEST-----".to_string(),
+            admin_email: "admin@example.com".to_string(),
+            web_server_type: None,
+            dcv_method: DcvMethod::Dns,
+        };
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+
+        let error: Box<dyn Error> = client.ssl_activate(&request).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<PlannedRequest>().unwrap().params["CertificateID"], 52556);
+
+        let client: NameCheapClient = client.with_policy(policy_fn("no-certificates", |mutation| match mutation {
+            Mutation::SslActivation { certificate_id } => Some(format!("Certificate {} is managed elsewhere", certificate_id)),
+            _ => None,
+        }));
+        let error: Box<dyn Error> = client.ssl_activate(&request).await.unwrap_err();
+        assert!(error.downcast_ref::<PolicyViolations>().is_some());
+    }
+}
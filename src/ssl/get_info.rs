@@ -0,0 +1,155 @@
+//! ### `ssl.getInfo` Implementation
+//!
+//! This module provides the implementation for the `ssl.getInfo` method of the NameCheap API.
+//!
+//! It retrieves the details of a certificate and, once issued, the PEM encoded certificate and
+//! its CA chain.
+//!
+
+use chrono::{ DateTime, FixedOffset, Utc };
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, get_nodes_at, parse_string, text_of };
+use crate::response::timestamp::{ server_offset, to_utc };
+
+/// ### SSL Info
+///
+/// #### Fields
+/// - `certificate_id`: The certificate ID.
+/// - `status`: The certificate status, e.g. `active`.
+/// - `status_description`: The description of the status.
+/// - `ssl_type`: The product, e.g. `PositiveSSL`.
+/// - `common_name`: The common name the certificate is issued for.
+/// - `issued_at`: The issue date, in UTC (optional).
+/// - `expires_at`: The expiration date, in UTC (optional).
+/// - `certificate`: The PEM encoded certificate, when requested and issued (optional).
+/// - `ca_certificates`: The PEM encoded intermediate and root certificates, in chain order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SslInfo {
+    pub certificate_id: i64,
+    pub status: String,
+    pub status_description: String,
+    pub ssl_type: String,
+    pub common_name: String,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub certificate: Option<String>,
+    pub ca_certificates: Vec<String>,
+}
+
+impl SslInfo {
+    /// Returns the certificate followed by its CA chain, as served by most web servers.
+    pub fn full_chain(&self) -> Option<String> {
+        let certificate: &String = self.certificate.as_ref()?;
+        let mut chain: Vec<&str> = vec![certificate.trim()];
+        chain.extend(self.ca_certificates.iter().map(|certificate| certificate.trim()));
+        Some(chain.join("\n") + "\n")
+    }
+}
+
+impl NameCheapClient {
+    /// - `ssl.getInfo`: Retrieves the details of a certificate
+    ///
+    /// With `with_certificate` set, the issued certificate and its CA chain are included.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let info = client.ssl_get_info(52556, true).await?;
+    /// if let Some(chain) = info.full_chain() {
+    ///     std::fs::write("example.com.crt", chain)?;
+    /// }
+    /// ```
+    pub async fn ssl_get_info(&self, certificate_id: i64, with_certificate: bool) -> Result<SslInfo, Box<dyn Error>> {
+        let command: String = "namecheap.ssl.getInfo".to_string();
+        let mut params: Value = json!({ "CertificateID": certificate_id });
+        if with_certificate {
+            params["Returncertificate"] = json!("true");
+            params["Returntype"] = json!("Individual");
+        }
+
+        let response: Value = Request::new(self, command, None, None, Some(params)).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_ssl_info(&response, certificate_id).ok_or_else(|| {
+            format!("Failed to extract the details of certificate: {}", certificate_id).into()
+        })
+    }
+}
+
+/// Parses a full `ssl.getInfo` response.
+pub(crate) fn parse_ssl_info(response: &Value, certificate_id: i64) -> Option<SslInfo> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/SSLGetInfoResult")?;
+    let offset: FixedOffset = server_offset(response);
+    let details: Option<&Value> = result.get("CertificateDetails");
+    let text = |node: Option<&Value>| node.and_then(text_of).map(|text| text.trim().to_string()).filter(|text| !text.is_empty());
+
+    let certificates: Option<&Value> = details.and_then(|details| details.get("Certificates"));
+    let ca_certificates: Vec<String> = certificates
+        .map(|certificates| get_nodes_at(certificates, "/CaCertificates/Certificate"))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|certificate| text(certificate.get("Certificate")))
+        .collect();
+
+    Some(SslInfo {
+        certificate_id,
+        status: parse_string(result, "status", ""),
+        status_description: parse_string(result, "status_description", ""),
+        ssl_type: parse_string(result, "type", ""),
+        common_name: text(details.and_then(|details| details.get("CommonName"))).unwrap_or_default(),
+        issued_at: to_utc(&parse_string(result, "issued_on", ""), offset),
+        expires_at: to_utc(&parse_string(result, "expires", ""), offset),
+        certificate: text(certificates.and_then(|certificates| certificates.get("Certificate"))),
+        ca_certificates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_ssl_info() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.ssl.getInfo">
+            <SSLGetInfoResult Status="active" StatusDescription="Certificate is active" Type="PositiveSSL" IssuedOn="10/14/2024" Expires="10/14/2025">
+              <CertificateDetails>
+                <CommonName><![CDATA[example.com]]></CommonName>
+                <Certificates CertificateReturned="true" ReturnType="INDIVIDUAL">
+                  <Certificate><![CDATA[-----BEGIN CERTIFICATE-----
+LEAF
+-----END CERTIFICATE-----]]></Certificate>
+                  <CaCertificates>
+                    <Certificate Type="INTERMEDIATE"><Certificate><![CDATA[-----BEGIN CERTIFICATE-----
+INTERMEDIATE
+-----END CERTIFICATE-----]]></Certificate></Certificate>
+                  </CaCertificates>
+                </Certificates>
+              </CertificateDetails>
+            </SSLGetInfoResult>
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let info: SslInfo = parse_ssl_info(&response, 52556).unwrap();
+
+        assert_eq!(info.common_name, "example.com");
+        assert_eq!(info.status, "active");
+        assert!(info.expires_at.is_some());
+        assert_eq!(info.ca_certificates.len(), 1);
+        let chain: String = info.full_chain().unwrap();
+        assert!(chain.starts_with("-----BEGIN CERTIFICATE-----\nLEAF"));
+        assert!(chain.contains("INTERMEDIATE"));
+    }
+}
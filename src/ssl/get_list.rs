@@ -0,0 +1,287 @@
+//! ### `ssl.getList` Implementation
+//!
+//! This module provides the implementation for the `ssl.getList` method of the NameCheap API.
+//!
+//! It retrieves the SSL certificates of the account, filtered by status with `SslListType`.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use chrono::{ DateTime, FixedOffset, Utc };
+use tracing::warn;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::paging::{ extract_pagination_info, PageSize, Pagination };
+use crate::response::parse_value::{ get_attr, get_node_at, parse_bool, parse_string };
+use crate::response::parsed_page::ParsedPage;
+use crate::response::timestamp::{ server_offset, to_utc };
+
+/// The certificate statuses `ssl.getList` can filter on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum SslListType {
+    #[default]
+    #[serde(rename = "ALL")]
+    All,
+    Processing,
+    EmailSent,
+    TechnicalProblem,
+    InProgress,
+    Completed,
+    Deactivated,
+    Active,
+    Cancelled,
+    NewPurchase,
+    NewRenewal,
+}
+
+impl SslListType {
+    /// Every list type, in the order of `limits::SSL_LIST_TYPES`.
+    pub const ALL: [SslListType; 11] = [
+        SslListType::All,
+        SslListType::Processing,
+        SslListType::EmailSent,
+        SslListType::TechnicalProblem,
+        SslListType::InProgress,
+        SslListType::Completed,
+        SslListType::Deactivated,
+        SslListType::Active,
+        SslListType::Cancelled,
+        SslListType::NewPurchase,
+        SslListType::NewRenewal,
+    ];
+
+    /// Returns the value sent as `ListType`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslListType::All => "ALL",
+            SslListType::Processing => "Processing",
+            SslListType::EmailSent => "EmailSent",
+            SslListType::TechnicalProblem => "TechnicalProblem",
+            SslListType::InProgress => "InProgress",
+            SslListType::Completed => "Completed",
+            SslListType::Deactivated => "Deactivated",
+            SslListType::Active => "Active",
+            SslListType::Cancelled => "Cancelled",
+            SslListType::NewPurchase => "NewPurchase",
+            SslListType::NewRenewal => "NewRenewal",
+        }
+    }
+}
+
+/// ### SSL List Query
+///
+/// The options of an `ssl.getList` call.
+///
+/// #### Fields
+/// - `page`: The page to fetch, starting at 1.
+/// - `page_size`: The number of certificates per page.
+/// - `list_type`: The certificate status to list, every certificate by default.
+/// - `search_term`: Only certificates whose host name contains the term (optional).
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SslListQuery {
+    pub page: i64,
+    pub page_size: PageSize,
+    pub list_type: SslListType,
+    pub search_term: Option<String>,
+}
+
+impl Default for SslListQuery {
+    fn default() -> Self {
+        SslListQuery { page: 1, page_size: PageSize::DEFAULT, list_type: SslListType::All, search_term: None }
+    }
+}
+
+impl SslListQuery {
+    /// Creates a query for the first page of every certificate.
+    pub fn new() -> Self {
+        SslListQuery::default()
+    }
+
+    /// Sets the page to fetch, pages below 1 fetch the first page.
+    pub fn page(mut self, page: i64) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    /// Sets the number of certificates per page.
+    pub fn page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the certificate status to list.
+    pub fn list_type(mut self, list_type: SslListType) -> Self {
+        self.list_type = list_type;
+        self
+    }
+
+    /// Only lists certificates whose host name contains `term`.
+    pub fn search_term(mut self, term: &str) -> Self {
+        self.search_term = Some(term.to_string());
+        self
+    }
+
+    /// Returns the request parameters of the query, except `Page`.
+    pub fn params(&self) -> Value {
+        let mut params: Value = json!({
+            "PageSize": self.page_size.get(),
+            "ListType": self.list_type.as_str(),
+        });
+        if let Some(term) = &self.search_term {
+            params["SearchTerm"] = json!(term);
+        }
+        params
+    }
+}
+
+/// ### SSL Certificate
+///
+/// A certificate of an `ssl.getList` response.
+///
+/// #### Fields
+/// - `certificate_id`: The certificate ID.
+/// - `host_name`: The host name the certificate is issued for, empty before activation.
+/// - `ssl_type`: The product, e.g. `PositiveSSL`.
+/// - `status`: The certificate status, e.g. `active` or `newpurchase`.
+/// - `is_expired`: Whether the certificate has expired.
+/// - `purchased_at`: The purchase date, in UTC (optional).
+/// - `expires_at`: The expiration date, in UTC (optional).
+/// - `activation_expires_at`: The last day the certificate can be activated, in UTC (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SslCertificate {
+    pub certificate_id: i64,
+    pub host_name: String,
+    pub ssl_type: String,
+    pub status: String,
+    pub is_expired: bool,
+    pub purchased_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub activation_expires_at: Option<DateTime<Utc>>,
+}
+
+impl NameCheapClient {
+    /// - `ssl.getList`: Gets the SSL certificates of the account
+    ///
+    /// Returns one page of certificates matching the query, and the pagination of the result.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let query = SslListQuery::new().list_type(SslListType::Active);
+    /// let (certificates, pagination) = client.ssl_get_list(&query).await.unwrap();
+    /// ```
+    pub async fn ssl_get_list(
+        &self,
+        query: &SslListQuery
+    ) -> Result<(Vec<SslCertificate>, Pagination), Box<dyn Error>> {
+        let command: String = "namecheap.ssl.getList".to_string();
+
+        let response: Value = Request::new(
+            self,
+            command,
+            Some(query.page.max(1)),
+            None,
+            Some(query.params())
+        ).flatten_text().send().await?;
+        check_api_errors(&response)?;
+
+        Ok(parse_ssl_list(&response))
+    }
+}
+
+/// Parses a full `ssl.getList` response into certificates and pagination.
+pub(crate) fn parse_ssl_list(response: &Value) -> (Vec<SslCertificate>, Pagination) {
+    let offset: FixedOffset = server_offset(response);
+    let page: ParsedPage<SslCertificate> = ParsedPage::parse(
+        get_node_at(response, "/ApiResponse/CommandResponse/SSLListResult/SSL"),
+        |certificate| parse_ssl_certificate(certificate, offset)
+    );
+    for issue in &page.skipped {
+        warn!("Skipped certificate at index {}: {}", issue.index, issue.reason);
+    }
+
+    let paging: Option<&Value> = get_node_at(response, "/ApiResponse/CommandResponse/Paging");
+    let (current_page, _, _, total_pages) = extract_pagination_info(paging);
+    (page.items, Pagination { current_page, total_pages })
+}
+
+/// Parses a single `SSL` element of an `ssl.getList` response.
+fn parse_ssl_certificate(certificate: &Value, offset: FixedOffset) -> Result<SslCertificate, String> {
+    let certificate_id: i64 = get_attr(certificate, "certificate_id")
+        .ok_or("missing or invalid CertificateID attribute")?;
+
+    Ok(SslCertificate {
+        certificate_id,
+        host_name: parse_string(certificate, "host_name", ""),
+        ssl_type: parse_string(certificate, "ssltype", ""),
+        status: parse_string(certificate, "status", ""),
+        is_expired: parse_bool(certificate, "is_expired_yn", "false", "true"),
+        purchased_at: to_utc(&parse_string(certificate, "purchase_date", ""), offset),
+        expires_at: to_utc(&parse_string(certificate, "expire_date", ""), offset),
+        activation_expires_at: to_utc(&parse_string(certificate, "activation_expire_date", ""), offset),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::SSL_LIST_TYPES;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    const SAMPLE_RESPONSE: &str =
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<ApiResponse Status="OK" xmlns="http://api.namecheap.com/xml.response">
+  <Errors />
+  <RequestedCommand>namecheap.ssl.getList</RequestedCommand>
+  <CommandResponse Type="namecheap.ssl.getList">
+    <SSLListResult>
+      <SSL CertificateID="52556" HostName="example.com" SSLType="PositiveSSL" PurchaseDate="10/14/2024" ExpireDate="10/14/2025" ActivationExpireDate="04/12/2025" IsExpiredYN="false" Status="active" />
+      <SSL CertificateID="52557" HostName="" SSLType="EssentialSSL" PurchaseDate="10/15/2024" ExpireDate="" ActivationExpireDate="04/13/2025" IsExpiredYN="false" Status="newpurchase" />
+    </SSLListResult>
+    <Paging>
+      <TotalItems>2</TotalItems>
+      <CurrentPage>1</CurrentPage>
+      <PageSize>20</PageSize>
+    </Paging>
+  </CommandResponse>
+  <GMTTimeDifference>--5:00</GMTTimeDifference>
+</ApiResponse>"#;
+
+    #[test]
+    fn test_ssl_list_type_matches_api_values() {
+        let values: Vec<&str> = SslListType::ALL.iter().map(SslListType::as_str).collect();
+        assert_eq!(values, SSL_LIST_TYPES);
+        for list_type in SslListType::ALL {
+            assert_eq!(serde_json::to_value(list_type).unwrap(), json!(list_type.as_str()));
+        }
+
+        let query: SslListQuery = SslListQuery::new().list_type(SslListType::NewPurchase).search_term("example");
+        assert_eq!(
+            query.params(),
+            json!({ "PageSize": 20, "ListType": "NewPurchase", "SearchTerm": "example" })
+        );
+    }
+
+    #[test]
+    fn test_parse_ssl_list() {
+        let response: Value = parse_xml_to_json_with(SAMPLE_RESPONSE, ParseOptions::flat()).unwrap();
+        let (certificates, pagination) = parse_ssl_list(&response);
+
+        assert_eq!(certificates.len(), 2);
+        assert_eq!(certificates[0].certificate_id, 52556);
+        assert_eq!(certificates[0].ssl_type, "PositiveSSL");
+        assert!(certificates[0].expires_at.is_some());
+        assert_eq!(certificates[1].status, "newpurchase");
+        assert_eq!(certificates[1].expires_at, None);
+        assert_eq!(pagination, Pagination { current_page: 1, total_pages: 1 });
+    }
+}
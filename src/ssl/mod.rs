@@ -0,0 +1,15 @@
+//! ## SSL API
+//! The SSL API manages the SSL certificates purchased through the account.
+//!
+//! ### Available Methods
+//! - `namecheap.ssl.getList`: Retrieve a list of SSL certificates associated with your account.
+//! - `namecheap.ssl.activate`: Activate an SSL certificate.
+//! - `namecheap.ssl.getInfo`: Retrieve detailed information about an SSL certificate.
+//!
+
+/// - **ssl.getList**
+pub mod get_list;
+/// - **ssl.activate**
+pub mod activate;
+/// - **ssl.getInfo**
+pub mod get_info;
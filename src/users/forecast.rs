@@ -0,0 +1,299 @@
+//! ### Renewal Cost Forecast
+//!
+//! Sums what the account will be charged for renewals coming up within a horizon (domains,
+//! WHOIS guard subscriptions and SSL certificates, priced with `users.getPricing`) and
+//! compares it with the available balance, so finance automation can top the account up
+//! before auto-renewals fail.
+//!
+
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Domain };
+use crate::response::money::{ Currency, Money };
+use crate::response::paging::Pagination;
+use crate::response::parse_value::parse_decimal_str;
+use crate::response::timestamp::parse_date;
+use crate::ssl::get_list::{ SslCertificate, SslListQuery, SslListType };
+use crate::users::get_balances::AccountBalances;
+use crate::users::get_pricing::{ Action, Price, PricingMatrix, ProductType };
+use crate::utils::charges::sum;
+
+/// What is renewed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum RenewalKind {
+    Domain,
+    WhoisGuard,
+    Ssl,
+}
+
+/// ### Upcoming Renewal
+///
+/// #### Fields
+/// - `kind`: What is renewed.
+/// - `name`: The domain, or the certificate host name (its ID before activation).
+/// - `expires_at`: When the subscription expires.
+/// - `auto_renew`: Whether it renews automatically, charging the balance.
+/// - `price`: The price of a one-year renewal, `None` when the pricing has none.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct UpcomingRenewal {
+    pub kind: RenewalKind,
+    pub name: String,
+    pub expires_at: DateTime<Utc>,
+    pub auto_renew: bool,
+    pub price: Option<Money>,
+}
+
+/// ### Renewal Forecast
+///
+/// #### Fields
+/// - `horizon_days`: The number of days looked ahead.
+/// - `renewals`: The renewals due within the horizon, soonest first.
+/// - `total`: The price of every priced renewal.
+/// - `auto_renew_total`: The price of the renewals charged automatically.
+/// - `available_balance`: The balance available for purchases.
+/// - `shortfall`: How much `auto_renew_total` exceeds the balance, `None` when it is covered.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct RenewalForecast {
+    pub horizon_days: i64,
+    pub renewals: Vec<UpcomingRenewal>,
+    pub total: Money,
+    pub auto_renew_total: Money,
+    pub available_balance: Money,
+    pub shortfall: Option<Money>,
+}
+
+impl RenewalForecast {
+    /// Returns the renewals without a price, left out of the totals.
+    pub fn unpriced(&self) -> Vec<&UpcomingRenewal> {
+        self.renewals.iter().filter(|renewal| renewal.price.is_none()).collect()
+    }
+}
+
+/// The pricing a forecast is computed with.
+///
+/// #### Fields
+/// - `domains`: The `DOMAIN` pricing.
+/// - `whois_guard`: The `WHOISGUARD` pricing (optional).
+/// - `ssl`: The `SSLCERTIFICATE` pricing (optional).
+#[derive(Debug, Clone)]
+pub struct ForecastPricing {
+    pub domains: PricingMatrix,
+    pub whois_guard: Option<PricingMatrix>,
+    pub ssl: Option<PricingMatrix>,
+}
+
+/// Returns the one-year renewal price of `product`, else its one-year registration price.
+fn renewal_price(matrix: &PricingMatrix, product: &str) -> Option<Money> {
+    matrix.price_for(product, Action::Renew, 1)
+        .or_else(|| matrix.price_for(product, Action::Register, 1))
+        .map(|price| price.effective_price().clone())
+}
+
+/// Returns the first one-year price of `matrix`, renewals first.
+fn any_renewal_price(matrix: &PricingMatrix) -> Option<Money> {
+    let yearly = |action: Action| matrix.prices.iter().find(|price: &&Price| price.action == action && price.is_for_years(1));
+    yearly(Action::Renew)
+        .or_else(|| yearly(Action::Register))
+        .map(|price| price.effective_price().clone())
+}
+
+/// Builds the forecast of the renewals due before `now + horizon_days`.
+///
+/// Domains renew with their auto-renew setting, as do the WHOIS guard subscriptions of
+/// domains with WHOIS guard enabled. Certificates are never renewed automatically.
+///
+/// Fails if a total overflows.
+pub fn forecast_renewals(
+    horizon_days: i64,
+    now: DateTime<Utc>,
+    domains: &[Domain],
+    certificates: &[SslCertificate],
+    pricing: &ForecastPricing,
+    balances: &AccountBalances
+) -> Result<RenewalForecast, String> {
+    let horizon: DateTime<Utc> = now + Duration::days(horizon_days);
+    let due = |expires_at: &DateTime<Utc>| *expires_at <= horizon;
+    let mut renewals: Vec<UpcomingRenewal> = Vec::new();
+
+    for domain in domains {
+        if let Some(expires_at) = domain.expires_at.filter(due) {
+            let tld: &str = domain.name.split_once('.').map(|(_, tld)| tld).unwrap_or("");
+            renewals.push(UpcomingRenewal {
+                kind: RenewalKind::Domain,
+                name: domain.name.to_ascii_lowercase(),
+                expires_at,
+                auto_renew: domain.auto_renew,
+                price: renewal_price(&pricing.domains, tld),
+            });
+        }
+        let whois_guard_expires: Option<DateTime<Utc>> = domain.whois_guard_expires
+            .as_deref()
+            .and_then(parse_date)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|time| time.and_utc());
+        if let Some(expires_at) = whois_guard_expires.filter(due) && domain.whois_guard.is_enabled() {
+            renewals.push(UpcomingRenewal {
+                kind: RenewalKind::WhoisGuard,
+                name: domain.name.to_ascii_lowercase(),
+                expires_at,
+                auto_renew: domain.auto_renew,
+                price: pricing.whois_guard.as_ref().and_then(any_renewal_price),
+            });
+        }
+    }
+    for certificate in certificates {
+        if let Some(expires_at) = certificate.expires_at.filter(due) {
+            let name: String = if certificate.host_name.is_empty() {
+                certificate.certificate_id.to_string()
+            } else {
+                certificate.host_name.clone()
+            };
+            renewals.push(UpcomingRenewal {
+                kind: RenewalKind::Ssl,
+                name,
+                expires_at,
+                auto_renew: false,
+                price: pricing.ssl.as_ref().and_then(|matrix| renewal_price(matrix, &certificate.ssl_type)),
+            });
+        }
+    }
+    renewals.sort_by(|a, b| a.expires_at.cmp(&b.expires_at).then_with(|| a.name.cmp(&b.name)));
+
+    let currency: &Currency = &balances.currency;
+    let total_of = |auto_renew_only: bool| -> Result<Money, String> {
+        let prices = renewals
+            .iter()
+            .filter(|renewal| !auto_renew_only || renewal.auto_renew)
+            .filter_map(|renewal| renewal.price.as_ref());
+        Ok(sum(prices)?
+            .into_iter()
+            .find(|total| &total.currency == currency)
+            .unwrap_or_else(|| Money::new(parse_decimal_str("0").unwrap_or_default(), currency.clone())))
+    };
+    let total: Money = total_of(false)?;
+    let auto_renew_total: Money = total_of(true)?;
+    let shortfall: Option<Money> = if auto_renew_total.currency == balances.available_balance.currency {
+        Some(auto_renew_total.try_add(&balances.available_balance.negated())?).filter(Money::is_positive)
+    } else {
+        None
+    };
+
+    Ok(RenewalForecast {
+        horizon_days,
+        renewals,
+        total,
+        auto_renew_total,
+        available_balance: balances.available_balance.clone(),
+        shortfall,
+    })
+}
+
+impl NameCheapClient {
+    /// Forecasts the renewal costs of the next `horizon_days` days against the balance.
+    ///
+    /// Lists every domain and active certificate, and fetches the pricing of each product
+    /// type with something due.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let forecast = client.forecast_renewal_costs(30).await?;
+    /// if let Some(shortfall) = &forecast.shortfall {
+    ///     println!("Top up {} before auto-renewals fail", shortfall);
+    /// }
+    /// ```
+    pub async fn forecast_renewal_costs(&self, horizon_days: i64) -> Result<RenewalForecast, Box<dyn Error>> {
+        let domains: Vec<Domain> = self.domains_get_list_all().await?;
+
+        let mut certificates: Vec<SslCertificate> = Vec::new();
+        let mut query: SslListQuery = SslListQuery::new().list_type(SslListType::Active);
+        loop {
+            let (page, pagination): (Vec<SslCertificate>, Pagination) = self.ssl_get_list(&query).await?;
+            certificates.extend(page);
+            if pagination.current_page >= pagination.total_pages {
+                break;
+            }
+            query = query.page(pagination.current_page + 1);
+        }
+
+        let horizon: DateTime<Utc> = Utc::now() + Duration::days(horizon_days);
+        let whois_guard_due: bool = domains.iter().any(|domain| domain.whois_guard.is_enabled() && domain.whois_guard_expires.is_some());
+        let ssl_due: bool = certificates.iter().any(|certificate| certificate.expires_at.is_some_and(|expires| expires <= horizon));
+
+        let pricing: ForecastPricing = ForecastPricing {
+            domains: self.users_get_pricing(ProductType::Domain, None).await?,
+            whois_guard: if whois_guard_due { Some(self.users_get_pricing(ProductType::WhoisGuard, None).await?) } else { None },
+            ssl: if ssl_due { Some(self.users_get_pricing(ProductType::SslCertificate, None).await?) } else { None },
+        };
+        let balances: AccountBalances = self.users_get_balances().await?;
+
+        Ok(forecast_renewals(horizon_days, Utc::now(), &domains, &certificates, &pricing, &balances)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{ Value, json };
+    use crate::users::get_pricing::parse_pricing;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    fn matrix(product_type: ProductType, xml: &str) -> PricingMatrix {
+        let response: Value = parse_xml_to_json(&format!(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.getPricing"><UserGetPricingResult>{}</UserGetPricingResult></CommandResponse></ApiResponse>"#,
+            xml
+        )).unwrap();
+        parse_pricing(&response, product_type).unwrap()
+    }
+
+    #[test]
+    fn test_forecast_renewals() {
+        let now: DateTime<Utc> = Utc::now();
+        let days = |days: i64| (now + Duration::days(days)).to_rfc3339();
+        let domains: Vec<Domain> = serde_json::from_value(json!([
+            { "id": 1, "name": "example.com", "autoRenew": true, "expiresAt": days(10), "whoisGuard": "ENABLED", "whoisGuardExpires": (now + Duration::days(25)).format("%m/%d/%Y").to_string() },
+            { "id": 2, "name": "example.net", "autoRenew": false, "expiresAt": days(20) },
+            { "id": 3, "name": "example.org", "autoRenew": true, "expiresAt": days(90) },
+        ])).unwrap();
+        let certificates: Vec<SslCertificate> = serde_json::from_value(json!([
+            { "certificateId": 7, "hostName": "", "sslType": "PositiveSSL", "status": "active", "isExpired": false, "expiresAt": days(5) },
+        ])).unwrap();
+        let pricing: ForecastPricing = ForecastPricing {
+            domains: matrix(ProductType::Domain, r#"<ProductType Name="domains"><ProductCategory Name="renew">
+                <Product Name="com"><Price Duration="1" DurationType="YEAR" YourPrice="14.98" Currency="USD" /></Product>
+                <Product Name="net"><Price Duration="1" DurationType="YEAR" YourPrice="16.98" Currency="USD" /></Product>
+                </ProductCategory></ProductType>"#),
+            whois_guard: Some(matrix(ProductType::WhoisGuard, r#"<ProductType Name="whoisguard"><ProductCategory Name="renew">
+                <Product Name="whoisguard-one-year"><Price Duration="1" DurationType="YEAR" YourPrice="2.88" Currency="USD" /></Product>
+                </ProductCategory></ProductType>"#)),
+            ssl: None,
+        };
+        let balances: AccountBalances = serde_json::from_value(json!({
+            "currency": "USD",
+            "availableBalance": { "amount": "10.00", "currency": "USD" },
+            "accountBalance": { "amount": "10.00", "currency": "USD" },
+        })).unwrap();
+
+        let forecast: RenewalForecast = forecast_renewals(30, now, &domains, &certificates, &pricing, &balances).unwrap();
+        let kinds: Vec<(RenewalKind, &str)> = forecast.renewals.iter().map(|renewal| (renewal.kind, renewal.name.as_str())).collect();
+        assert_eq!(kinds, [
+            (RenewalKind::Ssl, "7"),
+            (RenewalKind::Domain, "example.com"),
+            (RenewalKind::Domain, "example.net"),
+            (RenewalKind::WhoisGuard, "example.com"),
+        ]);
+        assert_eq!(forecast.unpriced().len(), 1);
+        assert_eq!(forecast.total.to_string(), "34.84 USD");
+        assert_eq!(forecast.auto_renew_total.to_string(), "17.86 USD");
+        assert_eq!(forecast.shortfall.map(|shortfall| shortfall.to_string()).as_deref(), Some("7.86 USD"));
+    }
+}
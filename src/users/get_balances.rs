@@ -0,0 +1,107 @@
+//! ### `users.getBalances` Implementation
+//!
+//! This module provides the implementation for the `users.getBalances` method of the NameCheap API.
+//!
+//! It retrieves the balances of the account, in the account currency.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::get_node_at;
+
+/// ### Account Balances
+///
+/// #### Fields
+/// - `currency`: The account currency, every amount below is in this currency.
+/// - `available_balance`: The balance available for purchases.
+/// - `account_balance`: The total balance of the account.
+/// - `earned_amount`: The amount earned through referrals (optional).
+/// - `withdrawable_amount`: The amount that can be withdrawn (optional).
+/// - `funds_required_for_auto_renew`: The funds needed to auto-renew the expiring domains (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct AccountBalances {
+    pub currency: Currency,
+    pub available_balance: Money,
+    pub account_balance: Money,
+    pub earned_amount: Option<Money>,
+    pub withdrawable_amount: Option<Money>,
+    pub funds_required_for_auto_renew: Option<Money>,
+}
+
+impl NameCheapClient {
+    /// - `users.getBalances`: Retrieves the balances of the account
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let balances: AccountBalances = client.users_get_balances().await.unwrap();
+    /// println!("Available: {}", balances.available_balance);
+    /// ```
+    pub async fn users_get_balances(&self) -> Result<AccountBalances, Box<dyn Error>> {
+        let command: String = "namecheap.users.getBalances".to_string();
+
+        let response: Value = Request::new(self, command, None, None, None).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_balances(&response).ok_or_else(|| "Failed to extract the account balances".into())
+    }
+
+    /// Detects the account currency from the `Currency` attribute of `users.getBalances`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let currency: Currency = client.account_currency().await.unwrap();
+    /// println!("Billed in {}", currency);
+    /// ```
+    pub async fn account_currency(&self) -> Result<Currency, Box<dyn Error>> {
+        Ok(self.users_get_balances().await?.currency)
+    }
+}
+
+/// Parses a full `users.getBalances` response.
+pub(crate) fn parse_balances(response: &Value) -> Option<AccountBalances> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/UserGetBalancesResult")?;
+    let currency: Currency = detect_currency(result).unwrap_or_default();
+
+    Some(AccountBalances {
+        available_balance: parse_money(result, "available_balance", &currency)?,
+        account_balance: parse_money(result, "account_balance", &currency)?,
+        earned_amount: parse_money(result, "earned_amount", &currency),
+        withdrawable_amount: parse_money(result, "withdrawable_amount", &currency),
+        funds_required_for_auto_renew: parse_money(result, "funds_required_for_auto_renew", &currency),
+        currency,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_balances() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.getBalances">
+            <UserGetBalancesResult Currency="EUR" AvailableBalance="4932.96" AccountBalance="4932.96" EarnedAmount="381.70" WithdrawableAmount="1243.36" FundsRequiredForAutoRenew="0.00" />
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let balances: AccountBalances = parse_balances(&response).unwrap();
+
+        assert_eq!(balances.currency, Currency::Eur);
+        assert_eq!(balances.available_balance.to_string(), "4932.96 EUR");
+        assert_eq!(balances.withdrawable_amount.map(|amount| amount.currency), Some(Currency::Eur));
+    }
+}
@@ -0,0 +1,303 @@
+//! ### `users.getPricing` Implementation
+//!
+//! This module provides the implementation for the `users.getPricing` method of the NameCheap API.
+//!
+//! The response nests prices by product type, category (`register`, `renew`, ...), product
+//! (the TLD for domains) and duration. It is flattened into a [`PricingMatrix`] answering
+//! lookups such as `price_for("com", Action::Renew, 2)`; the raw result is kept alongside for
+//! audit.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::error::Error;
+use std::fmt;
+use tracing::info;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::{ get_attr, get_node_at, get_nodes_at, parse_string, text_of };
+
+/// The product types priced by `users.getPricing`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ProductType {
+    Domain,
+    SslCertificate,
+    WhoisGuard,
+}
+
+impl ProductType {
+    /// Returns the `ProductType` parameter value.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProductType::Domain => "DOMAIN",
+            ProductType::SslCertificate => "SSLCERTIFICATE",
+            ProductType::WhoisGuard => "WHOISGUARD",
+        }
+    }
+}
+
+/// The action a price applies to, the product category of the response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Action {
+    Register,
+    Renew,
+    Reactivate,
+    Transfer,
+    /// Any other category, kept lowercased.
+    Other(String),
+}
+
+impl Action {
+    /// Returns the category name, lowercased.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Action::Register => "register",
+            Action::Renew => "renew",
+            Action::Reactivate => "reactivate",
+            Action::Transfer => "transfer",
+            Action::Other(category) => category,
+        }
+    }
+}
+
+impl From<&str> for Action {
+    fn from(category: &str) -> Self {
+        match category.trim().to_ascii_lowercase().as_str() {
+            "register" => Action::Register,
+            "renew" => Action::Renew,
+            "reactivate" => Action::Reactivate,
+            "transfer" => Action::Transfer,
+            other => Action::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// ### Price
+///
+/// #### Fields
+/// - `product`: The product, lowercased (the TLD for domains, e.g. `com`).
+/// - `action`: The action priced.
+/// - `duration`: The duration, in `duration_type` units.
+/// - `duration_type`: The unit of the duration, e.g. `YEAR`.
+/// - `price`: The price for this account, promotions applied.
+/// - `regular_price`: The list price (optional).
+/// - `promotion_price`: The promotional price, when a promotion applies (optional).
+/// - `additional_cost`: Fees charged on top, e.g. the ICANN fee (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Price {
+    pub product: String,
+    pub action: Action,
+    pub duration: u32,
+    pub duration_type: String,
+    pub price: Money,
+    pub regular_price: Option<Money>,
+    pub promotion_price: Option<Money>,
+    pub additional_cost: Option<Money>,
+}
+
+impl Price {
+    /// Returns the price paid: the promotional price if any, otherwise `price`.
+    pub fn effective_price(&self) -> &Money {
+        self.promotion_price.as_ref().unwrap_or(&self.price)
+    }
+
+    /// Returns `true` if the price is for `years` years.
+    pub fn is_for_years(&self, years: u32) -> bool {
+        self.duration == years && self.duration_type.eq_ignore_ascii_case("YEAR")
+    }
+}
+
+/// ### Pricing Matrix
+///
+/// #### Fields
+/// - `product_type`: The product type priced.
+/// - `currency`: The currency of every price.
+/// - `prices`: One price per product, action and duration, in response order.
+/// - `raw`: The `UserGetPricingResult` element as parsed, categories included.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq)]
+pub struct PricingMatrix {
+    pub product_type: ProductType,
+    pub currency: Currency,
+    pub prices: Vec<Price>,
+    pub raw: Value,
+}
+
+impl PricingMatrix {
+    /// Returns the price of `action` on `product` for `years` years.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let renewal = matrix.price_for("com", Action::Renew, 2).unwrap();
+    /// println!("{} (regular {:?})", renewal.effective_price(), renewal.regular_price);
+    /// ```
+    pub fn price_for(&self, product: &str, action: Action, years: u32) -> Option<&Price> {
+        let product: String = product.trim_start_matches('.').to_ascii_lowercase();
+        self.prices
+            .iter()
+            .find(|price| price.product == product && price.action == action && price.is_for_years(years))
+    }
+
+    /// Returns every price of `action` on `product`, shortest duration first.
+    pub fn prices_for(&self, product: &str, action: Action) -> Vec<&Price> {
+        let product: String = product.trim_start_matches('.').to_ascii_lowercase();
+        let mut prices: Vec<&Price> = self.prices
+            .iter()
+            .filter(|price| price.product == product && price.action == action)
+            .collect();
+        prices.sort_by_key(|price| price.duration);
+        prices
+    }
+
+    /// Returns the categories of the response, lowercased, in response order.
+    pub fn categories(&self) -> Vec<String> {
+        get_nodes_at(&self.raw, "/ProductType")
+            .into_iter()
+            .flat_map(|product_type| get_nodes_at(product_type, "/ProductCategory"))
+            .map(|category| parse_string(category, "name", "").to_ascii_lowercase())
+            .collect()
+    }
+}
+
+impl NameCheapClient {
+    /// - `users.getPricing`: Retrieves the prices of a product type for this account
+    ///
+    /// With a `promo_code`, the prices reflect the promotion.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let matrix: PricingMatrix = client.users_get_pricing(ProductType::Domain, None).await.unwrap();
+    /// if let Some(price) = matrix.price_for("com", Action::Register, 1) {
+    ///     println!("Registering a .com costs {}", price.effective_price());
+    /// }
+    /// ```
+    pub async fn users_get_pricing(
+        &self,
+        product_type: ProductType,
+        promo_code: Option<&str>
+    ) -> Result<PricingMatrix, Box<dyn Error>> {
+        let command: String = "namecheap.users.getPricing".to_string();
+        let mut params: Value = json!({ "ProductType": product_type.as_str() });
+        if let Some(promo_code) = promo_code {
+            params["PromotionCode"] = json!(promo_code);
+        }
+
+        let response: Value = Request::new(self, command, None, None, Some(params)).flatten_text().send().await?;
+        check_api_errors(&response)?;
+        info!("Response: {:#?}", response);
+
+        parse_pricing(&response, product_type).ok_or_else(|| "Failed to extract the pricing".into())
+    }
+}
+
+/// Parses a full `users.getPricing` response.
+pub(crate) fn parse_pricing(response: &Value, product_type: ProductType) -> Option<PricingMatrix> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/UserGetPricingResult")?;
+    let default_currency: Currency = detect_currency(result).unwrap_or_default();
+    let mut prices: Vec<Price> = Vec::new();
+
+    for category in get_nodes_at(result, "/ProductType").into_iter().flat_map(|node| get_nodes_at(node, "/ProductCategory")) {
+        let action: Action = Action::from(parse_string(category, "name", "").as_str());
+        for product in get_nodes_at(category, "/Product") {
+            let name: String = parse_string(product, "name", "").to_ascii_lowercase();
+            for node in get_nodes_at(product, "/Price") {
+                let currency: Currency = detect_currency(node).unwrap_or_else(|| default_currency.clone());
+                // `YourPrice` is the account price, older responses only carry `Price`
+                let Some(price) = parse_money(node, "your_price", &currency).or_else(|| parse_money(node, "price", &currency)) else {
+                    continue;
+                };
+                prices.push(Price {
+                    product: name.clone(),
+                    action: action.clone(),
+                    duration: get_attr(node, "duration").unwrap_or(1),
+                    duration_type: parse_string(node, "duration_type", "YEAR").to_ascii_uppercase(),
+                    price,
+                    regular_price: parse_money(node, "regular_price", &currency),
+                    promotion_price: positive_money(node, "promotion_price", &currency),
+                    additional_cost: positive_money(node, "your_additional_cost", &currency)
+                        .or_else(|| positive_money(node, "additional_cost", &currency)),
+                });
+            }
+        }
+    }
+
+    Some(PricingMatrix {
+        product_type,
+        currency: default_currency,
+        prices,
+        raw: result.clone(),
+    })
+}
+
+/// Parses a money value, `None` when it is zero: the API reports absent amounts as `0`.
+fn positive_money(node: &Value, key: &str, currency: &Currency) -> Option<Money> {
+    let text: &str = node.get(key).and_then(text_of)?;
+    if text.trim().parse::<f64>().ok()? <= 0.0 {
+        return None;
+    }
+    parse_money(node, key, currency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+    #[test]
+    fn test_parse_pricing() {
+        let response: Value = parse_xml_to_json_with(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.getPricing">
+            <UserGetPricingResult>
+              <ProductType Name="domains">
+                <ProductCategory Name="register">
+                  <Product Name="com">
+                    <Price Duration="1" DurationType="YEAR" Price="8.88" RegularPrice="10.98" YourPrice="8.88" PromotionPrice="5.98" YourAdditionalCost="0.18" Currency="USD" />
+                    <Price Duration="2" DurationType="YEAR" Price="19.86" RegularPrice="21.96" YourPrice="19.86" PromotionPrice="0.0" YourAdditionalCost="0.36" Currency="USD" />
+                  </Product>
+                </ProductCategory>
+                <ProductCategory Name="renew">
+                  <Product Name="com">
+                    <Price Duration="2" DurationType="YEAR" Price="27.96" RegularPrice="27.96" YourPrice="26.96" PromotionPrice="0.0" YourAdditionalCost="0.36" Currency="USD" />
+                  </Product>
+                </ProductCategory>
+              </ProductType>
+            </UserGetPricingResult>
+            </CommandResponse></ApiResponse>"#,
+            ParseOptions::flat()
+        ).unwrap();
+        let matrix: PricingMatrix = parse_pricing(&response, ProductType::Domain).unwrap();
+
+        assert_eq!(matrix.prices.len(), 3);
+        assert_eq!(matrix.categories(), ["register", "renew"]);
+
+        let renewal: &Price = matrix.price_for(".COM", Action::Renew, 2).unwrap();
+        assert_eq!(renewal.effective_price().to_string(), "26.96 USD");
+        assert_eq!(renewal.regular_price.as_ref().map(Money::to_string).as_deref(), Some("27.96 USD"));
+        assert!(renewal.promotion_price.is_none());
+
+        let registration: &Price = matrix.price_for("com", Action::Register, 1).unwrap();
+        assert_eq!(registration.effective_price().to_string(), "5.98 USD");
+        assert_eq!(registration.additional_cost.as_ref().map(Money::to_string).as_deref(), Some("0.18 USD"));
+        assert!(matrix.price_for("com", Action::Transfer, 1).is_none());
+        assert_eq!(matrix.prices_for("com", Action::Register).len(), 2);
+    }
+}
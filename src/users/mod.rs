@@ -0,0 +1,15 @@
+//! ## Users API
+//! The Users API provides methods to manage the account, including balances and pricing.
+//!
+//! ### Available Methods
+//! - `namecheap.users.getBalances`: Get the current balance of your Namecheap account.
+//! - `namecheap.users.getPricing`: Get the prices of a product type, as a pricing matrix.
+//!
+
+/// - **users.getBalances**
+pub mod get_balances;
+
+/// - **users.getPricing**
+pub mod get_pricing;
+/// - **renewal cost forecast**
+pub mod forecast;
@@ -0,0 +1,171 @@
+//! ### `users.address.create` Implementation
+//!
+//! This module provides the implementation for the `users.address.create` method of the NameCheap API.
+//!
+//! It stores a new address, described by a [`ContactProfile`], in the account.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value, json };
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Contact };
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_attr, get_node_at };
+
+/// ### Contact Profile
+///
+/// A named address, as stored in the account address book.
+///
+/// #### Fields
+/// - `address_name`: The name of the address, used to match local and stored addresses.
+/// - `contact`: The contact details; `type_` and `read_only` are not stored.
+/// - `is_default`: Whether the address is the default address of the account.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ContactProfile {
+    pub address_name: String,
+    pub contact: Contact,
+    pub is_default: bool,
+}
+
+impl ContactProfile {
+    /// Creates a profile that is not the default address.
+    pub fn new(address_name: &str, contact: Contact) -> Self {
+        ContactProfile { address_name: address_name.to_string(), contact, is_default: false }
+    }
+
+    /// Builds the request parameters shared by `users.address.create` and `users.address.update`.
+    ///
+    /// The address API names two contact fields differently from the domain contacts:
+    /// `Organization` and `Zip`.
+    pub fn to_params(&self) -> Map<String, Value> {
+        let mut params: Map<String, Value> = self.contact
+            .to_params("")
+            .into_iter()
+            .map(|(key, value)| match key.as_str() {
+                "OrganizationName" => ("Organization".to_string(), value),
+                "PostalCode" => ("Zip".to_string(), value),
+                _ => (key, value),
+            })
+            .collect();
+        params.insert("AddressName".to_string(), json!(self.address_name));
+        params.insert("DefaultYN".to_string(), json!(if self.is_default { 1 } else { 0 }));
+        params
+    }
+}
+
+impl NameCheapClient {
+    /// - `users.address.create`: Stores a new address
+    ///
+    /// Returns the ID of the new address. In dry-run mode nothing is stored, the call fails with
+    /// the [`PlannedRequest`](crate::utils::request_builder::PlannedRequest) it would have sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let address_id: i64 = client.users_address_create(&ContactProfile::new("billing", contact)).await.unwrap();
+    /// ```
+    pub async fn users_address_create(&self, profile: &ContactProfile) -> Result<i64, Box<dyn Error>> {
+        let command: String = "namecheap.users.address.create".to_string();
+
+        let request: Request = Request::new(self, command, None, None, Some(Value::Object(profile.to_params())));
+        self.check_policies(&Mutation::Address { address_name: profile.address_name.clone(), address_id: None })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        self.log_contact_response(&response);
+
+        parse_address_id(&response, "AddressCreateResult").ok_or_else(|| {
+            format!("Failed to create the address: {}", profile.address_name).into()
+        })
+    }
+}
+
+/// Parses the address ID of a successful `users.address.create` or `users.address.update` response.
+pub(crate) fn parse_address_id(response: &Value, result: &str) -> Option<i64> {
+    let result: &Value = get_node_at(response, &format!("/ApiResponse/CommandResponse/{}", result))?;
+    if !get_attr::<String>(result, "success").is_some_and(|success| success.eq_ignore_ascii_case("true")) {
+        return None;
+    }
+    get_attr(result, "address_id")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::policy::{ policy_fn, PolicyViolations };
+    use crate::utils::request_builder::PlannedRequest;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    pub(crate) fn sample_contact() -> Contact {
+        Contact {
+            type_: String::new(),
+            address_1: "8939 S. Cross Blvd".to_string(),
+            address_2: String::new(),
+            city: "Phoenix".to_string(),
+            country: "US".to_string(),
+            email_address: "admin@example.com".to_string(),
+            fax: String::new(),
+            first_name: "John".to_string(),
+            job_title: String::new(),
+            last_name: "Smith".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.6613102107".to_string(),
+            phone_ext: String::new(),
+            postal_code: "85044".to_string(),
+            state_province: "AZ".to_string(),
+            state_province_choice: String::new(),
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_params_and_result() {
+        let params: Map<String, Value> = ContactProfile::new("billing", sample_contact()).to_params();
+
+        assert_eq!(params["AddressName"], "billing");
+        assert_eq!(params["Organization"], "Example Inc");
+        assert_eq!(params["Zip"], "85044");
+        assert_eq!(params["DefaultYN"], 0);
+        assert!(!params.contains_key("PostalCode") && !params.contains_key("Fax"));
+
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.address.create">
+            <AddressCreateResult Success="true" AddressId="1041" AddressName="billing" />
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        assert_eq!(parse_address_id(&response, "AddressCreateResult"), Some(1041));
+        assert_eq!(parse_address_id(&response, "AddressUpdateResult"), None);
+    }
+
+    #[tokio::test]
+    async fn test_users_address_create_guards() {
+        let profile: ContactProfile = ContactProfile::new("billing", sample_contact());
+        let client: NameCheapClient = NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        ).with_dry_run(true);
+
+        let error: Box<dyn Error> = client.users_address_create(&profile).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<PlannedRequest>().unwrap().params["AddressName"], "billing");
+
+        let client: NameCheapClient = client.with_policy(policy_fn("read-only-address-book", |mutation| match mutation {
+            Mutation::Address { .. } => Some("The address book is read only".to_string()),
+            _ => None,
+        }));
+        let error: Box<dyn Error> = client.users_address_update(1041, &profile).await.unwrap_err();
+        assert!(error.downcast_ref::<PolicyViolations>().is_some());
+    }
+}
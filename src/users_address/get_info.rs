@@ -0,0 +1,102 @@
+//! ### `users.address.getInfo` Implementation
+//!
+//! This module provides the implementation for the `users.address.getInfo` method of the NameCheap API.
+//!
+//! It retrieves the details of a stored address as a [`ContactProfile`].
+//!
+
+use serde_json::{ Value, json };
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Contact };
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_node_at, parse_string };
+use crate::users_address::create::ContactProfile;
+
+impl NameCheapClient {
+    /// - `users.address.getInfo`: Retrieves a stored address
+    ///
+    /// The contact of the profile can be used as a domain contact, e.g. with
+    /// [`ContactSet::uniform`](crate::ContactSet::uniform).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let profile: ContactProfile = client.users_address_get_info(1041).await.unwrap();
+    /// let contacts: ContactSet = ContactSet::uniform(profile.contact);
+    /// ```
+    pub async fn users_address_get_info(&self, address_id: i64) -> Result<ContactProfile, Box<dyn Error>> {
+        let command: String = "namecheap.users.address.getInfo".to_string();
+        let params: Value = json!({ "AddressId": address_id });
+
+        let response: Value = Request::new(self, command, None, None, Some(params)).send().await?;
+        check_api_errors(&response)?;
+        self.log_contact_response(&response);
+
+        parse_address_info(&response).ok_or_else(|| {
+            format!("Failed to extract the address: {}", address_id).into()
+        })
+    }
+}
+
+/// Parses a full `users.address.getInfo` response.
+///
+/// The details are child elements, named as in the request parameters.
+pub(crate) fn parse_address_info(response: &Value) -> Option<ContactProfile> {
+    let result: &Value = get_node_at(response, "/ApiResponse/CommandResponse/GetAddressInfoResult")?;
+    let field = |key: &str| parse_string(result, key, "");
+
+    Some(ContactProfile {
+        address_name: field("AddressName"),
+        is_default: field("Default_YN").eq_ignore_ascii_case("true"),
+        contact: Contact {
+            type_: String::new(),
+            address_1: field("Address1"),
+            address_2: field("Address2"),
+            city: field("City"),
+            country: field("Country"),
+            email_address: field("EmailAddress"),
+            fax: field("Fax"),
+            first_name: field("FirstName"),
+            job_title: field("JobTitle"),
+            last_name: field("LastName"),
+            organization_name: field("Organization"),
+            phone: field("Phone"),
+            phone_ext: field("PhoneExt"),
+            postal_code: field("Zip"),
+            state_province: field("StateProvince"),
+            state_province_choice: field("StateProvinceChoice"),
+            read_only: false,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_address_info() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.address.getInfo">
+            <GetAddressInfoResult>
+              <AddressId>1041</AddressId><UserName>example</UserName><AddressName>billing</AddressName>
+              <Default_YN>false</Default_YN><FirstName>John</FirstName><LastName>Smith</LastName>
+              <Organization>Example Inc</Organization><Address1>8939 S. Cross Blvd</Address1>
+              <City>Phoenix</City><StateProvince>AZ</StateProvince><Zip>85044</Zip><Country>US</Country>
+              <Phone>+1.6613102107</Phone><EmailAddress>admin@example.com</EmailAddress>
+            </GetAddressInfoResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let profile: ContactProfile = parse_address_info(&response).unwrap();
+
+        assert_eq!(profile.address_name, "billing");
+        assert!(!profile.is_default);
+        assert_eq!(profile.contact.organization_name, "Example Inc");
+        assert_eq!(profile.contact.postal_code, "85044");
+        assert_eq!(profile.contact.address_2, "");
+    }
+}
@@ -0,0 +1,87 @@
+//! ### `users.address.getList` Implementation
+//!
+//! This module provides the implementation for the `users.address.getList` method of the NameCheap API.
+//!
+//! It lists the addresses stored in the account, by ID and name.
+//!
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::response::errors::check_api_errors;
+use crate::response::parse_value::{ get_attr, get_nodes_at, parse_string };
+
+/// ### Address Summary
+///
+/// #### Fields
+/// - `address_id`: The ID of the stored address.
+/// - `address_name`: The name of the address, unique within the account.
+/// - `is_default`: Whether this is the default address of the account.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct AddressSummary {
+    pub address_id: i64,
+    pub address_name: String,
+    pub is_default: bool,
+}
+
+impl NameCheapClient {
+    /// - `users.address.getList`: Lists the stored addresses
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// for address in client.users_address_get_list().await.unwrap() {
+    ///     println!("{} {}", address.address_id, address.address_name);
+    /// }
+    /// ```
+    pub async fn users_address_get_list(&self) -> Result<Vec<AddressSummary>, Box<dyn Error>> {
+        let command: String = "namecheap.users.address.getList".to_string();
+
+        let response: Value = Request::new(self, command, None, None, None).send().await?;
+        check_api_errors(&response)?;
+        self.log_contact_response(&response);
+
+        Ok(parse_address_list(&response))
+    }
+}
+
+/// Parses a full `users.address.getList` response.
+pub(crate) fn parse_address_list(response: &Value) -> Vec<AddressSummary> {
+    get_nodes_at(response, "/ApiResponse/CommandResponse/AddressGetListResult/List")
+        .into_iter()
+        .filter_map(|node| Some(AddressSummary {
+            address_id: get_attr(node, "address_id")?,
+            address_name: parse_string(node, "address_name", ""),
+            is_default: parse_string(node, "is_default", "false").eq_ignore_ascii_case("true"),
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xml_parser::parse_xml_to_json;
+
+    #[test]
+    fn test_parse_address_list() {
+        let response: Value = parse_xml_to_json(
+            r#"<ApiResponse Status="OK"><Errors /><CommandResponse Type="namecheap.users.address.getList">
+            <AddressGetListResult>
+              <List AddressId="0" AddressName="Primary Address" IsDefault="true" />
+              <List AddressId="1041" AddressName="billing" />
+            </AddressGetListResult>
+            </CommandResponse></ApiResponse>"#
+        ).unwrap();
+        let addresses: Vec<AddressSummary> = parse_address_list(&response);
+
+        assert_eq!(addresses.len(), 2);
+        assert!(addresses[0].is_default);
+        assert_eq!((addresses[1].address_id, addresses[1].address_name.as_str()), (1041, "billing"));
+    }
+}
@@ -0,0 +1,24 @@
+//! ## Users Address API
+//! The Users Address API manages the addresses stored in the account, which can be reused as
+//! domain contacts.
+//!
+//! ### Available Methods
+//! - `namecheap.users.address.getList`: Retrieve a list of addresses associated with a user.
+//! - `namecheap.users.address.getInfo`: Retrieve detailed information about a specific address.
+//! - `namecheap.users.address.create`: Create a new address for a user.
+//! - `namecheap.users.address.update`: Update the details of an existing address.
+//!
+//! [`sync`] keeps the stored addresses in line with local [`ContactProfile`](create::ContactProfile)
+//! definitions.
+//!
+
+/// - **users.address.getList**
+pub mod get_list;
+/// - **users.address.getInfo**
+pub mod get_info;
+/// - **users.address.create**
+pub mod create;
+/// - **users.address.update**
+pub mod update;
+/// - **Address book sync**
+pub mod sync;
@@ -0,0 +1,158 @@
+//! ### Address Book Sync
+//!
+//! Keeps the addresses stored in the account in line with local [`ContactProfile`]
+//! definitions, e.g. read from a config file. Addresses are matched by name: missing ones are
+//! created, changed ones updated, stored addresses without a local profile are left alone.
+//!
+//! The stable address IDs returned can then be referenced instead of inline contacts, see
+//! [`users_address_get_info`](crate::NameCheapClient::users_address_get_info).
+//!
+
+use serde::{ Serialize, Deserialize };
+use std::collections::{ BTreeMap, HashSet };
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::policy::Mutation;
+use crate::users_address::create::ContactProfile;
+use crate::users_address::get_list::AddressSummary;
+use crate::utils::correlation::in_operation;
+
+/// What the sync did with one profile.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum AddressSyncAction {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// ### Synced Address
+///
+/// #### Fields
+/// - `address_name`: The name of the profile.
+/// - `address_id`: The ID of the stored address, `None` for an address a dry run would create.
+/// - `action`: Whether the address was created, updated or already up to date.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct SyncedAddress {
+    pub address_name: String,
+    pub address_id: Option<i64>,
+    pub action: AddressSyncAction,
+}
+
+/// Returns the address IDs of a sync by address name.
+pub fn address_ids(synced: &[SyncedAddress]) -> BTreeMap<String, i64> {
+    synced
+        .iter()
+        .filter_map(|address| Some((address.address_name.clone(), address.address_id?)))
+        .collect()
+}
+
+/// Decides what to do with `profile` given the stored address of the same name, if any.
+pub fn sync_action(profile: &ContactProfile, stored: Option<&ContactProfile>) -> AddressSyncAction {
+    match stored {
+        None => AddressSyncAction::Created,
+        // Compared as sent, so fields the address book does not store are ignored
+        Some(stored) if stored.to_params() == profile.to_params() => AddressSyncAction::Unchanged,
+        Some(_) => AddressSyncAction::Updated,
+    }
+}
+
+impl NameCheapClient {
+    /// Creates and updates stored addresses to match `profiles`.
+    ///
+    /// Returns one `SyncedAddress` per profile, in order. In dry-run mode nothing is changed and
+    /// the actions that would be taken are returned, still checked against the client policies.
+    /// Two profiles with the same name are an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let synced = client.sync_address_book(vec![ContactProfile::new("billing", contact)]).await.unwrap();
+    /// let ids = address_ids(&synced);
+    /// println!("billing is address {}", ids["billing"]);
+    /// ```
+    pub async fn sync_address_book(&self, profiles: Vec<ContactProfile>) -> Result<Vec<SyncedAddress>, Box<dyn Error>> {
+        let mut names: HashSet<&str> = HashSet::new();
+        if let Some(duplicate) = profiles.iter().find(|profile| !names.insert(profile.address_name.as_str())) {
+            return Err(format!("Duplicate address profile: {}", duplicate.address_name).into());
+        }
+
+        in_operation("users.address.sync", async {
+            let existing: Vec<AddressSummary> = self.users_address_get_list().await?;
+            let mut synced: Vec<SyncedAddress> = Vec::with_capacity(profiles.len());
+
+            for profile in &profiles {
+                let summary: Option<&AddressSummary> = existing
+                    .iter()
+                    .find(|summary| summary.address_name == profile.address_name);
+                let stored: Option<ContactProfile> = match summary {
+                    Some(summary) => Some(self.users_address_get_info(summary.address_id).await?),
+                    None => None,
+                };
+                let action: AddressSyncAction = sync_action(profile, stored.as_ref());
+
+                let address_id: Option<i64> = match (action, summary) {
+                    (AddressSyncAction::Unchanged, Some(summary)) => Some(summary.address_id),
+                    _ if self.dry_run => {
+                        // A change the policies refuse fails the dry run as it would the sync
+                        let address_id: Option<i64> = summary.map(|summary| summary.address_id);
+                        self.check_policies(&Mutation::Address { address_name: profile.address_name.clone(), address_id })?;
+                        address_id
+                    }
+                    (AddressSyncAction::Updated, Some(summary)) => {
+                        Some(self.users_address_update(summary.address_id, profile).await?)
+                    }
+                    _ => Some(self.users_address_create(profile).await?),
+                };
+                synced.push(SyncedAddress { address_name: profile.address_name.clone(), address_id, action });
+            }
+            Ok::<_, Box<dyn Error>>(synced)
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::users_address::create::tests::sample_contact;
+
+    #[test]
+    fn test_sync_action() {
+        let profile: ContactProfile = ContactProfile::new("billing", sample_contact());
+        let mut stored: ContactProfile = profile.clone();
+        stored.contact.type_ = "Registrant".to_string();
+
+        assert_eq!(sync_action(&profile, None), AddressSyncAction::Created);
+        assert_eq!(sync_action(&profile, Some(&stored)), AddressSyncAction::Unchanged);
+        stored.contact.city = "Tempe".to_string();
+        assert_eq!(sync_action(&profile, Some(&stored)), AddressSyncAction::Updated);
+    }
+
+    #[test]
+    fn test_address_ids() {
+        let synced: Vec<SyncedAddress> = vec![
+            SyncedAddress { address_name: "billing".to_string(), address_id: Some(1041), action: AddressSyncAction::Unchanged },
+            SyncedAddress { address_name: "tech".to_string(), address_id: None, action: AddressSyncAction::Created },
+        ];
+        assert_eq!(address_ids(&synced), BTreeMap::from([("billing".to_string(), 1041)]));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_profiles() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let profile: ContactProfile = ContactProfile::new("billing", sample_contact());
+        let error: Box<dyn Error> = client.sync_address_book(vec![profile.clone(), profile]).await.unwrap_err();
+        assert!(error.to_string().contains("Duplicate address profile"));
+    }
+}
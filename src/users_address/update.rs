@@ -0,0 +1,49 @@
+//! ### `users.address.update` Implementation
+//!
+//! This module provides the implementation for the `users.address.update` method of the NameCheap API.
+//!
+//! It replaces the details of a stored address with those of a [`ContactProfile`].
+//!
+
+use serde_json::{ Map, Value, json };
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::utils::request_builder::Request;
+use crate::policy::Mutation;
+use crate::response::errors::check_api_errors;
+use crate::users_address::create::{ parse_address_id, ContactProfile };
+
+impl NameCheapClient {
+    /// - `users.address.update`: Updates a stored address
+    ///
+    /// Returns the ID of the address. In dry-run mode nothing is stored, the call fails with the
+    /// [`PlannedRequest`](crate::utils::request_builder::PlannedRequest) it would have sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// client.users_address_update(1041, &ContactProfile::new("billing", contact)).await.unwrap();
+    /// ```
+    pub async fn users_address_update(&self, address_id: i64, profile: &ContactProfile) -> Result<i64, Box<dyn Error>> {
+        let command: String = "namecheap.users.address.update".to_string();
+        let mut params: Map<String, Value> = profile.to_params();
+        params.insert("AddressId".to_string(), json!(address_id));
+
+        let request: Request = Request::new(self, command, None, None, Some(Value::Object(params)));
+        self.check_policies(&Mutation::Address { address_name: profile.address_name.clone(), address_id: Some(address_id) })?;
+        if request.is_dry_run() {
+            return Err(request.planned().into());
+        }
+
+        let _mutation = self.begin_mutation()?;
+        let response: Value = request.send().await?;
+        check_api_errors(&response)?;
+        self.log_contact_response(&response);
+
+        parse_address_id(&response, "AddressUpdateResult").ok_or_else(|| {
+            format!("Failed to update the address: {}", profile.address_name).into()
+        })
+    }
+}
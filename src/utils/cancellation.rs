@@ -0,0 +1,144 @@
+//! ### Cancellation
+//!
+//! Long operations (batches, zone plans across the account, auto-pagination, rate limiter and
+//! cooldown waits) can be cancelled with a `CancellationToken`, e.g. when the server request
+//! they run for times out:
+//! - [`NameCheapClient::with_cancellation`] cancels every request of the client and of its later
+//!   clones: a request not sent yet, or still waiting for the rate limiter or a cooldown, fails
+//!   with [`Cancelled`]. Requests already sent complete.
+//! - [`Batch::with_cancellation`](crate::batch::Batch::with_cancellation) stops starting items
+//!   once the token is cancelled, the report keeps the finished ones.
+//!
+//! Either way the operation ends with a typed [`Cancelled`] reporting how far it got.
+//!
+//! ```rust
+//! let token = CancellationToken::new();
+//! let scoped = client.clone().with_cancellation(token.clone());
+//! tokio::spawn(async move { tokio::time::sleep(Duration::from_secs(25)).await; token.cancel() });
+//! let report = scoped.replace_address_across_zones("203.0.113.10", "198.51.100.10", &["A"], false).await?;
+//! if let Some(cancelled) = &report.cancelled {
+//!     println!("{}", cancelled);
+//! }
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::hash::{ Hash, Hasher };
+use tokio_util::sync::CancellationToken;
+
+// crate imports
+use crate::NameCheapClient;
+
+/// An operation stopped by its cancellation token.
+///
+/// #### Fields
+/// - `operation`: The cancelled operation, e.g. `batch.run` or an API command.
+/// - `completed`: The number of items completed before the cancellation.
+/// - `total`: The number of items the operation was started with, if known.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Cancelled {
+    pub operation: String,
+    pub completed: usize,
+    pub total: Option<usize>,
+}
+
+impl Cancelled {
+    /// Creates the error of `operation`, cancelled after `completed` of `total` items.
+    pub fn new(operation: &str, completed: usize, total: Option<usize>) -> Self {
+        Cancelled { operation: operation.to_string(), completed, total }
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{} cancelled after {} of {} items", self.operation, self.completed, total),
+            None => write!(f, "{} cancelled after {} items", self.operation, self.completed),
+        }
+    }
+}
+
+impl Error for Cancelled {}
+
+/// An optional cancellation token.
+///
+/// Like `ProgressHandle` it is not part of the identity of the value holding it: it compares
+/// and hashes equal regardless of the token.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Option<CancellationToken>);
+
+impl CancelHandle {
+    /// Creates a handle watching `token`.
+    pub fn new(token: CancellationToken) -> Self {
+        CancelHandle(Some(token))
+    }
+
+    /// Returns the token, if one is set.
+    pub fn token(&self) -> Option<&CancellationToken> {
+        self.0.as_ref()
+    }
+
+    /// Returns `true` once the token is cancelled, never without a token.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Runs `future` to completion unless the token is cancelled first, then returns `None`.
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        match &self.0 {
+            Some(token) => tokio::select! {
+                // Checked first, so a cancelled token never starts the future
+                biased;
+                _ = token.cancelled() => None,
+                output = future => Some(output),
+            },
+            None => Some(future.await),
+        }
+    }
+}
+
+impl PartialEq for CancelHandle {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for CancelHandle {}
+
+impl Hash for CancelHandle {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl NameCheapClient {
+    /// Cancels the requests of this client and of its later clones once `token` is cancelled.
+    ///
+    /// Scope the token to one operation by setting it on a clone:
+    /// `client.clone().with_cancellation(token)`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.shared = self.shared.with_cancellation(CancelHandle::new(token));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_handle() {
+        assert_eq!(CancelHandle::default().run(async { 1 }).await, Some(1));
+
+        let token: CancellationToken = CancellationToken::new();
+        let handle: CancelHandle = CancelHandle::new(token.clone());
+        assert_eq!(handle.run(async { 2 }).await, Some(2));
+
+        token.cancel();
+        assert!(handle.is_cancelled());
+        assert_eq!(handle.run(async { 3 }).await, None);
+        assert_eq!(Cancelled::new("batch.run", 2, Some(5)).to_string(), "batch.run cancelled after 2 of 5 items");
+    }
+}
@@ -0,0 +1,247 @@
+//! ### Charges Ledger
+//!
+//! Purchases (`domains.create`, `domains.renew`, `domains.reactivate`, `ssl.create`, ...)
+//! report a `ChargedAmount` in the account currency. A `ChargesLedger` attached to the client
+//! with [`NameCheapClient::with_charges_ledger`] records every one of them, for this client and
+//! all its clones, so a bulk job can report exactly what it spent: in total, per command and
+//! per domain.
+//!
+//! Totals are kept per currency, amounts of different currencies are never added. A total too
+//! large to represent is an error rather than a wrong amount.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{ Arc, Mutex };
+
+// crate imports
+use crate::NameCheapClient;
+use crate::response::money::{ detect_currency, parse_money, Currency, Money };
+use crate::response::parse_value::text_of;
+use crate::utils::correlation::current_correlation_id;
+
+/// ### Charge
+///
+/// #### Fields
+/// - `command`: The API command that charged the account, e.g. `namecheap.domains.renew`.
+/// - `domain`: The domain charged for (optional).
+/// - `amount`: The charged amount.
+/// - `correlation_id`: The operation the charge was made in (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Charge {
+    pub command: String,
+    pub domain: Option<String>,
+    pub amount: Money,
+    pub correlation_id: Option<String>,
+}
+
+/// Accumulates the charges of a session.
+#[derive(Debug, Default)]
+pub struct ChargesLedger {
+    charges: Mutex<Vec<Charge>>,
+}
+
+impl ChargesLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a charge.
+    pub fn record(&self, charge: Charge) {
+        if let Ok(mut charges) = self.charges.lock() {
+            charges.push(charge);
+        }
+    }
+
+    /// Records the charges found in a parsed response of `command`.
+    ///
+    /// Every result element with a `ChargedAmount` is a charge, for the domain named by the
+    /// element or else by the request. Responses without a currency are in the default one.
+    pub fn record_response(&self, command: &str, domain_name: Option<&str>, response: &Value) {
+        let currency: Currency = detect_currency(response).unwrap_or_default();
+        let mut found: Vec<Charge> = Vec::new();
+        collect_charges(response, &mut |result: &Value| {
+            let Some(amount) = parse_money(result, "charged_amount", &currency) else { return };
+            let domain: Option<String> = ["domain", "domain_name"]
+                .iter()
+                .find_map(|key| result.get(*key).and_then(text_of))
+                .map(str::to_string)
+                .or_else(|| domain_name.map(str::to_string));
+            found.push(Charge {
+                command: command.to_string(),
+                domain,
+                amount,
+                correlation_id: current_correlation_id(),
+            });
+        });
+        for charge in found {
+            self.record(charge);
+        }
+    }
+
+    /// Returns every recorded charge, oldest first.
+    pub fn charges(&self) -> Vec<Charge> {
+        self.charges.lock().map(|charges| charges.clone()).unwrap_or_default()
+    }
+
+    /// Returns `true` if nothing was charged.
+    pub fn is_empty(&self) -> bool {
+        self.charges.lock().map(|charges| charges.is_empty()).unwrap_or(true)
+    }
+
+    /// Returns the total spent, one amount per currency ordered by currency code.
+    pub fn total(&self) -> Result<Vec<Money>, String> {
+        sum(self.charges().iter().map(|charge| &charge.amount))
+    }
+
+    /// Returns the totals per command.
+    pub fn totals_by_command(&self) -> Result<BTreeMap<String, Vec<Money>>, String> {
+        self.totals_by(|charge| Some(charge.command.clone()))
+    }
+
+    /// Returns the totals per domain; charges without a domain are left out.
+    pub fn totals_by_domain(&self) -> Result<BTreeMap<String, Vec<Money>>, String> {
+        self.totals_by(|charge| charge.domain.clone())
+    }
+
+    /// Forgets every recorded charge, e.g. between two jobs.
+    pub fn clear(&self) {
+        if let Ok(mut charges) = self.charges.lock() {
+            charges.clear();
+        }
+    }
+
+    fn totals_by(&self, key: impl Fn(&Charge) -> Option<String>) -> Result<BTreeMap<String, Vec<Money>>, String> {
+        let charges: Vec<Charge> = self.charges();
+        let mut groups: BTreeMap<String, Vec<&Money>> = BTreeMap::new();
+        for charge in &charges {
+            if let Some(key) = key(charge) {
+                groups.entry(key).or_default().push(&charge.amount);
+            }
+        }
+        groups.into_iter().map(|(key, amounts)| Ok((key, sum(amounts)?))).collect()
+    }
+}
+
+/// Calls `visit` on every object of the response.
+fn collect_charges(json: &Value, visit: &mut impl FnMut(&Value)) {
+    match json {
+        Value::Object(map) => {
+            visit(json);
+            for (key, value) in map {
+                if key != "$text" {
+                    collect_charges(value, visit);
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_charges(value, visit)),
+        _ => {}
+    }
+}
+
+/// Adds up amounts per currency, ordered by currency code.
+///
+/// Fails if a total overflows.
+pub(crate) fn sum<'a>(amounts: impl IntoIterator<Item = &'a Money>) -> Result<Vec<Money>, String> {
+    let mut totals: BTreeMap<String, Money> = BTreeMap::new();
+    for money in amounts {
+        match totals.get_mut(money.currency.code()) {
+            Some(total) => *total = total.try_add(money)?,
+            None => {
+                totals.insert(money.currency.code().to_string(), money.clone());
+            }
+        }
+    }
+    Ok(totals.into_values().collect())
+}
+
+impl NameCheapClient {
+    /// Records every charge of this client and its clones in `ledger`.
+    ///
+    /// Keep a clone of the `Arc` to read the totals. Like `with_rate_limiter`, call this before
+    /// cloning the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let ledger = Arc::new(ChargesLedger::new());
+    /// let client = NameCheapClient::new_from_env()?.with_charges_ledger(ledger.clone());
+    /// // ... renew domains ...
+    /// println!("Spent {:?}", ledger.total()?);
+    /// ```
+    pub fn with_charges_ledger(mut self, ledger: Arc<ChargesLedger>) -> Self {
+        self.shared = self.shared.with_charges(ledger);
+        self
+    }
+
+    /// Returns the attached charges ledger, if any.
+    pub fn charges_ledger(&self) -> Option<&ChargesLedger> {
+        self.shared.charges()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn amounts(totals: &[Money]) -> Vec<String> {
+        totals.iter().map(|money| money.to_string()).collect()
+    }
+
+    #[test]
+    fn test_record_response() {
+        let ledger: ChargesLedger = ChargesLedger::new();
+        let renew: Value = json!({ "ApiResponse": { "CommandResponse": {
+            "DomainRenewResult": { "domain_name": "example.com", "charged_amount": "10.8700" }
+        } } });
+        let create: Value = json!({ "ApiResponse": { "CommandResponse": {
+            "DomainCreateResult": { "domain": "example.net", "charged_amount": "20.3600" }
+        } } });
+        ledger.record_response("namecheap.domains.renew", None, &renew);
+        ledger.record_response("namecheap.domains.create", Some("example.net"), &create);
+        ledger.record_response("namecheap.domains.renew", Some("example.com"), &renew);
+        ledger.record_response("namecheap.domains.getList", None, &json!({ "status": "OK" }));
+
+        assert_eq!(ledger.charges().len(), 3);
+        assert_eq!(amounts(&ledger.total().unwrap()), ["42.1000 USD"]);
+        assert_eq!(amounts(&ledger.totals_by_command().unwrap()["namecheap.domains.renew"]), ["21.7400 USD"]);
+        assert_eq!(amounts(&ledger.totals_by_domain().unwrap()["example.net"]), ["20.3600 USD"]);
+
+        ledger.clear();
+        assert!(ledger.is_empty());
+    }
+
+    #[test]
+    fn test_currencies_are_not_mixed() {
+        let ledger: ChargesLedger = ChargesLedger::new();
+        let eur: Value = json!({ "Result": { "currency": "EUR", "charged_amount": "5.00" } });
+        ledger.record_response("namecheap.ssl.create", Some("example.com"), &eur);
+        ledger.record_response("namecheap.ssl.create", Some("example.com"), &json!({ "Result": { "charged_amount": "1.5" } }));
+
+        assert_eq!(amounts(&ledger.totals_by_domain().unwrap()["example.com"]), ["5.00 EUR", "1.5 USD"]);
+    }
+
+    #[test]
+    fn test_ledger_shared_by_clones() {
+        let ledger: Arc<ChargesLedger> = Arc::new(ChargesLedger::new());
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_charges_ledger(ledger.clone());
+
+        client.clone().charges_ledger().unwrap().record_response(
+            "namecheap.domains.renew",
+            Some("example.com"),
+            &json!({ "Result": { "charged_amount": "1.00" } })
+        );
+        assert_eq!(ledger.charges().len(), 1);
+        assert_eq!(ledger.charges()[0].domain.as_deref(), Some("example.com"));
+    }
+}
@@ -0,0 +1,139 @@
+//! ### Throttling Cooldown
+//!
+//! A `RateLimiter` paces calls, but NameCheap may still throttle the account: its quota is
+//! shared with other tools, or the hourly budget runs out. Once one request is throttled, every
+//! other call of the client would fail the same way and burn more of the budget. The first
+//! throttled response therefore starts a cooldown shared by all clones of the client: calls
+//! issued during it wait until it ends, then go out again.
+//!
+//! A throttled response is an HTTP `429` or a `Too many requests` API error. The cooldown lasts
+//! for its `Retry-After` header when present, `QUOTA_WINDOW` otherwise.
+
+use reqwest::header::{ HeaderMap, RETRY_AFTER };
+use reqwest::StatusCode;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+// crate imports
+use crate::utils::validate::RATE_LIMIT_ERRORS;
+
+/// How long the per-minute quota takes to reset, the cooldown when no `Retry-After` is given.
+pub const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+/// A pause shared by all clones of a client.
+#[derive(Debug, Default)]
+pub struct Cooldown {
+    until: Mutex<Option<Instant>>,
+}
+
+impl Cooldown {
+    /// Creates a cooldown that is not active.
+    pub fn new() -> Self {
+        Cooldown::default()
+    }
+
+    /// Pauses calls for `duration`, keeping a longer cooldown already running.
+    pub fn trigger(&self, duration: Duration) {
+        let until: Instant = Instant::now() + duration;
+        if let Ok(mut current) = self.until.lock() {
+            *current = Some(current.map_or(until, |current| current.max(until)));
+        }
+    }
+
+    /// Returns the time left until calls resume, `None` if they are not paused.
+    pub fn remaining(&self) -> Option<Duration> {
+        let until: Instant = (*self.until.lock().ok()?)?;
+        Some(until.saturating_duration_since(Instant::now())).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Waits until the cooldown ends, at once if it is not active.
+    ///
+    /// A cooldown extended while waiting is waited for as well.
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.remaining() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// Starts a cooldown if the response was throttled.
+    ///
+    /// # Parameters
+    ///
+    /// - `status`: The HTTP status of the response
+    /// - `headers`: The response headers, read for `Retry-After`
+    /// - `body`: The XML body, if it was read
+    ///
+    /// # Returns
+    ///
+    /// The cooldown started, or `None` if the response was not throttled
+    pub fn observe(&self, status: StatusCode, headers: &HeaderMap, body: Option<&str>) -> Option<Duration> {
+        if status != StatusCode::TOO_MANY_REQUESTS && !body.is_some_and(is_throttled) {
+            return None;
+        }
+
+        let duration: Duration = retry_after(headers).unwrap_or(QUOTA_WINDOW);
+        warn!("Request throttled, pausing all calls for {:?}", duration);
+        self.trigger(duration);
+        Some(duration)
+    }
+}
+
+/// Returns `true` if an XML body carries a rate limit error.
+fn is_throttled(body: &str) -> bool {
+    RATE_LIMIT_ERRORS.iter().any(|number| body.contains(&format!("Number=\"{}\"", number)))
+        || body.to_lowercase().contains("too many requests")
+}
+
+/// Reads a `Retry-After` header given in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds: u64 = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_observe() {
+        let cooldown: Cooldown = Cooldown::new();
+        let mut headers: HeaderMap = HeaderMap::new();
+        let ok: &str = r#"<ApiResponse Status="OK"><Errors /></ApiResponse>"#;
+        let throttled: &str = r#"<ApiResponse Status="ERROR"><Errors><Error Number="500000">Too many requests</Error></Errors></ApiResponse>"#;
+
+        assert_eq!(cooldown.observe(StatusCode::OK, &headers, Some(ok)), None);
+        assert_eq!(cooldown.remaining(), None);
+
+        assert_eq!(cooldown.observe(StatusCode::OK, &headers, Some(throttled)), Some(QUOTA_WINDOW));
+        assert!(cooldown.remaining().unwrap() > Duration::from_secs(59));
+
+        // A shorter cooldown does not cut the running one short
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+        assert_eq!(cooldown.observe(StatusCode::TOO_MANY_REQUESTS, &headers, None), Some(Duration::from_secs(5)));
+        assert!(cooldown.remaining().unwrap() > Duration::from_secs(59));
+    }
+
+    #[tokio::test]
+    async fn test_wait_shared_between_tasks() {
+        let cooldown: std::sync::Arc<Cooldown> = std::sync::Arc::new(Cooldown::new());
+        let start: Instant = Instant::now();
+        cooldown.wait().await;
+        assert!(start.elapsed() < Duration::from_millis(10));
+
+        cooldown.trigger(Duration::from_millis(30));
+        let waiters: Vec<_> = (0..3)
+            .map(|_| {
+                let cooldown = cooldown.clone();
+                tokio::spawn(async move { cooldown.wait().await })
+            })
+            .collect();
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(cooldown.remaining(), None);
+    }
+}
@@ -0,0 +1,119 @@
+//! ### Correlation IDs
+//!
+//! Every logical operation (planning and applying a zone change, a batch run such as a bulk
+//! renewal, a CLI command) runs under one `CorrelationId`. It is recorded on the
+//! `operation` tracing span wrapping the work, so every log line of every request made for
+//! it carries the ID, and it is stamped on the audit records (`LedgerEntry`,
+//! `JournalEntry`) and the typed errors (`ApiError`, `ZoneConflict`) created inside it.
+//! A user reporting a failure can quote the one ID found in the error message.
+//!
+//! Nested operations keep the ID of the outermost one. The ID is task-local: work moved to
+//! another task with `tokio::spawn` must be wrapped in [`with_correlation_id`] again.
+
+use ring::rand::{ SecureRandom, SystemRandom };
+use serde::{ Serialize, Deserialize };
+use std::fmt;
+use std::future::Future;
+use tracing::Instrument;
+
+tokio::task_local! {
+    static CURRENT: CorrelationId;
+}
+
+/// An operation identifier, 16 lowercase hex digits.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(transparent)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    /// Generates a new random ID.
+    pub fn new() -> Self {
+        let mut bytes: [u8; 8] = [0; 8];
+        if SystemRandom::new().fill(&mut bytes).is_err() {
+            // Fall back to the clock, an ID only has to be unique enough to search logs for
+            let nanos: u128 = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            bytes = (nanos as u64).to_be_bytes();
+        }
+        CorrelationId(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    /// Uses an existing ID, e.g. one received from a caller.
+    pub fn from_string(id: impl Into<String>) -> Self {
+        CorrelationId(id.into())
+    }
+
+    /// Returns the ID.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the ID of the operation the current task runs in, if any.
+    pub fn current() -> Option<CorrelationId> {
+        CURRENT.try_with(|id| id.clone()).ok()
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Returns the current correlation ID as a string, for stamping records and errors.
+pub fn current_correlation_id() -> Option<String> {
+    CorrelationId::current().map(|id| id.0)
+}
+
+/// Runs `future` as the operation `name`.
+///
+/// Inside another operation the ID of that operation is kept, otherwise a new one is
+/// generated.
+pub async fn in_operation<F: Future>(name: &str, future: F) -> F::Output {
+    let id: CorrelationId = CorrelationId::current().unwrap_or_default();
+    with_correlation_id(id, name, future).await
+}
+
+/// Runs `future` as the operation `name` under the given ID.
+pub async fn with_correlation_id<F: Future>(id: CorrelationId, name: &str, future: F) -> F::Output {
+    let span = tracing::info_span!("operation", operation = name, correlation_id = %id);
+    CURRENT.scope(id, future.instrument(span)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_ids_differ() {
+        let id: CorrelationId = CorrelationId::new();
+        assert_eq!(id.as_str().len(), 16);
+        assert!(id.as_str().chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, CorrelationId::new());
+    }
+
+    #[tokio::test]
+    async fn test_nested_operations_keep_the_outer_id() {
+        assert_eq!(current_correlation_id(), None);
+
+        let outer: CorrelationId = CorrelationId::from_string("0123456789abcdef");
+        let (seen_outer, seen_inner) = with_correlation_id(outer, "bulk", async {
+            let inner = in_operation("item", async { current_correlation_id() }).await;
+            (current_correlation_id(), inner)
+        }).await;
+        assert_eq!(seen_outer.as_deref(), Some("0123456789abcdef"));
+        assert_eq!(seen_inner.as_deref(), Some("0123456789abcdef"));
+
+        let fresh: Option<String> = in_operation("plan", async { current_correlation_id() }).await;
+        assert!(fresh.is_some_and(|id| id != "0123456789abcdef"));
+    }
+}
@@ -0,0 +1,307 @@
+//! ### Encrypted Credential Cache
+//!
+//! Stores the credentials of the account in a file encrypted with a passphrase, so operator
+//! machines need no plaintext `.env` file. The key is derived with PBKDF2-HMAC-SHA256 from the
+//! passphrase and a random salt, the credentials are sealed with ChaCha20-Poly1305. The file
+//! is JSON holding the KDF parameters, the nonce and the ciphertext, and is only readable by
+//! its owner.
+//!
+//! The CLI writes it with `namecheap auth login`; daemons can read it with
+//! [`CredentialCache::load`].
+
+use ring::aead::{ Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN };
+use ring::pbkdf2::{ self, PBKDF2_HMAC_SHA256 };
+use ring::rand::{ SecureRandom, SystemRandom };
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+use std::fmt;
+use std::fs::{ self, OpenOptions };
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{ Path, PathBuf };
+
+// crate imports
+use crate::NameCheapClient;
+
+/// The PBKDF2 iterations of new cache files.
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+/// The most PBKDF2 iterations accepted, so a tampered file cannot stall [`CredentialCache::load`].
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// ### Stored Credentials
+///
+/// #### Fields
+/// - `user_name`: The account user name.
+/// - `api_key`: The API key.
+/// - `client_ip`: The whitelisted client IP.
+/// - `production`: Whether to use the production API.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct StoredCredentials {
+    pub user_name: String,
+    pub api_key: String,
+    pub client_ip: String,
+    pub production: bool,
+}
+
+impl fmt::Debug for StoredCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredCredentials")
+            .field("user_name", &self.user_name)
+            .field("api_key", &"<redacted>")
+            .field("client_ip", &self.client_ip)
+            .field("production", &self.production)
+            .finish()
+    }
+}
+
+impl StoredCredentials {
+    /// Creates a client with the credentials.
+    pub fn client(&self) -> NameCheapClient {
+        NameCheapClient::new(
+            self.user_name.clone(),
+            self.api_key.clone(),
+            self.client_ip.clone(),
+            self.user_name.clone(),
+            self.production
+        )
+    }
+}
+
+/// The on-disk format of the cache.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// An error reading the credential cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CredentialCacheError {
+    /// The passphrase does not decrypt the cache, or the file was tampered with.
+    WrongPassphrase,
+    /// The file is not a credential cache of a supported version.
+    Invalid(String),
+}
+
+impl fmt::Display for CredentialCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialCacheError::WrongPassphrase => write!(f, "Wrong passphrase for the credential cache"),
+            CredentialCacheError::Invalid(reason) => write!(f, "Invalid credential cache: {}", reason),
+        }
+    }
+}
+
+impl Error for CredentialCacheError {}
+
+/// ### Credential Cache
+///
+/// #### Fields
+/// - `path`: The cache file.
+/// - `iterations`: The PBKDF2 iterations used when saving.
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct CredentialCache {
+    pub path: PathBuf,
+    pub iterations: u32,
+}
+
+impl CredentialCache {
+    /// Creates a cache stored at `path`.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        CredentialCache { path: path.as_ref().to_path_buf(), iterations: DEFAULT_KDF_ITERATIONS }
+    }
+
+    /// Sets the PBKDF2 iterations used when saving, at most [`MAX_KDF_ITERATIONS`].
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations.clamp(1, MAX_KDF_ITERATIONS);
+        self
+    }
+
+    /// Returns `true` if the cache file exists.
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    /// Encrypts `credentials` with `passphrase` and writes them, replacing the file.
+    pub fn save(&self, credentials: &StoredCredentials, passphrase: &str) -> Result<(), Box<dyn Error>> {
+        let random: SystemRandom = SystemRandom::new();
+        let mut salt: [u8; SALT_LEN] = [0; SALT_LEN];
+        let mut nonce: [u8; NONCE_LEN] = [0; NONCE_LEN];
+        random.fill(&mut salt).map_err(|_| "Failed to generate a salt")?;
+        random.fill(&mut nonce).map_err(|_| "Failed to generate a nonce")?;
+
+        let key: LessSafeKey = derive_key(passphrase, &salt, self.iterations);
+        let mut sealed: Vec<u8> = serde_json::to_vec(credentials)?;
+        key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| "Failed to encrypt the credentials")?;
+
+        let file: CacheFile = CacheFile {
+            version: 1,
+            kdf: "pbkdf2-sha256".to_string(),
+            iterations: self.iterations,
+            salt: to_hex(&salt),
+            nonce: to_hex(&nonce),
+            ciphertext: to_hex(&sealed),
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        // Left over by an interrupted save
+        match fs::remove_file(&tmp) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        }
+
+        // Created owner-only, the credentials are never readable by others
+        let mut options: OpenOptions = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut handle: fs::File = options.open(&tmp)?;
+        handle.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+        handle.sync_all()?;
+        drop(handle);
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Reads and decrypts the credentials.
+    pub fn load(&self, passphrase: &str) -> Result<StoredCredentials, Box<dyn Error>> {
+        let text: String = fs::read_to_string(&self.path)?;
+        let file: CacheFile = serde_json::from_str(&text)
+            .map_err(|err| CredentialCacheError::Invalid(err.to_string()))?;
+        if file.version != 1 || file.kdf != "pbkdf2-sha256" {
+            return Err(CredentialCacheError::Invalid(format!("unsupported version {} ({})", file.version, file.kdf)).into());
+        }
+        if !(1..=MAX_KDF_ITERATIONS).contains(&file.iterations) {
+            return Err(CredentialCacheError::Invalid(format!("{} iterations is out of range", file.iterations)).into());
+        }
+
+        let invalid = |field: &str| CredentialCacheError::Invalid(format!("malformed {}", field));
+        let salt: Vec<u8> = from_hex(&file.salt).ok_or_else(|| invalid("salt"))?;
+        let nonce: [u8; NONCE_LEN] = from_hex(&file.nonce)
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| invalid("nonce"))?;
+        let mut sealed: Vec<u8> = from_hex(&file.ciphertext).ok_or_else(|| invalid("ciphertext"))?;
+
+        let key: LessSafeKey = derive_key(passphrase, &salt, file.iterations);
+        let plain: &mut [u8] = key
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| CredentialCacheError::WrongPassphrase)?;
+        Ok(serde_json::from_slice(plain).map_err(|err| CredentialCacheError::Invalid(err.to_string()))?)
+    }
+
+    /// Deletes the cache file, returns `false` if there was none.
+    pub fn remove(&self) -> Result<bool, Box<dyn Error>> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> LessSafeKey {
+    let mut key: [u8; KEY_LEN] = [0; KEY_LEN];
+    let iterations: NonZeroU32 = NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN);
+    pbkdf2::derive(PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &key).expect("key length matches the algorithm"))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials() -> StoredCredentials {
+        StoredCredentials {
+            user_name: "example".to_string(),
+            api_key: "0123456789abcdef".to_string(),
+            client_ip: "203.0.113.10".to_string(),
+            production: true,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-credentials-{}.enc", std::process::id()));
+        let cache: CredentialCache = CredentialCache::new(&path).with_iterations(1_000);
+
+        cache.save(&credentials(), "correct horse").unwrap();
+        let text: String = fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("0123456789abcdef"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        assert_eq!(cache.load("correct horse").unwrap(), credentials());
+        let error: Box<dyn Error> = cache.load("wrong").unwrap_err();
+        assert_eq!(error.downcast_ref::<CredentialCacheError>(), Some(&CredentialCacheError::WrongPassphrase));
+
+        assert!(cache.remove().unwrap());
+        assert!(!cache.remove().unwrap());
+    }
+
+    #[test]
+    fn test_load_rejects_tampered_iterations() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-credentials-iterations-{}.enc", std::process::id()));
+        let cache: CredentialCache = CredentialCache::new(&path).with_iterations(1_000);
+        cache.save(&credentials(), "correct horse").unwrap();
+
+        for iterations in [0, u32::MAX] {
+            let mut file: CacheFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            file.iterations = iterations;
+            fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+            let error: Box<dyn Error> = cache.load("correct horse").unwrap_err();
+            assert!(matches!(error.downcast_ref::<CredentialCacheError>(), Some(CredentialCacheError::Invalid(_))));
+        }
+        assert_eq!(CredentialCache::new(&path).with_iterations(u32::MAX).iterations, MAX_KDF_ITERATIONS);
+        cache.remove().unwrap();
+    }
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        assert!(!format!("{:?}", credentials()).contains("0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(from_hex(&to_hex(&[0, 15, 255])), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! ### Credential Helpers
+//!
+//! A credential helper is an external command printing the API key on stdout, e.g. a password
+//! manager CLI:
+//!
+//! ```text
+//! op read op://vault/namecheap/key
+//! ```
+//!
+//! The command is split into arguments with shell-style quoting and run directly, without a
+//! shell, so the same configuration works whatever the user's shell is. It only runs when a
+//! client is constructed, never when the configuration is loaded.
+
+use std::error::Error;
+use std::fmt;
+use std::process::{ Command, Output, Stdio };
+
+/// ### Credential Helper
+///
+/// #### Fields
+/// - `command`: The command line, e.g. `op read "op://vault/namecheap/key"`.
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct CredentialHelper {
+    pub command: String,
+}
+
+/// An error running a credential helper.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CredentialHelperError {
+    pub command: String,
+    pub message: String,
+}
+
+impl fmt::Display for CredentialHelperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Credential helper `{}` failed: {}", self.command, self.message)
+    }
+}
+
+impl Error for CredentialHelperError {}
+
+impl CredentialHelper {
+    /// Creates a helper for a command line.
+    pub fn new(command: &str) -> Self {
+        CredentialHelper { command: command.trim().to_string() }
+    }
+
+    /// Runs the helper and returns the first line it printed, trimmed.
+    ///
+    /// Fails if the command cannot be started, exits unsuccessfully or prints nothing.
+    pub fn fetch(&self) -> Result<String, CredentialHelperError> {
+        let error = |message: String| CredentialHelperError { command: self.command.clone(), message };
+        let args: Vec<String> = split_command(&self.command).map_err(error)?;
+        let (program, args) = args.split_first().ok_or_else(|| error("empty command".to_string()))?;
+
+        let output: Output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| error(err.to_string()))?;
+        if !output.status.success() {
+            let stderr: String = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(error(format!("{} {}", output.status, stderr).trim().to_string()));
+        }
+
+        let stdout: String = String::from_utf8(output.stdout).map_err(|_| error("output is not UTF-8".to_string()))?;
+        match stdout.lines().next().map(str::trim) {
+            Some(secret) if !secret.is_empty() => Ok(secret.to_string()),
+            _ => Err(error("printed no credential".to_string())),
+        }
+    }
+}
+
+/// Splits a command line into arguments.
+///
+/// Supports single quotes (literal), double quotes (with `\"` and `\\` escapes) and backslash
+/// escapes outside of quotes, like POSIX shells, but no expansions.
+pub fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = Vec::new();
+    let mut current: String = String::new();
+    let mut in_arg: bool = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_arg = true;
+                current.push(chars.next().ok_or("trailing backslash")?);
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("op read op://vault/namecheap/key").unwrap(), vec!["op", "read", "op://vault/namecheap/key"]);
+        assert_eq!(
+            split_command(r#"pass show 'namecheap/api key' "a \"b\"" c\ d ''"#).unwrap(),
+            vec!["pass", "show", "namecheap/api key", "a \"b\"", "c d", ""]
+        );
+        assert!(split_command("op read 'unterminated").is_err());
+        assert!(split_command("  ").unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fetch() {
+        assert_eq!(CredentialHelper::new("echo '  s3cret  '").fetch().unwrap(), "s3cret");
+        assert!(CredentialHelper::new("false").fetch().is_err());
+        assert!(CredentialHelper::new("true").fetch().unwrap_err().message.contains("no credential"));
+        assert!(CredentialHelper::new("namecheap-no-such-helper").fetch().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! ### Per-Domain Locks
+//!
+//! `domains.dns.setHosts` replaces the whole zone, so every zone change is a read-modify-write:
+//! fetch the records, change them, submit them all. Two tasks changing the same zone at once
+//! would each submit their own copy and one change would be lost.
+//!
+//! Mutating zone helpers hold an async lock per account and domain for the duration of the
+//! read-modify-write. The locks are process-wide, so separate `NameCheapClient` clones for
+//! the same account share them. Use `NameCheapClient::without_domain_locks` to opt out, e.g.
+//! when the caller already serializes changes.
+
+use std::collections::HashMap;
+use std::sync::{ Arc, LazyLock, Mutex };
+use tokio::sync::{ Mutex as AsyncMutex, OwnedMutexGuard };
+
+// crate imports
+use crate::NameCheapClient;
+
+static DOMAIN_LOCKS: LazyLock<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>> = LazyLock::new(Default::default);
+
+/// Holds the lock of a domain until dropped.
+pub type DomainLockGuard = OwnedMutexGuard<()>;
+
+impl NameCheapClient {
+    /// Waits for and takes the lock of `domain`, `None` when domain locks are disabled.
+    pub async fn lock_domain(&self, domain: &str) -> Option<DomainLockGuard> {
+        if self.skip_domain_locks {
+            return None;
+        }
+
+        let key: String = format!("{}:{}", self.api_user.to_lowercase(), domain.trim_end_matches('.').to_lowercase());
+        let lock: Arc<AsyncMutex<()>> = DOMAIN_LOCKS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(key)
+            .or_default()
+            .clone();
+
+        Some(lock.lock_owned().await)
+    }
+
+    /// Disables the per-domain locks around zone read-modify-write operations.
+    pub fn without_domain_locks(mut self) -> Self {
+        self.skip_domain_locks = true;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn client() -> NameCheapClient {
+        NameCheapClient::new(
+            "lock_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "lock_user".to_string(),
+            false
+        )
+    }
+
+    #[tokio::test]
+    async fn test_lock_domain_serializes_same_domain() {
+        let client: NameCheapClient = client();
+        let guard: Option<DomainLockGuard> = client.lock_domain("Example.com").await;
+        assert!(guard.is_some());
+
+        let blocked = tokio::time::timeout(Duration::from_millis(20), client.lock_domain("example.com.")).await;
+        assert!(blocked.is_err());
+
+        // Other domains and opted-out clients are not blocked
+        assert!(client.lock_domain("example.org").await.is_some());
+        assert!(client.clone().without_domain_locks().lock_domain("example.com").await.is_none());
+
+        drop(guard);
+        assert!(client.lock_domain("example.com").await.is_some());
+    }
+}
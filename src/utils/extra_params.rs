@@ -0,0 +1,255 @@
+//! ### Extra Request Parameters
+//!
+//! NameCheap accepts optional parameters on many commands that the typed methods do not
+//! expose, e.g. `PromotionCode` on `domains.create` or `IsPremiumDomain` on `domains.renew`.
+//! [`ExtraParams::scope`] adds them to every request made by a future, so any high-level
+//! method can pass them for one call without a dedicated argument. Requests built directly
+//! take them with [`Request::extra_param`].
+//! - the name must be in [`SAFE_EXTRA_PARAMS`], other names fail with [`ExtraParamError`];
+//!   the `unchecked` variants skip that check;
+//! - the global parameters (`ApiUser`, `ApiKey`, `UserName`, `Command`, `ClientIp`) are always
+//!   refused, they are set from the client;
+//! - a parameter the method sets itself takes precedence over an extra one of the same name.
+//!
+//! Like correlation IDs the scope is task-local: work moved to another task with
+//! `tokio::spawn` must be wrapped in [`ExtraParams::scope`] again. Extra parameters show in the
+//! `params` of dry-run previews.
+//!
+//! ```rust
+//! let promo = ExtraParams::new().with_extra_param("PromotionCode", "SAVE10")?;
+//! let result = promo.scope(client.domains_create("example.com", 1, &contacts)).await?;
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Map, Value };
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+
+// crate imports
+use crate::utils::request_builder::Request;
+
+tokio::task_local! {
+    static CURRENT: ExtraParams;
+}
+
+/// The global request parameters, set from the client credentials and the command.
+pub const RESERVED_PARAMS: [&str; 5] = ["ApiUser", "ApiKey", "UserName", "Command", "ClientIp"];
+
+/// The optional parameters accepted without the `unchecked` variants.
+pub const SAFE_EXTRA_PARAMS: [&str; 9] = [
+    "Years",
+    "PromotionCode",
+    "IsPremiumDomain",
+    "PremiumPrice",
+    "EapFee",
+    "AddFreeWhoisguard",
+    "WGEnabled",
+    "IdnCode",
+    "Nameservers",
+];
+
+/// An extra parameter refused by [`ExtraParams::with_extra_param`] or [`Request::extra_param`].
+///
+/// #### Fields
+/// - `name`: The parameter name.
+/// - `reserved`: `true` for a global parameter, refused even when unchecked.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ExtraParamError {
+    pub name: String,
+    pub reserved: bool,
+}
+
+impl fmt::Display for ExtraParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reserved {
+            write!(f, "Parameter {:?} is set by the client and cannot be overridden", self.name)
+        } else {
+            write!(f, "Parameter {:?} is not a known optional parameter, add it unchecked", self.name)
+        }
+    }
+}
+
+impl std::error::Error for ExtraParamError {}
+
+/// Checks an extra parameter name, against [`SAFE_EXTRA_PARAMS`] unless `unchecked`.
+fn check_name(name: &str, unchecked: bool) -> Result<(), ExtraParamError> {
+    if RESERVED_PARAMS.iter().any(|reserved| reserved.eq_ignore_ascii_case(name)) {
+        return Err(ExtraParamError { name: name.to_string(), reserved: true });
+    }
+    if !unchecked && !SAFE_EXTRA_PARAMS.contains(&name) {
+        return Err(ExtraParamError { name: name.to_string(), reserved: false });
+    }
+    Ok(())
+}
+
+/// ### Extra Parameters
+///
+/// A set of optional parameters, each checked when added. Deliberately not deserializable, so
+/// parameters never bypass the checks.
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ExtraParams(BTreeMap<String, String>);
+
+impl ExtraParams {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an optional parameter.
+    ///
+    /// Fails if `name` is not in [`SAFE_EXTRA_PARAMS`].
+    pub fn with_extra_param(mut self, name: &str, value: impl ToString) -> Result<Self, ExtraParamError> {
+        check_name(name, false)?;
+        self.0.insert(name.to_string(), value.to_string());
+        Ok(self)
+    }
+
+    /// Adds any parameter, e.g. one documented after this release.
+    ///
+    /// Fails only on the global parameters, see [`RESERVED_PARAMS`].
+    pub fn with_unchecked_extra_param(mut self, name: &str, value: impl ToString) -> Result<Self, ExtraParamError> {
+        check_name(name, true)?;
+        self.0.insert(name.to_string(), value.to_string());
+        Ok(self)
+    }
+
+    /// Returns the value of a parameter.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Returns `true` if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the parameters of the current scope, empty outside of one.
+    pub fn current() -> ExtraParams {
+        CURRENT.try_with(ExtraParams::clone).unwrap_or_default()
+    }
+
+    /// Runs `future` adding these parameters to every request it makes.
+    ///
+    /// Inside another scope the parameters of both apply, these ones win on a name clash.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        let mut params: ExtraParams = ExtraParams::current();
+        params.0.extend(self.0);
+        CURRENT.scope(params, future).await
+    }
+}
+
+/// Adds the extra parameters to the parameters of a request, without replacing any it sets.
+pub(crate) fn merge_extra_params(params: Option<Value>, extra: &ExtraParams) -> Option<Value> {
+    if extra.is_empty() {
+        return params;
+    }
+    match params {
+        Some(Value::Object(mut params)) => {
+            for (name, value) in &extra.0 {
+                params.entry(name.clone()).or_insert_with(|| Value::String(value.clone()));
+            }
+            Some(Value::Object(params))
+        }
+        None => {
+            let params: Map<String, Value> = extra.0
+                .iter()
+                .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+                .collect();
+            Some(Value::Object(params))
+        }
+        params => params,
+    }
+}
+
+impl Request<'_> {
+    /// Adds an optional parameter to this request.
+    ///
+    /// Fails if `name` is not in [`SAFE_EXTRA_PARAMS`].
+    pub fn extra_param(self, name: &str, value: impl ToString) -> Result<Self, ExtraParamError> {
+        check_name(name, false)?;
+        Ok(self.with_extra_params(&ExtraParams(BTreeMap::from([(name.to_string(), value.to_string())]))))
+    }
+
+    /// Adds any parameter to this request.
+    ///
+    /// Fails only on the global parameters, see [`RESERVED_PARAMS`].
+    pub fn unchecked_extra_param(self, name: &str, value: impl ToString) -> Result<Self, ExtraParamError> {
+        check_name(name, true)?;
+        Ok(self.with_extra_params(&ExtraParams(BTreeMap::from([(name.to_string(), value.to_string())]))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::NameCheapClient;
+
+    fn client() -> NameCheapClient {
+        NameCheapClient::new(
+            "api_user".to_string(),
+            "api_key".to_string(),
+            "127.0.0.1".to_string(),
+            "user_name".to_string(),
+            false
+        )
+    }
+
+    #[test]
+    fn test_with_extra_param() {
+        assert!(ExtraParams::new().with_extra_param("PromotionCode", "SAVE10").is_ok());
+        assert!(!ExtraParams::new().with_extra_param("Foo", 1).unwrap_err().reserved);
+        assert!(ExtraParams::new().with_extra_param("ApiKey", "other").unwrap_err().reserved);
+        assert!(ExtraParams::new().with_unchecked_extra_param("Foo", 1).is_ok());
+        assert!(ExtraParams::new().with_unchecked_extra_param("clientip", "10.0.0.1").unwrap_err().reserved);
+    }
+
+    #[tokio::test]
+    async fn test_extra_params_in_scope() {
+        let client: NameCheapClient = client();
+        let params: ExtraParams = ExtraParams::new()
+            .with_extra_param("Years", 2).unwrap()
+            .with_extra_param("PromotionCode", "SAVE10").unwrap();
+
+        let (url, planned) = params.scope(async {
+            let request: Request = Request::new(&client, "namecheap.domains.renew".to_string(), None, None, Some(json!({ "Years": "1" })));
+            let planned: Value = Request::new(&client, "namecheap.domains.getInfo".to_string(), None, None, None).planned().params;
+            (request.build_url(), planned)
+        }).await;
+        assert!(url.contains("PromotionCode=SAVE10"));
+        assert!(url.contains("Years=1") && !url.contains("Years=2"));
+        assert_eq!(planned, json!({ "PromotionCode": "SAVE10", "Years": "2" }));
+
+        // Nothing leaks out of the scope
+        let request: Request = Request::new(&client, "namecheap.domains.getInfo".to_string(), None, None, None);
+        assert_eq!(request.planned().params, json!({}));
+    }
+
+    #[test]
+    fn test_request_extra_param() {
+        let client: NameCheapClient = client();
+        let request: Request = Request::new(&client, "namecheap.domains.renew".to_string(), None, None, Some(json!({ "Years": "1" })))
+            .extra_param("PromotionCode", "SAVE10").unwrap()
+            .extra_param("Years", 3).unwrap();
+        assert_eq!(request.planned().params, json!({ "PromotionCode": "SAVE10", "Years": "1" }));
+
+        let request: Request = Request::new(&client, "namecheap.domains.renew".to_string(), None, None, None);
+        assert!(request.clone().extra_param("Foo", 1).is_err());
+        assert!(request.unchecked_extra_param("ApiKey", "other").unwrap_err().reserved);
+    }
+
+    #[test]
+    fn test_deserialized_client_cannot_inject_params() {
+        let mut value: Value = serde_json::to_value(client()).unwrap();
+        value["extraParams"] = json!({ "ApiKey": "injected", "Years": "9" });
+        let client: NameCheapClient = serde_json::from_value(value).unwrap();
+
+        let url: String = Request::new(&client, "namecheap.domains.getList".to_string(), None, None, None).build_url();
+        assert_eq!(url.matches("ApiKey=").count(), 1);
+        assert!(!url.contains("injected") && !url.contains("Years"));
+    }
+}
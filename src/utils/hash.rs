@@ -0,0 +1,23 @@
+//! ### Stable Hashing
+//!
+//! `std::hash` output may change between Rust versions, so values that are persisted or
+//! compared across processes (ledger keys, zone snapshot hashes) use 64-bit FNV-1a instead.
+
+/// Returns the 64-bit FNV-1a hash of `input` as 16 lowercase hex digits.
+pub fn fnv1a_hex(input: &str) -> String {
+    let hash: u64 = input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ (byte as u64)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_hex() {
+        assert_eq!(fnv1a_hex(""), "cbf29ce484222325");
+        assert_eq!(fnv1a_hex("a"), "af63dc4c8601ec8c");
+    }
+}
@@ -1,3 +1,20 @@
 pub mod tracer;
 pub mod request_builder;
-pub mod xml_parser;
\ No newline at end of file
+pub mod xml_parser;
+pub mod rate_limit;
+pub mod cooldown;
+pub mod transport;
+pub mod cancellation;
+pub mod domain_lock;
+pub mod hash;
+pub mod shared;
+pub mod shutdown;
+pub mod validate;
+pub mod state_store;
+pub mod credential_helper;
+pub mod credential_cache;
+pub mod correlation;
+pub mod privacy;
+pub mod extra_params;
+pub mod progress;
+pub mod charges;
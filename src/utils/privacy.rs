@@ -0,0 +1,216 @@
+//! ### Contact Privacy
+//!
+//! Contacts hold personal data (names, emails, phones, postal addresses). With
+//! [`PrivacyMode::Minimized`], set with [`NameCheapClient::with_privacy`], the client keeps it
+//! out of what it writes itself:
+//! - request URLs are logged with contact fields masked, see [`redact_url`];
+//! - responses carrying contacts (`domains.getContacts`, `users.address.*`) are not logged;
+//! - the findings of `audit_contacts` hold masked values.
+//!
+//! Masked values keep enough to correlate changes: an email keeps its first character and
+//! domain, other values their last characters, and every masked value ends with a short stable
+//! hash of the original, so the same value always masks the same way. For snapshots and
+//! debug output of your own, use [`Contact::minimized`] and [`ContactSet::minimized`].
+//!
+//! ```rust
+//! let client = NameCheapClient::new_from_env()?.with_privacy(PrivacyMode::Minimized);
+//! let contacts = client.domains_get_contact_set("example.com").await?;
+//! println!("{:?}", contacts.minimized());
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use tracing::info;
+
+// crate imports
+use crate::{ NameCheapClient, Contact, ContactSet };
+use crate::utils::hash::fnv1a_hex;
+
+/// The request parameter suffixes of contact fields holding personal data, e.g.
+/// `RegistrantEmailAddress` or `Phone`.
+const PII_PARAMS: &[&str] = &[
+    "FirstName",
+    "LastName",
+    "Address1",
+    "Address2",
+    "City",
+    "PostalCode",
+    "EmailAddress",
+    "Phone",
+    "PhoneExt",
+    "Fax",
+];
+
+/// How contact personal data appears in logs and reports.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum PrivacyMode {
+    /// Contacts appear as they are.
+    #[default]
+    Full,
+    /// Contact personal data is masked, see [`mask_email`] and [`mask_value`].
+    Minimized,
+}
+
+impl PrivacyMode {
+    /// Returns `true` for `Minimized`.
+    pub fn is_minimized(&self) -> bool {
+        *self == PrivacyMode::Minimized
+    }
+}
+
+/// Returns the correlation tag of a value: 8 hex digits of its stable hash.
+fn tag(value: &str) -> String {
+    fnv1a_hex(value.trim())[..8].to_string()
+}
+
+/// Masks an email address, keeping its first character and domain.
+///
+/// `john.doe@example.com` becomes `j***@example.com#` followed by the tag of the address,
+/// compared case-insensitively. Empty values stay empty.
+pub fn mask_email(email: &str) -> String {
+    let email: String = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}#{}", first, domain, tag(&email))
+        }
+        None => mask_value(&email),
+    }
+}
+
+/// Masks a value, keeping at most its last 2 characters, e.g. `+1.5555551234` becomes
+/// `***34#` followed by the tag of the value. Empty values stay empty.
+pub fn mask_value(value: &str) -> String {
+    let value: &str = value.trim();
+    if value.is_empty() {
+        return String::new();
+    }
+    let chars: Vec<char> = value.chars().collect();
+    // Short values would be given away by their last characters
+    let kept: String = if chars.len() > 6 { chars[chars.len() - 2..].iter().collect() } else { String::new() };
+    format!("***{}#{}", kept, tag(value))
+}
+
+/// Returns `true` if the request parameter `name` holds contact personal data.
+pub fn is_pii_param(name: &str) -> bool {
+    PII_PARAMS.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// Returns `url` with the values of its contact parameters masked with [`mask_value`].
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if is_pii_param(name) => format!("{}={}", name, mask_value(value)),
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", base, query.join("&"))
+}
+
+impl Contact {
+    /// Returns a copy with its personal data masked.
+    ///
+    /// The role, organization, state and country are kept, they do not identify a person.
+    pub fn minimized(&self) -> Contact {
+        Contact {
+            first_name: mask_value(&self.first_name),
+            last_name: mask_value(&self.last_name),
+            address_1: mask_value(&self.address_1),
+            address_2: mask_value(&self.address_2),
+            city: mask_value(&self.city),
+            postal_code: mask_value(&self.postal_code),
+            email_address: mask_email(&self.email_address),
+            phone: mask_value(&self.phone),
+            phone_ext: mask_value(&self.phone_ext),
+            fax: mask_value(&self.fax),
+            ..self.clone()
+        }
+    }
+}
+
+impl ContactSet {
+    /// Returns a copy with the personal data of every contact masked, see [`Contact::minimized`].
+    pub fn minimized(&self) -> ContactSet {
+        ContactSet {
+            registrant: self.registrant.minimized(),
+            tech: self.tech.minimized(),
+            admin: self.admin.minimized(),
+            aux_billing: self.aux_billing.minimized(),
+        }
+    }
+}
+
+impl NameCheapClient {
+    /// Sets how contact personal data appears in the logs and reports of this client.
+    pub fn with_privacy(mut self, privacy: PrivacyMode) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Logs a response holding contacts, unless contact data is minimized.
+    pub(crate) fn log_contact_response(&self, response: &Value) {
+        if self.privacy.is_minimized() {
+            info!("Response received, contacts withheld from the log");
+        } else {
+            info!("Response: {:#?}", response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masking() {
+        let email: String = mask_email("John.Doe@Example.com");
+        assert!(email.starts_with("j***@example.com#"));
+        assert_eq!(email, mask_email("john.doe@example.com"));
+        assert_ne!(email, mask_email("jane.doe@example.com"));
+
+        assert!(mask_value("+1.5555551234").starts_with("***34#"));
+        assert!(mask_value("Doe").starts_with("***#"));
+        assert_eq!(mask_value(" "), "");
+
+        let url: String = redact_url("https://api?ApiUser=user&RegistrantEmailAddress=john@example.com&TechCity=Paris&Command=x");
+        assert!(url.contains("ApiUser=user&RegistrantEmailAddress=***om#"));
+        assert!(!url.contains("Paris"));
+        assert!(url.ends_with("&Command=x"));
+    }
+
+    #[test]
+    fn test_contact_minimized() {
+        let contact: Contact = Contact {
+            type_: "Registrant".to_string(),
+            address_1: String::new(),
+            address_2: String::new(),
+            city: "Phoenix".to_string(),
+            country: "US".to_string(),
+            email_address: "john@example.com".to_string(),
+            fax: String::new(),
+            first_name: "John".to_string(),
+            job_title: String::new(),
+            last_name: "Smith".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.6613102107".to_string(),
+            phone_ext: String::new(),
+            postal_code: "85044".to_string(),
+            state_province: "AZ".to_string(),
+            state_province_choice: String::new(),
+            read_only: false,
+        };
+        let minimized: Contact = contact.minimized();
+
+        assert_ne!(minimized.first_name, "John");
+        assert!(minimized.email_address.starts_with("j***@example.com#"));
+        assert!(minimized.phone.starts_with("***07#"));
+        assert_eq!((minimized.organization_name.as_str(), minimized.country.as_str()), ("Example Inc", "US"));
+        assert_eq!(minimized.address_1, "");
+    }
+}
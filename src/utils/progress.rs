@@ -0,0 +1,228 @@
+//! ### Progress Reporting
+//!
+//! Long-running bulk operations (the batch executor, auto-pagination) report their progress
+//! to an optional `ProgressSink`: how many items are processed out of how many, the item
+//! just finished and an estimate of the time left. A CLI renders it as a progress bar, a
+//! service exports it as metrics, e.g. by polling a [`LatestProgress`].
+//!
+//! Any `Fn(&Progress) + Send + Sync` closure is a sink. Operations take it as a
+//! `ProgressHandle`, which is empty by default so reporting costs nothing when unused.
+
+use serde::{ Serialize, Deserialize };
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::sync::{ Arc, Mutex };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::time::{ Duration, Instant };
+
+/// ### Progress
+///
+/// #### Fields
+/// - `operation`: The operation reporting, e.g. `batch.run`.
+/// - `processed`: The number of items finished so far.
+/// - `total`: The number of items, once known (optional).
+/// - `current`: The item just finished, e.g. a domain name (optional).
+/// - `elapsed`: The time since the operation started.
+/// - `eta`: The estimated time left, once an item finished and the total is known (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Progress {
+    pub operation: String,
+    pub processed: usize,
+    pub total: Option<usize>,
+    pub current: Option<String>,
+    pub elapsed: Duration,
+    pub eta: Option<Duration>,
+}
+
+impl Progress {
+    /// Returns the finished fraction between `0.0` and `1.0`, once the total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.processed as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+
+    /// Returns `true` once every item is processed.
+    pub fn is_done(&self) -> bool {
+        self.total.is_some_and(|total| self.processed >= total)
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{}/{}", self.processed, total)?,
+            None => write!(f, "{}", self.processed)?,
+        }
+        if let Some(current) = &self.current {
+            write!(f, " {}", current)?;
+        }
+        if let Some(eta) = self.eta.filter(|_| !self.is_done()) {
+            write!(f, " (about {}s left)", eta.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives progress updates.
+pub trait ProgressSink: Send + Sync {
+    /// Called after every finished item.
+    fn update(&self, progress: &Progress);
+}
+
+impl<F> ProgressSink for F where F: Fn(&Progress) + Send + Sync {
+    fn update(&self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// A sink keeping the latest update, for polling e.g. from a metrics endpoint.
+#[derive(Debug, Default)]
+pub struct LatestProgress {
+    latest: Mutex<Option<Progress>>,
+}
+
+impl LatestProgress {
+    /// Returns the latest update, if any.
+    pub fn get(&self) -> Option<Progress> {
+        self.latest.lock().ok()?.clone()
+    }
+}
+
+impl ProgressSink for LatestProgress {
+    fn update(&self, progress: &Progress) {
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = Some(progress.clone());
+        }
+    }
+}
+
+/// An optional progress sink, shared by clones.
+///
+/// Like `SharedState` it is not part of the identity of the value holding it: it compares
+/// and hashes equal regardless of the sink.
+#[derive(Clone, Default)]
+pub struct ProgressHandle(Option<Arc<dyn ProgressSink>>);
+
+impl ProgressHandle {
+    /// Creates a handle reporting to `sink`.
+    pub fn new(sink: impl ProgressSink + 'static) -> Self {
+        ProgressHandle(Some(Arc::new(sink)))
+    }
+
+    /// Creates a handle reporting to a shared sink.
+    pub fn shared(sink: Arc<dyn ProgressSink>) -> Self {
+        ProgressHandle(Some(sink))
+    }
+
+    /// Returns `true` if a sink is set.
+    pub fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Starts tracking an operation over `total` items, if known.
+    pub fn tracker(&self, operation: &str, total: Option<usize>) -> ProgressTracker {
+        ProgressTracker {
+            operation: operation.to_string(),
+            total: Mutex::new(total),
+            processed: AtomicUsize::new(0),
+            started: Instant::now(),
+            sink: self.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for ProgressHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ProgressHandle").field(&self.is_set()).finish()
+    }
+}
+
+impl PartialEq for ProgressHandle {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ProgressHandle {}
+
+impl Hash for ProgressHandle {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+/// Counts finished items of one operation and reports them to the sink.
+#[derive(Debug)]
+pub struct ProgressTracker {
+    operation: String,
+    total: Mutex<Option<usize>>,
+    processed: AtomicUsize,
+    started: Instant,
+    sink: ProgressHandle,
+}
+
+impl ProgressTracker {
+    /// Sets the total once it is known, e.g. after the first page.
+    pub fn set_total(&self, total: usize) {
+        if let Ok(mut current) = self.total.lock() {
+            *current = Some(total);
+        }
+    }
+
+    /// Records a finished item and reports the progress.
+    pub fn advance(&self, current: &str) {
+        let processed: usize = self.processed.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(sink) = &self.sink.0 {
+            sink.update(&self.progress(processed, Some(current.to_string())));
+        }
+    }
+
+    /// Returns the progress with `processed` items finished.
+    fn progress(&self, processed: usize, current: Option<String>) -> Progress {
+        let total: Option<usize> = self.total.lock().ok().and_then(|total| *total);
+        let elapsed: Duration = self.started.elapsed();
+        let eta: Option<Duration> = match total {
+            Some(total) if processed > 0 => {
+                Some(elapsed.mul_f64(total.saturating_sub(processed) as f64 / processed as f64))
+            }
+            _ => None,
+        };
+        Progress { operation: self.operation.clone(), processed, total, current, elapsed, eta }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_reports_each_item() {
+        let latest: Arc<LatestProgress> = Arc::new(LatestProgress::default());
+        let handle: ProgressHandle = ProgressHandle::shared(latest.clone());
+        let tracker: ProgressTracker = handle.tracker("batch.run", None);
+
+        tracker.advance("example.com");
+        let progress: Progress = latest.get().unwrap();
+        assert_eq!((progress.processed, progress.total, progress.eta), (1, None, None));
+
+        tracker.set_total(4);
+        tracker.advance("example.net");
+        let progress: Progress = latest.get().unwrap();
+        assert_eq!(progress.processed, 2);
+        assert_eq!(progress.fraction(), Some(0.5));
+        assert_eq!(progress.current.as_deref(), Some("example.net"));
+        assert!(progress.eta.is_some());
+        assert!(progress.to_string().starts_with("2/4 example.net"));
+    }
+
+    #[test]
+    fn test_empty_handle() {
+        let handle: ProgressHandle = ProgressHandle::default();
+        assert!(!handle.is_set());
+        handle.tracker("batch.run", Some(1)).advance("example.com");
+        assert_eq!(handle, ProgressHandle::new(|_: &Progress| {}));
+    }
+}
@@ -0,0 +1,211 @@
+//! ### Rate Limiting
+//!
+//! NameCheap throttles API access to 20 calls per minute, 700 per hour and 8000 per day
+//! per account. Helpers that fan out over many domains pace their calls with a `RateLimiter`
+//! so they do not get the account locked out.
+//!
+//! Once the limiter is saturated, calls queue up by [`Priority`]: a waiting `High` call (a CLI
+//! command) gets the next slot before any `Normal` one, and `Background` calls (the watcher)
+//! only get slots nobody else waits for. Calls of the same priority keep their arrival order.
+
+use serde::{ Serialize, Deserialize };
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+// crate imports
+use crate::limits::MAX_CALLS_PER_MINUTE;
+
+/// The minimum interval between calls that stays within 20 calls per minute.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(60 / (MAX_CALLS_PER_MINUTE as u64));
+
+/// The class of a call waiting for the `RateLimiter`, lowest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Priority {
+    /// Traffic nobody waits on, e.g. watcher polls.
+    Background,
+    /// The default.
+    #[default]
+    Normal,
+    /// Interactive calls, e.g. CLI commands.
+    High,
+}
+
+/// Spaces out calls by a minimum interval.
+///
+/// The limiter is shared by reference. Concurrent callers are served by priority, then in
+/// the order they call `acquire_with`.
+#[derive(Debug)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    queue: Mutex<Queue>,
+    changed: Notify,
+}
+
+/// The callers waiting for a slot and the time of the last granted one.
+#[derive(Debug, Default)]
+struct Queue {
+    last: Option<Instant>,
+    /// Tickets ordered head first: highest priority, then lowest sequence number.
+    waiting: BTreeSet<(Reverse<Priority>, u64)>,
+    next_seq: u64,
+}
+
+/// A place in the queue, given up when the waiting call is dropped.
+struct Ticket<'a> {
+    limiter: &'a RateLimiter,
+    key: (Reverse<Priority>, u64),
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut queue) = self.limiter.queue.lock() {
+            queue.waiting.remove(&self.key);
+        }
+        self.limiter.changed.notify_waiters();
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(DEFAULT_MIN_INTERVAL)
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing one call per `min_interval`.
+    pub fn new(min_interval: Duration) -> Self {
+        RateLimiter {
+            min_interval,
+            queue: Mutex::new(Queue::default()),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Creates a limiter allowing `calls` calls per minute.
+    pub fn per_minute(calls: u32) -> Self {
+        RateLimiter::new(Duration::from_secs(60) / calls.max(1))
+    }
+
+    /// Waits until the next call is allowed, with `Normal` priority.
+    pub async fn acquire(&self) {
+        self.acquire_with(Priority::Normal).await
+    }
+
+    /// Waits until the next call of `priority` is allowed.
+    ///
+    /// Dropping the future gives up its place in the queue.
+    pub async fn acquire_with(&self, priority: Priority) {
+        let ticket: Ticket<'_> = self.enqueue(priority);
+
+        loop {
+            // Registered before the queue is read, so no change is missed
+            let changed = self.changed.notified();
+            let ready_at: Option<Instant> = {
+                let mut queue = self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                if queue.waiting.first() != Some(&ticket.key) {
+                    None
+                } else {
+                    let now: Instant = Instant::now();
+                    match queue.last.map(|last| last + self.min_interval).filter(|at| *at > now) {
+                        Some(at) => Some(at),
+                        None => {
+                            queue.last = Some(now);
+                            // The ticket leaves the queue on drop, waking the next head
+                            return;
+                        }
+                    }
+                }
+            };
+
+            match ready_at {
+                // A higher priority caller arriving meanwhile takes over the head
+                Some(at) => tokio::select! {
+                    _ = tokio::time::sleep_until(at) => {}
+                    _ = changed => {}
+                },
+                None => changed.await,
+            }
+        }
+    }
+
+    /// Adds a ticket for `priority` and wakes the head, which may no longer be first.
+    fn enqueue(&self, priority: Priority) -> Ticket<'_> {
+        let mut queue = self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let key = (Reverse(priority), queue.next_seq);
+        queue.next_seq += 1;
+        queue.waiting.insert(key);
+        drop(queue);
+
+        self.changed.notify_waiters();
+        Ticket { limiter: self, key }
+    }
+
+    /// Returns the number of callers waiting for a slot.
+    pub fn waiting(&self) -> usize {
+        self.queue.lock().map(|queue| queue.waiting.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_calls() {
+        let limiter: RateLimiter = RateLimiter::new(Duration::from_millis(20));
+        let start: Instant = Instant::now();
+
+        limiter.acquire().await;
+        limiter.acquire().await;
+        limiter.acquire().await;
+
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert_eq!(RateLimiter::per_minute(20).min_interval, DEFAULT_MIN_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_serves_by_priority() {
+        let limiter: Arc<RateLimiter> = Arc::new(RateLimiter::new(Duration::from_millis(30)));
+        let order: Arc<Mutex<Vec<&str>>> = Arc::new(Mutex::new(Vec::new()));
+        // Takes the first slot, the calls below queue behind it
+        limiter.acquire().await;
+
+        let mut handles = Vec::new();
+        for (name, priority) in [("background", Priority::Background), ("normal", Priority::Normal), ("high", Priority::High), ("normal2", Priority::Normal)] {
+            let (limiter, order) = (limiter.clone(), order.clone());
+            handles.push(tokio::spawn(async move {
+                limiter.acquire_with(priority).await;
+                order.lock().unwrap().push(name);
+            }));
+            // Lets each call join the queue before the next one
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), ["high", "normal", "normal2", "background"]);
+        assert_eq!(limiter.waiting(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_caller_leaves_queue() {
+        let limiter: RateLimiter = RateLimiter::new(Duration::from_millis(20));
+        limiter.acquire().await;
+
+        let high = tokio::time::timeout(Duration::from_millis(5), limiter.acquire_with(Priority::High));
+        assert!(high.await.is_err());
+        assert_eq!(limiter.waiting(), 0);
+
+        let start: Instant = Instant::now();
+        limiter.acquire_with(Priority::Background).await;
+        assert!(start.elapsed() < Duration::from_millis(30));
+    }
+}
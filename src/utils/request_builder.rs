@@ -3,27 +3,80 @@
 //! requests to the NameCheap API. It allows you to set up the necessary parameters,
 //! build the URL for the API request, and send the request.
 
-use reqwest::{ Client, Response, Method, RequestBuilder };
+use reqwest::{ Response, Method, RequestBuilder, StatusCode };
+use reqwest::header::HeaderMap;
 use tracing::info;
 use serde::{ Serialize, Deserialize };
-use anyhow::{ Result, anyhow };
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
 use serde_json::Value;
 
 // crate imports
-use crate::{ NameCheapClient, NAMECHEAP_API_URL, NAMECHEAP_SANDBOX_API_URL };
-use crate::utils::xml_parser::parse_xml_to_json;
+use crate::{ NameCheapClient, DEFAULT_MAX_RESPONSE_BYTES, NAMECHEAP_API_URL, NAMECHEAP_SANDBOX_API_URL };
+use crate::response::errors::ResponseTooLarge;
+use crate::utils::cancellation::Cancelled;
+use crate::utils::cooldown::Cooldown;
+use crate::utils::extra_params::{ merge_extra_params, ExtraParams };
+use crate::utils::privacy::redact_url;
+use crate::utils::rate_limit::Priority;
+use crate::utils::transport::TransportResponse;
+use crate::utils::xml_parser::{ parse_xml_to_json_with, ParseOptions };
+
+/// A request that was not sent because the client is in dry-run mode.
+///
+/// Methods returning JSON return it serialized with `dryRun: true`, typed methods fail with it
+/// as their error, recover it with `error.downcast_ref::<PlannedRequest>()`.
+///
+/// #### Fields
+/// - `dry_run`: Always `true`, marks the value as a preview rather than an API result.
+/// - `command`: The API command that would be executed.
+/// - `domain_name`: The `DomainName` parameter, if any.
+/// - `params`: The command parameters, without credentials.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq)]
+pub struct PlannedRequest {
+    pub dry_run: bool,
+    pub command: String,
+    pub domain_name: Option<String>,
+    pub params: Value,
+}
+
+impl fmt::Display for PlannedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dry run: {} was not sent", self.command)
+    }
+}
+
+impl Error for PlannedRequest {}
+
+/// Returns `true` if `command` only reads, e.g. `namecheap.domains.getList` or
+/// `namecheap.domains.check`. Read commands are still sent in dry-run mode.
+pub fn is_read_command(command: &str) -> bool {
+    let method: &str = command.rsplit('.').next().unwrap_or(command);
+    method.starts_with("get") || method == "check"
+}
+
+impl From<PlannedRequest> for Value {
+    fn from(planned: PlannedRequest) -> Self {
+        serde_json::to_value(planned).unwrap_or(Value::Null)
+    }
+}
 
 /// A builder for constructing requests to the NameCheap API.
 ///
 /// The `RequestBuilder` struct is used to create and configure requests
 /// to be sent to the NameCheap API. It holds the necessary client
 /// information and the specific command to be executed.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+///
+/// The client is borrowed, building a request does not copy its credentials.
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[derive(PartialEq, Eq, Hash)]
-pub struct Request {
+pub struct Request<'a> {
     /// The `NameCheapClient` instance containing API credentials and configuration.
-    client: NameCheapClient,
+    client: &'a NameCheapClient,
     /// The specific API command to be executed.
     command: String,
     /// The page number for paginated results (optional).
@@ -34,10 +87,13 @@ pub struct Request {
     domain_id: Option<i64>,
     /// Additional parameters for the API request (optional).
     params: Option<Value>,
-
+    /// How the XML response is converted to JSON.
+    parse_options: ParseOptions,
+    /// The class the request queues with on a saturated rate limiter, the client's by default.
+    priority: Priority,
 }
 
-impl Request {
+impl<'a> Request<'a> {
     /// Creates a new `RequestBuilder` instance.
     ///
     /// # Parameters
@@ -45,12 +101,14 @@ impl Request {
     /// - `client`: A `NameCheapClient` instance with the necessary credentials.
     /// - `command`: A `String` representing the API command to be executed.
     /// - `page`: An optional page number for paginated results.
+    /// - `params`: The command parameters, the extra parameters of the current
+    ///   [`ExtraParams::scope`] are added to them.
     ///
     /// # Returns
     ///
     /// A new `RequestBuilder` instance.
     pub fn new(
-        client: NameCheapClient,
+        client: &'a NameCheapClient,
         command: String,
         page: Option<i64>,
         domain_name: Option<String>,
@@ -61,12 +119,34 @@ impl Request {
             command,
             page,
             domain_name,
-            params,
+            params: merge_extra_params(params, &ExtraParams::current()),
             domain_id: None,
-
+            parse_options: ParseOptions::default(),
+            priority: client.priority,
         }
     }
 
+    /// Flattens text-only elements of the response into plain strings.
+    ///
+    /// Typed APIs read values through [`text_of`](crate::response::parse_value::text_of),
+    /// which accepts both shapes, and flatten by default.
+    pub fn flatten_text(mut self) -> Self {
+        self.parse_options = ParseOptions::flat();
+        self
+    }
+
+    /// Adds extra parameters, without replacing any the request sets.
+    pub(crate) fn with_extra_params(mut self, extra: &ExtraParams) -> Self {
+        self.params = merge_extra_params(self.params.take(), extra);
+        self
+    }
+
+    /// Sets the priority of this request on a saturated rate limiter.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Builds the URL for the API request.
     ///
     /// This method constructs the full URL for the API request based on the
@@ -107,19 +187,46 @@ impl Request {
         }
 
         // mostly used for the domains.dns lowkey ehh
-        // Add additional parameters if they exist
-        if let Some(ref params) = self.params {
-            if let Some(sld) = params.get("SLD") {
-                url.push_str(&format!("&SLD={}", sld.as_str().unwrap_or("")));
+        // Add additional parameters if they exist, values are url encoded
+        if let Some(Value::Object(params)) = self.params.as_ref() {
+            let mut serializer = form_urlencoded::Serializer::new(String::new());
+            for (key, value) in params {
+                match value {
+                    Value::Null => {}
+                    Value::String(value) => {
+                        serializer.append_pair(key, value);
+                    }
+                    value => {
+                        serializer.append_pair(key, &value.to_string());
+                    }
+                }
             }
-            if let Some(tld) = params.get("TLD") {
-                url.push_str(&format!("&TLD={}", tld.as_str().unwrap_or("")));
+
+            let encoded: String = serializer.finish();
+            if !encoded.is_empty() {
+                url.push('&');
+                url.push_str(&encoded);
             }
         }
 
         url
     }
 
+    /// Returns the request as a `PlannedRequest`, without sending it.
+    pub fn planned(&self) -> PlannedRequest {
+        PlannedRequest {
+            dry_run: true,
+            command: self.command.clone(),
+            domain_name: self.domain_name.clone(),
+            params: self.params.clone().unwrap_or(Value::Object(Default::default())),
+        }
+    }
+
+    /// Returns `true` if the client is in dry-run mode.
+    pub fn is_dry_run(&self) -> bool {
+        self.client.dry_run
+    }
+
     /// Sends the API request and returns the response.
     ///
     /// This method sends a GET request to the constructed URL and returns the
@@ -128,31 +235,89 @@ impl Request {
     /// # Returns
     ///
     /// A `Result` containing the `Value` if successful, or an `Error` if the request fails.
-    pub async fn send(&self) -> Result<Value> {
+    pub async fn send(&self) -> Result<Value, Box<dyn Error>> {
+        let response_text: String = self.send_text().await?;
+
+        // Parse XML to JSON
+        let json_value: Value = parse_xml_to_json_with(&response_text, self.parse_options)?;
+
+        if let Some(ledger) = self.client.shared.charges() {
+            ledger.record_response(&self.command, self.domain_name.as_deref(), &json_value);
+        }
+        Ok(json_value)
+    }
+
+    /// Sends the API request and returns the raw XML body.
+    ///
+    /// Used by hot paths that parse the XML straight into typed structs, see
+    /// [`typed_xml`](crate::response::typed_xml).
+    ///
+    /// In dry-run mode a command that is not a read command fails with its
+    /// [`PlannedRequest`], whether or not the calling method checked [`is_dry_run`](Self::is_dry_run).
+    pub async fn send_text(&self) -> Result<String, Box<dyn Error>> {
+        if self.client.dry_run && !is_read_command(&self.command) {
+            return Err(Box::new(self.planned()));
+        }
         let url: String = self.build_url();
-        info!("Sending request to URL: {:#?}", url);
+        if self.client.privacy.is_minimized() {
+            info!("Sending request to URL: {:#?}", redact_url(&url));
+        } else {
+            info!("Sending request to URL: {:#?}", url);
+        }
 
-        let client: Client = Client::new();
-        let request: RequestBuilder = client
+        // Paused while the account is throttled, see `cooldown`
+        let cooldown: &Cooldown = self.client.shared.cooldown();
+        // Every wait ends early once the client is cancelled, see `cancellation`
+        let _slot = self.client.shared.cancellation().run(async {
+            if let Some(limiter) = self.client.shared.limiter() {
+                limiter.acquire_with(self.priority).await;
+            }
+            cooldown.wait().await;
+            // Held until the body is read, see `accounts`
+            match self.client.shared.account_slot() {
+                Some(slot) => Some(slot.acquire().await),
+                None => None,
+            }
+        }).await.ok_or_else(|| Cancelled::new(&self.command, 0, Some(1)))?;
+        let limit: usize = self.client.max_response_bytes.unwrap_or(DEFAULT_MAX_RESPONSE_BYTES);
+
+        if let Some(transport) = self.client.shared.transport() {
+            let response: TransportResponse = transport.get(&url).await.map_err(|error| -> Box<dyn Error> { error })?;
+            let throttled: Option<Duration> = cooldown.observe(response.status, &response.headers, Some(&response.body));
+            if let Some(duration) = throttled && response.status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(format!("Throttled by the API, calls resume in {:?}", duration).into());
+            }
+            if response.body.len() > limit {
+                return Err(Box::new(ResponseTooLarge { limit, received: response.body.len() }));
+            }
+            return Ok(response.body);
+        }
+
+        // The pooled HTTP client shared by every clone of the NameCheapClient
+        let request: RequestBuilder = self.client.shared
+            .http()
             .request(Method::GET, &url)
             .header("Accept", "application/xml")
             .header("Content-Type", "application/xml");
 
         let response: Response = request.send().await?;
+        let status: StatusCode = response.status();
+        let headers: HeaderMap = response.headers().clone();
+        if let Some(duration) = cooldown.observe(status, &headers, None) {
+            return Err(format!("Throttled by the API, calls resume in {:?}", duration).into());
+        }
 
         // Ensure we're receiving XML
         if let Some(content_type) = response.headers().get("Content-Type") {
             if !content_type.to_str().unwrap_or("").contains("xml") {
-                return Err(anyhow!("Response is not XML"));
+                return Err("Response is not XML".into());
             }
         }
 
-        // Get the response body as a string
-        let response_text: String = response.text().await?;
-
-        // Parse XML to JSON
-        let json_value: Value = parse_xml_to_json(&response_text)?;
-        Ok(json_value)
+        // Get the response body as a string, bounded by the client's limit
+        let body: String = read_body_limited(response, limit).await?;
+        cooldown.observe(status, &headers, Some(&body));
+        Ok(body)
     }
 
     /// Sets the domain name for the request.
@@ -183,3 +348,103 @@ impl Request {
         self
     }
 }
+
+/// Reads a response body, aborting with `ResponseTooLarge` once it exceeds `limit` bytes.
+///
+/// An announced `Content-Length` above the limit fails before anything is read, otherwise the
+/// body is read chunk by chunk so an oversized body never gets fully buffered.
+async fn read_body_limited(mut response: Response, limit: usize) -> Result<String, Box<dyn Error>> {
+    if let Some(length) = response.content_length() && (length as usize) > limit {
+        return Err(Box::new(ResponseTooLarge { limit, received: length as usize }));
+    }
+
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(Box::new(ResponseTooLarge { limit, received: body.len() + chunk.len() }));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(String::from_utf8(body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// Serves a single HTTP response with `body`, optionally without a `Content-Length`.
+    async fn serve_once(body: String, content_length: bool) -> String {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let head: String = if content_length {
+                format!("HTTP/1.1 200 OK\r\nContent-Type: application/xml\r\nContent-Length: {}\r\n\r\n", body.len())
+            } else {
+                "HTTP/1.1 200 OK\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n".to_string()
+            };
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(body.as_bytes()).await;
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_build_url_borrows_client() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let params: Value = serde_json::json!({ "SLD": "example", "TLD": "com", "Address1": "a b" });
+        let request: Request = Request::new(&client, "namecheap.domains.dns.getHosts".to_string(), Some(2), None, Some(params));
+
+        let url: String = request.build_url();
+        assert!(url.starts_with(NAMECHEAP_SANDBOX_API_URL));
+        assert!(url.contains("&Page=2"));
+        assert!(url.contains("&Address1=a+b&SLD=example&TLD=com"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_holds_back_mutations() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_dry_run(true);
+        let request: Request = Request::new(&client, "namecheap.domains.renew".to_string(), None, Some("example.com".to_string()), None);
+
+        let error: Box<dyn Error> = request.send().await.unwrap_err();
+        let planned: &PlannedRequest = error.downcast_ref::<PlannedRequest>().unwrap();
+        assert_eq!(planned.command, "namecheap.domains.renew");
+
+        assert!(is_read_command("namecheap.domains.dns.getHosts"));
+        assert!(is_read_command("namecheap.domains.check"));
+        assert!(!is_read_command("namecheap.ssl.activate"));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited() {
+        let body: String = format!("<ApiResponse>{}</ApiResponse>", "x".repeat(4096));
+
+        let url: String = serve_once(body.clone(), true).await;
+        let response: Response = reqwest::get(&url).await.unwrap();
+        assert_eq!(read_body_limited(response, 1 << 20).await.unwrap(), body);
+
+        for content_length in [true, false] {
+            let url: String = serve_once(body.clone(), content_length).await;
+            let response: Response = reqwest::get(&url).await.unwrap();
+            let error: Box<dyn Error> = read_body_limited(response, 1024).await.unwrap_err();
+            let error: &ResponseTooLarge = error.downcast_ref::<ResponseTooLarge>().unwrap();
+            assert_eq!(error.limit, 1024);
+            assert!(error.received > 1024);
+        }
+    }
+}
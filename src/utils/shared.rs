@@ -0,0 +1,281 @@
+//! ### Shared Client State
+//!
+//! `NameCheapClient` is cloned freely (into tasks, batch operations, helpers holding their own
+//! copy). State that must be shared between those clones, rather than copied, lives behind an
+//! `Arc` in `SharedState`:
+//! - the `reqwest::Client`, so every clone uses the same connection pool;
+//! - the optional `Transport` replacing it, e.g. a simulation;
+//! - the optional client-wide `RateLimiter`, so every clone draws from the same budget;
+//! - the throttling `Cooldown`, so a throttled response pauses every clone;
+//! - the optional `AccountSlot`, so every clone is scheduled under the same account of an
+//!   `AccountManager`;
+//! - the optional cancellation token, so a cancelled operation stops every clone;
+//! - the optional `Shutdown` handle, so no clone issues changes once shutdown begins;
+//! - the optional `ChargesLedger`, so the charges of every clone add up in one place;
+//! - the optional label `StateStore`, so every clone resolves the same domain labels;
+//! - the optional `ZoneHistory` (`audit_log` feature), so every clone records zone states;
+//! - the mutation policies, so every clone is guarded by them.
+//!
+//! The credentials and the other configuration fields are plain fields, copied by `clone`.
+//!
+//! Configure the client before cloning it: the builders changing shared state
+//! (`with_rate_limiter`, `with_transport`, `with_policy`, ...) give the client a new
+//! `SharedState`, clones made earlier keep the previous one and stop sharing with it.
+//!
+//! `SharedState` is skipped by serde. Two clients only compare equal if they share it, i.e. one
+//! is a clone of the other made after its last builder call.
+
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::sync::Arc;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::accounts::AccountSlot;
+use crate::policy::Policy;
+use crate::utils::cancellation::CancelHandle;
+use crate::utils::charges::ChargesLedger;
+use crate::utils::cooldown::Cooldown;
+use crate::utils::rate_limit::RateLimiter;
+use crate::utils::state_store::StateStore;
+use crate::utils::transport::Transport;
+#[cfg(feature = "audit_log")]
+use crate::zone::history::ZoneHistory;
+use crate::utils::shutdown::{ MutationGuard, Shutdown, ShuttingDown };
+
+/// The state shared by all clones of a `NameCheapClient`.
+#[derive(Clone, Default)]
+pub struct SharedState(Arc<SharedInner>);
+
+#[derive(Clone, Default)]
+struct SharedInner {
+    http: reqwest::Client,
+    transport: Option<Arc<dyn Transport>>,
+    limiter: Option<Arc<RateLimiter>>,
+    cooldown: Arc<Cooldown>,
+    account: Option<AccountSlot>,
+    cancellation: CancelHandle,
+    shutdown: Option<Shutdown>,
+    charges: Option<Arc<ChargesLedger>>,
+    labels: Option<Arc<dyn StateStore>>,
+    #[cfg(feature = "audit_log")]
+    history: Option<Arc<ZoneHistory>>,
+    policies: Vec<Arc<dyn Policy>>,
+}
+
+impl SharedState {
+    /// Returns the pooled HTTP client.
+    pub fn http(&self) -> &reqwest::Client {
+        &self.0.http
+    }
+
+    /// Returns the transport replacing the HTTP client, if one is set.
+    pub fn transport(&self) -> Option<&dyn Transport> {
+        self.0.transport.as_deref()
+    }
+
+    /// Returns a new state sending requests through `transport`.
+    pub(crate) fn with_transport(&self, transport: Arc<dyn Transport>) -> SharedState {
+        self.rebuild(|inner| inner.transport = Some(transport))
+    }
+
+    /// Returns the client-wide rate limiter, if one is set.
+    pub fn limiter(&self) -> Option<&RateLimiter> {
+        self.0.limiter.as_deref()
+    }
+
+    /// Returns the throttling cooldown.
+    pub fn cooldown(&self) -> &Cooldown {
+        &self.0.cooldown
+    }
+
+    /// Returns a new state pausing on `cooldown`.
+    pub(crate) fn with_cooldown(&self, cooldown: Arc<Cooldown>) -> SharedState {
+        self.rebuild(|inner| inner.cooldown = cooldown)
+    }
+
+    /// Returns the account slot of an `AccountManager`, if one is set.
+    pub fn account_slot(&self) -> Option<&AccountSlot> {
+        self.0.account.as_ref()
+    }
+
+    /// Returns a new state scheduling requests under `slot`.
+    pub(crate) fn with_account_slot(&self, slot: AccountSlot) -> SharedState {
+        self.rebuild(|inner| inner.account = Some(slot))
+    }
+
+    /// Returns the cancellation token of the client, empty if none is set.
+    pub fn cancellation(&self) -> &CancelHandle {
+        &self.0.cancellation
+    }
+
+    /// Returns a new state cancelled with `cancellation`.
+    pub(crate) fn with_cancellation(&self, cancellation: CancelHandle) -> SharedState {
+        self.rebuild(|inner| inner.cancellation = cancellation)
+    }
+
+    /// Returns the shutdown handle, if one is set.
+    pub fn shutdown(&self) -> Option<&Shutdown> {
+        self.0.shutdown.as_ref()
+    }
+
+    /// Returns the charges ledger, if one is set.
+    pub fn charges(&self) -> Option<&ChargesLedger> {
+        self.0.charges.as_deref()
+    }
+
+    /// Returns a new state recording charges in `ledger`.
+    pub(crate) fn with_charges(&self, ledger: Arc<ChargesLedger>) -> SharedState {
+        self.rebuild(|inner| inner.charges = Some(ledger))
+    }
+
+    /// Returns the label store, if one is set.
+    pub fn labels(&self) -> Option<&dyn StateStore> {
+        self.0.labels.as_deref()
+    }
+
+    /// Returns a new state resolving labels from `store`.
+    pub(crate) fn with_labels(&self, store: Arc<dyn StateStore>) -> SharedState {
+        self.rebuild(|inner| inner.labels = Some(store))
+    }
+
+    /// Returns the zone history, if one is set.
+    #[cfg(feature = "audit_log")]
+    pub fn history(&self) -> Option<&ZoneHistory> {
+        self.0.history.as_deref()
+    }
+
+    /// Returns a new state recording zone states in `history`.
+    #[cfg(feature = "audit_log")]
+    pub(crate) fn with_history(&self, history: Arc<ZoneHistory>) -> SharedState {
+        self.rebuild(|inner| inner.history = Some(history))
+    }
+
+    /// Returns the mutation policies, in the order they were attached.
+    pub fn policies(&self) -> &[Arc<dyn Policy>] {
+        &self.0.policies
+    }
+
+    /// Returns a new state also checking mutations against `policy`.
+    pub(crate) fn with_policy(&self, policy: Arc<dyn Policy>) -> SharedState {
+        self.rebuild(|inner| inner.policies.push(policy))
+    }
+
+    /// Returns a new state with the same contents, changed by `change`.
+    fn rebuild(&self, change: impl FnOnce(&mut SharedInner)) -> SharedState {
+        let mut inner: SharedInner = (*self.0).clone();
+        change(&mut inner);
+        SharedState(Arc::new(inner))
+    }
+
+    /// Returns `true` if both handles point to the same state.
+    pub fn ptr_eq(&self, other: &SharedState) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for SharedState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedState")
+            .field("transport", &self.0.transport.is_some())
+            .field("limiter", &self.0.limiter)
+            .field("cooldown", &self.0.cooldown.remaining())
+            .field("account", &self.0.account)
+            .field("cancellation", &self.0.cancellation)
+            .field("shutdown", &self.0.shutdown)
+            .field("charges", &self.0.charges.is_some())
+            .field("labels", &self.0.labels.is_some())
+            .field("policies", &self.0.policies.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SharedState {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl Eq for SharedState {}
+
+impl Hash for SharedState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl NameCheapClient {
+    /// Paces every API call of this client and its clones with `limiter`.
+    ///
+    /// Call this before cloning the client: clones made earlier keep the previous state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client = NameCheapClient::new_from_env()?.with_rate_limiter(RateLimiter::per_minute(20));
+    /// ```
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.shared = self.shared.rebuild(|inner| inner.limiter = Some(Arc::new(limiter)));
+        self
+    }
+
+    /// Refuses new changes from this client and its clones once `shutdown` begins.
+    ///
+    /// Mutating calls register as in flight with [`Shutdown::begin_mutation`] so
+    /// `Shutdown::shutdown` waits for them. Like `with_rate_limiter`, call this before cloning.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shared = self.shared.rebuild(|inner| inner.shutdown = Some(shutdown));
+        self
+    }
+
+    /// Registers a mutation with the client's shutdown handle, if any.
+    ///
+    /// Returns [`ShuttingDown`](crate::utils::shutdown::ShuttingDown) once shutdown has begun.
+    pub(crate) fn begin_mutation(&self) -> Result<Option<MutationGuard>, ShuttingDown> {
+        self.shared.shutdown().map(Shutdown::begin_mutation).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_clones_share_state() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_rate_limiter(RateLimiter::new(Duration::from_millis(10)));
+        let clone: NameCheapClient = client.clone();
+
+        assert!(clone.shared.ptr_eq(&client.shared));
+        assert!(clone.shared.limiter().is_some());
+        assert_eq!(clone, client);
+
+        let restored: NameCheapClient = serde_json::from_str(&serde_json::to_string(&client).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&client).unwrap());
+        assert_ne!(restored, client);
+    }
+
+    #[test]
+    fn test_clones_made_before_configuring_do_not_share() {
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        );
+        let early: NameCheapClient = client.clone();
+        let client: NameCheapClient = client.with_rate_limiter(RateLimiter::new(Duration::from_millis(10)));
+
+        assert!(early.shared.limiter().is_none());
+        assert!(!early.shared.ptr_eq(&client.shared));
+        assert_ne!(early, client);
+        assert!(client.clone().shared.ptr_eq(&client.shared));
+    }
+}
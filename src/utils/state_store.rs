@@ -0,0 +1,216 @@
+//! ### State Stores
+//!
+//! Long-running components (the watcher, drift checks) remember what they last saw to report
+//! only changes. Kept in memory, that state is lost on every restart and the first poll after
+//! a redeploy either reports nothing or reports everything again. A `StateStore` persists it
+//! as JSON values under string keys:
+//! - [`JsonFileStore`]: the default, one JSON file holding every key, replaced atomically.
+//! - [`MemoryStore`]: in-process only, for tests.
+//! - `SledStore`: an embedded sled database, with the `sled` feature.
+//!
+//! Use [`load_state`] and [`save_state`] to store typed values.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{ Map, Value };
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{ Path, PathBuf };
+use std::sync::Mutex;
+
+/// Persists JSON values under string keys.
+pub trait StateStore: Send + Sync {
+    /// Returns the value stored under `key`, `None` if there is none.
+    fn load(&self, key: &str) -> Result<Option<Value>, Box<dyn Error>>;
+
+    /// Stores `value` under `key`, replacing the previous value.
+    fn save(&self, key: &str, value: &Value) -> Result<(), Box<dyn Error>>;
+
+    /// Removes the value stored under `key`, if any.
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>>;
+}
+
+/// Loads a typed value from a store.
+pub fn load_state<T: DeserializeOwned>(store: &dyn StateStore, key: &str) -> Result<Option<T>, Box<dyn Error>> {
+    match store.load(key)? {
+        Some(value) => Ok(Some(serde_json::from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Saves a typed value to a store.
+pub fn save_state<T: Serialize>(store: &dyn StateStore, key: &str, value: &T) -> Result<(), Box<dyn Error>> {
+    store.save(key, &serde_json::to_value(value)?)
+}
+
+/// Keeps state in memory, lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    values: Mutex<HashMap<String, Value>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl StateStore for MemoryStore {
+    fn load(&self, key: &str) -> Result<Option<Value>, Box<dyn Error>> {
+        Ok(self.values.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(key).cloned())
+    }
+
+    fn save(&self, key: &str, value: &Value) -> Result<(), Box<dyn Error>> {
+        self.values.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(key.to_string(), value.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>> {
+        self.values.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(key);
+        Ok(())
+    }
+}
+
+/// Keeps state in a single JSON object file, `{ "<key>": <value>, ... }`.
+///
+/// Every save writes a temporary file next to it and renames it over the original, so a crash
+/// mid-write never leaves a truncated file behind.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    // Serializes read-modify-write cycles within the process
+    write: Mutex<()>,
+}
+
+impl JsonFileStore {
+    /// Creates a store backed by `path`, the file is created on the first save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        JsonFileStore { path: path.into(), write: Mutex::new(()) }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_all(&self) -> Result<Map<String, Value>, Box<dyn Error>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) if text.trim().is_empty() => Ok(Map::new()),
+            Ok(text) => match serde_json::from_str(&text)? {
+                Value::Object(values) => Ok(values),
+                _ => Err(format!("State file {} is not a JSON object", self.path.display()).into()),
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Map::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write_all(&self, values: &Map<String, Value>) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temporary: OsString = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(values)?)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Map<String, Value>)) -> Result<(), Box<dyn Error>> {
+        let _write = self.write.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut values: Map<String, Value> = self.read_all()?;
+        change(&mut values);
+        self.write_all(&values)
+    }
+}
+
+impl StateStore for JsonFileStore {
+    fn load(&self, key: &str) -> Result<Option<Value>, Box<dyn Error>> {
+        Ok(self.read_all()?.remove(key))
+    }
+
+    fn save(&self, key: &str, value: &Value) -> Result<(), Box<dyn Error>> {
+        self.update(|values| {
+            values.insert(key.to_string(), value.clone());
+        })
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>> {
+        self.update(|values| {
+            values.remove(key);
+        })
+    }
+}
+
+/// Keeps state in an embedded sled database, one entry per key.
+#[cfg(feature = "sled")]
+pub struct SledStore {
+    db: sled::Db,
+}
+
+#[cfg(feature = "sled")]
+impl SledStore {
+    /// Opens or creates the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(SledStore { db: sled::open(path)? })
+    }
+}
+
+#[cfg(feature = "sled")]
+impl StateStore for SledStore {
+    fn load(&self, key: &str) -> Result<Option<Value>, Box<dyn Error>> {
+        match self.db.get(key)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn save(&self, key: &str, value: &Value) -> Result<(), Box<dyn Error>> {
+        self.db.insert(key, serde_json::to_vec(value)?)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Box<dyn Error>> {
+        self.db.remove(key)?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_file_store_round_trip() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-state-{}/state.json", std::process::id()));
+        let store: JsonFileStore = JsonFileStore::new(&path);
+        assert_eq!(store.load("watcher").unwrap(), None);
+
+        store.save("watcher", &json!({ "zoneHashes": { "example.com": "abc" } })).unwrap();
+        save_state(&store, "drift", &vec!["example.com"]).unwrap();
+
+        // A fresh store, as after a restart
+        let reopened: JsonFileStore = JsonFileStore::new(&path);
+        assert_eq!(reopened.load("watcher").unwrap().unwrap()["zoneHashes"]["example.com"], "abc");
+        assert_eq!(load_state::<Vec<String>>(&reopened, "drift").unwrap(), Some(vec!["example.com".to_string()]));
+
+        reopened.remove("drift").unwrap();
+        assert_eq!(store.load("drift").unwrap(), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-state-corrupt-{}.json", std::process::id()));
+        fs::write(&path, "[1, 2]").unwrap();
+        assert!(JsonFileStore::new(&path).load("watcher").is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+//! ### Transport
+//!
+//! Requests go out over the pooled `reqwest::Client` by default. A `Transport` set with
+//! [`NameCheapClient::with_transport`] replaces the HTTP call for this client and all its
+//! clones, e.g. the [`SimulatedTransport`](crate::simulation::SimulatedTransport) used for load
+//! tests. Everything around the call (rate limiting, cooldowns, response limits, parsing) is
+//! unchanged.
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+// crate imports
+use crate::NameCheapClient;
+
+/// The future returned by [`Transport::get`].
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<TransportResponse, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// ### Transport Response
+///
+/// #### Fields
+/// - `status`: The HTTP status.
+/// - `headers`: The response headers.
+/// - `body`: The XML body.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// Creates a `200 OK` response carrying `body`.
+    pub fn ok(body: String) -> Self {
+        TransportResponse { status: StatusCode::OK, headers: HeaderMap::new(), body }
+    }
+}
+
+/// Sends the requests of a client.
+pub trait Transport: Send + Sync {
+    /// Sends a `GET` request to `url`, the full API URL including the command and credentials.
+    fn get<'a>(&'a self, url: &'a str) -> TransportFuture<'a>;
+}
+
+/// Shares a transport with the caller, e.g. to read the stats of a simulation.
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn get<'a>(&'a self, url: &'a str) -> TransportFuture<'a> {
+        (**self).get(url)
+    }
+}
+
+impl NameCheapClient {
+    /// Sends every request of this client and its clones through `transport`.
+    ///
+    /// Like `with_rate_limiter`, call this before cloning the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client = client.with_transport(SimulatedTransport::new(SimulationConfig::default()));
+    /// ```
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.shared = self.shared.with_transport(Arc::new(transport));
+        self
+    }
+}
@@ -0,0 +1,179 @@
+//! ### Credential Validation
+//!
+//! `NameCheapClient::validate` performs the cheapest authenticated call available (the first
+//! page of `domains.getList` with the smallest page size) and interprets the outcome, so
+//! applications can fail fast at startup with a clear reason instead of on their first
+//! real call.
+
+use serde::{ Serialize, Deserialize };
+use serde_json::{ Value, json };
+use std::fmt;
+use std::time::{ Duration, Instant };
+
+// crate imports
+use crate::{ NameCheapClient, NAMECHEAP_API_URL, NAMECHEAP_SANDBOX_API_URL };
+use crate::response::errors::{ check_api_errors, ApiError };
+use crate::response::paging::PageSize;
+use crate::utils::request_builder::Request;
+
+/// Error numbers reported for an invalid API user, user name or key.
+const CREDENTIAL_ERRORS: [&str; 6] = ["1010101", "1010102", "1010104", "1011102", "1017101", "1017105"];
+
+/// Error numbers reported for a client IP that is not whitelisted.
+const IP_ERRORS: [&str; 3] = ["1011150", "1017150", "2011150"];
+
+/// Error numbers reported when the account is throttled.
+pub(crate) const RATE_LIMIT_ERRORS: [&str; 1] = ["500000"];
+
+/// Why a client failed validation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ValidationFailure {
+    /// The API user, user name or API key was rejected.
+    InvalidCredentials { error: ApiError },
+    /// The client IP is not on the account's API whitelist.
+    IpNotWhitelisted { client_ip: String, error: ApiError },
+    /// The account is throttled, the credentials could not be checked.
+    RateLimited { error: ApiError },
+    /// Another error reported by the API.
+    Api { error: ApiError },
+    /// The endpoint could not be reached or answered with an unreadable response.
+    Unreachable { message: String },
+}
+
+impl ValidationFailure {
+    /// Classifies an error reported by the API.
+    pub fn from_api_error(error: ApiError, client_ip: &str) -> Self {
+        let number: &str = error.number.as_str();
+        let message: String = error.message.to_lowercase();
+        if IP_ERRORS.contains(&number) || message.contains("request ip") || message.contains("whitelist") {
+            ValidationFailure::IpNotWhitelisted { client_ip: client_ip.to_string(), error }
+        } else if CREDENTIAL_ERRORS.contains(&number) || message.contains("api key") || message.contains("apiuser") {
+            ValidationFailure::InvalidCredentials { error }
+        } else if RATE_LIMIT_ERRORS.contains(&number) || message.contains("too many requests") {
+            ValidationFailure::RateLimited { error }
+        } else {
+            ValidationFailure::Api { error }
+        }
+    }
+}
+
+impl fmt::Display for ValidationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationFailure::InvalidCredentials { error } => write!(f, "Invalid API credentials ({})", error),
+            ValidationFailure::IpNotWhitelisted { client_ip, error } =>
+                write!(f, "Client IP {} is not whitelisted for API access ({})", client_ip, error),
+            ValidationFailure::RateLimited { error } => write!(f, "Rate limited by the API ({})", error),
+            ValidationFailure::Api { error } => write!(f, "{}", error),
+            ValidationFailure::Unreachable { message } => write!(f, "API unreachable: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ValidationFailure {}
+
+/// ### Validation Report
+///
+/// The outcome of `NameCheapClient::validate`.
+///
+/// #### Fields
+/// - `endpoint`: The API endpoint that was called.
+/// - `production`: Whether the endpoint is production rather than the sandbox.
+/// - `client_ip`: The client IP sent with the call.
+/// - `elapsed`: How long the call took.
+/// - `failure`: Why validation failed, `None` when the client is usable.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ValidationReport {
+    pub endpoint: String,
+    pub production: bool,
+    pub client_ip: String,
+    pub elapsed: Duration,
+    pub failure: Option<ValidationFailure>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if the client can make API calls.
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// Returns the failure as an error, for `?` at startup.
+    pub fn into_result(self) -> Result<ValidationReport, ValidationFailure> {
+        match self.failure {
+            Some(failure) => Err(failure),
+            None => Ok(self),
+        }
+    }
+}
+
+impl NameCheapClient {
+    /// Checks that the client can make API calls, with a single cheap call.
+    ///
+    /// Never returns an error: failures are described in the report.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let client = NameCheapClient::new_from_env()?;
+    /// client.validate().await.into_result()?;
+    /// ```
+    pub async fn validate(&self) -> ValidationReport {
+        let params: Value = json!({ "PageSize": PageSize::MIN.get() });
+        let request: Request = Request::new(
+            self,
+            "namecheap.domains.getList".to_string(),
+            Some(1),
+            None,
+            Some(params)
+        );
+
+        let started: Instant = Instant::now();
+        let failure: Option<ValidationFailure> = match request.send().await {
+            Ok(response) =>
+                check_api_errors(&response)
+                    .err()
+                    .map(|error| ValidationFailure::from_api_error(error, &self.client_ip)),
+            Err(error) => Some(ValidationFailure::Unreachable { message: error.to_string() }),
+        };
+
+        ValidationReport {
+            endpoint: (if self.production { NAMECHEAP_API_URL } else { NAMECHEAP_SANDBOX_API_URL }).to_string(),
+            production: self.production,
+            client_ip: self.client_ip.clone(),
+            elapsed: started.elapsed(),
+            failure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(number: &str, message: &str) -> ApiError {
+        ApiError::new(number, message)
+    }
+
+    #[test]
+    fn test_failure_classification() {
+        let failure: ValidationFailure = ValidationFailure::from_api_error(
+            api_error("1011150", "Parameter RequestIP is invalid"),
+            "203.0.113.10"
+        );
+        assert!(matches!(failure, ValidationFailure::IpNotWhitelisted { .. }));
+        assert!(failure.to_string().contains("203.0.113.10"));
+
+        let failure: ValidationFailure = ValidationFailure::from_api_error(
+            api_error("1011102", "API Key is invalid or API access has not been enabled"),
+            "203.0.113.10"
+        );
+        assert!(matches!(failure, ValidationFailure::InvalidCredentials { .. }));
+
+        let failure: ValidationFailure = ValidationFailure::from_api_error(api_error("2019166", "Domain not found"), "");
+        assert!(matches!(failure, ValidationFailure::Api { .. }));
+    }
+}
@@ -1,14 +1,24 @@
 //! ## XML Parser
-//! 
-//! 
+//!
+//! Converts Namecheap XML responses into a JSON `Value` tree. `parse_xml_to_json` and
+//! `parse_xml_bytes` are the stable entry points of the parser: they never panic, malformed,
+//! truncated or adversarial input (invalid UTF-8, nesting deeper than `MAX_DEPTH`) is
+//! reported as an error. Property tests below and the `fuzz/` harness exercise that contract.
 
 use xml::reader::{ EventReader, XmlEvent };
 use xml::ParserConfig;
+use serde::{ Serialize, Deserialize };
 use serde_json::{ Value, json, Map };
 use anyhow::{ Result, anyhow };
 
+/// The deepest element nesting accepted.
+///
+/// Namecheap responses nest a handful of levels, the limit keeps adversarial input from
+/// building a tree deep enough to overflow the stack when it is dropped or serialized.
+pub const MAX_DEPTH: usize = 128;
 
-fn convert_camel_to_snake(name: &str) -> String {
+/// Converts an XML attribute name to the snake_case key used in the parsed JSON, e.g. `IsDDNSEnabled` to `is_ddnsenabled`.
+pub(crate) fn convert_camel_to_snake(name: &str) -> String {
     let mut result: String = String::new();
     let mut prev_was_upper: bool = false;
 
@@ -28,6 +38,28 @@ fn convert_camel_to_snake(name: &str) -> String {
     result
 }
 
+/// ### Parse Options
+///
+/// Options of [`parse_xml_to_json_with`]. The default keeps the historical shape of the tree.
+///
+/// #### Fields
+/// - `flatten_text`: Turns elements holding only text into plain strings, e.g.
+///   `<Nameserver>ns1</Nameserver>` into `"ns1"` instead of `{ "$text": "ns1" }`. Elements with
+///   attributes or children stay objects, attribute-only elements are always flat objects.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    pub flatten_text: bool,
+}
+
+impl ParseOptions {
+    /// Returns options flattening text-only elements, used by the typed APIs.
+    pub fn flat() -> Self {
+        ParseOptions { flatten_text: true }
+    }
+}
+
 /// Parses XML string into a JSON Value
 ///
 /// This function takes an XML string and converts it into a serde_json Value,
@@ -41,10 +73,18 @@ fn convert_camel_to_snake(name: &str) -> String {
 ///
 /// A `Result` containing the parsed JSON `Value` if successful, or an `Error` if parsing fails.
 pub fn parse_xml_to_json(xml_str: &str) -> Result<Value> {
+    parse_xml_to_json_with(xml_str, ParseOptions::default())
+}
+
+/// Parses XML string into a JSON Value, shaped by `options`
+///
+/// Same contract as [`parse_xml_to_json`].
+pub fn parse_xml_to_json_with(xml_str: &str, options: ParseOptions) -> Result<Value> {
     let config: ParserConfig = ParserConfig::new()
         .trim_whitespace(true)
         .ignore_comments(true)
-        .coalesce_characters(true);
+        .coalesce_characters(true)
+        .cdata_to_characters(true);
 
     let cursor: std::io::Cursor<&[u8]> = std::io::Cursor::new(xml_str.as_bytes());
     let reader: EventReader<std::io::Cursor<&[u8]>> = EventReader::new_with_config(cursor, config);
@@ -55,6 +95,9 @@ pub fn parse_xml_to_json(xml_str: &str) -> Result<Value> {
     for event in reader {
         match event {
             Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                if stack.len() >= MAX_DEPTH {
+                    return Err(anyhow!("XML nesting deeper than {} elements", MAX_DEPTH));
+                }
                 let mut obj: Map<String, Value> = Map::new();
 
                 // Add attributes as properties with @ prefix
@@ -70,11 +113,14 @@ pub fn parse_xml_to_json(xml_str: &str) -> Result<Value> {
                 if let Some((element_name, mut obj)) = stack.pop() {
                     // If we have text content, add it
                     let trimmed = current_text.trim();
-                    if !trimmed.is_empty() {
-                        obj.insert("$text".to_string(), json!(trimmed));
-                    }
-
-                    let json_obj = Value::Object(obj);
+                    let json_obj = if options.flatten_text && obj.is_empty() && !trimmed.is_empty() {
+                        json!(trimmed)
+                    } else {
+                        if !trimmed.is_empty() {
+                            obj.insert("$text".to_string(), json!(trimmed));
+                        }
+                        Value::Object(obj)
+                    };
 
                     if stack.is_empty() {
                         // We're at the root
@@ -113,3 +159,96 @@ pub fn parse_xml_to_json(xml_str: &str) -> Result<Value> {
 
     Err(anyhow!("Unexpected end of XML document"))
 }
+
+/// Parses raw XML bytes into a JSON Value, rejecting invalid UTF-8
+///
+/// # Parameters
+///
+/// - `bytes`: The raw response body
+///
+/// # Returns
+///
+/// A `Result` containing the parsed JSON `Value` if successful, or an `Error` if the bytes are
+/// not UTF-8 or parsing fails.
+pub fn parse_xml_bytes(bytes: &[u8]) -> Result<Value> {
+    let xml_str: &str = std::str::from_utf8(bytes).map_err(|e| anyhow!("Response is not valid UTF-8: {}", e))?;
+    parse_xml_to_json(xml_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn nested(depth: usize) -> String {
+        format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth))
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        assert!(parse_xml_to_json(&nested(MAX_DEPTH)).is_ok());
+        assert!(parse_xml_to_json(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(parse_xml_to_json(&nested(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_flatten_text() {
+        let xml: &str = r#"<ApiResponse Status="OK"><Errors><Error Number="1">Failed</Error></Errors>
+            <Result><Nameserver>ns1.example.net</Nameserver><Nameserver>ns2.example.net</Nameserver><Empty /></Result>
+            <GMTTimeDifference>--5:00</GMTTimeDifference></ApiResponse>"#;
+
+        let flat: Value = parse_xml_to_json_with(xml, ParseOptions::flat()).unwrap();
+        assert_eq!(flat["ApiResponse"]["Result"]["Nameserver"], json!(["ns1.example.net", "ns2.example.net"]));
+        assert_eq!(flat["ApiResponse"]["GMTTimeDifference"], "--5:00");
+        assert_eq!(flat["ApiResponse"]["Result"]["Empty"], json!({}));
+        assert_eq!(flat["ApiResponse"]["Errors"]["Error"], json!({ "number": "1", "$text": "Failed" }));
+
+        let wrapped: Value = parse_xml_to_json(xml).unwrap();
+        assert_eq!(wrapped["ApiResponse"]["GMTTimeDifference"], json!({ "$text": "--5:00" }));
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        assert!(parse_xml_bytes(b"<ApiResponse Status=\"\xff\" />").is_err());
+        assert!(parse_xml_bytes(b"<ApiResponse Status=\"OK\" />").is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_arbitrary_text_never_panics(xml in ".{0,512}") {
+            let _ = parse_xml_to_json(&xml);
+            let _ = crate::response::typed_xml::scan_elements(&xml, "host", &["TotalItems"]);
+        }
+
+        #[test]
+        fn prop_arbitrary_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+            let _ = parse_xml_bytes(&bytes);
+        }
+
+        #[test]
+        fn prop_xml_like_input_never_panics(
+            parts in proptest::collection::vec(
+                prop_oneof![
+                    Just("<a>".to_string()),
+                    Just("</a>".to_string()),
+                    Just("<b x=\"1\"/>".to_string()),
+                    Just("<![CDATA[".to_string()),
+                    Just("]]>".to_string()),
+                    Just("&amp;".to_string()),
+                    Just("&bogus;".to_string()),
+                    "[a-z<>/=\"' ]{0,8}",
+                ],
+                0..64
+            )
+        ) {
+            let _ = parse_xml_to_json(&parts.concat());
+        }
+
+        #[test]
+        fn prop_huge_attributes_round_trip(value in "[a-zA-Z0-9 .:/-]{0,4096}") {
+            let xml: String = format!(r#"<ApiResponse><host Address="{}" /></ApiResponse>"#, value);
+            let json: Value = parse_xml_to_json(&xml).unwrap();
+            prop_assert_eq!(json["ApiResponse"]["host"]["address"].as_str(), Some(value.as_str()));
+        }
+    }
+}
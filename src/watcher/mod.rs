@@ -0,0 +1,438 @@
+//! ## Watcher
+//! Polls the account on an interval and emits a `WatchEvent` when something changes: a domain
+//! enters the expiry window, the records of a watched zone change, or a zone drifts from (or
+//! returns to) its desired records.
+//!
+//! Events are delivered to any number of consumers:
+//! - [`Watcher::subscribe`]: a broadcast receiver, every subscriber sees every event but a slow
+//!   one skips ahead (`RecvError::Lagged`) once it is `capacity` events behind.
+//! - [`Watcher::stream`]: a bounded stream with an explicit overflow and sampling policy, see
+//!   [`stream`]. Prefer it for slow consumers (webhooks, databases) watching large portfolios
+//!   at short intervals.
+//!
+//! The first poll only records the current state, events are emitted for later changes. With
+//! [`Watcher::with_state_store`] the state survives restarts, so a redeploy neither reports
+//! every zone as changed nor forgets which domains were already reported as expiring.
+//!
+//! # Example
+//!
+//! ```rust
+//! let mut watcher = Watcher::new(client)
+//!     .interval(Duration::from_secs(900))
+//!     .expiring_within(30)
+//!     .watch_zone("example.com");
+//! let mut events = watcher.stream(StreamConfig::new(64, Overflow::Conflate));
+//! let handle = watcher.start();
+//! while let Some(event) = events.recv().await {
+//!     println!("{:?}", event);
+//! }
+//! ```
+
+/// - **bounded event stream**
+pub mod stream;
+
+use chrono::{ DateTime, Utc };
+use serde::{ Serialize, Deserialize };
+use std::collections::{ BTreeMap, BTreeSet };
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{ info, warn };
+
+// crate imports
+use crate::{ NameCheapClient, Domain, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::rate_limit::Priority;
+use crate::utils::shutdown::Shutdown;
+use crate::utils::state_store::{ load_state, save_state, StateStore };
+use crate::watcher::stream::{ event_stream, EventReceiver, EventSender, StreamConfig };
+use crate::zone::split_domain;
+use crate::zone::diff::{ ChangeKind, ZonePlan };
+use crate::zone::snapshot::snapshot_hash;
+
+/// The default polling interval.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// The key the `WatchState` is stored under in a `StateStore`.
+pub const WATCH_STATE_KEY: &str = "watcher";
+
+/// An event emitted by the `Watcher`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum WatchEvent {
+    /// A domain entered the expiry window.
+    Expiring { domain: String, expires_at: Option<DateTime<Utc>>, days_left: i64 },
+    /// The records of a watched zone changed.
+    ZoneChanged { domain: String, previous_hash: String, hash: String },
+    /// A zone differs from its desired records in `changes` records.
+    Drift { domain: String, changes: usize },
+    /// A drifted zone matches its desired records again.
+    DriftResolved { domain: String },
+    /// A poll failed, `domain` is `None` for account wide calls.
+    Error { domain: Option<String>, message: String },
+}
+
+impl WatchEvent {
+    /// Returns the conflation key of the event, one per kind and domain.
+    pub fn key(&self) -> String {
+        match self {
+            WatchEvent::Expiring { domain, .. } => format!("expiring:{}", domain),
+            WatchEvent::ZoneChanged { domain, .. } => format!("zone:{}", domain),
+            WatchEvent::Drift { domain, .. } | WatchEvent::DriftResolved { domain } => format!("drift:{}", domain),
+            WatchEvent::Error { domain, .. } => format!("error:{}", domain.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+/// ### Watch State
+///
+/// What the watcher has seen so far.
+///
+/// #### Fields
+/// - `zone_hashes`: The last `snapshot_hash` of each watched zone.
+/// - `expiring`: The domains already reported as expiring.
+/// - `drifted`: The drifted zones already reported, with the `snapshot_hash` they drifted at.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct WatchState {
+    pub zone_hashes: BTreeMap<String, String>,
+    pub expiring: BTreeSet<String>,
+    #[serde(default)]
+    pub drifted: BTreeMap<String, String>,
+}
+
+impl WatchState {
+    /// Records the expiry window and returns an event for each domain newly inside it.
+    ///
+    /// Domains leaving the window (renewed, deleted) are forgotten, so they are reported again
+    /// the next time they enter it.
+    pub fn expiring_events(&mut self, domains: &[Domain], days: i64) -> Vec<WatchEvent> {
+        let now: DateTime<Utc> = Utc::now();
+        let inside: Vec<&Domain> = domains
+            .iter()
+            .filter(|domain| !domain.is_expired && domain.expires_within(days))
+            .collect();
+        self.expiring.retain(|name| inside.iter().any(|domain| &domain.name == name));
+
+        inside
+            .into_iter()
+            .filter(|domain| self.expiring.insert(domain.name.clone()))
+            .map(|domain| WatchEvent::Expiring {
+                domain: domain.name.clone(),
+                expires_at: domain.expires_at,
+                days_left: domain.expires_at.map_or(0, |expires| (expires - now).num_days()),
+            })
+            .collect()
+    }
+
+    /// Records the records of a zone and returns an event if they changed since the last call.
+    pub fn zone_event(&mut self, domain: &str, hosts: &[Host]) -> Option<WatchEvent> {
+        let hash: String = snapshot_hash(hosts);
+        match self.zone_hashes.insert(domain.to_string(), hash.clone()) {
+            Some(previous_hash) if previous_hash != hash => {
+                Some(WatchEvent::ZoneChanged { domain: domain.to_string(), previous_hash, hash })
+            }
+            _ => None,
+        }
+    }
+
+    /// Compares the records of a zone with its desired records and returns an event when the
+    /// zone starts drifting, drifts differently or is back in sync.
+    pub fn drift_event(&mut self, domain: &str, hosts: &[Host], desired: &[HostRequest]) -> Option<WatchEvent> {
+        let plan: ZonePlan = ZonePlan::new(domain, hosts, desired);
+        if !plan.has_changes() {
+            return self.drifted
+                .remove(domain)
+                .map(|_| WatchEvent::DriftResolved { domain: domain.to_string() });
+        }
+
+        let hash: String = snapshot_hash(hosts);
+        if self.drifted.get(domain) == Some(&hash) {
+            return None;
+        }
+        self.drifted.insert(domain.to_string(), hash);
+        let changes: usize = plan.changes.iter().filter(|change| change.kind != ChangeKind::Keep).count();
+        Some(WatchEvent::Drift { domain: domain.to_string(), changes })
+    }
+}
+
+/// Where the watcher delivers events.
+enum Sink {
+    Broadcast(broadcast::Sender<WatchEvent>),
+    Stream(EventSender),
+}
+
+/// Polls the account and emits `WatchEvent`s.
+pub struct Watcher {
+    client: NameCheapClient,
+    interval: Duration,
+    expiring_within: Option<i64>,
+    zones: Vec<String>,
+    drift: Vec<(String, Vec<HostRequest>)>,
+    state: WatchState,
+    store: Option<Arc<dyn StateStore>>,
+    sinks: Vec<Sink>,
+    shutdown: Shutdown,
+}
+
+impl Watcher {
+    /// Creates a watcher polling every `DEFAULT_WATCH_INTERVAL`, watching nothing yet.
+    ///
+    /// The client is switched to `Priority::Background`, so interactive calls sharing its rate
+    /// limiter go first.
+    pub fn new(client: NameCheapClient) -> Self {
+        Watcher {
+            client: client.with_priority(Priority::Background),
+            interval: DEFAULT_WATCH_INTERVAL,
+            expiring_within: None,
+            zones: Vec::new(),
+            drift: Vec::new(),
+            state: WatchState::default(),
+            store: None,
+            sinks: Vec::new(),
+            shutdown: Shutdown::default(),
+        }
+    }
+
+    /// Sets the polling interval.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Emits `Expiring` events for domains expiring within `days`.
+    pub fn expiring_within(mut self, days: i64) -> Self {
+        self.expiring_within = Some(days);
+        self
+    }
+
+    /// Emits `ZoneChanged` events when the records of `domain` change.
+    pub fn watch_zone(mut self, domain: &str) -> Self {
+        self.zones.push(domain.to_string());
+        self
+    }
+
+    /// Emits `Drift` events when the records of `domain` differ from `desired`.
+    pub fn watch_drift(mut self, domain: &str, desired: Vec<HostRequest>) -> Self {
+        self.drift.push((domain.to_string(), desired));
+        self
+    }
+
+    /// Stops polling once `shutdown` begins.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Persists the watch state in `store`, restoring the state saved by a previous run.
+    ///
+    /// Fails if the stored state cannot be read, rather than starting over and reporting
+    /// everything again.
+    pub fn with_state_store(mut self, store: Arc<dyn StateStore>) -> Result<Self, Box<dyn Error>> {
+        if let Some(state) = load_state::<WatchState>(store.as_ref(), WATCH_STATE_KEY)? {
+            self.state = state;
+        }
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    /// Returns what the watcher has seen so far.
+    pub fn state(&self) -> &WatchState {
+        &self.state
+    }
+
+    /// Returns a broadcast receiver of every event, lagging after `capacity` unread events.
+    ///
+    /// Subscribers share one broadcast channel, created with the capacity of the first call.
+    pub fn subscribe(&mut self, capacity: usize) -> broadcast::Receiver<WatchEvent> {
+        for sink in &self.sinks {
+            if let Sink::Broadcast(sender) = sink {
+                return sender.subscribe();
+            }
+        }
+        let (sender, receiver) = broadcast::channel(capacity.max(1));
+        self.sinks.push(Sink::Broadcast(sender));
+        receiver
+    }
+
+    /// Returns a bounded stream of every event, see [`stream`].
+    pub fn stream(&mut self, config: StreamConfig) -> EventReceiver {
+        let (sender, receiver) = event_stream(config);
+        self.sinks.push(Sink::Stream(sender));
+        receiver
+    }
+
+    /// Polls once and returns the events, without delivering them.
+    ///
+    /// The state is saved to the state store, if any, when the poll changed it.
+    pub async fn poll(&mut self) -> Vec<WatchEvent> {
+        let mut events: Vec<WatchEvent> = Vec::new();
+        let previous: WatchState = self.state.clone();
+
+        if let Some(days) = self.expiring_within {
+            match self.client.domains_get_list_all().await {
+                Ok(domains) => events.extend(self.state.expiring_events(&domains, days)),
+                Err(err) => events.push(WatchEvent::Error { domain: None, message: err.to_string() }),
+            }
+        }
+
+        // Zones both watched and checked for drift are fetched once
+        let mut domains: Vec<&String> = self.zones.iter().chain(self.drift.iter().map(|(domain, _)| domain)).collect();
+        domains.sort();
+        domains.dedup();
+        for domain in domains {
+            let Some((sld, tld)) = split_domain(domain) else {
+                events.push(WatchEvent::Error { domain: Some(domain.clone()), message: "Invalid domain".to_string() });
+                continue;
+            };
+            let hosts: Vec<Host> = match self.client.domains_dns_get_host_records(sld, tld).await.map_err(|err| err.to_string()) {
+                Ok(hosts) => hosts,
+                Err(message) => {
+                    events.push(WatchEvent::Error { domain: Some(domain.clone()), message });
+                    continue;
+                }
+            };
+            if self.zones.contains(domain) {
+                events.extend(self.state.zone_event(domain, &hosts));
+            }
+            for (_, desired) in self.drift.iter().filter(|(drifting, _)| drifting == domain) {
+                events.extend(self.state.drift_event(domain, &hosts, desired));
+            }
+        }
+
+        if let Some(store) = &self.store &&
+            self.state != previous &&
+            let Err(err) = save_state(store.as_ref(), WATCH_STATE_KEY, &self.state)
+        {
+            warn!("Failed to save the watch state: {}", err);
+        }
+        events
+    }
+
+    /// Delivers an event to every sink, dropping sinks whose consumers are gone.
+    async fn deliver(&mut self, event: WatchEvent) {
+        let mut closed: Vec<usize> = Vec::new();
+        for (index, sink) in self.sinks.iter().enumerate() {
+            let delivered: bool = match sink {
+                // A broadcast without receivers keeps its sender, subscribers may still come
+                Sink::Broadcast(sender) => {
+                    let _ = sender.send(event.clone());
+                    true
+                }
+                Sink::Stream(sender) => sender.send(event.clone()).await.is_ok(),
+            };
+            if !delivered {
+                closed.push(index);
+            }
+        }
+        for index in closed.into_iter().rev() {
+            self.sinks.remove(index);
+        }
+    }
+
+    /// Polls on the interval until shutdown, delivering events to the subscribers.
+    pub fn start(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let events: Vec<WatchEvent> = self.poll().await;
+                info!("Watcher poll emitted {} events", events.len());
+                for event in events {
+                    self.deliver(event).await;
+                }
+                if self.sinks.is_empty() {
+                    warn!("Watcher has no consumers left, stopping");
+                    return;
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(self.interval) => {}
+                    _ = self.shutdown.cancelled() => {
+                        info!("Watcher stopped by shutdown");
+                        return;
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use crate::utils::state_store::MemoryStore;
+
+    fn domain(name: &str, days: i64) -> Domain {
+        serde_json::from_value(serde_json::json!({ "id": 1, "name": name })).map(|mut domain: Domain| {
+            domain.expires_at = Some(Utc::now() + ChronoDuration::days(days));
+            domain
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_expiring_events_fire_once() {
+        let mut state: WatchState = WatchState::default();
+        let domains: Vec<Domain> = vec![domain("soon.com", 10), domain("later.com", 200)];
+
+        let events: Vec<WatchEvent> = state.expiring_events(&domains, 30);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].key(), "expiring:soon.com");
+        assert!(state.expiring_events(&domains, 30).is_empty());
+
+        // Renewed, then expiring again
+        assert!(state.expiring_events(&[domain("soon.com", 300)], 30).is_empty());
+        assert_eq!(state.expiring_events(&domains, 30).len(), 1);
+    }
+
+    #[test]
+    fn test_zone_event_after_first_poll() {
+        let mut state: WatchState = WatchState::default();
+        let mut host: Host = Host::new();
+        host.name = "@".to_string();
+        host.type_ = "A".to_string();
+        host.address = "203.0.113.10".to_string();
+
+        assert_eq!(state.zone_event("example.com", std::slice::from_ref(&host)), None);
+        assert_eq!(state.zone_event("example.com", std::slice::from_ref(&host)), None);
+        host.address = "203.0.113.11".to_string();
+        assert!(matches!(state.zone_event("example.com", &[host]), Some(WatchEvent::ZoneChanged { .. })));
+    }
+
+    #[test]
+    fn test_drift_events() {
+        let mut state: WatchState = WatchState::default();
+        let mut host: Host = Host::new();
+        host.name = "@".to_string();
+        host.type_ = "A".to_string();
+        host.address = "203.0.113.10".to_string();
+        host.ttl = 1800;
+        let desired: Vec<HostRequest> = vec![host.to_host_request()];
+        let live: Vec<Host> = vec![Host { address: "203.0.113.99".to_string(), ..host.clone() }];
+
+        assert_eq!(state.drift_event("example.com", &[host.clone()], &desired), None);
+        assert!(matches!(state.drift_event("example.com", &live, &desired), Some(WatchEvent::Drift { changes: 2, .. })));
+        assert_eq!(state.drift_event("example.com", &live, &desired), None);
+        assert_eq!(
+            state.drift_event("example.com", &[host], &desired),
+            Some(WatchEvent::DriftResolved { domain: "example.com".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_state_is_restored_from_store() {
+        let store: Arc<MemoryStore> = Arc::new(MemoryStore::new());
+        let mut state: WatchState = WatchState::default();
+        state.zone_hashes.insert("example.com".to_string(), "abc".to_string());
+        save_state(store.as_ref(), WATCH_STATE_KEY, &state).unwrap();
+
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(), "key".to_string(), "127.0.0.1".to_string(), "user".to_string(), false
+        );
+        let watcher: Watcher = Watcher::new(client).with_state_store(store).unwrap();
+        assert_eq!(watcher.state(), &state);
+    }
+}
@@ -0,0 +1,342 @@
+//! ### Bounded Event Stream
+//!
+//! A broadcast channel keeps up to its capacity for every subscriber and makes slow ones skip
+//! ahead with `Lagged`, a fast watcher over a large portfolio then mostly produces lost events.
+//! `event_stream` is the bounded alternative: a single consumer queue whose `Overflow` policy
+//! decides what happens when the consumer falls behind, and an optional sampling interval
+//! limiting how often events of the same key are accepted.
+//!
+//! - `Overflow::Wait`: the watcher waits for room, slowing polling down to the consumer.
+//! - `Overflow::DropOldest`: the oldest pending event is dropped.
+//! - `Overflow::Conflate`: at most one event per key is pending, a newer event of the same key
+//!   (e.g. the zone of the same domain changed again) replaces the pending one in place. When
+//!   the queue is full of other keys, the oldest is dropped.
+//!
+//! Memory is bounded by `capacity` events under every policy, dropped events are counted.
+
+use std::collections::{ HashMap, VecDeque };
+use std::fmt;
+use std::error::Error;
+use std::sync::{ Arc, Mutex };
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::time::{ Duration, Instant };
+use tokio::sync::Notify;
+
+// crate imports
+use crate::watcher::WatchEvent;
+
+/// What a full stream does with a new event.
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Waits until the consumer makes room.
+    #[default]
+    Wait,
+    /// Drops the oldest pending event.
+    DropOldest,
+    /// Replaces the pending event of the same key, or drops the oldest one.
+    Conflate,
+}
+
+/// ### Stream Config
+///
+/// #### Fields
+/// - `capacity`: The maximum number of pending events, at least 1.
+/// - `overflow`: What happens to a new event when the stream is full.
+/// - `sample_every`: Accept at most one event per key within this interval (optional).
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct StreamConfig {
+    pub capacity: usize,
+    pub overflow: Overflow,
+    pub sample_every: Option<Duration>,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig { capacity: 256, overflow: Overflow::Wait, sample_every: None }
+    }
+}
+
+impl StreamConfig {
+    /// Creates a config with the given capacity and overflow policy.
+    pub fn new(capacity: usize, overflow: Overflow) -> Self {
+        StreamConfig { capacity: capacity.max(1), overflow, sample_every: None }
+    }
+
+    /// Accepts at most one event per key within `interval`.
+    pub fn sample_every(mut self, interval: Duration) -> Self {
+        self.sample_every = Some(interval);
+        self
+    }
+}
+
+/// The receiving side of the stream was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamClosed;
+
+impl fmt::Display for StreamClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The event stream receiver was dropped")
+    }
+}
+
+impl Error for StreamClosed {}
+
+struct State {
+    queue: VecDeque<WatchEvent>,
+    last_accepted: HashMap<String, Instant>,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Shared {
+    config: StreamConfig,
+    state: Mutex<State>,
+    items: Notify,
+    space: Notify,
+    dropped: AtomicU64,
+}
+
+impl Shared {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Creates a bounded event stream.
+///
+/// # Example
+///
+/// ```rust
+/// let (sender, mut receiver) = event_stream(StreamConfig::new(64, Overflow::Conflate));
+/// tokio::spawn(async move {
+///     while let Some(event) = receiver.recv().await {
+///         println!("{:?}", event);
+///     }
+/// });
+/// ```
+pub fn event_stream(config: StreamConfig) -> (EventSender, EventReceiver) {
+    let config: StreamConfig = StreamConfig { capacity: config.capacity.max(1), ..config };
+    let shared: Arc<Shared> = Arc::new(Shared {
+        config,
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(config.capacity),
+            last_accepted: HashMap::new(),
+            senders: 1,
+            receiver_alive: true,
+        }),
+        items: Notify::new(),
+        space: Notify::new(),
+        dropped: AtomicU64::new(0),
+    });
+    (EventSender { shared: shared.clone() }, EventReceiver { shared })
+}
+
+/// The sending side of an event stream, cloneable.
+pub struct EventSender {
+    shared: Arc<Shared>,
+}
+
+impl EventSender {
+    /// Sends an event according to the stream's overflow and sampling policy.
+    ///
+    /// Only waits with `Overflow::Wait`, fails once the receiver is dropped.
+    pub async fn send(&self, event: WatchEvent) -> Result<(), StreamClosed> {
+        let config: StreamConfig = self.shared.config;
+        let key: String = event.key();
+
+        loop {
+            let space = self.shared.space.notified();
+            {
+                let mut state = self.shared.state();
+                if !state.receiver_alive {
+                    return Err(StreamClosed);
+                }
+
+                let now: Instant = Instant::now();
+                if let Some(interval) = config.sample_every &&
+                    state.last_accepted.get(&key).is_some_and(|last| now.duration_since(*last) < interval)
+                {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+
+                let accepted: bool = match config.overflow {
+                    Overflow::Conflate => {
+                        if let Some(pending) = state.queue.iter_mut().find(|pending| pending.key() == key) {
+                            *pending = event.clone();
+                            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                        } else {
+                            if state.queue.len() >= config.capacity {
+                                state.queue.pop_front();
+                                self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            state.queue.push_back(event.clone());
+                        }
+                        true
+                    }
+                    Overflow::DropOldest => {
+                        if state.queue.len() >= config.capacity {
+                            state.queue.pop_front();
+                            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        state.queue.push_back(event.clone());
+                        true
+                    }
+                    Overflow::Wait if state.queue.len() < config.capacity => {
+                        state.queue.push_back(event.clone());
+                        true
+                    }
+                    Overflow::Wait => false,
+                };
+
+                if accepted {
+                    if config.sample_every.is_some() {
+                        state.last_accepted.insert(key, now);
+                    }
+                    self.shared.items.notify_one();
+                    return Ok(());
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Returns the number of events dropped, conflated or sampled out so far.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` once the receiver was dropped.
+    pub fn is_closed(&self) -> bool {
+        !self.shared.state().receiver_alive
+    }
+}
+
+impl Clone for EventSender {
+    fn clone(&self) -> Self {
+        self.shared.state().senders += 1;
+        EventSender { shared: self.shared.clone() }
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.state();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.shared.items.notify_one();
+        }
+    }
+}
+
+/// The receiving side of an event stream.
+pub struct EventReceiver {
+    shared: Arc<Shared>,
+}
+
+impl EventReceiver {
+    /// Receives the next event, `None` once every sender is dropped and the queue is drained.
+    pub async fn recv(&mut self) -> Option<WatchEvent> {
+        loop {
+            let items = self.shared.items.notified();
+            {
+                let mut state = self.shared.state();
+                if let Some(event) = state.queue.pop_front() {
+                    self.shared.space.notify_one();
+                    return Some(event);
+                }
+                if state.senders == 0 {
+                    return None;
+                }
+            }
+            items.await;
+        }
+    }
+
+    /// Receives a pending event without waiting.
+    pub fn try_recv(&mut self) -> Option<WatchEvent> {
+        let event: Option<WatchEvent> = self.shared.state().queue.pop_front();
+        if event.is_some() {
+            self.shared.space.notify_one();
+        }
+        event
+    }
+
+    /// Returns the number of pending events.
+    pub fn len(&self) -> usize {
+        self.shared.state().queue.len()
+    }
+
+    /// Returns `true` if no event is pending.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of events dropped, conflated or sampled out so far.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        self.shared.state().receiver_alive = false;
+        self.shared.space.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(domain: &str, hash: &str) -> WatchEvent {
+        WatchEvent::ZoneChanged { domain: domain.to_string(), previous_hash: "0".to_string(), hash: hash.to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_conflation_keeps_latest_per_key() {
+        let (sender, mut receiver) = event_stream(StreamConfig::new(2, Overflow::Conflate));
+        sender.send(changed("a.com", "1")).await.unwrap();
+        sender.send(changed("b.com", "1")).await.unwrap();
+        sender.send(changed("a.com", "2")).await.unwrap();
+        sender.send(changed("c.com", "1")).await.unwrap();
+
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.try_recv(), Some(changed("b.com", "1")));
+        assert_eq!(receiver.try_recv(), Some(changed("c.com", "1")));
+        assert_eq!(receiver.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_applies_back_pressure() {
+        let (sender, mut receiver) = event_stream(StreamConfig::new(1, Overflow::Wait));
+        sender.send(changed("a.com", "1")).await.unwrap();
+
+        let blocked = tokio::time::timeout(Duration::from_millis(50), sender.send(changed("b.com", "1"))).await;
+        assert!(blocked.is_err());
+
+        let producer = tokio::spawn(async move {
+            sender.send(changed("b.com", "1")).await.unwrap();
+        });
+        assert_eq!(receiver.recv().await, Some(changed("a.com", "1")));
+        assert_eq!(receiver.recv().await, Some(changed("b.com", "1")));
+        producer.await.unwrap();
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_and_closed_receiver() {
+        let config: StreamConfig = StreamConfig::new(8, Overflow::DropOldest).sample_every(Duration::from_secs(60));
+        let (sender, receiver) = event_stream(config);
+        sender.send(changed("a.com", "1")).await.unwrap();
+        sender.send(changed("a.com", "2")).await.unwrap();
+        assert_eq!(receiver.len(), 1);
+        assert_eq!(sender.dropped(), 1);
+
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(changed("b.com", "1")).await, Err(StreamClosed));
+    }
+}
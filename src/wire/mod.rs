@@ -0,0 +1,202 @@
+//! ### Wire Format
+//!
+//! The JSON produced by serializing the public models is part of the crate's API: other
+//! services store it, diff it and parse it in other languages. This module documents that
+//! format and pins it with fixture tests, so a refactor that renames or reshapes a field
+//! fails the build instead of breaking downstream consumers.
+//!
+//! #### Guarantees
+//! - Field names and value shapes of `Domain`, `Host`, `Contact` and `Pagination` only change
+//!   in a major version. The fixtures under `src/wire/fixtures` are the reference forms.
+//! - New optional fields may be added in minor versions, consumers should ignore unknown keys.
+//! - `Domain` follows the `snake_case` feature on output and accepts both key styles on input.
+//! - Renamed fields keep accepting their old name as an alias, see [`FIELD_ALIASES`] and
+//!   [`AliasPolicy`] for how long.
+
+use serde::Serialize;
+
+/// How long a deprecated or alternative field name keeps being accepted on input.
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum AliasPolicy {
+    /// Accepted for the lifetime of the current major version, removal is a breaking change.
+    Stable,
+    /// Accepted but deprecated since the given version, removed in the next major version.
+    Deprecated { since: &'static str },
+}
+
+/// An alternative field name accepted when deserializing a model.
+///
+/// #### Fields
+/// - `type_name`: The model, e.g. `Domain`.
+/// - `field`: The Rust field name.
+/// - `alias`: The accepted alternative key.
+/// - `policy`: How long the alias is accepted.
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct FieldAlias {
+    pub type_name: &'static str,
+    pub field: &'static str,
+    pub alias: &'static str,
+    pub policy: AliasPolicy,
+}
+
+const fn stable(type_name: &'static str, field: &'static str, alias: &'static str) -> FieldAlias {
+    FieldAlias { type_name, field, alias, policy: AliasPolicy::Stable }
+}
+
+/// Every alias accepted on input, keep in sync with the `#[serde(alias)]` attributes.
+pub const FIELD_ALIASES: &[FieldAlias] = &[
+    stable("Domain", "is_expired", "isExpired"),
+    stable("Domain", "is_expired", "is_expired"),
+    stable("Domain", "is_locked", "isLocked"),
+    stable("Domain", "is_locked", "is_locked"),
+    stable("Domain", "auto_renew", "autoRenew"),
+    stable("Domain", "auto_renew", "auto_renew"),
+    stable("Domain", "whois_guard", "whoisGuard"),
+    stable("Domain", "whois_guard", "whois_guard"),
+    stable("Domain", "is_premium", "isPremium"),
+    stable("Domain", "is_premium", "is_premium"),
+    stable("Domain", "is_our_dns", "isOurDns"),
+    stable("Domain", "is_our_dns", "is_our_dns"),
+    stable("Domain", "is_dns_active", "isDnsActive"),
+    stable("Domain", "is_dns_active", "is_dns_active"),
+    stable("Domain", "is_failover_active", "isFailoverActive"),
+    stable("Domain", "is_failover_active", "is_failover_active"),
+    stable("Domain", "whois_guard_expires", "whoisGuardExpires"),
+    stable("Domain", "whois_guard_expires", "whois_guard_expires"),
+    stable("Domain", "created_at", "createdAt"),
+    stable("Domain", "created_at", "created_at"),
+    stable("Domain", "expires_at", "expiresAt"),
+    stable("Domain", "expires_at", "expires_at"),
+];
+
+/// Returns the aliases accepted for a model.
+pub fn aliases_for(type_name: &str) -> impl Iterator<Item = &'static FieldAlias> + '_ {
+    FIELD_ALIASES.iter().filter(move |alias| alias.type_name == type_name)
+}
+
+/// Returns the aliases that are deprecated and will be removed in the next major version.
+pub fn deprecated_aliases() -> impl Iterator<Item = &'static FieldAlias> {
+    FIELD_ALIASES.iter().filter(|alias| matches!(alias.policy, AliasPolicy::Deprecated { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+    use std::fmt::Debug;
+
+    // crate imports
+    use crate::{ Contact, Domain, Host, WhoisGuardStatus };
+    use crate::response::paging::Pagination;
+    use crate::response::parse_value::Extra;
+
+    /// Asserts that `value` serializes exactly to `fixture` and parses back to itself.
+    fn assert_round_trip<T>(value: &T, fixture: &str) where T: Serialize + DeserializeOwned + PartialEq + Debug {
+        let expected: Value = serde_json::from_str(fixture).unwrap();
+        assert_eq!(serde_json::to_value(value).unwrap(), expected);
+        assert_eq!(&serde_json::from_value::<T>(expected).unwrap(), value);
+    }
+
+    fn domain() -> Domain {
+        Domain {
+            id: 1,
+            name: "example.com".to_string(),
+            user: "owner".to_string(),
+            created: "07/14/2024".to_string(),
+            expires: "07/14/2025".to_string(),
+            is_expired: false,
+            is_locked: true,
+            auto_renew: true,
+            whois_guard: WhoisGuardStatus::Enabled,
+            is_premium: false,
+            is_our_dns: true,
+            is_dns_active: Some(true),
+            is_failover_active: None,
+            whois_guard_expires: None,
+            created_at: Some("2024-07-14T04:00:00Z".parse().unwrap()),
+            expires_at: Some("2025-07-14T04:00:00Z".parse().unwrap()),
+            extra: Extra::new(),
+        }
+    }
+
+    #[test]
+    fn test_domain_wire_format() {
+        #[cfg(not(feature = "snake_case"))]
+        assert_round_trip(&domain(), include_str!("fixtures/domain.json"));
+        #[cfg(feature = "snake_case")]
+        assert_round_trip(&domain(), include_str!("fixtures/domain_snake_case.json"));
+
+        // Both key styles are accepted on input regardless of the feature
+        let camel: Domain = serde_json::from_str(include_str!("fixtures/domain.json")).unwrap();
+        let snake: Domain = serde_json::from_str(include_str!("fixtures/domain_snake_case.json")).unwrap();
+        assert_eq!(camel, snake);
+    }
+
+    #[test]
+    fn test_host_wire_format() {
+        let host: Host = Host {
+            host_id: "12345".to_string(),
+            name: "www".to_string(),
+            address: "203.0.113.10".to_string(),
+            type_: "A".to_string(),
+            is_active: true,
+            ttl: 1800,
+            mx_pref: "10".to_string(),
+            ..Host::new()
+        };
+        assert_round_trip(&host, include_str!("fixtures/host.json"));
+    }
+
+    #[test]
+    fn test_contact_wire_format() {
+        let contact: Contact = Contact {
+            type_: "Registrant".to_string(),
+            address_1: "1 Main Street".to_string(),
+            address_2: String::new(),
+            city: "Springfield".to_string(),
+            country: "US".to_string(),
+            email_address: "owner@example.com".to_string(),
+            fax: String::new(),
+            first_name: "Jane".to_string(),
+            job_title: String::new(),
+            last_name: "Doe".to_string(),
+            organization_name: "Example Inc".to_string(),
+            phone: "+1.5555550100".to_string(),
+            phone_ext: String::new(),
+            postal_code: "12345".to_string(),
+            state_province: "IL".to_string(),
+            state_province_choice: "S".to_string(),
+            read_only: false,
+        };
+        assert_round_trip(&contact, include_str!("fixtures/contact.json"));
+    }
+
+    #[test]
+    fn test_pagination_wire_format() {
+        assert_round_trip(&Pagination { current_page: 2, total_pages: 5 }, include_str!("fixtures/pagination.json"));
+    }
+
+    #[test]
+    fn test_domain_aliases_are_accepted() {
+        let fixture: Value = serde_json::from_str(include_str!("fixtures/domain.json")).unwrap();
+
+        for alias in aliases_for("Domain") {
+            let mut object = fixture.as_object().unwrap().clone();
+            let camel: String = alias.field
+                .split('_')
+                .enumerate()
+                .map(|(i, part)| if i == 0 { part.to_string() } else { part[..1].to_uppercase() + &part[1..] })
+                .collect();
+            let value: Value = object.remove(&camel).unwrap();
+            object.insert(alias.alias.to_string(), value);
+
+            let parsed: Domain = serde_json::from_value(Value::Object(object)).unwrap();
+            assert_eq!(parsed, domain(), "alias {} of {}", alias.alias, alias.field);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! ### Record Annotations
+//!
+//! Namecheap has no record comments. This module keeps labels, owners and comments for
+//! host records in a local JSON sidecar file, keyed by domain and [`RecordKey`], so that
+//! plans can show why a record exists before it is changed or deleted.
+//!
+//! #### File format
+//! ```json
+//! {
+//!   "example.com": {
+//!     "www CNAME example.com": { "owner": "web-team", "labels": ["prod"], "comment": "Main site" }
+//!   }
+//! }
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::{ Path, PathBuf };
+use tracing::info;
+
+// crate imports
+use crate::zone::RecordKey;
+
+/// The local metadata attached to a record.
+///
+/// #### Fields
+/// - `owner`: The team or person responsible for the record.
+/// - `labels`: Free-form labels, e.g. `prod` or `verification`.
+/// - `comment`: Why the record exists.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct RecordAnnotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl fmt::Display for RecordAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(owner) = &self.owner {
+            parts.push(format!("owner: {}", owner));
+        }
+        if !self.labels.is_empty() {
+            parts.push(format!("labels: {}", self.labels.join(",")));
+        }
+        if let Some(comment) = &self.comment {
+            parts.push(comment.clone());
+        }
+        write!(f, "{}", parts.join(" | "))
+    }
+}
+
+/// A local store of record annotations backed by a JSON file.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationStore {
+    /// The sidecar file the store is loaded from and saved to.
+    path: PathBuf,
+    /// Annotations per domain, keyed by the `RecordKey` display form.
+    records: BTreeMap<String, BTreeMap<String, RecordAnnotation>>,
+}
+
+impl AnnotationStore {
+    /// Loads the store from a JSON file, a missing file yields an empty store.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let records = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(AnnotationStore { path, records })
+    }
+
+    /// Writes the store back to its JSON file.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.records)?)?;
+        info!("Saved record annotations to {}", self.path.display());
+        Ok(())
+    }
+
+    /// Returns the annotation of a record, if any.
+    pub fn get(&self, domain: &str, key: &RecordKey) -> Option<&RecordAnnotation> {
+        self.records.get(&domain.to_lowercase())?.get(&key.to_string())
+    }
+
+    /// Sets the annotation of a record, replacing any previous one.
+    pub fn annotate(&mut self, domain: &str, key: &RecordKey, annotation: RecordAnnotation) {
+        self.records
+            .entry(domain.to_lowercase())
+            .or_default()
+            .insert(key.to_string(), annotation);
+    }
+
+    /// Removes the annotation of a record, returning it.
+    pub fn remove(&mut self, domain: &str, key: &RecordKey) -> Option<RecordAnnotation> {
+        self.records.get_mut(&domain.to_lowercase())?.remove(&key.to_string())
+    }
+
+    /// Drops the annotations of records that no longer exist in the zone.
+    ///
+    /// Returns the number of annotations removed.
+    pub fn prune(&mut self, domain: &str, live: &[RecordKey]) -> usize {
+        let live: Vec<String> = live
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        match self.records.get_mut(&domain.to_lowercase()) {
+            Some(annotations) => {
+                let before: usize = annotations.len();
+                annotations.retain(|key, _| live.contains(key));
+                before - annotations.len()
+            }
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Host;
+    use crate::zone::diff::{ ChangeKind, ZonePlan };
+
+    #[test]
+    fn test_annotations_merged_into_plan() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-annotations-{}.json", std::process::id()));
+        let key: RecordKey = RecordKey::new("old", "A", "203.0.113.20");
+
+        let mut store: AnnotationStore = AnnotationStore::load(&path).unwrap();
+        store.annotate("Example.com", &key, RecordAnnotation {
+            owner: Some("infra".to_string()),
+            labels: vec!["legacy".to_string()],
+            comment: Some("Old load balancer".to_string()),
+        });
+        store.save().unwrap();
+
+        let store: AnnotationStore = AnnotationStore::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let current: Vec<Host> = vec![Host {
+            name: "old".to_string(),
+            type_: "A".to_string(),
+            address: "203.0.113.20".to_string(),
+            ttl: 1800,
+            ..Host::new()
+        }];
+        let mut plan: ZonePlan = ZonePlan::new("example.com", &current, &[]);
+        plan.annotate(&store);
+
+        let deleted = plan.changes_of(ChangeKind::Delete).next().unwrap();
+        assert_eq!(deleted.annotation.as_ref().and_then(|a| a.owner.as_deref()), Some("infra"));
+        assert!(plan.to_string().contains("Old load balancer"));
+    }
+}
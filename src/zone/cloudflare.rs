@@ -0,0 +1,232 @@
+//! ### Cloudflare Import and Export
+//!
+//! This module converts between NameCheap host records and the JSON record format of the
+//! Cloudflare API (`GET /zones/{zone_id}/dns_records`), to migrate zones in either direction.
+//!
+//! #### Mapping
+//! - Cloudflare names are fully qualified, NameCheap host names are relative to the domain.
+//! - Cloudflare's automatic TTL (`1`) becomes the NameCheap default TTL.
+//! - Proxied records are imported with their origin address, the proxying is lost.
+//! - NameCheap `ALIAS` records are exported as `CNAME`s, which Cloudflare flattens at the apex.
+//! - Types without an equivalent (`SRV`, `URL`, `MXE`, ...) are skipped.
+//!
+//! Every mapped or skipped record is reported as a [`ConversionWarning`](crate::zone::interop::ConversionWarning).
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+// crate imports
+use crate::Host;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::interop::{ absolute_name, clamp_ttl, fully_qualified, Conversion };
+use crate::zone::relative_name;
+
+/// The TTL Cloudflare uses for "automatic".
+pub const CLOUDFLARE_AUTO_TTL: i64 = 1;
+
+/// The record types carried over in both directions.
+const SHARED_TYPES: [&str; 7] = ["A", "AAAA", "CNAME", "MX", "TXT", "NS", "CAA"];
+
+fn auto_ttl() -> i64 {
+    CLOUDFLARE_AUTO_TTL
+}
+
+/// ### Cloudflare Record
+///
+/// A DNS record in the format of the Cloudflare API.
+///
+/// #### Fields
+/// - `record_type`: The record type, e.g. `A` (`type` in JSON).
+/// - `name`: The fully qualified record name, e.g. `www.example.com`.
+/// - `content`: The record value.
+/// - `ttl`: The TTL in seconds, `1` for automatic.
+/// - `proxied`: Whether Cloudflare proxies the record.
+/// - `priority`: The MX preference (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct CloudflareRecord {
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub name: String,
+    pub content: String,
+    #[serde(default = "auto_ttl")]
+    pub ttl: i64,
+    #[serde(default)]
+    pub proxied: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+}
+
+impl fmt::Display for CloudflareRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.name, self.record_type, self.content)
+    }
+}
+
+/// Reads a Cloudflare record export, either a bare array or an API response with `result`.
+pub fn parse_cloudflare_json(json: &str) -> Result<Vec<CloudflareRecord>, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(json)?;
+    let records: Value = match value {
+        Value::Object(mut object) => object.remove("result").ok_or("Missing `result` array")?,
+        records => records,
+    };
+    Ok(serde_json::from_value(records)?)
+}
+
+/// Converts Cloudflare records of `domain` into `HostRequest`s for `domains_dns_replace_hosts`.
+pub fn from_cloudflare(domain: &str, records: &[CloudflareRecord]) -> Conversion<HostRequest> {
+    let mut conversion: Conversion<HostRequest> = Conversion::default();
+
+    for record in records {
+        let record_type: String = record.record_type.to_uppercase();
+        if !SHARED_TYPES.contains(&record_type.as_str()) {
+            conversion.warn(record, format!("{} records are not supported by NameCheap, skipped", record_type));
+            continue;
+        }
+        let Some(host_name) = relative_name(domain, &record.name) else {
+            conversion.warn(record, format!("name is outside of {}, skipped", domain));
+            continue;
+        };
+        if record_type == "NS" && host_name == "@" {
+            conversion.warn(record, "apex nameservers are not host records, skipped");
+            continue;
+        }
+        if record.proxied {
+            conversion.warn(record, "proxied by Cloudflare, imported with the origin address");
+        }
+
+        let address: String = match record_type.as_str() {
+            "CNAME" | "MX" | "NS" => fully_qualified(&record.content),
+            "TXT" => unquote(&record.content).to_string(),
+            _ => record.content.clone(),
+        };
+        let ttl: Option<String> = match record.ttl {
+            CLOUDFLARE_AUTO_TTL => None,
+            ttl => {
+                let (ttl, warning) = clamp_ttl(ttl);
+                if let Some(warning) = warning {
+                    conversion.warn(record, warning);
+                }
+                Some(ttl.to_string())
+            }
+        };
+        let mx_pref: Option<String> = (record_type == "MX").then(|| record.priority.unwrap_or(10).to_string());
+
+        conversion.records.push(HostRequest::new(host_name, record_type, address, mx_pref, None, ttl, None, None));
+    }
+
+    conversion
+}
+
+/// Converts the host records of `domain` into Cloudflare records, ready for the Cloudflare API.
+pub fn to_cloudflare(domain: &str, hosts: &[Host]) -> Conversion<CloudflareRecord> {
+    let mut conversion: Conversion<CloudflareRecord> = Conversion::default();
+
+    for host in hosts {
+        let label: String = format!("{} {} {}", host.name, host.type_, host.address);
+        let record_type: String = match host.type_.to_uppercase().as_str() {
+            "ALIAS" => {
+                conversion.warn(&label, "exported as a CNAME, which Cloudflare flattens at the apex");
+                "CNAME".to_string()
+            }
+            record_type if SHARED_TYPES.contains(&record_type) => record_type.to_string(),
+            record_type => {
+                conversion.warn(&label, format!("{} records have no Cloudflare equivalent, skipped", record_type));
+                continue;
+            }
+        };
+
+        let content: String = match record_type.as_str() {
+            "CNAME" | "MX" | "NS" => host.address.trim().trim_end_matches('.').to_string(),
+            _ => host.address.clone(),
+        };
+        let priority: Option<u16> = match record_type.as_str() {
+            "MX" => Some(host.mx_pref.parse().unwrap_or(10)),
+            _ => None,
+        };
+
+        conversion.records.push(CloudflareRecord {
+            record_type,
+            name: absolute_name(domain, &host.name),
+            content,
+            ttl: host.ttl,
+            proxied: false,
+            priority,
+        });
+    }
+
+    conversion
+}
+
+/// Removes the quotes Cloudflare may put around TXT values.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str, mx_pref: &str) -> Host {
+        let mut host: Host = Host::new();
+        host.name = name.to_string();
+        host.type_ = type_.to_string();
+        host.address = address.to_string();
+        host.mx_pref = mx_pref.to_string();
+        host.ttl = 1800;
+        host
+    }
+
+    #[test]
+    fn test_from_cloudflare() {
+        let records: Vec<CloudflareRecord> = parse_cloudflare_json(
+            r#"{ "result": [
+                { "type": "A", "name": "example.com", "content": "203.0.113.10", "ttl": 1, "proxied": true },
+                { "type": "CNAME", "name": "www.example.com", "content": "example.com", "ttl": 300 },
+                { "type": "MX", "name": "example.com", "content": "mail.example.com", "ttl": 3600, "priority": 5 },
+                { "type": "TXT", "name": "example.com", "content": "\"v=spf1 -all\"", "ttl": 30 },
+                { "type": "SRV", "name": "_sip._tcp.example.com", "content": "0 5 5060 sip.example.com", "ttl": 1 }
+            ] }"#
+        ).unwrap();
+        let conversion: Conversion<HostRequest> = from_cloudflare("example.com", &records);
+
+        assert_eq!(conversion.records.len(), 4);
+        assert_eq!(conversion.records[0].host_name, "@");
+        assert_eq!(conversion.records[0].ttl, None);
+        assert_eq!(conversion.records[1].address, "example.com.");
+        assert_eq!(conversion.records[2].mx_pref.as_deref(), Some("5"));
+        assert_eq!(conversion.records[3].address, "v=spf1 -all");
+        assert_eq!(conversion.records[3].ttl.as_deref(), Some("60"));
+
+        // Proxied A, clamped TTL and skipped SRV
+        assert_eq!(conversion.warnings.len(), 3);
+        assert!(conversion.warnings[2].message.contains("SRV"));
+    }
+
+    #[test]
+    fn test_to_cloudflare() {
+        let hosts: Vec<Host> = vec![
+            host("@", "ALIAS", "app.example.net.", "10"),
+            host("@", "MX", "mail.example.com.", "20"),
+            host("old", "URL", "https://example.com", "10")
+        ];
+        let conversion: Conversion<CloudflareRecord> = to_cloudflare("example.com", &hosts);
+
+        assert_eq!(conversion.records.len(), 2);
+        assert_eq!(conversion.records[0].record_type, "CNAME");
+        assert_eq!(conversion.records[0].name, "example.com");
+        assert_eq!(conversion.records[0].content, "app.example.net");
+        assert_eq!(conversion.records[1].priority, Some(20));
+        assert_eq!(conversion.warnings.len(), 2);
+
+        // The export reads back into the same records
+        let round_trip: Conversion<HostRequest> = from_cloudflare("example.com", &conversion.records);
+        assert_eq!(round_trip.records[1].address, "mail.example.com.");
+        assert!(round_trip.is_lossless());
+    }
+}
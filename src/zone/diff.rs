@@ -0,0 +1,243 @@
+//! ### Zone Diff
+//!
+//! This module computes a `ZonePlan`: the records to create, update, delete or keep to go
+//! from the live host records of a domain to a desired set of records.
+//!
+//! `domains.dns.setHosts` replaces the whole zone, so the plan also carries the full list of
+//! records to submit (`desired`).
+
+use serde::{ Serialize, Deserialize };
+use std::collections::HashMap;
+use std::fmt;
+
+// crate imports
+use crate::Host;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::RecordKey;
+use crate::zone::annotations::{ AnnotationStore, RecordAnnotation };
+
+/// The kind of a planned change.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Create,
+    Update,
+    Delete,
+    Keep,
+}
+
+/// A single planned change to a record.
+///
+/// #### Fields
+/// - `kind`: Whether the record is created, updated, deleted or kept.
+/// - `key`: The record the change applies to.
+/// - `ttl_before`: The live TTL, `None` for created records.
+/// - `ttl_after`: The desired TTL, `None` for deleted records.
+/// - `annotation`: The local annotation of the record, if any (see [`ZonePlan::annotate`]).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PlannedChange {
+    pub kind: ChangeKind,
+    pub key: RecordKey,
+    pub ttl_before: Option<i64>,
+    pub ttl_after: Option<i64>,
+    pub annotation: Option<RecordAnnotation>,
+}
+
+/// The plan of changes for one domain.
+///
+/// #### Fields
+/// - `domain`: The domain the plan applies to.
+/// - `changes`: One change per record, in live zone order followed by created records.
+/// - `desired`: The complete list of records to submit with `setHosts`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ZonePlan {
+    pub domain: String,
+    pub changes: Vec<PlannedChange>,
+    pub desired: Vec<HostRequest>,
+}
+
+impl ZonePlan {
+    /// Computes the plan to go from the `current` records to the `desired` records.
+    ///
+    /// Records are matched by [`RecordKey`], a matching record whose TTL or MX preference
+    /// differs is an update. A desired record without TTL keeps the live TTL.
+    pub fn new(domain: &str, current: &[Host], desired: &[HostRequest]) -> Self {
+        let desired_by_key: HashMap<RecordKey, &HostRequest> = desired
+            .iter()
+            .map(|host| (RecordKey::from(host), host))
+            .collect();
+        let current_by_key: HashMap<RecordKey, &Host> = current
+            .iter()
+            .map(|host| (RecordKey::from(host), host))
+            .collect();
+
+        let mut changes: Vec<PlannedChange> = Vec::new();
+        let mut submitted: Vec<HostRequest> = Vec::new();
+
+        for host in current {
+            let key: RecordKey = RecordKey::from(host);
+            match desired_by_key.get(&key) {
+                Some(wanted) => {
+                    let ttl_after: i64 = wanted.ttl
+                        .as_deref()
+                        .and_then(|ttl| ttl.trim().parse::<i64>().ok())
+                        .unwrap_or(host.ttl);
+                    let mx_changed: bool = wanted.mx_pref
+                        .as_deref()
+                        .is_some_and(|mx_pref| mx_pref != host.mx_pref);
+                    let kind: ChangeKind = if ttl_after != host.ttl || mx_changed {
+                        ChangeKind::Update
+                    } else {
+                        ChangeKind::Keep
+                    };
+
+                    let mut request: HostRequest = (*wanted).clone();
+                    request.ttl = Some(ttl_after.to_string());
+                    submitted.push(request);
+
+                    changes.push(PlannedChange {
+                        kind,
+                        key,
+                        ttl_before: Some(host.ttl),
+                        ttl_after: Some(ttl_after),
+                        annotation: None,
+                    });
+                }
+                None =>
+                    changes.push(PlannedChange {
+                        kind: ChangeKind::Delete,
+                        key,
+                        ttl_before: Some(host.ttl),
+                        ttl_after: None,
+                        annotation: None,
+                    }),
+            }
+        }
+
+        for host in desired {
+            let key: RecordKey = RecordKey::from(host);
+            if current_by_key.contains_key(&key) || changes.iter().any(|c| c.key == key) {
+                continue;
+            }
+
+            submitted.push(host.clone());
+            changes.push(PlannedChange {
+                kind: ChangeKind::Create,
+                ttl_before: None,
+                ttl_after: host.ttl.as_deref().and_then(|ttl| ttl.trim().parse::<i64>().ok()),
+                key,
+                annotation: None,
+            });
+        }
+
+        ZonePlan {
+            domain: domain.to_string(),
+            changes,
+            desired: submitted,
+        }
+    }
+
+    /// Returns `true` if applying the plan would modify the zone.
+    pub fn has_changes(&self) -> bool {
+        self.changes.iter().any(|change| change.kind != ChangeKind::Keep)
+    }
+
+    /// Returns the changes of the given kind.
+    pub fn changes_of(&self, kind: ChangeKind) -> impl Iterator<Item = &PlannedChange> {
+        self.changes.iter().filter(move |change| change.kind == kind)
+    }
+
+    /// Attaches the local annotations of the domain to each planned change.
+    ///
+    /// This lets a reviewer see who owns a record and why it exists before it is deleted.
+    pub fn annotate(&mut self, store: &AnnotationStore) {
+        for change in &mut self.changes {
+            change.annotation = store.get(&self.domain, &change.key).cloned();
+        }
+    }
+}
+
+impl fmt::Display for ZonePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Plan for {}:", self.domain)?;
+        for change in &self.changes {
+            let symbol: &str = match change.kind {
+                ChangeKind::Create => "+",
+                ChangeKind::Update => "~",
+                ChangeKind::Delete => "-",
+                ChangeKind::Keep => " ",
+            };
+            write!(f, "  {} {}", symbol, change.key)?;
+            if change.kind == ChangeKind::Update {
+                write!(
+                    f,
+                    " (ttl {} -> {})",
+                    change.ttl_before.unwrap_or_default(),
+                    change.ttl_after.unwrap_or_default()
+                )?;
+            }
+            if let Some(annotation) = &change.annotation {
+                write!(f, "  # {}", annotation)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str, ttl: i64) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ttl,
+            ..Host::new()
+        }
+    }
+
+    fn request(name: &str, type_: &str, address: &str, ttl: Option<&str>) -> HostRequest {
+        HostRequest::new(
+            name.to_string(),
+            type_.to_string(),
+            address.to_string(),
+            None,
+            None,
+            ttl.map(|ttl| ttl.to_string()),
+            None,
+            None
+        )
+    }
+
+    #[test]
+    fn test_zone_plan() {
+        let current: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10", 1800),
+            host("www", "CNAME", "example.com.", 1800),
+            host("old", "A", "203.0.113.20", 1800)
+        ];
+        let desired: Vec<HostRequest> = vec![
+            request("@", "A", "203.0.113.10", None),
+            request("www", "CNAME", "example.com", Some("300")),
+            request("api", "A", "203.0.113.30", Some("60"))
+        ];
+
+        let plan: ZonePlan = ZonePlan::new("example.com", &current, &desired);
+
+        assert!(plan.has_changes());
+        assert_eq!(plan.changes_of(ChangeKind::Keep).count(), 1);
+        assert_eq!(plan.changes_of(ChangeKind::Update).count(), 1);
+        assert_eq!(plan.changes_of(ChangeKind::Delete).next().unwrap().key.name, "old");
+        assert_eq!(plan.changes_of(ChangeKind::Create).next().unwrap().key.name, "api");
+        assert_eq!(plan.desired.len(), 3);
+        assert_eq!(plan.desired[0].ttl.as_deref(), Some("1800"));
+    }
+}
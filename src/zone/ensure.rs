@@ -0,0 +1,198 @@
+//! ### Ensure Records
+//!
+//! This module makes sure a set of records exists in a zone without touching the other
+//! records. Calling it again with the same records is a no-op, so it is safe to run from
+//! provisioning scripts.
+//!
+//! `replace_records` additionally removes the records that would conflict with the new ones,
+//! e.g. an old `A` record at the apex when pointing the apex somewhere else.
+
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::correlation::in_operation;
+use crate::zone::diff::ZonePlan;
+use crate::zone::{ split_domain, RecordKey };
+
+/// Returns the live records of a zone with `records` added or updated.
+///
+/// A record that already exists (same [`RecordKey`]) is replaced by its ensured version, so
+/// a TTL or MX preference set on it is applied. Other records are kept as they are.
+pub fn ensured_zone(current: &[Host], records: &[HostRequest]) -> Vec<HostRequest> {
+    let mut desired: Vec<HostRequest> = current
+        .iter()
+        .map(|host| host.to_host_request())
+        .collect();
+
+    for record in records {
+        let key: RecordKey = RecordKey::from(record);
+        match desired.iter_mut().find(|existing| RecordKey::from(&**existing) == key) {
+            Some(existing) => {
+                let ttl: Option<String> = record.ttl.clone().or(existing.ttl.take());
+                *existing = HostRequest { ttl, ..record.clone() };
+            }
+            None => desired.push(record.clone()),
+        }
+    }
+
+    desired
+}
+
+/// The record types that point a name at a server, at most one kind of which should be
+/// present at a given name.
+pub const ADDRESS_TYPES: [&str; 7] = ["A", "AAAA", "ALIAS", "CNAME", "URL", "URL301", "FRAME"];
+
+fn is_address_type(record_type: &str) -> bool {
+    ADDRESS_TYPES.iter().any(|address_type| address_type.eq_ignore_ascii_case(record_type))
+}
+
+/// Returns the live records of a zone with `records` added, replacing conflicting records.
+///
+/// A live record conflicts when it has an address type (see [`ADDRESS_TYPES`]) and shares its
+/// name with one of the address records of `records`. Other records, such as `MX` or `TXT` at
+/// the same name, are kept.
+pub fn replaced_zone(current: &[Host], records: &[HostRequest]) -> Vec<HostRequest> {
+    let replaced_names: Vec<String> = records
+        .iter()
+        .filter(|record| is_address_type(&record.record_type))
+        .map(|record| RecordKey::from(record).name)
+        .collect();
+
+    let kept: Vec<Host> = current
+        .iter()
+        .filter(|host| {
+            !is_address_type(&host.type_) || !replaced_names.contains(&RecordKey::from(*host).name)
+        })
+        .cloned()
+        .collect();
+
+    ensured_zone(&kept, records)
+}
+
+impl NameCheapClient {
+    /// Makes sure `records` exist in the zone of `domain`, leaving other records untouched.
+    ///
+    /// The zone is only submitted when something changes. The returned plan shows what
+    /// changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let record = HostRequest::new("@".to_string(), "TXT".to_string(), "hello".to_string(), None, None, None, None, None);
+    /// let plan = client.ensure_records("example.com", &[record]).await.unwrap();
+    /// assert!(!client.ensure_records("example.com", &[record]).await.unwrap().has_changes());
+    /// ```
+    pub async fn ensure_records(
+        &self,
+        domain: &str,
+        records: &[HostRequest]
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        in_operation("zone.ensure_records", async {
+            let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+            let _lock = self.lock_domain(domain).await;
+            let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+            let plan: ZonePlan = ZonePlan::new(domain, &current, &ensured_zone(&current, records));
+
+            if plan.has_changes() {
+                self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+            }
+            Ok(plan)
+        }).await
+    }
+
+    /// Makes sure `records` exist in the zone of `domain`, removing conflicting records.
+    ///
+    /// See [`replaced_zone`] for which records are considered conflicting.
+    pub async fn replace_records(
+        &self,
+        domain: &str,
+        records: &[HostRequest]
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        in_operation("zone.replace_records", async {
+            let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+            let _lock = self.lock_domain(domain).await;
+            let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+            let plan: ZonePlan = ZonePlan::new(domain, &current, &replaced_zone(&current, records));
+
+            if plan.has_changes() {
+                self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+            }
+            Ok(plan)
+        }).await
+    }
+
+    /// Makes sure a single record exists in the zone of `domain`.
+    pub async fn ensure_record(
+        &self,
+        domain: &str,
+        record: HostRequest
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_records(domain, &[record]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::diff::ChangeKind;
+
+    fn host(name: &str, type_: &str, address: &str) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ttl: 1800,
+            ..Host::new()
+        }
+    }
+
+    fn txt(name: &str, value: &str) -> HostRequest {
+        HostRequest::new(name.to_string(), "TXT".to_string(), value.to_string(), None, None, None, None, None)
+    }
+
+    #[test]
+    fn test_ensured_zone_is_idempotent() {
+        let current: Vec<Host> = vec![host("@", "A", "203.0.113.10"), host("@", "TXT", "existing")];
+
+        let desired: Vec<HostRequest> = ensured_zone(&current, &[txt("@", "existing"), txt("@", "new")]);
+        let plan: ZonePlan = ZonePlan::new("example.com", &current, &desired);
+        assert_eq!(plan.changes_of(ChangeKind::Create).count(), 1);
+        assert_eq!(plan.changes_of(ChangeKind::Delete).count(), 0);
+        assert_eq!(desired[1].ttl.as_deref(), Some("1800"));
+
+        let current: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10"),
+            host("@", "TXT", "existing"),
+            host("@", "TXT", "new")
+        ];
+        let desired: Vec<HostRequest> = ensured_zone(&current, &[txt("@", "new")]);
+        assert!(!ZonePlan::new("example.com", &current, &desired).has_changes());
+    }
+
+    #[test]
+    fn test_replaced_zone_removes_conflicts() {
+        let current: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10"),
+            host("@", "MX", "mail.example.com."),
+            host("www", "A", "203.0.113.10")
+        ];
+        let cname: HostRequest = HostRequest::new(
+            "www".to_string(),
+            "CNAME".to_string(),
+            "example.github.io.".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None
+        );
+
+        let desired: Vec<HostRequest> = replaced_zone(&current, &[cname]);
+        let plan: ZonePlan = ZonePlan::new("example.com", &current, &desired);
+        assert_eq!(plan.changes_of(ChangeKind::Delete).next().unwrap().key.name, "www");
+        assert_eq!(plan.changes_of(ChangeKind::Create).count(), 1);
+        assert_eq!(plan.changes_of(ChangeKind::Keep).count(), 2);
+    }
+}
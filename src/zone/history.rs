@@ -0,0 +1,315 @@
+//! ### Record History
+//!
+//! With the `audit_log` feature, a client with a [`ZoneHistory`] attached
+//! ([`NameCheapClient::with_zone_history`]) records the state of every zone it reads with
+//! `domains.dns.getHosts` or writes with `domains.dns.setHosts`. [`NameCheapClient::record_history`]
+//! then answers "when did www change and to what?" locally, without a DNS history service.
+//!
+//! The history is an append-only JSON lines file, one line per zone state. A state is only
+//! appended when the zone differs from the last one recorded for the domain, so polling an
+//! unchanged zone does not grow the file. Changes made outside the client (the dashboard,
+//! another tool) show up as `observed` at the next read.
+
+use chrono::{ DateTime, Utc };
+use serde::{ Serialize, Deserialize };
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{ File, OpenOptions };
+use std::io::{ BufRead, BufReader, Write };
+use std::path::{ Path, PathBuf };
+use std::sync::{ Arc, Mutex };
+use tracing::warn;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::limits::DEFAULT_TTL;
+use crate::utils::correlation::current_correlation_id;
+use crate::utils::hash::fnv1a_hex;
+
+/// How a zone state was learned.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum HistorySource {
+    /// Read from the API with `domains.dns.getHosts`.
+    Observed,
+    /// Written by this client with `domains.dns.setHosts`.
+    Applied,
+}
+
+/// ### Record Value
+///
+/// #### Fields
+/// - `record_type`: The record type, e.g. `A` or `CNAME`.
+/// - `address`: The value of the record.
+/// - `ttl`: The TTL in seconds.
+/// - `mx_pref`: The MX preference, empty for other record types.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecordValue {
+    pub record_type: String,
+    pub address: String,
+    pub ttl: i64,
+    #[serde(default)]
+    pub mx_pref: String,
+}
+
+/// ### Zone State
+///
+/// #### Fields
+/// - `domain`: The domain of the zone.
+/// - `recorded_at`: When the state was read or written.
+/// - `source`: Whether the state was observed or applied.
+/// - `correlation_id`: The operation that read or wrote the zone (optional).
+/// - `hash`: A hash of the records, independent of their order.
+/// - `records`: The values of every host name, host names lowercase.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ZoneState {
+    pub domain: String,
+    pub recorded_at: DateTime<Utc>,
+    pub source: HistorySource,
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    pub hash: String,
+    pub records: BTreeMap<String, Vec<RecordValue>>,
+}
+
+/// ### Record Version
+///
+/// One value of a host name over time.
+///
+/// #### Fields
+/// - `recorded_at`: When the value was first seen.
+/// - `source`: Whether the value was observed or applied.
+/// - `correlation_id`: The operation that read or wrote it (optional).
+/// - `values`: The records of the host name, empty once it was deleted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct RecordVersion {
+    pub recorded_at: DateTime<Utc>,
+    pub source: HistorySource,
+    pub correlation_id: Option<String>,
+    pub values: Vec<RecordValue>,
+}
+
+impl ZoneState {
+    /// Creates the state of `domain` holding `records`, as (host name, value) pairs.
+    pub fn new(domain: &str, source: HistorySource, records: impl IntoIterator<Item = (String, RecordValue)>) -> Self {
+        let mut by_host: BTreeMap<String, Vec<RecordValue>> = BTreeMap::new();
+        for (host_name, value) in records {
+            by_host.entry(host_name.to_ascii_lowercase()).or_default().push(value);
+        }
+        by_host.values_mut().for_each(|values| values.sort());
+        let hash: String = fnv1a_hex(&serde_json::to_string(&by_host).unwrap_or_default());
+
+        ZoneState {
+            domain: domain.to_ascii_lowercase(),
+            recorded_at: Utc::now(),
+            source,
+            correlation_id: current_correlation_id(),
+            hash,
+            records: by_host,
+        }
+    }
+
+    /// Creates a state from the records read with `domains.dns.getHosts`.
+    pub fn observed(domain: &str, hosts: &[Host]) -> Self {
+        ZoneState::new(domain, HistorySource::Observed, hosts.iter().map(|host| {
+            (host.name.clone(), RecordValue {
+                record_type: host.type_.to_ascii_uppercase(),
+                address: host.address.clone(),
+                ttl: host.ttl,
+                mx_pref: if host.type_.eq_ignore_ascii_case("MX") { host.mx_pref.clone() } else { String::new() },
+            })
+        }))
+    }
+
+    /// Creates a state from the records written with `domains.dns.setHosts`.
+    pub fn applied(domain: &str, hosts: &[HostRequest]) -> Self {
+        ZoneState::new(domain, HistorySource::Applied, hosts.iter().map(|host| {
+            (host.host_name.clone(), RecordValue {
+                record_type: host.record_type.to_ascii_uppercase(),
+                address: host.address.clone(),
+                ttl: host.ttl.as_deref().and_then(|ttl| ttl.parse().ok()).unwrap_or(DEFAULT_TTL),
+                mx_pref: match host.record_type.eq_ignore_ascii_case("MX") {
+                    true => host.mx_pref.clone().unwrap_or_default(),
+                    false => String::new(),
+                },
+            })
+        }))
+    }
+}
+
+/// An append-only history of zone states.
+#[derive(Debug)]
+pub struct ZoneHistory {
+    path: PathBuf,
+    // The hash of the last state of each domain, to skip unchanged zones
+    last: Mutex<BTreeMap<String, String>>,
+}
+
+impl ZoneHistory {
+    /// Opens the history at `path`, a missing file yields an empty history.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let history: ZoneHistory = ZoneHistory { path: path.as_ref().to_path_buf(), last: Mutex::new(BTreeMap::new()) };
+        let last: BTreeMap<String, String> = history
+            .states()?
+            .into_iter()
+            .map(|state| (state.domain, state.hash))
+            .collect();
+        *history.last.lock().map_err(|e| e.to_string())? = last;
+        Ok(history)
+    }
+
+    /// Returns every recorded state, oldest first.
+    ///
+    /// Unreadable lines (e.g. a line cut short by a crash) are skipped with a warning.
+    pub fn states(&self) -> Result<Vec<ZoneState>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut states: Vec<ZoneState> = Vec::new();
+        for (number, line) in BufReader::new(File::open(&self.path)?).lines().enumerate() {
+            let line: String = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ZoneState>(&line) {
+                Ok(state) => states.push(state),
+                Err(error) => warn!("Skipping history line {}: {}", number + 1, error),
+            }
+        }
+        Ok(states)
+    }
+
+    /// Appends `state` unless the zone is unchanged since the last state of its domain.
+    ///
+    /// Returns `true` if the state was appended.
+    pub fn record(&self, state: &ZoneState) -> Result<bool, Box<dyn Error>> {
+        let mut last = self.last.lock().map_err(|e| e.to_string())?;
+        if last.get(&state.domain) == Some(&state.hash) {
+            return Ok(false);
+        }
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file: File = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(state)?)?;
+        file.flush()?;
+
+        last.insert(state.domain.clone(), state.hash.clone());
+        Ok(true)
+    }
+
+    /// Returns the values `host_name` of `domain` had over time, oldest first.
+    ///
+    /// Consecutive states with the same values are merged into the first of them. A host name
+    /// missing from a state after being present yields a version with no values.
+    pub fn record_history(&self, domain: &str, host_name: &str) -> Result<Vec<RecordVersion>, Box<dyn Error>> {
+        let (domain, host_name) = (domain.to_ascii_lowercase(), host_name.to_ascii_lowercase());
+        let mut versions: Vec<RecordVersion> = Vec::new();
+
+        for state in self.states()?.into_iter().filter(|state| state.domain == domain) {
+            let values: Vec<RecordValue> = state.records.get(&host_name).cloned().unwrap_or_default();
+            let unchanged: bool = match versions.last() {
+                Some(previous) => previous.values == values,
+                None => values.is_empty(),
+            };
+            if !unchanged {
+                versions.push(RecordVersion {
+                    recorded_at: state.recorded_at,
+                    source: state.source,
+                    correlation_id: state.correlation_id,
+                    values,
+                });
+            }
+        }
+        Ok(versions)
+    }
+}
+
+impl NameCheapClient {
+    /// Records the zone states read and written by this client and its clones in `history`.
+    ///
+    /// Like `with_rate_limiter`, call this before cloning the client.
+    pub fn with_zone_history(mut self, history: Arc<ZoneHistory>) -> Self {
+        self.shared = self.shared.with_history(history);
+        self
+    }
+
+    /// Returns the values a host name of `domain` had over time, see [`ZoneHistory::record_history`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// for version in client.record_history("example.com", "www")? {
+    ///     println!("{} {:?} {:?}", version.recorded_at, version.source, version.values);
+    /// }
+    /// ```
+    pub fn record_history(&self, domain: &str, host_name: &str) -> Result<Vec<RecordVersion>, Box<dyn Error>> {
+        self.shared
+            .history()
+            .ok_or("No zone history, see with_zone_history")?
+            .record_history(domain, host_name)
+    }
+
+    /// Appends a zone state to the history, if one is attached.
+    ///
+    /// The history is an audit aid: a failing write is logged and does not fail the call.
+    pub(crate) fn record_zone_state(&self, state: impl FnOnce() -> ZoneState) {
+        if let Some(history) = self.shared.history()
+            && let Err(error) = history.record(&state())
+        {
+            warn!("Failed to record zone history: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str) -> Host {
+        serde_json::from_value(serde_json::json!({ "name": name, "type": type_, "address": address })).unwrap()
+    }
+
+    #[test]
+    fn test_record_history() {
+        let path: PathBuf = std::env::temp_dir().join(format!("namecheap-history-{}.jsonl", std::process::id()));
+        let history: ZoneHistory = ZoneHistory::open(&path).unwrap();
+
+        let first: Vec<Host> = vec![host("www", "A", "203.0.113.10"), host("@", "A", "203.0.113.10")];
+        assert!(history.record(&ZoneState::observed("example.com", &first)).unwrap());
+        // Unchanged zones are not appended again
+        assert!(!history.record(&ZoneState::observed("Example.com", &first)).unwrap());
+
+        let moved: Vec<HostRequest> = vec![
+            HostRequest::new("WWW".to_string(), "A".to_string(), "198.51.100.7".to_string(), None, None, None, None, None),
+            HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, None, None, None),
+        ];
+        history.record(&ZoneState::applied("example.com", &moved)).unwrap();
+        // Another host changing is a new state, but not a new version of www
+        let apex: Vec<Host> = vec![host("www", "A", "198.51.100.7"), host("@", "A", "198.51.100.8")];
+        history.record(&ZoneState::observed("example.com", &apex)).unwrap();
+        history.record(&ZoneState::observed("example.com", &apex[1..])).unwrap();
+
+        // A reopened history, as after a restart
+        let reopened: ZoneHistory = ZoneHistory::open(&path).unwrap();
+        assert!(!reopened.record(&ZoneState::observed("example.com", &apex[1..])).unwrap());
+        let versions: Vec<RecordVersion> = reopened.record_history("example.com", "www").unwrap();
+        let addresses: Vec<Vec<&str>> = versions
+            .iter()
+            .map(|version| version.values.iter().map(|value| value.address.as_str()).collect())
+            .collect();
+        assert_eq!(addresses, [vec!["203.0.113.10"], vec!["198.51.100.7"], vec![]]);
+        assert_eq!(versions[1].source, HistorySource::Applied);
+        assert_eq!(reopened.record_history("example.net", "www").unwrap(), []);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
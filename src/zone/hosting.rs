@@ -0,0 +1,139 @@
+//! ### Hosting Provider Setup
+//!
+//! This module points the apex and `www` of a domain at a popular static hosting provider in
+//! one call. Existing `A`/`AAAA`/`ALIAS`/`CNAME`/`URL` records at those names are replaced,
+//! mail and verification records are left alone.
+//!
+//! #### Providers
+//! - GitHub Pages: apex `A` records to the GitHub Pages IPs, `www` `CNAME` to `<owner>.github.io`.
+//! - Vercel: apex `A 76.76.21.21`, `www` `CNAME` to `cname.vercel-dns.com`.
+//! - Netlify: apex `A 75.2.60.5`, `www` `CNAME` to `<site>.netlify.app`.
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::diff::ZonePlan;
+
+/// The GitHub Pages apex addresses.
+pub const GITHUB_PAGES_IPS: [&str; 4] = [
+    "185.199.108.153",
+    "185.199.109.153",
+    "185.199.110.153",
+    "185.199.111.153",
+];
+
+/// The Vercel apex address.
+pub const VERCEL_IP: &str = "76.76.21.21";
+
+/// The Netlify load balancer apex address.
+pub const NETLIFY_IP: &str = "75.2.60.5";
+
+/// A static hosting provider.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum HostingProvider {
+    /// GitHub Pages, `owner` is the user or organization serving the site.
+    GitHubPages { owner: String },
+    Vercel,
+    /// Netlify, `site` is the Netlify site name (`<site>.netlify.app`).
+    Netlify { site: String },
+}
+
+impl HostingProvider {
+    /// Returns the apex and `www` records for the provider.
+    pub fn records(&self) -> Vec<HostRequest> {
+        let record = |host_name: &str, record_type: &str, address: &str| {
+            HostRequest::new(
+                host_name.to_string(),
+                record_type.to_string(),
+                address.to_string(),
+                None,
+                None,
+                None,
+                None,
+                None
+            )
+        };
+
+        match self {
+            HostingProvider::GitHubPages { owner } => {
+                let mut records: Vec<HostRequest> = GITHUB_PAGES_IPS
+                    .iter()
+                    .map(|ip| record("@", "A", ip))
+                    .collect();
+                records.push(record("www", "CNAME", &format!("{}.github.io.", owner.to_lowercase())));
+                records
+            }
+            HostingProvider::Vercel =>
+                vec![record("@", "A", VERCEL_IP), record("www", "CNAME", "cname.vercel-dns.com.")],
+            HostingProvider::Netlify { site } =>
+                vec![
+                    record("@", "A", NETLIFY_IP),
+                    record("www", "CNAME", &format!("{}.netlify.app.", site.to_lowercase()))
+                ],
+        }
+    }
+}
+
+impl NameCheapClient {
+    /// Points the apex and `www` of `domain` at a hosting provider.
+    ///
+    /// Conflicting records are replaced through the zone diff, the returned plan shows what
+    /// changed. Running it again is a no-op.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let plan = client.setup_hosting("example.com", &HostingProvider::Vercel).await.unwrap();
+    /// println!("{}", plan);
+    /// ```
+    pub async fn setup_hosting(
+        &self,
+        domain: &str,
+        provider: &HostingProvider
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.replace_records(domain, &provider.records()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Host;
+    use crate::zone::diff::ChangeKind;
+    use crate::zone::ensure::replaced_zone;
+
+    #[test]
+    fn test_github_pages_replaces_apex() {
+        let current: Vec<Host> = vec![
+            Host {
+                name: "@".to_string(),
+                type_: "URL".to_string(),
+                address: "http://www.example.com/".to_string(),
+                ttl: 1800,
+                ..Host::new()
+            },
+            Host {
+                name: "@".to_string(),
+                type_: "TXT".to_string(),
+                address: "v=spf1 -all".to_string(),
+                ttl: 1800,
+                ..Host::new()
+            }
+        ];
+        let provider: HostingProvider = HostingProvider::GitHubPages { owner: "Octo".to_string() };
+
+        let plan: ZonePlan = ZonePlan::new(
+            "example.com",
+            &current,
+            &replaced_zone(&current, &provider.records())
+        );
+        assert_eq!(plan.changes_of(ChangeKind::Create).count(), 5);
+        assert_eq!(plan.changes_of(ChangeKind::Delete).next().unwrap().key.record_type, "URL");
+        assert_eq!(plan.changes_of(ChangeKind::Keep).count(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+//! ### DNS Provider Interop
+//!
+//! Shared types of the converters between NameCheap host records and other DNS providers'
+//! formats (`zone::cloudflare`, `zone::route53`). Converters never fail on a single record:
+//! records that cannot be carried over exactly are mapped or skipped, with a warning.
+
+use serde::{ Serialize, Deserialize };
+use std::fmt;
+
+// crate imports
+use crate::limits::{ MAX_TTL, MIN_TTL };
+
+/// A record that was changed or skipped during a conversion.
+///
+/// #### Fields
+/// - `record`: The record, as `<name> <type> <value>` in the source format.
+/// - `message`: What happened to the record and why.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ConversionWarning {
+    pub record: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConversionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.record, self.message)
+    }
+}
+
+/// The result of a conversion: the converted records and the warnings raised along the way.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Conversion<T> {
+    pub records: Vec<T>,
+    pub warnings: Vec<ConversionWarning>,
+}
+
+impl<T> Default for Conversion<T> {
+    fn default() -> Self {
+        Conversion { records: Vec::new(), warnings: Vec::new() }
+    }
+}
+
+impl<T> Conversion<T> {
+    /// Returns `true` if every record was converted exactly.
+    pub fn is_lossless(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Records a warning about `record`.
+    pub(crate) fn warn(&mut self, record: impl fmt::Display, message: impl Into<String>) {
+        self.warnings.push(ConversionWarning { record: record.to_string(), message: message.into() });
+    }
+}
+
+/// Clamps a TTL into the range accepted by NameCheap, returning a warning when it changed.
+pub(crate) fn clamp_ttl(ttl: i64) -> (i64, Option<String>) {
+    let clamped: i64 = ttl.clamp(MIN_TTL, MAX_TTL);
+    if clamped == ttl {
+        (ttl, None)
+    } else {
+        (clamped, Some(format!("TTL {} is outside of {}..={}, using {}", ttl, MIN_TTL, MAX_TTL, clamped)))
+    }
+}
+
+/// Returns `name` as a fully qualified name with a trailing dot.
+pub(crate) fn fully_qualified(name: &str) -> String {
+    format!("{}.", name.trim().trim_end_matches('.'))
+}
+
+/// Returns the fully qualified name of a NameCheap host name, without a trailing dot.
+pub(crate) fn absolute_name(domain: &str, host_name: &str) -> String {
+    let domain: &str = domain.trim_end_matches('.');
+    match host_name.trim() {
+        "" | "@" => domain.to_string(),
+        name => format!("{}.{}", name.trim_end_matches('.'), domain),
+    }
+}
@@ -0,0 +1,317 @@
+//! ### Zone Linting
+//!
+//! This module checks the host records of a domain for common problems and returns
+//! structured findings that can fail a CI job.
+//!
+//! #### Rules
+//! - `DanglingCname`: A CNAME points to a name inside the zone that has no records.
+//! - `CnameConflict`: A CNAME shares its name with other records.
+//! - `MissingSpf`: The apex has no SPF (`v=spf1`) TXT record.
+//! - `MultipleSpf`: A name has more than one SPF TXT record, which invalidates SPF.
+//! - `MxPointsToCname`: An MX record targets a name that is a CNAME (RFC 2181).
+//! - `MissingCaa`: The zone has no CAA record restricting certificate issuance.
+//! - `LongTtlOnEphemeralAddress`: A record pointing at an ephemeral address has a long TTL.
+
+use serde::{ Serialize, Deserialize };
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::zone::relative_name;
+
+/// The severity of a lint finding.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// The rule that produced a lint finding.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum LintRule {
+    DanglingCname,
+    CnameConflict,
+    MissingSpf,
+    MultipleSpf,
+    MxPointsToCname,
+    MissingCaa,
+    LongTtlOnEphemeralAddress,
+}
+
+/// A single lint finding.
+///
+/// #### Fields
+/// - `rule`: The rule that produced the finding.
+/// - `severity`: How serious the finding is.
+/// - `name`: The host name the finding applies to (`@` for the apex).
+/// - `message`: A human readable description.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub name: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}] {:?} at {}: {}", self.severity, self.rule, self.name, self.message)
+    }
+}
+
+/// The configuration of the linter.
+///
+/// #### Fields
+/// - `max_ephemeral_ttl`: The maximum TTL allowed on records pointing at ephemeral addresses.
+/// - `ephemeral_prefixes`: Address prefixes considered ephemeral, e.g. a cloud provider's
+///   dynamic IP range (`"203.0.113."`) or hostname suffix (`".compute.amazonaws.com"`).
+/// - `require_caa`: Whether a missing CAA record is reported.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct LintConfig {
+    pub max_ephemeral_ttl: i64,
+    pub ephemeral_prefixes: Vec<String>,
+    pub require_caa: bool,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            max_ephemeral_ttl: 300,
+            ephemeral_prefixes: Vec::new(),
+            require_caa: true,
+        }
+    }
+}
+
+impl LintConfig {
+    fn is_ephemeral(&self, address: &str) -> bool {
+        let address: String = address.trim_end_matches('.').to_lowercase();
+        self.ephemeral_prefixes.iter().any(|pattern| {
+            let pattern: String = pattern.to_lowercase();
+            address.starts_with(&pattern) || (pattern.starts_with('.') && address.ends_with(&pattern))
+        })
+    }
+}
+
+/// Returns `true` if any finding has `Error` severity.
+pub fn has_errors(findings: &[LintFinding]) -> bool {
+    findings.iter().any(|finding| finding.severity == LintSeverity::Error)
+}
+
+/// Lints the host records of a domain.
+///
+/// # Parameters
+///
+/// - `domain`: The domain the records belong to, e.g. `example.com`
+/// - `hosts`: The host records of the domain
+/// - `config`: The linter configuration
+///
+/// # Returns
+///
+/// The findings, sorted by descending severity
+pub fn lint_zone(domain: &str, hosts: &[Host], config: &LintConfig) -> Vec<LintFinding> {
+    let mut findings: Vec<LintFinding> = Vec::new();
+    let mut finding = |rule: LintRule, severity: LintSeverity, name: &str, message: String| {
+        findings.push(LintFinding { rule, severity, name: name.to_string(), message });
+    };
+
+    let name_of = |host: &Host| -> String {
+        match host.name.trim().to_lowercase() {
+            name if name.is_empty() => "@".to_string(),
+            name => name,
+        }
+    };
+    let is_type = |host: &Host, record_type: &str| host.type_.eq_ignore_ascii_case(record_type);
+
+    let names: HashSet<String> = hosts.iter().map(name_of).collect();
+    let cname_names: HashSet<String> = hosts
+        .iter()
+        .filter(|host| is_type(host, "CNAME"))
+        .map(name_of)
+        .collect();
+
+    for host in hosts.iter().filter(|host| is_type(host, "CNAME")) {
+        let name: String = name_of(host);
+        if let Some(target) = relative_name(domain, &host.address) &&
+            !names.contains(&target) && !names.contains("*")
+        {
+            finding(
+                LintRule::DanglingCname,
+                LintSeverity::Error,
+                &name,
+                format!("CNAME points to {} which has no records in the zone", host.address)
+            );
+        }
+        if hosts.iter().any(|other| name_of(other) == name && !is_type(other, "CNAME")) {
+            finding(
+                LintRule::CnameConflict,
+                LintSeverity::Error,
+                &name,
+                "CNAME cannot coexist with other records at the same name".to_string()
+            );
+        }
+    }
+
+    for host in hosts.iter().filter(|host| is_type(host, "MX")) {
+        if let Some(target) = relative_name(domain, &host.address) &&
+            cname_names.contains(&target)
+        {
+            finding(
+                LintRule::MxPointsToCname,
+                LintSeverity::Error,
+                &name_of(host),
+                format!("MX points to {} which is a CNAME", host.address)
+            );
+        }
+    }
+
+    let mut spf_names: Vec<String> = hosts
+        .iter()
+        .filter(|host| is_type(host, "TXT") && host.address.trim().to_lowercase().starts_with("v=spf1"))
+        .map(name_of)
+        .collect();
+    if !spf_names.iter().any(|name| name == "@") {
+        finding(
+            LintRule::MissingSpf,
+            LintSeverity::Warning,
+            "@",
+            "No SPF record, mail from this domain may be rejected or spoofed".to_string()
+        );
+    }
+    spf_names.sort();
+    let mut duplicates: Vec<String> = spf_names
+        .windows(2)
+        .filter(|pair| pair[0] == pair[1])
+        .map(|pair| pair[0].clone())
+        .collect();
+    duplicates.dedup();
+    for name in duplicates {
+        finding(
+            LintRule::MultipleSpf,
+            LintSeverity::Error,
+            &name,
+            "Multiple SPF records at the same name invalidate SPF".to_string()
+        );
+    }
+
+    if config.require_caa && !hosts.iter().any(|host| is_type(host, "CAA")) {
+        finding(
+            LintRule::MissingCaa,
+            LintSeverity::Info,
+            "@",
+            "No CAA record, any certificate authority may issue certificates".to_string()
+        );
+    }
+
+    for host in hosts {
+        if host.ttl > config.max_ephemeral_ttl && config.is_ephemeral(&host.address) {
+            finding(
+                LintRule::LongTtlOnEphemeralAddress,
+                LintSeverity::Warning,
+                &name_of(host),
+                format!(
+                    "TTL {} on ephemeral address {} exceeds {}",
+                    host.ttl,
+                    host.address,
+                    config.max_ephemeral_ttl
+                )
+            );
+        }
+    }
+
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+    findings
+}
+
+impl NameCheapClient {
+    /// Fetches the host records of a domain and lints them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let findings = client.domains_dns_lint("domain", "com", &LintConfig::default()).await.unwrap();
+    /// if has_errors(&findings) {
+    ///     std::process::exit(1);
+    /// }
+    /// ```
+    pub async fn domains_dns_lint(
+        &self,
+        sld: &str,
+        tld: &str,
+        config: &LintConfig
+    ) -> Result<Vec<LintFinding>, Box<dyn Error>> {
+        let hosts: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+        Ok(lint_zone(&format!("{}.{}", sld, tld), &hosts, config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str, ttl: i64) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ttl,
+            ..Host::new()
+        }
+    }
+
+    fn rules(findings: &[LintFinding]) -> Vec<LintRule> {
+        findings.iter().map(|finding| finding.rule).collect()
+    }
+
+    #[test]
+    fn test_lint_zone_reports_problems() {
+        let hosts: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10", 3600),
+            host("www", "CNAME", "missing.example.com.", 1800),
+            host("mail", "CNAME", "mail.provider.net.", 1800),
+            host("@", "MX", "mail.example.com.", 1800),
+            host("@", "TXT", "v=spf1 include:_spf.google.com ~all", 1800),
+            host("@", "TXT", "v=spf1 -all", 1800)
+        ];
+        let config: LintConfig = LintConfig {
+            ephemeral_prefixes: vec!["203.0.113.".to_string()],
+            ..LintConfig::default()
+        };
+
+        let findings: Vec<LintFinding> = lint_zone("example.com", &hosts, &config);
+        let rules: Vec<LintRule> = rules(&findings);
+
+        assert!(rules.contains(&LintRule::DanglingCname));
+        assert!(rules.contains(&LintRule::MxPointsToCname));
+        assert!(rules.contains(&LintRule::MultipleSpf));
+        assert!(rules.contains(&LintRule::MissingCaa));
+        assert!(rules.contains(&LintRule::LongTtlOnEphemeralAddress));
+        assert!(!rules.contains(&LintRule::MissingSpf));
+        assert!(has_errors(&findings));
+        assert_eq!(findings[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_lint_zone_clean() {
+        let hosts: Vec<Host> = vec![
+            host("@", "A", "198.51.100.10", 1800),
+            host("www", "CNAME", "example.com.", 1800),
+            host("@", "TXT", "v=spf1 -all", 1800),
+            host("@", "CAA", "0 issue \"letsencrypt.org\"", 1800)
+        ];
+
+        assert!(lint_zone("example.com", &hosts, &LintConfig::default()).is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+//! ## Zone Management
+//! Helpers that treat the host records of a domain as a whole zone, on top of the
+//! `domains.dns.getHosts` and `domains.dns.setHosts` commands.
+//!
+//! ### Available Modules
+//! - `diff`: Computes the plan of changes between the live zone and a desired set of records.
+//! - `annotations`: A local sidecar file associating labels, owners and comments with records.
+//! - `lint`: Checks a zone for common problems (dangling CNAMEs, SPF issues, missing CAA, ...).
+//! - `reverse`: Finds the records across all domains that point at an IP address or hostname.
+//! - `replace`: Rewrites the records pointing at an old address across all domains.
+//! - `template`: Provisions a zone from a set of records with `{{variables}}`.
+//! - `ensure`: Idempotently adds records to a zone without touching the others.
+//! - `verification`: Domain verification records for Google, Microsoft 365, AWS and GitHub.
+//! - `hosting`: Apex and `www` setup for GitHub Pages, Vercel and Netlify.
+//! - `snapshot`: Zone snapshot hashes and a compare-and-set `setHosts`.
+//! - `interop`: Shared types of the converters to and from other DNS providers.
+//! - `cloudflare`: Import and export in the Cloudflare API record format.
+//! - `route53`: Import from and export to AWS Route53 record sets and change batches.
+//! - `sync`: Desired-state files, adopted from a live zone.
+//! - `history`: The values of each record over time (`audit_log` feature).
+//!
+//! Records are identified by a [`RecordKey`] made of the host name, record type and address,
+//! normalized so that `WWW`/`www` or `example.com.`/`example.com` compare equal.
+//!
+//! Host lists read with `domains_dns_get_hosts` or `domains_dns_get_host_records` and submitted
+//! with `setHosts` are in canonical order, see [`sort_hosts`], so diffs, snapshots and audit
+//! logs are stable across runs whatever order the API returns the records in.
+
+use serde::{ Serialize, Deserialize };
+use std::fmt;
+
+// crate imports
+use crate::Host;
+use crate::domains_dns::set_hosts::HostRequest;
+
+/// - **zone diff and plan**
+pub mod diff;
+/// - **record annotations**
+pub mod annotations;
+/// - **zone linting**
+pub mod lint;
+/// - **reverse lookup**
+pub mod reverse;
+/// - **address search-and-replace**
+pub mod replace;
+/// - **zone templates**
+pub mod template;
+/// - **ensure records**
+pub mod ensure;
+/// - **verification records**
+pub mod verification;
+/// - **hosting provider setup**
+pub mod hosting;
+/// - **snapshots and compare-and-set**
+pub mod snapshot;
+/// - **desired-state files**
+pub mod sync;
+/// - **record history**
+#[cfg(feature = "audit_log")]
+pub mod history;
+/// - **provider interop**
+pub mod interop;
+/// - **Cloudflare import/export**
+pub mod cloudflare;
+/// - **Route53 interop**
+pub mod route53;
+
+/// A stable identifier for a host record.
+///
+/// #### Fields
+/// - `name`: The host name, lowercased (e.g. `@`, `www`).
+/// - `record_type`: The record type, uppercased (e.g. `A`, `CNAME`).
+/// - `address`: The record value, with hostnames lowercased and the trailing dot removed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecordKey {
+    pub name: String,
+    pub record_type: String,
+    pub address: String,
+}
+
+impl RecordKey {
+    /// Creates a normalized `RecordKey`.
+    pub fn new(name: &str, record_type: &str, address: &str) -> Self {
+        let record_type: String = record_type.trim().to_uppercase();
+        let address: &str = address.trim();
+
+        // TXT values are case sensitive, hostnames are not
+        let address: String = match record_type.as_str() {
+            "TXT" => address.to_string(),
+            _ => address.trim_end_matches('.').to_lowercase(),
+        };
+
+        let name: String = match name.trim().to_lowercase() {
+            name if name.is_empty() => "@".to_string(),
+            name => name,
+        };
+
+        RecordKey { name, record_type, address }
+    }
+}
+
+impl fmt::Display for RecordKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.name, self.record_type, self.address)
+    }
+}
+
+impl From<&Host> for RecordKey {
+    fn from(host: &Host) -> Self {
+        RecordKey::new(&host.name, &host.type_, &host.address)
+    }
+}
+
+impl From<&HostRequest> for RecordKey {
+    fn from(host: &HostRequest) -> Self {
+        RecordKey::new(&host.host_name, &host.record_type, &host.address)
+    }
+}
+
+/// Sorts host records canonically: by name, then type, then value, compared as [`RecordKey`]s.
+///
+/// The sort is stable, records with the same key keep their relative order.
+pub fn sort_hosts(hosts: &mut [Host]) {
+    hosts.sort_by_cached_key(|host| RecordKey::from(host));
+}
+
+/// Sorts host record requests in the same canonical order as [`sort_hosts`].
+pub fn sort_host_requests(hosts: &mut [HostRequest]) {
+    hosts.sort_by_cached_key(|host| RecordKey::from(host));
+}
+
+/// Returns the host name of `target` relative to `domain`, if it is inside the zone.
+///
+/// For `domain = "example.com"`, `mail.example.com.` gives `mail` and `example.com` gives `@`.
+/// Targets outside the zone give `None`.
+pub fn relative_name(domain: &str, target: &str) -> Option<String> {
+    let domain: String = domain.trim_end_matches('.').to_lowercase();
+    let target: String = target.trim().trim_end_matches('.').to_lowercase();
+
+    if target == domain {
+        return Some("@".to_string());
+    }
+    target
+        .strip_suffix(&format!(".{}", domain))
+        .map(|name| name.to_string())
+}
+
+/// Splits a domain into its SLD and TLD, e.g. `example.co.uk` into `example` and `co.uk`.
+pub fn split_domain(domain: &str) -> Option<(&str, &str)> {
+    let domain: &str = domain.trim().trim_end_matches('.');
+    match domain.split_once('.') {
+        Some((sld, tld)) if !sld.is_empty() && !tld.is_empty() => Some((sld, tld)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_key_normalization() {
+        assert_eq!(
+            RecordKey::new("WWW", "cname", "Example.com."),
+            RecordKey::new("www", "CNAME", "example.com")
+        );
+        assert_ne!(RecordKey::new("@", "TXT", "Token"), RecordKey::new("@", "TXT", "token"));
+        assert_eq!(RecordKey::new("", "A", "1.2.3.4").name, "@");
+    }
+
+    #[test]
+    fn test_sort_hosts() {
+        let host = |name: &str, type_: &str, address: &str| Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ..Host::new()
+        };
+        let mut hosts: Vec<Host> = vec![
+            host("www", "CNAME", "example.com."),
+            host("@", "TXT", "v=spf1 -all"),
+            host("@", "A", "203.0.113.20"),
+            host("@", "A", "203.0.113.10")
+        ];
+        let mut requests: Vec<HostRequest> = hosts.iter().rev().map(Host::to_host_request).collect();
+
+        sort_hosts(&mut hosts);
+        sort_host_requests(&mut requests);
+        let order: Vec<&str> = hosts.iter().map(|host| host.address.as_str()).collect();
+        assert_eq!(order, ["203.0.113.10", "203.0.113.20", "v=spf1 -all", "example.com."]);
+        assert_eq!(requests.iter().map(|host| host.address.as_str()).collect::<Vec<&str>>(), order);
+    }
+
+    #[test]
+    fn test_relative_name() {
+        assert_eq!(relative_name("example.com", "mail.example.com."), Some("mail".to_string()));
+        assert_eq!(relative_name("example.com", "Example.com"), Some("@".to_string()));
+        assert_eq!(relative_name("example.com", "notexample.com"), None);
+    }
+
+    #[test]
+    fn test_split_domain() {
+        assert_eq!(split_domain("example.co.uk"), Some(("example", "co.uk")));
+        assert_eq!(split_domain("example.com."), Some(("example", "com")));
+        assert_eq!(split_domain("localhost"), None);
+    }
+}
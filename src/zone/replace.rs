@@ -0,0 +1,148 @@
+//! ### Address Search-and-Replace
+//!
+//! This module rewrites every record pointing at an old IP address or hostname, across all
+//! domains of the account, to point at a new one. This is the "we moved data centers"
+//! operation.
+//!
+//! Affected domains are found with a [`ReverseLookup`], each zone is then planned with a
+//! [`ZonePlan`] and, unless running dry, submitted with `domains.dns.setHosts`.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::batch::{ Batch, BatchReport };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::diff::ZonePlan;
+use crate::zone::reverse::{ RecordMatch, ReverseLookup };
+use crate::zone::split_domain;
+
+/// Returns the records of a zone with every address equal to `old` replaced by `new`.
+///
+/// Only records whose type is in `record_types` are rewritten, an empty `record_types`
+/// rewrites every type. Addresses are compared case-insensitively, ignoring a trailing dot.
+pub fn replace_address(hosts: &[Host], old: &str, new: &str, record_types: &[&str]) -> Vec<HostRequest> {
+    let normalize = |address: &str| address.trim().trim_end_matches('.').to_lowercase();
+    let old: String = normalize(old);
+
+    hosts
+        .iter()
+        .map(|host| {
+            let mut request: HostRequest = host.to_host_request();
+            let type_matches: bool =
+                record_types.is_empty() ||
+                record_types.iter().any(|record_type| record_type.eq_ignore_ascii_case(&host.type_));
+            if type_matches && normalize(&host.address) == old {
+                request.address = new.to_string();
+            }
+            request
+        })
+        .collect()
+}
+
+impl NameCheapClient {
+    /// Rewrites every record pointing at `old` to point at `new`, across all domains.
+    ///
+    /// # Parameters
+    ///
+    /// - `old`: The IP address or hostname to replace
+    /// - `new`: The replacement IP address or hostname
+    /// - `record_types`: The record types to rewrite, e.g. `&["A"]`; empty rewrites every type
+    /// - `dry_run`: When `true`, plans are computed but nothing is submitted
+    ///
+    /// # Returns
+    ///
+    /// A report with the `ZonePlan` of each affected domain, keyed by domain name. With a
+    /// client set up with `with_cancellation`, a cancelled run reports the zones it got to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let report = client.replace_address_across_zones("203.0.113.10", "198.51.100.10", &["A"], true).await.unwrap();
+    /// for item in report.succeeded() {
+    ///     println!("{:?}", item.outcome);
+    /// }
+    /// ```
+    pub async fn replace_address_across_zones(
+        &self,
+        old: &str,
+        new: &str,
+        record_types: &[&str],
+        dry_run: bool
+    ) -> Result<BatchReport<ZonePlan>, Box<dyn Error>> {
+        let lookup: ReverseLookup = ReverseLookup::new(self.clone());
+        let matches: Vec<RecordMatch> = lookup.find_records_pointing_to(old).await?;
+
+        let domains: BTreeSet<String> = matches
+            .into_iter()
+            .filter(|found| {
+                record_types.is_empty() ||
+                    record_types
+                        .iter()
+                        .any(|record_type| record_type.eq_ignore_ascii_case(&found.record.type_))
+            })
+            .map(|found| found.domain)
+            .collect();
+
+        // Zones not started yet are skipped once the client is cancelled
+        let mut batch: Batch = Batch::new();
+        if let Some(token) = self.shared.cancellation().token() {
+            batch = batch.with_cancellation(token.clone());
+        }
+
+        let lookup: &ReverseLookup = &lookup;
+        let report: BatchReport<ZonePlan> = batch.run(
+            domains.into_iter().collect(),
+            |domain| async move {
+                let _lock = self.lock_domain(&domain).await;
+                lookup.invalidate(&domain).await;
+                let hosts: Vec<Host> = lookup.host_records(&domain).await?;
+                let desired: Vec<HostRequest> = replace_address(&hosts, old, new, record_types);
+                let plan: ZonePlan = ZonePlan::new(&domain, &hosts, &desired);
+
+                if !dry_run && plan.has_changes() {
+                    let (sld, tld) = split_domain(&domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+                    self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+                }
+                Ok(plan)
+            }
+        ).await;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::diff::ChangeKind;
+
+    fn host(name: &str, type_: &str, address: &str) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ttl: 1800,
+            ..Host::new()
+        }
+    }
+
+    #[test]
+    fn test_replace_address() {
+        let hosts: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10"),
+            host("www", "CNAME", "example.com."),
+            host("legacy", "URL", "203.0.113.10")
+        ];
+
+        let desired: Vec<HostRequest> = replace_address(&hosts, "203.0.113.10", "198.51.100.10", &["A"]);
+        assert_eq!(desired[0].address, "198.51.100.10");
+        assert_eq!(desired[2].address, "203.0.113.10");
+
+        let plan: ZonePlan = ZonePlan::new("example.com", &hosts, &desired);
+        assert_eq!(plan.changes_of(ChangeKind::Delete).count(), 1);
+        assert_eq!(plan.changes_of(ChangeKind::Create).count(), 1);
+        assert_eq!(plan.desired.len(), 3);
+    }
+}
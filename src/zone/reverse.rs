@@ -0,0 +1,184 @@
+//! ### Reverse Lookup
+//!
+//! This module finds the host records, across every domain of the account, that point at a
+//! given IP address or hostname. This is useful before decommissioning a server or load
+//! balancer.
+//!
+//! Scanning calls `domains.dns.getHosts` once per domain, so calls are paced with a
+//! [`RateLimiter`] and the host records are cached for `cache_ttl` to make repeated lookups
+//! cheap.
+
+use serde::{ Serialize, Deserialize };
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::warn;
+
+// crate imports
+use crate::{ NameCheapClient, Domain, Host };
+use crate::utils::rate_limit::RateLimiter;
+use crate::zone::split_domain;
+
+/// A host record pointing at the looked up target.
+///
+/// #### Fields
+/// - `domain`: The domain the record belongs to.
+/// - `record`: The matching host record.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct RecordMatch {
+    pub domain: String,
+    pub record: Host,
+}
+
+/// Scans host records across domains, with rate limiting and a per-domain cache.
+///
+/// # Example
+///
+/// ```rust
+/// let lookup = ReverseLookup::new(client);
+/// for found in lookup.find_records_pointing_to("203.0.113.10").await.unwrap() {
+///     println!("{}: {} {}", found.domain, found.record.name, found.record.type_);
+/// }
+/// ```
+pub struct ReverseLookup {
+    client: NameCheapClient,
+    limiter: RateLimiter,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, Vec<Host>)>>,
+}
+
+impl ReverseLookup {
+    /// The default lifetime of cached host records.
+    pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+    /// Creates a reverse lookup with the default rate limit and cache lifetime.
+    pub fn new(client: NameCheapClient) -> Self {
+        ReverseLookup {
+            client,
+            limiter: RateLimiter::default(),
+            cache_ttl: Self::DEFAULT_CACHE_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the rate limiter used for `domains.dns.getHosts` calls.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Sets how long fetched host records are reused.
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// Returns the rate limiter pacing this lookup's API calls, to share its budget with other calls.
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    /// Drops every cached zone.
+    pub async fn clear_cache(&self) {
+        self.cache.lock().await.clear();
+    }
+
+    /// Drops the cached zone of a domain, the next lookup fetches it again.
+    pub async fn invalidate(&self, domain: &str) {
+        self.cache.lock().await.remove(&domain.to_lowercase());
+    }
+
+    /// Returns the host records of a domain, from the cache when fresh.
+    pub async fn host_records(&self, domain: &str) -> Result<Vec<Host>, Box<dyn Error>> {
+        let domain: String = domain.to_lowercase();
+        if let Some((fetched_at, hosts)) = self.cache.lock().await.get(&domain) &&
+            fetched_at.elapsed() < self.cache_ttl
+        {
+            return Ok(hosts.clone());
+        }
+
+        let (sld, tld) = split_domain(&domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+        self.limiter.acquire().await;
+        let hosts: Vec<Host> = self.client.domains_dns_get_host_records(sld, tld).await?;
+
+        self.cache.lock().await.insert(domain.clone(), (Instant::now(), hosts.clone()));
+        Ok(hosts)
+    }
+
+    /// Finds the records of every domain using NameCheap DNS that point at `target`.
+    ///
+    /// `target` is an IP address or hostname, compared case-insensitively and ignoring a
+    /// trailing dot. Domains whose records cannot be fetched are logged and skipped.
+    pub async fn find_records_pointing_to(&self, target: &str) -> Result<Vec<RecordMatch>, Box<dyn Error>> {
+        let domains: Vec<Domain> = self.client.domains_get_list_all().await?;
+        let mut matches: Vec<RecordMatch> = Vec::new();
+
+        for domain in domains.iter().filter(|domain| domain.is_our_dns) {
+            match self.host_records(&domain.name).await {
+                Ok(hosts) => matches.extend(records_pointing_to(&domain.name, &hosts, target)),
+                Err(error) => warn!("Skipping {} in reverse lookup: {}", domain.name, error),
+            }
+        }
+
+        Ok(matches)
+    }
+}
+
+impl NameCheapClient {
+    /// Finds the host records, across all domains, that point at an IP address or hostname.
+    ///
+    /// Runs a one-off [`ReverseLookup`]; keep a `ReverseLookup` around to reuse its cache.
+    pub async fn find_records_pointing_to(&self, target: &str) -> Result<Vec<RecordMatch>, Box<dyn Error>> {
+        ReverseLookup::new(self.clone()).find_records_pointing_to(target).await
+    }
+}
+
+/// Returns the records of `hosts` whose address is `target`.
+pub fn records_pointing_to(domain: &str, hosts: &[Host], target: &str) -> Vec<RecordMatch> {
+    let normalize = |address: &str| address.trim().trim_end_matches('.').to_lowercase();
+    let target: String = normalize(target);
+
+    hosts
+        .iter()
+        .filter(|host| normalize(&host.address) == target)
+        .map(|host| RecordMatch {
+            domain: domain.to_string(),
+            record: host.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            address: address.to_string(),
+            ..Host::new()
+        }
+    }
+
+    #[test]
+    fn test_records_pointing_to() {
+        let hosts: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10"),
+            host("www", "CNAME", "LB.example.net."),
+            host("api", "A", "203.0.113.11")
+        ];
+
+        let by_ip: Vec<RecordMatch> = records_pointing_to("example.com", &hosts, "203.0.113.10");
+        assert_eq!(by_ip.len(), 1);
+        assert_eq!(by_ip[0].record.name, "@");
+
+        let by_host: Vec<RecordMatch> = records_pointing_to("example.com", &hosts, "lb.example.net");
+        assert_eq!(by_host.len(), 1);
+        assert_eq!(by_host[0].domain, "example.com");
+    }
+}
@@ -0,0 +1,421 @@
+//! ### Route53 Interop
+//!
+//! This module converts between NameCheap host records and AWS Route53 record sets, read from
+//! `list-resource-record-sets` output or a `ChangeBatch`, and written as an `UPSERT`
+//! `ChangeBatch` for `change-resource-record-sets`.
+//!
+//! #### Mapping
+//! - One Route53 record set with several values becomes one host record per value, and host
+//!   records sharing a name and type are grouped back into one record set.
+//! - Route53 alias targets are imported as NameCheap `ALIAS` records.
+//! - NameCheap `ALIAS` records are exported according to an [`AliasMapping`], since Route53
+//!   only aliases AWS resources.
+//! - Routing policies (weighted, latency, ...) are flattened, every value is kept.
+//! - Types without an equivalent (`SOA`, `SRV`, `URL`, `MXE`, ...) are skipped.
+//!
+//! Every mapped or skipped record is reported as a [`ConversionWarning`](crate::zone::interop::ConversionWarning).
+
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+// crate imports
+use crate::Host;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::interop::{ absolute_name, clamp_ttl, fully_qualified, Conversion };
+use crate::zone::relative_name;
+
+/// The record types carried over in both directions.
+const SHARED_TYPES: [&str; 7] = ["A", "AAAA", "CNAME", "MX", "TXT", "NS", "CAA"];
+
+/// The longest character string of a TXT value.
+const TXT_CHUNK: usize = 255;
+
+/// A value of a Route53 record set.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ResourceRecord {
+    pub value: String,
+}
+
+/// The target of a Route53 alias record.
+///
+/// #### Fields
+/// - `hosted_zone_id`: The hosted zone of the target, e.g. `Z2FDTNDATAQYW2` for CloudFront.
+/// - `dns_name`: The target name.
+/// - `evaluate_target_health`: Whether Route53 checks the health of the target.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct AliasTarget {
+    pub hosted_zone_id: String,
+    #[serde(rename = "DNSName")]
+    pub dns_name: String,
+    #[serde(default)]
+    pub evaluate_target_health: bool,
+}
+
+/// ### Resource Record Set
+///
+/// A Route53 record set, in the JSON format of the AWS CLI.
+///
+/// #### Fields
+/// - `name`: The fully qualified name, with a trailing dot.
+/// - `record_type`: The record type, e.g. `A` (`Type` in JSON).
+/// - `ttl`: The TTL in seconds, absent on alias records.
+/// - `resource_records`: The values, empty on alias records.
+/// - `alias_target`: The alias target (optional).
+/// - `set_identifier`: The identifier of a routing policy record (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ResourceRecordSet {
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub record_type: String,
+    #[serde(rename = "TTL", default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resource_records: Vec<ResourceRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_target: Option<AliasTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_identifier: Option<String>,
+}
+
+impl fmt::Display for ResourceRecordSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.record_type)
+    }
+}
+
+/// The action of a `Change`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum ChangeAction {
+    Create,
+    Delete,
+    Upsert,
+}
+
+/// A change of a `ChangeBatch`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Change {
+    pub action: ChangeAction,
+    pub resource_record_set: ResourceRecordSet,
+}
+
+/// A Route53 `ChangeBatch`, as accepted by `aws route53 change-resource-record-sets`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ChangeBatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+/// How NameCheap `ALIAS` records are exported to Route53.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash, Default)]
+pub enum AliasMapping {
+    /// Skip `ALIAS` records.
+    #[default]
+    Skip,
+    /// Export `ALIAS` records as `CNAME`s, `ALIAS` records at the apex are skipped.
+    Cname,
+    /// Export `ALIAS` records as Route53 `A` aliases of an AWS resource in `hosted_zone_id`.
+    AliasTarget { hosted_zone_id: String },
+}
+
+/// Reads Route53 record sets from `list-resource-record-sets` output, a `ChangeBatch` or a bare array.
+///
+/// `DELETE` changes of a `ChangeBatch` are ignored.
+pub fn parse_route53_json(json: &str) -> Result<Vec<ResourceRecordSet>, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(json)?;
+    if let Some(record_sets) = value.get("ResourceRecordSets") {
+        return Ok(serde_json::from_value(record_sets.clone())?);
+    }
+    if value.get("Changes").is_some() {
+        let batch: ChangeBatch = serde_json::from_value(value)?;
+        return Ok(
+            batch.changes
+                .into_iter()
+                .filter(|change| change.action != ChangeAction::Delete)
+                .map(|change| change.resource_record_set)
+                .collect()
+        );
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Converts Route53 record sets of `domain` into `HostRequest`s for `domains_dns_replace_hosts`.
+pub fn from_route53(domain: &str, record_sets: &[ResourceRecordSet]) -> Conversion<HostRequest> {
+    let mut conversion: Conversion<HostRequest> = Conversion::default();
+
+    for record_set in record_sets {
+        let record_type: String = record_set.record_type.to_uppercase();
+        // Route53 escapes the wildcard label as `\052`
+        let name: String = record_set.name.replace("\\052", "*");
+        let Some(host_name) = relative_name(domain, &name) else {
+            conversion.warn(record_set, format!("name is outside of {}, skipped", domain));
+            continue;
+        };
+        if record_set.set_identifier.is_some() {
+            conversion.warn(record_set, "routing policy is not supported by NameCheap, every value is kept");
+        }
+
+        if let Some(alias) = &record_set.alias_target {
+            conversion.warn(
+                record_set,
+                format!("Route53 alias to {} imported as a NameCheap ALIAS", alias.dns_name)
+            );
+            let request: HostRequest = HostRequest::new(
+                host_name,
+                "ALIAS".to_string(),
+                fully_qualified(&alias.dns_name),
+                None,
+                None,
+                None,
+                None,
+                None
+            );
+            conversion.records.push(request);
+            continue;
+        }
+
+        if !SHARED_TYPES.contains(&record_type.as_str()) {
+            conversion.warn(record_set, format!("{} records are not supported by NameCheap, skipped", record_type));
+            continue;
+        }
+        if record_type == "NS" && host_name == "@" {
+            conversion.warn(record_set, "apex nameservers are not host records, skipped");
+            continue;
+        }
+
+        let ttl: Option<String> = record_set.ttl.map(|ttl| {
+            let (ttl, warning) = clamp_ttl(ttl);
+            if let Some(warning) = warning {
+                conversion.warn(record_set, warning);
+            }
+            ttl.to_string()
+        });
+
+        for record in &record_set.resource_records {
+            let (mx_pref, address) = match record_type.as_str() {
+                "MX" => {
+                    let Some((preference, exchange)) = record.value.trim().split_once(' ') else {
+                        conversion.warn(record_set, format!("invalid MX value {}, skipped", record.value));
+                        continue;
+                    };
+                    (Some(preference.to_string()), fully_qualified(exchange))
+                }
+                "CNAME" | "NS" => (None, fully_qualified(&record.value)),
+                "TXT" => (None, join_txt(&record.value)),
+                _ => (None, record.value.clone()),
+            };
+            conversion.records.push(
+                HostRequest::new(host_name.clone(), record_type.clone(), address, mx_pref, None, ttl.clone(), None, None)
+            );
+        }
+    }
+
+    conversion
+}
+
+/// Converts the host records of `domain` into an `UPSERT` `ChangeBatch`.
+///
+/// Host records sharing a name and type become one record set, with the TTL of the first.
+pub fn to_route53(domain: &str, hosts: &[Host], aliases: &AliasMapping) -> Conversion<ChangeBatch> {
+    let mut conversion: Conversion<ChangeBatch> = Conversion::default();
+    let mut record_sets: Vec<ResourceRecordSet> = Vec::new();
+
+    for host in hosts {
+        let label: String = format!("{} {} {}", host.name, host.type_, host.address);
+        let name: String = fully_qualified(&absolute_name(domain, &host.name));
+        let record_type: String = host.type_.to_uppercase();
+
+        if record_type == "ALIAS" {
+            let target: String = fully_qualified(&host.address);
+            match aliases {
+                AliasMapping::Skip => {
+                    conversion.warn(&label, "ALIAS records are skipped by the alias mapping");
+                }
+                AliasMapping::Cname if host.name == "@" => {
+                    conversion.warn(&label, "a CNAME cannot replace an ALIAS at the apex, skipped");
+                }
+                AliasMapping::Cname => {
+                    conversion.warn(&label, "exported as a CNAME");
+                    add_value(&mut conversion, &mut record_sets, &label, name, "CNAME", host.ttl, target);
+                }
+                AliasMapping::AliasTarget { hosted_zone_id } => {
+                    conversion.warn(&label, format!("exported as an A alias in hosted zone {}", hosted_zone_id));
+                    record_sets.push(ResourceRecordSet {
+                        name,
+                        record_type: "A".to_string(),
+                        ttl: None,
+                        resource_records: Vec::new(),
+                        alias_target: Some(AliasTarget {
+                            hosted_zone_id: hosted_zone_id.clone(),
+                            dns_name: target,
+                            evaluate_target_health: false,
+                        }),
+                        set_identifier: None,
+                    });
+                }
+            }
+            continue;
+        }
+        if !SHARED_TYPES.contains(&record_type.as_str()) {
+            conversion.warn(&label, format!("{} records have no Route53 equivalent, skipped", record_type));
+            continue;
+        }
+
+        let value: String = match record_type.as_str() {
+            "MX" => format!("{} {}", host.mx_pref.parse::<u16>().unwrap_or(10), fully_qualified(&host.address)),
+            "CNAME" | "NS" => fully_qualified(&host.address),
+            "TXT" => split_txt(&host.address),
+            _ => host.address.clone(),
+        };
+        add_value(&mut conversion, &mut record_sets, &label, name, &record_type, host.ttl, value);
+    }
+
+    let changes: Vec<Change> = record_sets
+        .into_iter()
+        .map(|resource_record_set| Change { action: ChangeAction::Upsert, resource_record_set })
+        .collect();
+    conversion.records.push(ChangeBatch { comment: Some(format!("Mirrored from NameCheap: {}", domain)), changes });
+    conversion
+}
+
+/// Adds a value to the record set of `name` and `record_type`, creating it if needed.
+fn add_value(
+    conversion: &mut Conversion<ChangeBatch>,
+    record_sets: &mut Vec<ResourceRecordSet>,
+    label: &str,
+    name: String,
+    record_type: &str,
+    ttl: i64,
+    value: String
+) {
+    let existing: Option<&mut ResourceRecordSet> = record_sets
+        .iter_mut()
+        .find(|set| set.name == name && set.record_type == record_type && set.alias_target.is_none());
+    match existing {
+        Some(set) => {
+            if set.ttl != Some(ttl) {
+                conversion.warn(label, format!("TTL {} differs from the record set, using {:?}", ttl, set.ttl));
+            }
+            set.resource_records.push(ResourceRecord { value });
+        }
+        None =>
+            record_sets.push(ResourceRecordSet {
+                name,
+                record_type: record_type.to_string(),
+                ttl: Some(ttl),
+                resource_records: vec![ResourceRecord { value }],
+                alias_target: None,
+                set_identifier: None,
+            }),
+    }
+}
+
+/// Joins the quoted character strings of a Route53 TXT value, e.g. `"a" "b"` into `ab`.
+fn join_txt(value: &str) -> String {
+    let value: &str = value.trim();
+    if !value.starts_with('"') {
+        return value.to_string();
+    }
+    value
+        .split("\" \"")
+        .map(|part| part.trim_matches('"').replace("\\\"", "\""))
+        .collect()
+}
+
+/// Quotes a TXT value for Route53, splitting it into 255 character strings.
+fn split_txt(value: &str) -> String {
+    let escaped: String = value.replace('"', "\\\"");
+    let chars: Vec<char> = escaped.chars().collect();
+    chars
+        .chunks(TXT_CHUNK)
+        .map(|chunk| format!("\"{}\"", chunk.iter().collect::<String>()))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str, ttl: i64) -> Host {
+        let mut host: Host = Host::new();
+        host.name = name.to_string();
+        host.type_ = type_.to_string();
+        host.address = address.to_string();
+        host.mx_pref = "10".to_string();
+        host.ttl = ttl;
+        host
+    }
+
+    #[test]
+    fn test_from_route53() {
+        let record_sets: Vec<ResourceRecordSet> = parse_route53_json(
+            r#"{ "ResourceRecordSets": [
+                { "Name": "example.com.", "Type": "SOA", "TTL": 900, "ResourceRecords": [{ "Value": "ns-1.awsdns-00.com. hostmaster. 1 7200 900 1209600 86400" }] },
+                { "Name": "example.com.", "Type": "A", "AliasTarget": { "HostedZoneId": "Z2FDTNDATAQYW2", "DNSName": "d111.cloudfront.net.", "EvaluateTargetHealth": false } },
+                { "Name": "example.com.", "Type": "MX", "TTL": 300, "ResourceRecords": [{ "Value": "10 mx1.example.com." }, { "Value": "20 mx2.example.com." }] },
+                { "Name": "\\052.example.com.", "Type": "TXT", "TTL": 300, "ResourceRecords": [{ "Value": "\"part one \" \"part two\"" }] }
+            ] }"#
+        ).unwrap();
+        let conversion: Conversion<HostRequest> = from_route53("example.com", &record_sets);
+
+        assert_eq!(conversion.records.len(), 4);
+        assert_eq!(conversion.records[0].record_type, "ALIAS");
+        assert_eq!(conversion.records[0].address, "d111.cloudfront.net.");
+        assert_eq!(conversion.records[2].mx_pref.as_deref(), Some("20"));
+        assert_eq!(conversion.records[2].address, "mx2.example.com.");
+        assert_eq!(conversion.records[3].host_name, "*");
+        assert_eq!(conversion.records[3].address, "part one part two");
+
+        // Skipped SOA and mapped alias
+        assert_eq!(conversion.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_to_route53() {
+        let hosts: Vec<Host> = vec![
+            host("@", "A", "203.0.113.10", 300),
+            host("@", "A", "203.0.113.11", 600),
+            host("@", "ALIAS", "d111.cloudfront.net", 300),
+            host("www", "ALIAS", "app.example.net.", 300),
+            host("@", "TXT", "v=spf1 -all", 300)
+        ];
+
+        let conversion: Conversion<ChangeBatch> = to_route53("example.com", &hosts, &AliasMapping::Cname);
+        let batch: &ChangeBatch = &conversion.records[0];
+        assert_eq!(batch.changes.len(), 3);
+        assert_eq!(batch.changes[0].resource_record_set.resource_records.len(), 2);
+        assert_eq!(batch.changes[1].resource_record_set.record_type, "CNAME");
+        assert_eq!(batch.changes[1].resource_record_set.name, "www.example.com.");
+        assert_eq!(batch.changes[2].resource_record_set.resource_records[0].value, "\"v=spf1 -all\"");
+        // Differing TTL, apex ALIAS skipped, www ALIAS as CNAME
+        assert_eq!(conversion.warnings.len(), 3);
+
+        let aliases: AliasMapping = AliasMapping::AliasTarget { hosted_zone_id: "Z2FDTNDATAQYW2".to_string() };
+        let conversion: Conversion<ChangeBatch> = to_route53("example.com", &hosts, &aliases);
+        let json: Value = serde_json::to_value(&conversion.records[0]).unwrap();
+        assert_eq!(json["Changes"][1]["Action"], "UPSERT");
+        assert_eq!(json["Changes"][1]["ResourceRecordSet"]["AliasTarget"]["DNSName"], "d111.cloudfront.net.");
+
+        // The batch reads back into the same TXT value
+        let record_sets: Vec<ResourceRecordSet> = parse_route53_json(&json.to_string()).unwrap();
+        let round_trip: Conversion<HostRequest> = from_route53("example.com", &record_sets);
+        assert!(round_trip.records.iter().any(|record| record.address == "v=spf1 -all"));
+    }
+}
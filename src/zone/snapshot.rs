@@ -0,0 +1,223 @@
+//! ### Zone Snapshots and Compare-and-Set
+//!
+//! `domains.dns.setHosts` has no concurrency control: a zone read by one tool and written back
+//! later silently overwrites changes made in between by anyone else (the dashboard, another
+//! process). This module adds an optimistic check: read a `ZoneSnapshot`, keep its `hash`,
+//! and submit with `set_hosts_if_unchanged`, which re-fetches the zone just before applying
+//! and fails with a `ZoneConflict` if it changed.
+//!
+//! The same hash is exposed alone by `zone_fingerprint`, so external systems can detect zone
+//! changes by comparing fingerprints instead of storing whole record sets.
+
+use serde::{ Serialize, Deserialize };
+use std::error::Error;
+use std::fmt;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::correlation::{ current_correlation_id, in_operation };
+use crate::utils::hash::fnv1a_hex;
+use crate::zone::diff::ZonePlan;
+use crate::zone::{ split_domain, RecordKey };
+
+/// Returns a hash of the records of a zone, independent of record order.
+///
+/// Two zones hash equally when they hold the same records (by [`RecordKey`]) with the same
+/// TTLs and MX preferences.
+pub fn snapshot_hash(hosts: &[Host]) -> String {
+    let mut lines: Vec<String> = hosts
+        .iter()
+        .map(|host| format!("{}|{}|{}", RecordKey::from(host), host.ttl, host.mx_pref))
+        .collect();
+    lines.sort();
+    fnv1a_hex(&lines.join("\n"))
+}
+
+/// The records of a zone at one point in time.
+///
+/// #### Fields
+/// - `domain`: The domain the records belong to.
+/// - `hosts`: The records.
+/// - `hash`: The [`snapshot_hash`] of the records.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ZoneSnapshot {
+    pub domain: String,
+    pub hosts: Vec<Host>,
+    pub hash: String,
+}
+
+impl ZoneSnapshot {
+    /// Creates a snapshot from the records of a zone.
+    pub fn new(domain: &str, hosts: Vec<Host>) -> Self {
+        ZoneSnapshot {
+            domain: domain.to_string(),
+            hash: snapshot_hash(&hosts),
+            hosts,
+        }
+    }
+}
+
+/// The zone changed between the caller's read and the write.
+///
+/// #### Fields
+/// - `domain`: The domain whose zone changed.
+/// - `expected`: The snapshot hash the caller read.
+/// - `actual`: The snapshot hash of the live zone.
+/// - `correlation_id`: The operation the conflict occurred in (optional).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ZoneConflict {
+    pub domain: String,
+    pub expected: String,
+    pub actual: String,
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+}
+
+impl fmt::Display for ZoneConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Zone of {} changed since it was read (expected {}, found {})",
+            self.domain,
+            self.expected,
+            self.actual
+        )?;
+        if let Some(id) = &self.correlation_id {
+            write!(f, " (correlation id {})", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ZoneConflict {}
+
+impl NameCheapClient {
+    /// Reads the records of a zone as a `ZoneSnapshot`.
+    pub async fn zone_snapshot(&self, domain: &str) -> Result<ZoneSnapshot, Box<dyn Error>> {
+        let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+        let hosts: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+        Ok(ZoneSnapshot::new(domain, hosts))
+    }
+
+    /// Returns the fingerprint of a zone: the [`snapshot_hash`] of its live records.
+    ///
+    /// The fingerprint only changes when a record, TTL or MX preference does, never with the
+    /// order the API returns records in. It equals the `hash` of a [`ZoneSnapshot`] of the same
+    /// zone, so it can be passed to [`set_hosts_if_unchanged`](Self::set_hosts_if_unchanged).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let fingerprint: String = client.zone_fingerprint("example", "com").await?;
+    /// if fingerprint != last_seen {
+    ///     println!("example.com changed");
+    /// }
+    /// ```
+    pub async fn zone_fingerprint(&self, sld: &str, tld: &str) -> Result<String, Box<dyn Error>> {
+        let hosts: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+        Ok(snapshot_hash(&hosts))
+    }
+
+    /// Replaces the records of a zone with `hosts`, only if the zone still matches `expected_hash`.
+    ///
+    /// The zone is re-fetched just before applying. If its hash differs from `expected_hash`
+    /// nothing is submitted and a [`ZoneConflict`] is returned, which callers can detect with
+    /// `error.downcast_ref::<ZoneConflict>()` to re-read and retry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let snapshot = client.zone_snapshot("example.com").await?;
+    /// let mut hosts: Vec<HostRequest> = snapshot.hosts.iter().map(|host| host.to_host_request()).collect();
+    /// hosts.retain(|host| host.host_name != "old");
+    /// client.set_hosts_if_unchanged("example.com", &snapshot.hash, &hosts).await?;
+    /// ```
+    pub async fn set_hosts_if_unchanged(
+        &self,
+        domain: &str,
+        expected_hash: &str,
+        hosts: &[HostRequest]
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        in_operation("zone.set_hosts_if_unchanged", async {
+            let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+            let _lock = self.lock_domain(domain).await;
+
+            let live: ZoneSnapshot = self.zone_snapshot(domain).await?;
+            if live.hash != expected_hash {
+                let conflict: Box<dyn Error> = Box::new(ZoneConflict {
+                    domain: domain.to_string(),
+                    expected: expected_hash.to_string(),
+                    actual: live.hash,
+                    correlation_id: current_correlation_id(),
+                });
+                return Err(conflict);
+            }
+
+            let plan: ZonePlan = ZonePlan::new(domain, &live.hosts, hosts);
+            if plan.has_changes() {
+                self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+            }
+            Ok(plan)
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::simulation::{ Latency, SimulatedTransport, SimulationConfig };
+
+    fn host(name: &str, address: &str, ttl: i64) -> Host {
+        Host {
+            name: name.to_string(),
+            type_: "A".to_string(),
+            address: address.to_string(),
+            ttl,
+            ..Host::new()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_hash() {
+        let a: Vec<Host> = vec![host("@", "203.0.113.10", 1800), host("www", "203.0.113.10", 1800)];
+        let reordered: Vec<Host> = vec![host("www", "203.0.113.10", 1800), host("@", "203.0.113.10", 1800)];
+        let changed_ttl: Vec<Host> = vec![host("@", "203.0.113.10", 300), host("www", "203.0.113.10", 1800)];
+
+        assert_eq!(snapshot_hash(&a), snapshot_hash(&reordered));
+        assert_ne!(snapshot_hash(&a), snapshot_hash(&changed_ttl));
+        assert_eq!(ZoneSnapshot::new("example.com", a.clone()).hash, snapshot_hash(&a));
+    }
+
+    #[tokio::test]
+    async fn test_zone_fingerprint() {
+        let config: SimulationConfig = SimulationConfig {
+            latency: Latency::Fixed(Duration::ZERO),
+            propagation_delay: Duration::ZERO,
+            ..SimulationConfig::default()
+        };
+        let record: HostRequest = HostRequest::new("@".to_string(), "A".to_string(), "203.0.113.10".to_string(), None, None, Some("1800".to_string()), None, None);
+        let simulation: Arc<SimulatedTransport> = Arc::new(SimulatedTransport::new(config).with_zone("example.com", std::slice::from_ref(&record)));
+        let client: NameCheapClient = NameCheapClient::new(
+            "user".to_string(),
+            "key".to_string(),
+            "127.0.0.1".to_string(),
+            "user".to_string(),
+            false
+        ).with_transport(simulation);
+
+        let before: String = client.zone_fingerprint("example", "com").await.unwrap();
+        assert_eq!(before, client.zone_snapshot("example.com").await.unwrap().hash);
+        assert_eq!(before, client.zone_fingerprint("example", "com").await.unwrap());
+
+        let www: HostRequest = HostRequest::new("www".to_string(), "CNAME".to_string(), "example.com.".to_string(), None, None, None, None, None);
+        client.domains_dns_replace_hosts("example", "com", &[record, www]).await.unwrap();
+        assert_ne!(before, client.zone_fingerprint("example", "com").await.unwrap());
+    }
+}
@@ -0,0 +1,588 @@
+//! ### Zone Sync Files
+//!
+//! A desired-state file describes the records a zone should hold, for declarative management
+//! (`namecheap dns diff`, drift watching). [`adopt`] bootstraps one from a live zone, so an
+//! existing domain can be brought under management without retyping its records:
+//!
+//! ```toml
+//! # Desired state of example.com
+//! domain = "example.com"
+//!
+//! # A: IPv4 address
+//! [[records]]
+//! host = "@"
+//! type = "A"
+//! address = "203.0.113.10"
+//! ttl = 1800
+//! ```
+//!
+//! The file is canonical: records are normalized like a [`RecordKey`] and sorted by type,
+//! host and address, each type introduced by a comment, so adopting an unchanged zone twice
+//! gives the same bytes and a diff of the file shows only real changes.
+//!
+//! #### Fragments
+//!
+//! Records shared by many domains (a `google-workspace` mail setup, CAA records) live in
+//! fragment files, `fragments/<name>.toml` next to the domain files, holding `records` and
+//! optionally an `include` of other fragments. A domain file includes them and adjusts them:
+//!
+//! ```toml
+//! domain = "example.com"
+//! include = ["google-workspace"]
+//!
+//! # Drop a fragment record
+//! [[exclude]]
+//! host = "@"
+//! type = "TXT"
+//! address = "v=spf1 include:_spf.google.com ~all"
+//!
+//! # A record with the same host, type and address as a fragment record overrides it
+//! [[records]]
+//! host = "@"
+//! type = "MX"
+//! address = "aspmx.l.google.com"
+//! ttl = 300
+//! mx_pref = 1
+//! ```
+//!
+//! [`DesiredState::resolve`] expands the fragments before diffing; the resolved state has no
+//! includes left.
+
+use serde::{ Serialize, Deserialize };
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::utils::progress::{ ProgressHandle, ProgressTracker };
+use crate::zone::{ split_domain, RecordKey };
+
+/// ### Desired Record
+///
+/// #### Fields
+/// - `host`: The host name, `@` for the apex.
+/// - `record_type`: The record type, e.g. `A` or `MX`.
+/// - `address`: The value of the record.
+/// - `ttl`: The TTL in seconds (optional, the client default when not set).
+/// - `mx_pref`: The MX preference (optional, MX records only).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DesiredRecord {
+    pub host: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mx_pref: Option<u16>,
+}
+
+impl DesiredRecord {
+    /// Creates the normalized record of a live host.
+    pub fn from_host(host: &Host) -> Self {
+        let key: RecordKey = RecordKey::from(host);
+        let is_mx: bool = key.record_type == "MX";
+        DesiredRecord {
+            host: key.name,
+            record_type: key.record_type,
+            address: key.address,
+            ttl: Some(host.ttl),
+            mx_pref: if is_mx { host.mx_pref.trim().parse().ok() } else { None },
+        }
+    }
+
+    /// Returns the record as a `setHosts` request.
+    pub fn to_host_request(&self) -> HostRequest {
+        HostRequest::new(
+            self.host.clone(),
+            self.record_type.clone(),
+            self.address.clone(),
+            self.mx_pref.map(|pref| pref.to_string()),
+            None,
+            self.ttl.map(|ttl| ttl.to_string()),
+            None,
+            None
+        )
+    }
+
+    /// Returns the record normalized like a [`RecordKey`].
+    pub fn normalized(&self) -> Self {
+        let key: RecordKey = self.key();
+        DesiredRecord { host: key.name, record_type: key.record_type, address: key.address, ..self.clone() }
+    }
+
+    /// Returns the identity of the record.
+    pub fn key(&self) -> RecordKey {
+        RecordKey::new(&self.host, &self.record_type, &self.address)
+    }
+
+    fn sort_key(&self) -> (&str, &str, &str) {
+        (&self.record_type, &self.host, &self.address)
+    }
+}
+
+/// ### Excluded Record
+///
+/// Leaves matching records of the included fragments out of a domain.
+///
+/// #### Fields
+/// - `host`: The host name.
+/// - `record_type`: The record type.
+/// - `address`: The value (optional, every value of the host and type when not set).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ExcludedRecord {
+    pub host: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
+
+impl ExcludedRecord {
+    /// Returns `true` if `record` is excluded.
+    pub fn matches(&self, record: &DesiredRecord) -> bool {
+        let excluded: RecordKey = RecordKey::new(&self.host, &self.record_type, self.address.as_deref().unwrap_or(""));
+        let key: RecordKey = record.key();
+        excluded.name == key.name &&
+            excluded.record_type == key.record_type &&
+            (self.address.is_none() || excluded.address == key.address)
+    }
+}
+
+/// ### Fragment
+///
+/// Records shared by several domains.
+///
+/// #### Fields
+/// - `description`: What the fragment sets up (optional).
+/// - `include`: Other fragments included first.
+/// - `records`: The records of the fragment.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Fragment {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub records: Vec<DesiredRecord>,
+}
+
+/// An error raised while resolving the fragments of a desired state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum FragmentError {
+    /// An included fragment does not exist.
+    UnknownFragment { name: String },
+    /// Fragments include each other in a cycle.
+    Cycle { chain: Vec<String> },
+}
+
+impl fmt::Display for FragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FragmentError::UnknownFragment { name } => write!(f, "Unknown fragment: {}", name),
+            FragmentError::Cycle { chain } => write!(f, "Fragments include each other: {}", chain.join(" -> ")),
+        }
+    }
+}
+
+impl Error for FragmentError {}
+
+/// The fragments available to desired states, by name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Fragments(BTreeMap<String, Fragment>);
+
+impl Fragments {
+    /// Creates an empty set of fragments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a fragment, replacing any fragment of the same name.
+    pub fn insert(&mut self, name: &str, fragment: Fragment) {
+        self.0.insert(name.to_string(), fragment);
+    }
+
+    /// Returns the fragment named `name`.
+    pub fn get(&self, name: &str) -> Option<&Fragment> {
+        self.0.get(name)
+    }
+
+    /// Reads every `<name>.toml` of `directory`; a missing directory has no fragments.
+    pub fn load_dir(directory: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut fragments: Fragments = Fragments::new();
+        if !directory.is_dir() {
+            return Ok(fragments);
+        }
+        for entry in fs::read_dir(directory)? {
+            let path: PathBuf = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "toml") {
+                continue;
+            }
+            let name: String = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let fragment: Fragment = toml::from_str(&fs::read_to_string(&path)?)
+                .map_err(|err| format!("Invalid fragment {}: {}", path.display(), err))?;
+            fragments.insert(&name, fragment);
+        }
+        Ok(fragments)
+    }
+
+    /// Appends the records of `name` and the fragments it includes, included ones first.
+    fn expand(&self, name: &str, chain: &mut Vec<String>, records: &mut Vec<DesiredRecord>) -> Result<(), FragmentError> {
+        if chain.iter().any(|seen| seen == name) {
+            let mut cycle: Vec<String> = chain.clone();
+            cycle.push(name.to_string());
+            return Err(FragmentError::Cycle { chain: cycle });
+        }
+        let fragment: &Fragment = self.get(name).ok_or_else(|| FragmentError::UnknownFragment { name: name.to_string() })?;
+
+        chain.push(name.to_string());
+        for included in &fragment.include {
+            self.expand(included, chain, records)?;
+        }
+        chain.pop();
+        records.extend(fragment.records.iter().map(DesiredRecord::normalized));
+        Ok(())
+    }
+}
+
+/// ### Desired State
+///
+/// #### Fields
+/// - `domain`: The domain of the zone.
+/// - `include`: The fragments whose records the zone also holds.
+/// - `exclude`: Fragment records left out of the zone.
+/// - `records`: The records of the zone, overriding fragment records with the same key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct DesiredState {
+    pub domain: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<ExcludedRecord>,
+    #[serde(default)]
+    pub records: Vec<DesiredRecord>,
+}
+
+impl DesiredState {
+    /// Creates the canonical desired state of a live zone.
+    pub fn from_hosts(domain: &str, hosts: &[Host]) -> Self {
+        let mut records: Vec<DesiredRecord> = hosts.iter().map(DesiredRecord::from_host).collect();
+        records.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+        records.dedup();
+        DesiredState {
+            domain: domain.trim_end_matches('.').to_lowercase(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            records,
+        }
+    }
+
+    /// Expands the included fragments into a state without includes.
+    ///
+    /// Fragment records come first, in include order, minus the excluded ones; a record of the
+    /// domain replaces a fragment record with the same key (host, type and address), e.g. to
+    /// change its TTL. The records of the result are canonical, as written by `to_toml`.
+    pub fn resolve(&self, fragments: &Fragments) -> Result<DesiredState, FragmentError> {
+        let mut records: Vec<DesiredRecord> = Vec::new();
+        for name in &self.include {
+            fragments.expand(name, &mut Vec::new(), &mut records)?;
+        }
+        records.retain(|record| !self.exclude.iter().any(|excluded| excluded.matches(record)));
+
+        // Later records win: the domain over fragments, later fragments over earlier ones
+        records.extend(self.records.iter().map(DesiredRecord::normalized));
+        let mut by_key: BTreeMap<RecordKey, DesiredRecord> = BTreeMap::new();
+        for record in records {
+            by_key.insert(record.key(), record);
+        }
+        let mut records: Vec<DesiredRecord> = by_key.into_values().collect();
+        records.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        Ok(DesiredState { domain: self.domain.clone(), include: Vec::new(), exclude: Vec::new(), records })
+    }
+
+    /// Reads a desired-state file and resolves it against the `fragments` directory next to it.
+    pub fn load_resolved(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let state: DesiredState = DesiredState::load(path)?;
+        if state.include.is_empty() {
+            return Ok(state.resolve(&Fragments::new())?);
+        }
+        let directory: PathBuf = path.parent().unwrap_or(Path::new(".")).join("fragments");
+        Ok(state.resolve(&Fragments::load_dir(&directory)?)?)
+    }
+
+    /// Parses a desired-state file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|err| err.to_string())
+    }
+
+    /// Reads a desired-state file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text: String = fs::read_to_string(path)?;
+        DesiredState::parse(&text).map_err(|err| format!("Invalid desired state {}: {}", path.display(), err).into())
+    }
+
+    /// Returns the records as `setHosts` requests.
+    pub fn to_host_requests(&self) -> Vec<HostRequest> {
+        self.records.iter().map(DesiredRecord::to_host_request).collect()
+    }
+
+    /// Writes the canonical TOML form, with a comment introducing each record type.
+    pub fn to_toml(&self) -> String {
+        // JSON string literals are valid TOML basic strings
+        let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+
+        let mut output: String = format!("# Desired state of {}\ndomain = {}\n", self.domain, quote(&self.domain));
+        if !self.include.is_empty() {
+            let names: Vec<String> = self.include.iter().map(|name| quote(name)).collect();
+            output.push_str(&format!("include = [{}]\n", names.join(", ")));
+        }
+        for excluded in &self.exclude {
+            output.push_str("\n[[exclude]]\n");
+            output.push_str(&format!("host = {}\n", quote(&excluded.host)));
+            output.push_str(&format!("type = {}\n", quote(&excluded.record_type)));
+            if let Some(address) = &excluded.address {
+                output.push_str(&format!("address = {}\n", quote(address)));
+            }
+        }
+        let mut previous_type: Option<&str> = None;
+        for record in &self.records {
+            if previous_type != Some(record.record_type.as_str()) {
+                output.push_str(&format!("\n# {}: {}\n", record.record_type, describe_type(&record.record_type)));
+                previous_type = Some(&record.record_type);
+            } else {
+                output.push('\n');
+            }
+            output.push_str("[[records]]\n");
+            output.push_str(&format!("host = {}\n", quote(&record.host)));
+            output.push_str(&format!("type = {}\n", quote(&record.record_type)));
+            output.push_str(&format!("address = {}\n", quote(&record.address)));
+            if let Some(ttl) = record.ttl {
+                output.push_str(&format!("ttl = {}\n", ttl));
+            }
+            if let Some(mx_pref) = record.mx_pref {
+                output.push_str(&format!("mx_pref = {}\n", mx_pref));
+            }
+        }
+        output
+    }
+}
+
+/// Returns a short description of a record type, for the comments of a desired-state file.
+fn describe_type(record_type: &str) -> &'static str {
+    match record_type {
+        "A" => "IPv4 address",
+        "AAAA" => "IPv6 address",
+        "ALIAS" => "apex alias to a hostname",
+        "CAA" => "certificate authority authorization",
+        "CNAME" => "alias to a canonical name",
+        "MX" => "mail exchanger",
+        "MXE" => "mail forwarding to an IPv4 address",
+        "NS" => "delegated name server",
+        "TXT" => "text, e.g. SPF or verification tokens",
+        "URL" | "URL301" | "FRAME" => "URL redirect",
+        _ => "other record type",
+    }
+}
+
+/// Fetches the live zone of `domain` and writes it as a desired-state file at `path`.
+///
+/// # Example
+///
+/// ```rust
+/// let state = zone::sync::adopt(&client, "example.com", Path::new("zones/example.com.toml")).await?;
+/// println!("Adopted {} records", state.records.len());
+/// ```
+pub async fn adopt(client: &NameCheapClient, domain: &str, path: &Path) -> Result<DesiredState, Box<dyn Error>> {
+    let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+    let hosts: Vec<Host> = client.domains_dns_get_host_records(sld, tld).await?;
+    let state: DesiredState = DesiredState::from_hosts(domain, &hosts);
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, state.to_toml())?;
+    Ok(state)
+}
+
+/// Returns the desired-state file of a domain in `directory`, `<domain>.toml`.
+pub fn desired_state_path(directory: &Path, domain: &str) -> PathBuf {
+    directory.join(format!("{}.toml", domain.trim_end_matches('.').to_lowercase()))
+}
+
+/// Reads and resolves every `<domain>.toml` of `directory`, ordered by domain.
+///
+/// Fragments are read from `fragments/` inside the directory.
+pub fn load_zone_dir(directory: &Path) -> Result<Vec<DesiredState>, Box<dyn Error>> {
+    let fragments: Fragments = Fragments::load_dir(&directory.join("fragments"))?;
+    let mut states: Vec<DesiredState> = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path: PathBuf = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|extension| extension == "toml") {
+            states.push(DesiredState::load(&path)?.resolve(&fragments)?);
+        }
+    }
+    states.sort_by(|a, b| a.domain.cmp(&b.domain));
+    Ok(states)
+}
+
+/// The outcome of adopting one domain: the file written, or the error.
+pub type AdoptOutcome = (String, Result<PathBuf, Box<dyn Error>>);
+
+/// Adopts several zones into `directory`, one `<domain>.toml` per domain, reporting progress.
+///
+/// A failing domain does not stop the others; the outcome of each domain is returned in order.
+pub async fn adopt_all(
+    client: &NameCheapClient,
+    domains: &[String],
+    directory: &Path,
+    progress: &ProgressHandle
+) -> Vec<AdoptOutcome> {
+    let tracker: ProgressTracker = progress.tracker("zone.adopt", Some(domains.len()));
+    let mut outcomes: Vec<AdoptOutcome> = Vec::with_capacity(domains.len());
+    for domain in domains {
+        let path: PathBuf = desired_state_path(directory, domain);
+        let outcome: Result<PathBuf, Box<dyn Error>> = adopt(client, domain, &path).await.map(|_| path);
+        tracker.advance(domain);
+        outcomes.push((domain.clone(), outcome));
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, type_: &str, address: &str, mx_pref: &str) -> Host {
+        serde_json::from_value(serde_json::json!({ "name": name, "type": type_, "address": address, "mxpref": mx_pref })).unwrap()
+    }
+
+    #[test]
+    fn test_canonical_file() {
+        let hosts: Vec<Host> = vec![
+            host("WWW", "cname", "Example.COM.", "10"),
+            host("@", "MX", "mx2.example.net.", "20"),
+            host("@", "A", "203.0.113.10", ""),
+            host("@", "TXT", "v=spf1 include:\"_spf\" ~all", ""),
+            host("@", "MX", "mx1.example.net.", "10"),
+        ];
+        let state: DesiredState = DesiredState::from_hosts("Example.com", &hosts);
+        let text: String = state.to_toml();
+
+        let types: Vec<&str> = state.records.iter().map(|record| record.record_type.as_str()).collect();
+        assert_eq!(types, ["A", "CNAME", "MX", "MX", "TXT"]);
+        assert_eq!(state.records[1].address, "example.com");
+        assert_eq!(state.records[1].mx_pref, None);
+        assert_eq!(state.records[2].mx_pref, Some(10));
+        assert!(text.starts_with("# Desired state of example.com\ndomain = \"example.com\"\n\n# A: IPv4 address\n[[records]]\n"));
+        assert_eq!(text.matches("# MX: mail exchanger").count(), 1);
+
+        // The order of the live records does not matter
+        let mut reversed: Vec<Host> = hosts.clone();
+        reversed.reverse();
+        assert_eq!(DesiredState::from_hosts("example.com", &reversed).to_toml(), text);
+
+        let parsed: DesiredState = DesiredState::parse(&text).unwrap();
+        assert_eq!(parsed, state);
+        assert_eq!(parsed.to_host_requests()[4].address, "v=spf1 include:\"_spf\" ~all");
+        assert!(DesiredState::parse("domain = \"example.com\"\nrecords = []\nextra = 1\n").is_err());
+    }
+
+    fn record(host: &str, record_type: &str, address: &str, ttl: Option<i64>) -> DesiredRecord {
+        DesiredRecord { host: host.to_string(), record_type: record_type.to_string(), address: address.to_string(), ttl, mx_pref: None }
+    }
+
+    fn workspace() -> Fragments {
+        let mut fragments: Fragments = Fragments::new();
+        fragments.insert("caa", Fragment { records: vec![record("@", "CAA", "0 issue \"letsencrypt.org\"", None)], ..Default::default() });
+        fragments.insert("google-workspace", Fragment {
+            description: Some("Google Workspace mail".to_string()),
+            include: vec!["caa".to_string()],
+            records: vec![
+                record("@", "MX", "ASPMX.L.GOOGLE.COM.", Some(3600)),
+                record("@", "TXT", "v=spf1 include:_spf.google.com ~all", None),
+            ],
+        });
+        fragments
+    }
+
+    #[test]
+    fn test_resolve_fragments() {
+        let text: &str = r#"
+            domain = "example.com"
+            include = ["google-workspace"]
+
+            [[exclude]]
+            host = "@"
+            type = "TXT"
+
+            [[records]]
+            host = "@"
+            type = "MX"
+            address = "aspmx.l.google.com"
+            ttl = 300
+
+            [[records]]
+            host = "www"
+            type = "CNAME"
+            address = "example.github.io"
+        "#;
+        let state: DesiredState = DesiredState::parse(text).unwrap();
+        assert_eq!(DesiredState::parse(&state.to_toml()).unwrap(), state);
+
+        let resolved: DesiredState = state.resolve(&workspace()).unwrap();
+        let records: Vec<(&str, &str, Option<i64>)> = resolved.records
+            .iter()
+            .map(|record| (record.record_type.as_str(), record.address.as_str(), record.ttl))
+            .collect();
+        assert_eq!(records, [
+            ("CAA", "0 issue \"letsencrypt.org\"", None),
+            ("CNAME", "example.github.io", None),
+            ("MX", "aspmx.l.google.com", Some(300)),
+        ]);
+        assert!(resolved.include.is_empty());
+    }
+
+    #[test]
+    fn test_fragment_errors() {
+        let mut fragments: Fragments = workspace();
+        let state: DesiredState = DesiredState::parse("domain = \"example.com\"\ninclude = [\"office\"]\n").unwrap();
+        assert_eq!(state.resolve(&fragments), Err(FragmentError::UnknownFragment { name: "office".to_string() }));
+
+        fragments.insert("caa", Fragment { include: vec!["google-workspace".to_string()], ..Default::default() });
+        let state: DesiredState = DesiredState::parse("domain = \"example.com\"\ninclude = [\"google-workspace\"]\n").unwrap();
+        let error: FragmentError = state.resolve(&fragments).unwrap_err();
+        assert_eq!(error.to_string(), "Fragments include each other: google-workspace -> caa -> google-workspace");
+    }
+
+    #[test]
+    fn test_load_zone_dir() {
+        let directory: PathBuf = std::env::temp_dir().join(format!("namecheap-zones-{}", std::process::id()));
+        fs::create_dir_all(directory.join("fragments")).unwrap();
+        fs::write(directory.join("fragments/caa.toml"), "[[records]]\nhost = \"@\"\ntype = \"CAA\"\naddress = \"0 issue \\\"letsencrypt.org\\\"\"\n").unwrap();
+        fs::write(directory.join("example.net.toml"), "domain = \"example.net\"\ninclude = [\"caa\"]\n").unwrap();
+        fs::write(directory.join("example.com.toml"), "domain = \"example.com\"\ninclude = [\"caa\"]\n").unwrap();
+
+        let states: Vec<DesiredState> = load_zone_dir(&directory).unwrap();
+        assert_eq!(states.iter().map(|state| state.domain.as_str()).collect::<Vec<_>>(), ["example.com", "example.net"]);
+        assert_eq!(states[1].records.len(), 1);
+        assert_eq!(DesiredState::load_resolved(&directory.join("example.com.toml")).unwrap(), states[0]);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
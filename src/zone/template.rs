@@ -0,0 +1,256 @@
+//! ### Zone Templates
+//!
+//! This module provides `ZoneTemplate`, a reusable set of host records containing
+//! `{{variables}}`, so standing up a new domain (web, mail and verification records) is a
+//! single call.
+//!
+//! The variable `{{domain}}` is always available and set to the domain the template is
+//! applied to.
+//!
+//! Templates are plain JSON, e.g.
+//!
+//! ```json
+//! {
+//!   "name": "web-and-mail",
+//!   "records": [
+//!     { "host_name": "@", "record_type": "A", "address": "{{web_ip}}", "ttl": "1800" },
+//!     { "host_name": "www", "record_type": "CNAME", "address": "{{domain}}." },
+//!     { "host_name": "@", "record_type": "MX", "address": "mail.{{domain}}.", "mx_pref": "10" }
+//!   ]
+//! }
+//! ```
+
+use serde::{ Serialize, Deserialize };
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+// crate imports
+use crate::{ NameCheapClient, Host };
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::diff::ZonePlan;
+use crate::zone::split_domain;
+use crate::utils::correlation::in_operation;
+use crate::limits::validate_ttl;
+
+pub use crate::limits::RECORD_TYPES;
+
+/// An error raised while rendering a `ZoneTemplate`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(PartialEq, Eq, Hash)]
+pub enum TemplateError {
+    /// A record references a variable that was not provided.
+    UnknownVariable { record: usize, variable: String },
+    /// A rendered record is not a valid host record.
+    InvalidRecord { record: usize, reason: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownVariable { record, variable } =>
+                write!(f, "Record {}: unknown variable {{{{{}}}}}", record + 1, variable),
+            TemplateError::InvalidRecord { record, reason } =>
+                write!(f, "Record {}: {}", record + 1, reason),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+/// A set of host records with `{{variables}}`.
+///
+/// #### Fields
+/// - `name`: The name of the template.
+/// - `records`: The records, any string field may contain `{{variables}}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct ZoneTemplate {
+    pub name: String,
+    pub records: Vec<HostRequest>,
+}
+
+impl ZoneTemplate {
+    /// Creates a template from a list of records.
+    pub fn new(name: &str, records: Vec<HostRequest>) -> Self {
+        ZoneTemplate { name: name.to_string(), records }
+    }
+
+    /// Substitutes the variables and validates the resulting records.
+    ///
+    /// `{{domain}}` is set to `domain` unless `vars` overrides it.
+    pub fn render(
+        &self,
+        domain: &str,
+        vars: &HashMap<String, String>
+    ) -> Result<Vec<HostRequest>, TemplateError> {
+        let mut vars: HashMap<String, String> = vars.clone();
+        vars.entry("domain".to_string()).or_insert_with(|| domain.to_string());
+
+        self.records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let substitute = |text: &str| substitute(text, &vars, index);
+                let substitute_opt = |text: &Option<String>| {
+                    text.as_deref().map(substitute).transpose()
+                };
+
+                let rendered: HostRequest = HostRequest {
+                    host_name: substitute(&record.host_name)?,
+                    record_type: substitute(&record.record_type)?.to_uppercase(),
+                    address: substitute(&record.address)?,
+                    mx_pref: substitute_opt(&record.mx_pref)?,
+                    email_type: substitute_opt(&record.email_type)?,
+                    ttl: substitute_opt(&record.ttl)?,
+                    flag: substitute_opt(&record.flag)?,
+                    tag: substitute_opt(&record.tag)?,
+                };
+                validate_record(&rendered).map_err(|reason| TemplateError::InvalidRecord {
+                    record: index,
+                    reason,
+                })?;
+                Ok(rendered)
+            })
+            .collect()
+    }
+}
+
+/// Replaces each `{{name}}` in `text` with its value from `vars`.
+fn substitute(text: &str, vars: &HashMap<String, String>, record: usize) -> Result<String, TemplateError> {
+    let mut output: String = String::with_capacity(text.len());
+    let mut rest: &str = text;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after: &str = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            return Err(TemplateError::InvalidRecord {
+                record,
+                reason: format!("unterminated variable in '{}'", text),
+            });
+        };
+
+        let variable: &str = after[..end].trim();
+        let value: &String = vars.get(variable).ok_or_else(|| TemplateError::UnknownVariable {
+            record,
+            variable: variable.to_string(),
+        })?;
+        output.push_str(value);
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Checks that a host record can be submitted with `domains.dns.setHosts`.
+pub fn validate_record(record: &HostRequest) -> Result<(), String> {
+    if record.host_name.trim().is_empty() {
+        return Err("host name is empty".to_string());
+    }
+    if !RECORD_TYPES.contains(&record.record_type.as_str()) {
+        return Err(format!("unsupported record type {}", record.record_type));
+    }
+    if record.address.trim().is_empty() {
+        return Err("address is empty".to_string());
+    }
+    if record.record_type == "MX" && record.mx_pref.is_none() {
+        return Err("MX record without mx_pref".to_string());
+    }
+    if let Some(ttl) = record.ttl.as_deref() {
+        let ttl: i64 = ttl.trim().parse().map_err(|_| format!("TTL {} is not a number", ttl))?;
+        validate_ttl(ttl)?;
+    }
+    Ok(())
+}
+
+impl NameCheapClient {
+    /// Renders a template for a domain and plans it against the live zone, without applying.
+    pub async fn plan_template(
+        &self,
+        domain: &str,
+        template: &ZoneTemplate,
+        vars: &HashMap<String, String>
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        let desired: Vec<HostRequest> = template.render(domain, vars)?;
+        let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+        let current: Vec<Host> = self.domains_dns_get_host_records(sld, tld).await?;
+
+        Ok(ZonePlan::new(domain, &current, &desired))
+    }
+
+    /// Renders a template for a domain and replaces the zone with the rendered records.
+    ///
+    /// Rendering errors are returned before anything is submitted. The returned plan shows
+    /// what changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let template: ZoneTemplate = serde_json::from_str(&std::fs::read_to_string("web.json")?)?;
+    /// let vars = HashMap::from([("web_ip".to_string(), "203.0.113.10".to_string())]);
+    /// let plan = client.apply_template("example.com", &template, &vars).await?;
+    /// println!("{}", plan);
+    /// ```
+    pub async fn apply_template(
+        &self,
+        domain: &str,
+        template: &ZoneTemplate,
+        vars: &HashMap<String, String>
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        in_operation("zone.apply_template", async {
+            let _lock = self.lock_domain(domain).await;
+            let plan: ZonePlan = self.plan_template(domain, template, vars).await?;
+            if plan.has_changes() {
+                let (sld, tld) = split_domain(domain).ok_or_else(|| format!("Invalid domain: {}", domain))?;
+                self.domains_dns_replace_hosts(sld, tld, &plan.desired).await?;
+            }
+            Ok(plan)
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template() -> ZoneTemplate {
+        serde_json
+            ::from_value(
+                json!({
+                "name": "web-and-mail",
+                "records": [
+                    { "host_name": "@", "record_type": "A", "address": "{{ web_ip }}", "ttl": "1800" },
+                    { "host_name": "www", "record_type": "cname", "address": "{{domain}}." },
+                    { "host_name": "@", "record_type": "MX", "address": "mail.{{domain}}.", "mx_pref": "10" }
+                ]
+            })
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_template() {
+        let vars = HashMap::from([("web_ip".to_string(), "203.0.113.10".to_string())]);
+        let records: Vec<HostRequest> = template().render("example.com", &vars).unwrap();
+
+        assert_eq!(records[0].address, "203.0.113.10");
+        assert_eq!(records[1].record_type, "CNAME");
+        assert_eq!(records[1].address, "example.com.");
+        assert_eq!(records[2].address, "mail.example.com.");
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let error: TemplateError = template().render("example.com", &HashMap::new()).unwrap_err();
+        assert_eq!(error, TemplateError::UnknownVariable { record: 0, variable: "web_ip".to_string() });
+
+        let mut invalid: ZoneTemplate = template();
+        invalid.records[2].mx_pref = None;
+        let vars = HashMap::from([("web_ip".to_string(), "203.0.113.10".to_string())]);
+        assert!(matches!(invalid.render("example.com", &vars), Err(TemplateError::InvalidRecord { record: 2, .. })));
+    }
+}
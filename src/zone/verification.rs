@@ -0,0 +1,157 @@
+//! ### Domain Verification Records
+//!
+//! This module builds the exact records that common providers ask for to prove domain
+//! ownership, and writes them with [`NameCheapClient::ensure_records`] so running a setup
+//! twice does nothing the second time.
+//!
+//! #### Providers
+//! - Google Search Console: `TXT @ google-site-verification=<token>`
+//! - Microsoft 365: `TXT @ MS=<code>`
+//! - AWS SES DKIM: `CNAME <token>._domainkey -> <token>.dkim.amazonses.com.`
+//! - AWS ACM DNS validation: the `CNAME` name/value pairs shown by ACM.
+//! - GitHub Pages: `TXT _github-pages-challenge-<owner> <code>`
+
+use std::error::Error;
+
+// crate imports
+use crate::NameCheapClient;
+use crate::domains_dns::set_hosts::HostRequest;
+use crate::zone::diff::ZonePlan;
+use crate::zone::relative_name;
+
+fn record(host_name: &str, record_type: &str, address: &str) -> HostRequest {
+    HostRequest::new(
+        host_name.to_string(),
+        record_type.to_string(),
+        address.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None
+    )
+}
+
+/// The Google Search Console verification record.
+pub fn google_site_verification(token: &str) -> HostRequest {
+    let token: &str = token.trim().trim_start_matches("google-site-verification=");
+    record("@", "TXT", &format!("google-site-verification={}", token))
+}
+
+/// The Microsoft 365 verification record, `code` is e.g. `ms12345678`.
+pub fn microsoft365_verification(code: &str) -> HostRequest {
+    let code: &str = code.trim().trim_start_matches("MS=");
+    record("@", "TXT", &format!("MS={}", code))
+}
+
+/// The AWS SES Easy DKIM records, one `CNAME` per token.
+pub fn ses_dkim_records(tokens: &[&str]) -> Vec<HostRequest> {
+    tokens
+        .iter()
+        .map(|token| {
+            let token: &str = token.trim();
+            record(
+                &format!("{}._domainkey", token),
+                "CNAME",
+                &format!("{}.dkim.amazonses.com.", token)
+            )
+        })
+        .collect()
+}
+
+/// The AWS ACM DNS validation records.
+///
+/// `cnames` are the `(name, value)` pairs shown by ACM, e.g.
+/// `("_a79865eb4cd1a6ab990a45779b4e0b96.example.com.", "_424c7224e9b0146f9a8808af955727d0.acm-validations.aws.")`.
+/// Names are made relative to `domain`, names outside the zone are rejected.
+pub fn acm_validation_records(domain: &str, cnames: &[(&str, &str)]) -> Result<Vec<HostRequest>, String> {
+    cnames
+        .iter()
+        .map(|(name, value)| {
+            let host_name: String = relative_name(domain, name).ok_or_else(|| {
+                format!("ACM validation name {} is not inside {}", name, domain)
+            })?;
+            Ok(record(&host_name, "CNAME", value.trim()))
+        })
+        .collect()
+}
+
+/// The GitHub Pages domain verification record for a user or organization.
+pub fn github_pages_verification(owner: &str, code: &str) -> HostRequest {
+    record(&format!("_github-pages-challenge-{}", owner.trim().to_lowercase()), "TXT", code.trim())
+}
+
+impl NameCheapClient {
+    /// Writes the Google Search Console verification record.
+    pub async fn setup_google_site_verification(
+        &self,
+        domain: &str,
+        token: &str
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_record(domain, google_site_verification(token)).await
+    }
+
+    /// Writes the Microsoft 365 verification record.
+    pub async fn setup_microsoft365_verification(
+        &self,
+        domain: &str,
+        code: &str
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_record(domain, microsoft365_verification(code)).await
+    }
+
+    /// Writes the AWS SES Easy DKIM records.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let plan = client.setup_ses_dkim("example.com", &["token1", "token2", "token3"]).await.unwrap();
+    /// println!("{}", plan);
+    /// ```
+    pub async fn setup_ses_dkim(&self, domain: &str, tokens: &[&str]) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_records(domain, &ses_dkim_records(tokens)).await
+    }
+
+    /// Writes the AWS ACM DNS validation records.
+    pub async fn setup_acm_validation(
+        &self,
+        domain: &str,
+        cnames: &[(&str, &str)]
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_records(domain, &acm_validation_records(domain, cnames)?).await
+    }
+
+    /// Writes the GitHub Pages domain verification record.
+    pub async fn setup_github_pages_verification(
+        &self,
+        domain: &str,
+        owner: &str,
+        code: &str
+    ) -> Result<ZonePlan, Box<dyn Error>> {
+        self.ensure_record(domain, github_pages_verification(owner, code)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_records() {
+        assert_eq!(google_site_verification("abc").address, "google-site-verification=abc");
+        assert_eq!(microsoft365_verification("MS=ms123").address, "MS=ms123");
+
+        let dkim: Vec<HostRequest> = ses_dkim_records(&["tok1"]);
+        assert_eq!(dkim[0].host_name, "tok1._domainkey");
+        assert_eq!(dkim[0].address, "tok1.dkim.amazonses.com.");
+
+        let acm: Vec<HostRequest> = acm_validation_records(
+            "example.com",
+            &[("_abc.www.example.com.", "_def.acm-validations.aws.")]
+        ).unwrap();
+        assert_eq!(acm[0].host_name, "_abc.www");
+        assert!(acm_validation_records("example.com", &[("_abc.other.com.", "x")]).is_err());
+
+        assert_eq!(github_pages_verification("Octo", "123").host_name, "_github-pages-challenge-octo");
+    }
+}
@@ -0,0 +1,195 @@
+//! ### Sandbox End-to-End Suite
+//!
+//! Runs real flows against the NameCheap sandbox. Enabled with the `e2e` feature:
+//!
+//! ```sh
+//! NAMECHEAP_USER_NAME=... NAMECHEAP_API_KEY=... NAMECHEAP_CLIENT_IP=... \
+//!     cargo test --features e2e --test sandbox_e2e -- --test-threads=1
+//! ```
+//!
+//! The suite refuses to run against production. Every change is registered with a
+//! `TestResources` guard which undoes it when the test ends, including on panic.
+//! Set `NAMECHEAP_E2E_DOMAIN` to reuse a sandbox domain instead of registering a new one,
+//! otherwise one domain is registered for the whole run and shared by every test. Sandbox
+//! registrations and renewals cannot be undone, the domain is left to expire.
+#![cfg(feature = "e2e")]
+
+use std::error::Error;
+use std::time::{ SystemTime, UNIX_EPOCH };
+use tokio::sync::OnceCell;
+
+use namecheap::{ NameCheapClient, Contact, ContactSet, Host };
+use namecheap::domains_dns::set_hosts::HostRequest;
+use namecheap::zone::split_domain;
+
+/// The sandbox domain shared by every test of the run, see [`test_domain`].
+static TEST_DOMAIN: OnceCell<String> = OnceCell::const_new();
+
+/// A change to undo when the test ends.
+enum Cleanup {
+    Hosts { domain: String, hosts: Vec<HostRequest> },
+    Contacts { domain: String, contacts: Box<ContactSet> },
+}
+
+/// Undoes the registered changes, newest first, when dropped.
+struct TestResources {
+    client: NameCheapClient,
+    cleanups: Vec<Cleanup>,
+}
+
+impl TestResources {
+    fn new(client: &NameCheapClient) -> Self {
+        TestResources { client: client.clone(), cleanups: Vec::new() }
+    }
+
+    /// Restores the current host records of `domain` when the test ends.
+    async fn snapshot_hosts(&mut self, domain: &str) -> Result<(), Box<dyn Error>> {
+        let (sld, tld) = split_domain(domain).ok_or("invalid domain")?;
+        let hosts: Vec<Host> = self.client.domains_dns_get_host_records(sld, tld).await?;
+        let hosts: Vec<HostRequest> = hosts.iter().map(Host::to_host_request).collect();
+        self.cleanups.push(Cleanup::Hosts { domain: domain.to_string(), hosts });
+        Ok(())
+    }
+
+    /// Restores the current contacts of `domain` when the test ends.
+    async fn snapshot_contacts(&mut self, domain: &str) -> Result<ContactSet, Box<dyn Error>> {
+        let contacts: ContactSet = self.client.domains_get_contact_set(domain).await?;
+        self.cleanups.push(Cleanup::Contacts { domain: domain.to_string(), contacts: Box::new(contacts.clone()) });
+        Ok(contacts)
+    }
+
+    async fn run(client: NameCheapClient, cleanups: Vec<Cleanup>) {
+        for cleanup in cleanups.into_iter().rev() {
+            let outcome: Result<(), Box<dyn Error>> = match &cleanup {
+                Cleanup::Hosts { domain, hosts } => {
+                    let (sld, tld) = split_domain(domain).unwrap_or_default();
+                    client.domains_dns_replace_hosts(sld, tld, hosts).await.map(|_| ())
+                }
+                Cleanup::Contacts { domain, contacts } =>
+                    client.domains_set_contacts(domain, contacts).await.map(|_| ()),
+            };
+            if let Err(error) = outcome {
+                eprintln!("Cleanup failed, restore manually: {}", error);
+            }
+        }
+    }
+}
+
+impl Drop for TestResources {
+    fn drop(&mut self) {
+        let client: NameCheapClient = self.client.clone();
+        let cleanups: Vec<Cleanup> = std::mem::take(&mut self.cleanups);
+
+        // The test's runtime may be shutting down (or panicking), clean up on a fresh one
+        let cleanup = std::thread::spawn(move || {
+            tokio::runtime::Builder
+                ::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("cleanup runtime")
+                .block_on(TestResources::run(client, cleanups))
+        });
+        if cleanup.join().is_err() {
+            eprintln!("Cleanup thread panicked");
+        }
+    }
+}
+
+fn sandbox_client() -> NameCheapClient {
+    let client: NameCheapClient = NameCheapClient::new_from_env().expect("NAMECHEAP_* credentials");
+    assert!(!client.production, "the e2e suite only runs against the sandbox");
+    client
+}
+
+fn test_contact() -> Contact {
+    Contact {
+        type_: String::new(),
+        address_1: "8939 S. Cross Blvd".to_string(),
+        address_2: String::new(),
+        city: "Phoenix".to_string(),
+        country: "US".to_string(),
+        email_address: "e2e@example.com".to_string(),
+        fax: String::new(),
+        first_name: "Sandbox".to_string(),
+        job_title: String::new(),
+        last_name: "Tester".to_string(),
+        organization_name: String::new(),
+        phone: "+1.6613102107".to_string(),
+        phone_ext: String::new(),
+        postal_code: "85044".to_string(),
+        state_province: "AZ".to_string(),
+        state_province_choice: String::new(),
+        read_only: false,
+    }
+}
+
+/// Returns the sandbox domain to test against, registering a throwaway one on first use when
+/// none is configured.
+async fn test_domain(client: &NameCheapClient) -> String {
+    TEST_DOMAIN.get_or_init(|| register_test_domain(client)).await.clone()
+}
+
+async fn register_test_domain(client: &NameCheapClient) -> String {
+    if let Ok(domain) = std::env::var("NAMECHEAP_E2E_DOMAIN") {
+        return domain;
+    }
+
+    let suffix: u128 = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let domain: String = format!("namecheap-rs-e2e-{}.com", suffix);
+    let result = client
+        .domains_create(&domain, 1, &ContactSet::uniform(test_contact())).await
+        .expect("sandbox registration");
+    assert!(result.registered, "sandbox did not register {}", domain);
+    domain
+}
+
+#[tokio::test]
+async fn e2e_dns_records_are_ensured_and_restored() {
+    let client: NameCheapClient = sandbox_client();
+    let domain: String = test_domain(&client).await;
+    let (sld, tld) = split_domain(&domain).unwrap();
+    let mut resources: TestResources = TestResources::new(&client);
+    resources.snapshot_hosts(&domain).await.unwrap();
+
+    let record: HostRequest = HostRequest::new(
+        "e2e".to_string(),
+        "TXT".to_string(),
+        "namecheap-rs e2e".to_string(),
+        None,
+        None,
+        Some("300".to_string()),
+        None,
+        None
+    );
+    assert!(client.ensure_record(&domain, record.clone()).await.unwrap().has_changes());
+    assert!(!client.ensure_record(&domain, record).await.unwrap().has_changes());
+
+    let hosts: Vec<Host> = client.domains_dns_get_host_records(sld, tld).await.unwrap();
+    assert!(hosts.iter().any(|host| host.name == "e2e" && host.address == "namecheap-rs e2e"));
+}
+
+#[tokio::test]
+async fn e2e_contacts_are_updated_and_restored() {
+    let client: NameCheapClient = sandbox_client();
+    let domain: String = test_domain(&client).await;
+    let mut resources: TestResources = TestResources::new(&client);
+    let original: ContactSet = resources.snapshot_contacts(&domain).await.unwrap();
+
+    let mut updated: ContactSet = original.clone();
+    updated.tech.organization_name = "namecheap-rs e2e".to_string();
+    client.domains_set_contacts(&domain, &updated).await.unwrap();
+
+    let contacts: ContactSet = client.domains_get_contact_set(&domain).await.unwrap();
+    assert_eq!(contacts.tech.organization_name, "namecheap-rs e2e");
+}
+
+#[tokio::test]
+async fn e2e_domain_is_renewed() {
+    let client: NameCheapClient = sandbox_client();
+    let domain: String = test_domain(&client).await;
+
+    let result = client.domains_renew(&domain, 1, None).await.unwrap();
+    assert!(result.renewed, "sandbox did not renew {}", domain);
+    assert!(result.domain.eq_ignore_ascii_case(&domain));
+    assert!(result.expires_at.is_some_and(|expires_at| expires_at > chrono::Utc::now()));
+}